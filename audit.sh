@@ -0,0 +1,12 @@
+#!/bin/bash
+set -e
+
+# Check all crates for RUSTSEC advisories, license and source issues
+# Requires: cargo install cargo-deny
+
+for manifest in Cargo.toml dao-contract/Cargo.toml dao-factory/Cargo.toml; do
+    echo "🔍 Checking $manifest..."
+    cargo deny --manifest-path "$manifest" check --config deny.toml
+done
+
+echo "✅ No advisories or policy violations"
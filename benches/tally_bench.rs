@@ -0,0 +1,180 @@
+// Vote tallying benchmarks
+//
+// The WASI worker is a binary crate, so the modules under test are pulled in
+// by path. Run natively with `cargo bench`, or with
+// `cargo bench --target wasm32-wasip1` (needs a wasmtime runner configured)
+// for numbers that match the OutLayer environment. See PERFORMANCE.md.
+
+#[allow(dead_code, unused_imports)]
+#[path = "../src/crypto.rs"]
+mod crypto;
+#[allow(dead_code, unused_imports)]
+#[path = "../src/logger.rs"]
+mod logger;
+#[allow(dead_code, unused_imports)]
+#[path = "../src/tally.rs"]
+mod tally;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tally::{TallyOptions, VoteData};
+
+const MASTER_SECRET: &[u8] = b"bench_secret_32_bytes_long_xxxx!";
+const DAO: &str = "dao.testnet";
+
+/// Votes with real ECIES ciphertexts (one per voter, alternating yes/no)
+fn encrypted_votes(count: usize) -> Vec<VoteData> {
+    (0..count)
+        .map(|i| {
+            let user = format!("voter{}.testnet", i);
+            let pubkey = crypto::derive_user_pubkey(MASTER_SECRET, DAO, &user).unwrap();
+            let vote = if i % 2 == 0 { "yes" } else { "no" };
+            let ciphertext = crypto::encrypt_vote(&pubkey, vote.as_bytes()).unwrap();
+            VoteData {
+                user,
+                encrypted_vote: hex::encode(ciphertext),
+                timestamp: 1_000 + i as u64,
+            }
+        })
+        .collect()
+}
+
+/// Votes with fixed-size fake ciphertexts (enough for hashing benchmarks)
+fn plain_votes(count: usize) -> Vec<VoteData> {
+    (0..count)
+        .map(|i| VoteData {
+            user: format!("voter{}.testnet", i),
+            encrypted_vote: "ab".repeat(113), // typical ECIES ciphertext length for "yes"
+            timestamp: 1_000 + i as u64,
+        })
+        .collect()
+}
+
+fn bench_tally_votes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tally_votes");
+    group.sample_size(10);
+    let quorum = serde_json::json!({ "Absolute": { "min_votes": 1 } });
+
+    for size in [100, 1_000, 10_000] {
+        let votes = encrypted_votes(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &votes, |b, votes| {
+            b.iter(|| tally::tally_votes(MASTER_SECRET, DAO, 1, votes, &quorum, &TallyOptions::default()).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_merkle_tree(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_merkle_tree_with_proofs");
+
+    for size in [1, 100, 1_000, 10_000] {
+        let votes = plain_votes(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &votes, |b, votes| {
+            b.iter(|| tally::build_merkle_tree_with_proofs(votes))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_votes_hash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_votes_hash");
+
+    for size in [1, 100, 1_000, 10_000] {
+        let votes = plain_votes(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &votes, |b, votes| {
+            b.iter(|| tally::compute_votes_hash(votes))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_key_derivation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("key_derivation_1000");
+    let contexts: Vec<(String, String)> = (0..1_000)
+        .map(|i| (DAO.to_string(), format!("voter{}.testnet", i)))
+        .collect();
+
+    group.bench_function("individual", |b| {
+        b.iter(|| {
+            for (dao, user) in &contexts {
+                crypto::derive_keypair(MASTER_SECRET, dao, user).unwrap();
+            }
+        })
+    });
+    group.bench_function("hkdf_expand_many", |b| {
+        b.iter(|| crypto::hkdf_expand_many(MASTER_SECRET, &contexts).unwrap())
+    });
+
+    group.finish();
+}
+
+fn bench_batch_encrypt(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encrypt_100_recipients");
+    group.sample_size(10);
+    let keys: Vec<(Vec<u8>, Vec<u8>)> = (0..100)
+        .map(|i| crypto::derive_keypair(MASTER_SECRET, DAO, &format!("voter{}.testnet", i)).unwrap())
+        .collect();
+    let pubkeys: Vec<&[u8]> = keys.iter().map(|(_, pubkey)| pubkey.as_slice()).collect();
+    let privkeys: Vec<&[u8]> = keys.iter().map(|(privkey, _)| privkey.as_slice()).collect();
+    let message = b"proposal 42 passed";
+
+    group.bench_function("sequential_encrypt", |b| {
+        b.iter(|| {
+            for pubkey in &pubkeys {
+                crypto::encrypt_vote(pubkey, message).unwrap();
+            }
+        })
+    });
+    group.bench_function("batch_encrypt_distinct", |b| {
+        b.iter(|| crypto::batch_encrypt_distinct(&pubkeys, message).unwrap())
+    });
+
+    let ciphertexts = crypto::batch_encrypt_distinct(&pubkeys, message).unwrap();
+    group.bench_function("sequential_decrypt", |b| {
+        b.iter(|| {
+            for (privkey, ciphertext) in privkeys.iter().zip(&ciphertexts) {
+                crypto::decrypt_vote_with_key(privkey, ciphertext).unwrap();
+            }
+        })
+    });
+    group.bench_function("batch_decrypt", |b| {
+        b.iter(|| crypto::batch_decrypt(&privkeys, &ciphertexts).unwrap())
+    });
+
+    group.finish();
+}
+
+fn bench_check_quorum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check_quorum");
+
+    let quorums = [
+        ("absolute", serde_json::json!({ "Absolute": { "min_votes": 100 } })),
+        (
+            "time_weighted",
+            serde_json::json!({
+                "TimeWeighted": { "base_votes": 100, "decay_halflife_ns": 86_400_000_000_000u64 }
+            }),
+        ),
+    ];
+
+    for (name, quorum) in &quorums {
+        group.bench_with_input(BenchmarkId::from_parameter(name), quorum, |b, quorum| {
+            b.iter(|| tally::check_quorum(quorum, 100, 150, 0, 120.5, None).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_tally_votes,
+    bench_merkle_tree,
+    bench_votes_hash,
+    bench_key_derivation,
+    bench_batch_encrypt,
+    bench_check_quorum
+);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+//! Print JSON schemas for the contract's public types
+//!
+//! Usage: `cargo run --bin generate_schema > schema.json`
+
+use private_dao_contract::types::*;
+use schemars::schema_for;
+
+fn main() {
+    let schemas = serde_json::json!({
+        "MembershipMode": schema_for!(MembershipMode),
+        "QuorumType": schema_for!(QuorumType),
+        "ProposalStatus": schema_for!(ProposalStatus),
+        "ProposalType": schema_for!(ProposalType),
+        "ProposalTemplate": schema_for!(ProposalTemplate),
+        "Proposal": schema_for!(Proposal),
+        "Vote": schema_for!(Vote),
+        "MerkleProof": schema_for!(MerkleProof),
+        "TallyResult": schema_for!(TallyResult),
+        "DAOConfig": schema_for!(DAOConfig),
+        "MemberInfo": schema_for!(MemberInfo),
+        "DAOInfo": schema_for!(DAOInfo),
+        "TallyResponse": schema_for!(TallyResponse),
+    });
+
+    println!("{}", serde_json::to_string_pretty(&schemas).unwrap());
+}
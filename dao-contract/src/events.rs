@@ -0,0 +1,103 @@
+//! NEP-297 events
+//!
+//! Logged as `EVENT_JSON:{"standard":"private_dao","version":"1.0.0","event":...,"data":...}`
+//! so indexers can follow the DAO without polling views.
+
+use near_sdk::serde::Serialize;
+use near_sdk::{env, serde_json, AccountId};
+
+use crate::types::ProposalStatus;
+
+/// Event standard name
+pub const EVENT_STANDARD: &str = "private_dao";
+
+/// Event standard version (bump when an event's fields change)
+pub const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+/// Milestones (percent of the quorum minimum) reported by `VoteMilestoneReached`
+pub const VOTE_MILESTONES: [u8; 4] = [25, 50, 75, 100];
+
+/// Events emitted by the contract
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data", rename_all = "snake_case")]
+pub enum Event {
+    /// Ballots on a proposal reached a share of its quorum minimum
+    /// `current_votes` counts every ballot (re-votes and dummies included), so
+    /// this is an activity signal, not a tally.
+    VoteMilestoneReached {
+        proposal_id: u64,
+        milestone_percent: u8,
+        current_votes: u64,
+        quorum_min: u64,
+        timestamp: u64,
+    },
+    /// An account became a member with an encryption key (`join_dao` / `complete_join`)
+    MemberJoined {
+        account_id: AccountId,
+        /// Membership start (0 for members added by `migrate_add_member`)
+        joined_at: u64,
+    },
+    /// A member left (`leave_dao`) or was removed by the owner (`remove_member`)
+    MemberLeft {
+        account_id: AccountId,
+        /// Owner who removed the member (None if they left themselves)
+        removed_by: Option<AccountId>,
+    },
+    ProposalCreated {
+        proposal_id: u64,
+        creator: AccountId,
+        timestamp: u64,
+    },
+    /// The creator edited a proposal before any vote (`amend_proposal`)
+    ProposalAmended {
+        proposal_id: u64,
+        by: AccountId,
+        title_changed: bool,
+        description_changed: bool,
+        amended_at: u64,
+    },
+    /// A ballot was stored; its content stays encrypted (it may be a dummy)
+    VoteCast {
+        proposal_id: u64,
+        voter: AccountId,
+        vote_index: u64,
+        timestamp: u64,
+    },
+    /// A tally decided the proposal (tallies that leave it active emit nothing)
+    ProposalFinalized {
+        proposal_id: u64,
+        status: ProposalStatus,
+        total_votes: u64,
+    },
+    /// The owner halted user-facing operations (`pause`)
+    DaoPaused {
+        by: AccountId,
+        timestamp: u64,
+    },
+    /// The owner resumed operations (`unpause`)
+    DaoUnpaused {
+        by: AccountId,
+        timestamp: u64,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+impl Event {
+    /// Log the event in NEP-297 format
+    pub fn emit(&self) {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_STANDARD_VERSION,
+            event: self,
+        };
+        env::log_str(&format!("EVENT_JSON:{}", serde_json::to_string(&log).unwrap()));
+    }
+}
@@ -0,0 +1,30 @@
+//! Cross-contract interface for third-party contracts
+//!
+//! Recommended integration points for contracts that act on DAO decisions
+//! (timelocks, multisigs, treasuries). Import this module and query the DAO
+//! instead of parsing the full `Proposal`. Importing the crate also links the
+//! DAO's exported methods into your WASM; if they clash with yours, copy this
+//! file instead.
+//!
+//! ```ignore
+//! use private_dao_contract::ext::ext_dao;
+//!
+//! ext_dao::ext(dao_account_id)
+//!     .with_static_gas(Gas::from_tgas(5))
+//!     .is_proposal_passed(proposal_id)
+//!     .then(Self::ext(env::current_account_id()).on_dao_decision(proposal_id));
+//! ```
+
+use near_sdk::ext_contract;
+
+use crate::types::ProposalStatus;
+
+/// View methods of `PrivateDAO` intended for other contracts
+#[ext_contract(ext_dao)]
+pub trait PrivateDAOView {
+    /// True only if the proposal exists and has status `Passed`
+    fn is_proposal_passed(&self, proposal_id: u64) -> bool;
+
+    /// Proposal status, or None if the proposal doesn't exist
+    fn get_proposal_status(&self, proposal_id: u64) -> Option<ProposalStatus>;
+}
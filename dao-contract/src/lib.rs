@@ -10,20 +10,26 @@
  * - TEE-based vote verification
  */
 
-mod types;
+pub mod events;
+pub mod ext;
+pub mod types;
 
-use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LookupMap, UnorderedMap, Vector};
+use std::collections::HashMap;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::Base64VecU8;
 use near_sdk::{
-    env, ext_contract, log, near_bindgen, AccountId, Gas, NearToken, Promise, BorshStorageKey,
-    PromiseError, PanicOnDefault,
+    env, ext_contract, log, near_bindgen, AccountId, Gas, GasWeight, NearToken, Promise, BorshStorageKey,
+    PromiseError, PromiseResult,
 };
 
 type Balance = u128;
 
+use events::{Event, VOTE_MILESTONES};
 use types::*;
 
-/// Minimum deposit for OutLayer execution (0.01 NEAR)
+/// Default minimum deposit for OutLayer execution (0.01 NEAR, see `DAOConfig::min_outlayer_deposit`)
 const MIN_OUTLAYER_DEPOSIT: Balance = 10_000_000_000_000_000_000_000;
 
 /// Storage deposit per member (for public key storage)
@@ -32,11 +38,93 @@ const STORAGE_DEPOSIT_PER_MEMBER: Balance = 2_000_000_000_000_000_000_000; // 0.
 /// Storage deposit per vote
 const STORAGE_DEPOSIT_PER_VOTE: Balance = 2_000_000_000_000_000_000_000; // 0.002 NEAR
 
+/// Storage deposit per proposal
+const PROPOSAL_STORAGE_DEPOSIT: Balance = 1_000_000_000_000_000_000_000; // 0.001 NEAR
+
+/// Per-record storage overhead charged by the protocol (bytes)
+const STORAGE_RECORD_OVERHEAD: u64 = 40;
+
+/// Upper bound for a collection entry's trie key (prefix + borsh-encoded index/ID)
+const STORAGE_KEY_BYTES: u64 = 16;
+
+/// Longest valid NEAR account ID (used for worst-case storage estimates)
+const MAX_ACCOUNT_ID_LEN: usize = 64;
+
+/// Safety margin applied to storage deposit estimates (percent)
+const STORAGE_ESTIMATE_MARGIN_PERCENT: u128 = 120;
+
 /// Gas for callback
 const CALLBACK_GAS: Gas = Gas::from_tgas(10);
 
-/// OutLayer contract ID
-const OUTLAYER_CONTRACT_ID: &str = "outlayer.testnet";
+/// Gas price used for cost estimates (yoctoNEAR per gas, protocol minimum)
+const ESTIMATED_GAS_PRICE: Balance = 100_000_000;
+
+/// OutLayer contract used when `new()` isn't given one
+const DEFAULT_OUTLAYER_CONTRACT_ID: &str = "outlayer.testnet";
+
+/// Keymaster secrets used when `new()` isn't given a `secrets_ref`
+const DEFAULT_SECRETS_REF: &str = r#"{"profile":"default","account_id":"zavodil2.testnet"}"#;
+
+/// Default page size for `get_proposal_with_votes`
+const DEFAULT_VOTES_PAGE_SIZE: u64 = 20;
+
+/// Maximum page size for `get_proposal_with_votes` (keeps view responses small)
+const MAX_VOTES_PAGE_SIZE: u64 = 50;
+
+/// Maximum page size for `get_votes_paginated`
+const MAX_VOTES_PAGINATED_LIMIT: u64 = 200;
+
+/// Most tags a proposal can carry
+const MAX_PROPOSAL_TAGS: usize = 5;
+
+/// Longest allowed tag (characters)
+const MAX_TAG_LENGTH: usize = 32;
+
+/// Default `DAOConfig::max_title_length` (characters)
+const DEFAULT_MAX_TITLE_LENGTH: usize = 200;
+
+/// Default `DAOConfig::max_description_length` (characters)
+const DEFAULT_MAX_DESCRIPTION_LENGTH: usize = 10_000;
+
+/// Lowest allowed `DAOConfig::max_title_length`
+const MAX_TITLE_LENGTH_FLOOR: usize = 5;
+
+/// Lowest allowed `DAOConfig::max_description_length`
+const MAX_DESCRIPTION_LENGTH_FLOOR: usize = 10;
+
+/// Contract version (bump on every upgrade)
+const CONTRACT_VERSION: &str = "1.0.0";
+
+/// Highest `DAOConfig::protocol_fee_basis_points` (10%)
+const MAX_PROTOCOL_FEE_BASIS_POINTS: u16 = 1_000;
+
+/// OutLayer executions kept in `outlayer_executions` (oldest are overwritten)
+const MAX_OUTLAYER_EXECUTION_HISTORY: u64 = 10_000;
+
+/// Maximum page size for `get_outlayer_execution_history`
+const MAX_OUTLAYER_HISTORY_PAGE_SIZE: u64 = 100;
+
+/// Maximum page size for `get_members`
+const MAX_MEMBERS_PAGE_SIZE: u64 = 100;
+
+/// Maximum page size for `get_proposals_by_status`
+const MAX_PROPOSALS_PAGE_SIZE: u64 = 50;
+
+/// Most options a multi-option proposal can offer
+const MAX_VOTE_OPTIONS: usize = 10;
+
+/// Longest allowed vote option (characters)
+const MAX_VOTE_OPTION_LENGTH: usize = 64;
+
+/// Longest delegation chain a vote may flow through (checked in `delegate_vote`)
+const MAX_DELEGATION_DEPTH: usize = 10;
+
+/// A chunked finalization without progress for this long can be abandoned by anyone (1 hour)
+const PARTIAL_TALLY_TIMEOUT_NS: u64 = 3_600_000_000_000;
+
+/// An OutLayer request whose callback hasn't run for this long (e.g. it ran out
+/// of gas) no longer blocks a retry (1 hour)
+const IN_FLIGHT_TIMEOUT_NS: u64 = 3_600_000_000_000;
 
 /// External contract interface for OutLayer
 #[ext_contract(ext_outlayer)]
@@ -60,18 +148,44 @@ trait ExtSelf {
     fn on_key_derived(
         &mut self,
         user: AccountId,
+        execution_index: Option<u64>,
         #[callback_result] result: Result<Option<DeriveKeyResponse>, PromiseError>,
     );
 
     fn on_votes_tallied(
         &mut self,
         proposal_id: u64,
+        execution_index: Option<u64>,
+        #[callback_result] result: Result<Option<TallyResponse>, PromiseError>,
+    );
+
+    fn on_proposal_key_derived(
+        &mut self,
+        user: AccountId,
+        proposal_id: u64,
+        #[callback_result] result: Result<Option<DeriveKeyResponse>, PromiseError>,
+    );
+
+    fn on_votes_tallied_chunk(
+        &mut self,
+        proposal_id: u64,
+        chunk_end: u64,
+        execution_index: Option<u64>,
         #[callback_result] result: Result<Option<TallyResponse>, PromiseError>,
     );
+
+    fn on_proposal_executed(&mut self, proposal_id: u64, deposit: NearToken);
+
+    fn on_replay_tallied(
+        &self,
+        proposal_id: u64,
+        cutoff_timestamp: u64,
+        #[callback_result] result: Result<Option<TallyResponse>, PromiseError>,
+    ) -> TallyResponse;
 }
 
 #[near_bindgen]
-#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+#[derive(BorshDeserialize, BorshSerialize)]
 #[borsh(crate = "near_sdk::borsh")]
 pub struct PrivateDAO {
     /// DAO owner (admin)
@@ -89,10 +203,18 @@ pub struct PrivateDAO {
     /// Member count (for public display)
     pub member_count: u64,
 
+    /// Iterable index of `members` (for `get_members`)
+    /// Members added before the index existed are backfilled with `migrate_index_members`
+    pub member_index: UnorderedSet<AccountId>,
+
     /// User public keys (account_id → public_key_hex)
     /// Public keys are used for client-side encryption
     pub user_pubkeys: LookupMap<AccountId, String>,
 
+    /// Algorithm each entry of `user_pubkeys` was derived with, if not HKDF
+    /// Tallies pass these to the worker so it re-derives the matching private keys
+    pub user_key_algorithms: LookupMap<AccountId, KeyDerivationAlgorithm>,
+
     /// Proposals (proposal_id → Proposal)
     pub proposals: UnorderedMap<u64, Proposal>,
 
@@ -101,6 +223,283 @@ pub struct PrivateDAO {
 
     /// Votes (proposal_id → Vector<Vote>)
     pub votes: LookupMap<u64, Vector<Vote>>,
+
+    /// Proposal templates (template name → ProposalTemplate)
+    pub proposal_templates: UnorderedMap<String, ProposalTemplate>,
+
+    /// Block timestamp of the initial `new()` call
+    pub deployed_at: u64,
+
+    /// Block timestamp of the last state migration (`reset_state`), 0 if never migrated
+    pub last_upgraded_at: u64,
+
+    /// WASM uploaded for UpgradeContract proposals (proposal_id → code)
+    /// Deployed by `execute_upgrade`; removed then or once the proposal is rejected, vetoed or cancelled
+    pub pending_upgrade_wasm: LookupMap<u64, Vec<u8>>,
+
+    /// If true, only whitelisted accounts can join (Public mode)
+    pub whitelist_mode: bool,
+
+    /// Accounts allowed to join when `whitelist_mode` is on
+    pub whitelisted: LookupSet<AccountId>,
+
+    /// Accounts banned from joining (account_id → reason)
+    pub blacklisted: LookupMap<AccountId, String>,
+
+    /// Owner-adjustable settings
+    pub config: DAOConfig,
+
+    /// Accounts with the Admin role (the owner is always an admin)
+    pub admins: UnorderedSet<AccountId>,
+
+    /// Admin veto approvals per passed proposal (proposal_id → approving admins)
+    pub veto_approvals: LookupMap<u64, Vector<AccountId>>,
+
+    /// Proposal-scoped public keys ("account_id:proposal_id" → public_key_hex)
+    pub proposal_pubkeys: LookupMap<String, String>,
+
+    /// Block of each account's pending join attempt (guards against duplicate OutLayer calls)
+    /// Cleared once the key is derived
+    pub block_height_of_last_join_attempt: LookupMap<AccountId, u64>,
+
+    /// How each pending key derivation was requested (`join_dao` vs `complete_join`)
+    /// Removed in `on_key_derived`
+    pub pending_join_type: LookupMap<AccountId, JoinType>,
+
+    /// Joins waiting for a successful key derivation (account_id → pending join)
+    /// Removed (and refunded on failure) in `on_key_derived`, or by `admin_refund_incomplete_join`
+    pub pending_key_derivations: UnorderedMap<AccountId, PendingKeyDerivation>,
+
+    /// Start of the current proposal rate-limit window (see `DAOConfig::proposal_period_ns`)
+    pub proposal_period_start: u64,
+
+    /// Proposals created in the current rate-limit window
+    pub proposals_in_period: u64,
+
+    /// Active proposals per creator (see `DAOConfig::max_active_proposals_per_creator`)
+    pub active_proposal_counts: LookupMap<AccountId, u64>,
+
+    /// Current delegate of each delegating member (delegator → delegate)
+    pub delegations: LookupMap<AccountId, AccountId>,
+
+    /// Highest vote milestone already announced per proposal (percent, see `events::VOTE_MILESTONES`)
+    pub proposal_vote_milestones: LookupMap<u64, u8>,
+
+    /// Funds for DAO-paid tallies (`OutLayerPayerMode::DAO`), topped up via `fund_treasury`
+    pub treasury_balance: Balance,
+
+    /// Protocol fees taken so far (see `DAOConfig::protocol_fee_basis_points`)
+    pub protocol_fee_collected: Balance,
+
+    /// Reverse index of `delegations` (delegate → number of delegators)
+    /// Only delegates with at least one delegator have an entry
+    pub delegations_received: UnorderedMap<AccountId, u64>,
+
+    /// Recent OutLayer executions, a ring buffer of `MAX_OUTLAYER_EXECUTION_HISTORY` entries
+    /// Execution `i` is stored at `i % MAX_OUTLAYER_EXECUTION_HISTORY`
+    pub outlayer_executions: Vector<OutLayerExecutionSummary>,
+
+    /// OutLayer executions requested so far (including ones dropped from the history)
+    pub outlayer_execution_count: u64,
+
+    /// Entries of `outlayer_executions` with a known outcome / that succeeded
+    /// Kept in sync so `get_outlayer_success_rate` doesn't read the whole history
+    pub outlayer_executions_completed: u64,
+    pub outlayer_executions_succeeded: u64,
+
+    /// OutLayer deployment that runs key derivation and tallies
+    pub outlayer_contract: AccountId,
+
+    /// Keymaster secrets (`DAO_MASTER_SECRET`) OutLayer injects into executions
+    /// Kept as a JSON string; see `get_secrets_ref`
+    pub secrets_ref: String,
+
+    /// Account `transfer_ownership` offered ownership to, until it accepts
+    pub pending_owner: Option<AccountId>,
+
+    /// Block timestamp of the pending `transfer_ownership` (0 if none)
+    pub pending_owner_since: u64,
+
+    /// OutLayer deposits of key derivations and tallies awaiting their callback (execution index → deposit)
+    /// Refunded to the payer if the OutLayer call fails
+    pub pending_deposits: LookupMap<u64, PendingDeposit>,
+
+    /// Emergency stop set by `pause`: joins, votes, proposals and tallies are rejected
+    pub paused: bool,
+
+    /// Chunked finalizations in progress (proposal_id → running tally)
+    pub partial_tallies: LookupMap<u64, PartialTally>,
+
+    /// Positions of each member's ballots ("account_id:proposal_id" → vote indices)
+    /// Lets a chunk carry all ballots of the voters it counts (see `finalize_proposal_chunk`)
+    pub ballot_indices: LookupMap<String, Vec<u64>>,
+
+    /// Cap on `member_count` enforced by `join_dao` / `add_member` (None = unlimited)
+    pub max_members: Option<u64>,
+
+    /// Open invitations to a private DAO, consumed by `complete_join`
+    pub pending_invitations: LookupMap<AccountId, InvitationInfo>,
+
+    /// IDs of proposals by status, for `get_proposals_by_status`
+    /// Kept in sync by `internal_index_proposal_status` (sets, so moves don't scan)
+    pub active_proposal_ids: UnorderedSet<u64>,
+    pub passed_proposal_ids: UnorderedSet<u64>,
+    pub rejected_proposal_ids: UnorderedSet<u64>,
+
+    /// Storage deposit each member paid to join, refunded when they leave or are removed
+    /// Missing for members who paid none (pre-approved, invited or migrated)
+    pub member_deposits: LookupMap<AccountId, Balance>,
+
+    /// IDs of proposals by tag, in creation order, for `get_proposals_by_tag`
+    pub tag_index: LookupMap<String, Vector<u64>>,
+
+    /// Accounts with a key derivation awaiting its OutLayer callback → request timestamp
+    /// (one at a time per account, until `IN_FLIGHT_TIMEOUT_NS` passes)
+    /// Cleared in `on_key_derived`, or by `admin_refund_incomplete_join`
+    pub key_derivations_in_flight: LookupMap<AccountId, u64>,
+
+    /// Proposals with a `finalize_proposal` tally awaiting its OutLayer callback → request timestamp
+    /// (one at a time per proposal, until `IN_FLIGHT_TIMEOUT_NS` passes)
+    /// Cleared in `on_votes_tallied`
+    pub tallies_in_flight: LookupMap<u64, u64>,
+
+    /// Ballots cast so far, re-votes included (see `get_governance_stats`)
+    pub total_votes_cast: u64,
+
+    /// Proposals whose one DAO-paid tally was already requested (`OutLayerPayerMode::DAO`)
+    pub dao_paid_tallies: LookupSet<u64>,
+
+    /// DAO-paid OutLayer deposits whose unused part may have been refunded to
+    /// the contract balance but not yet credited to `treasury_balance`
+    /// (see `reconcile_treasury`)
+    pub unreconciled_outlayer_spend: Balance,
+
+    /// Uploader and deposit of each `pending_upgrade_wasm` entry
+    pub upgrade_wasm_uploads: LookupMap<u64, UpgradeWasmUpload>,
+
+    /// Members with an encryption pubkey, i.e. who can vote
+    /// (snapshotted as `Proposal::eligible_voters`)
+    pub voting_member_count: u64,
+
+    /// Delegators of each delegate (delegate → delegators, reverse of `delegations`)
+    /// Lets a leaving or removed delegate's incoming delegations be cleared
+    pub delegators: LookupMap<AccountId, Vec<AccountId>>,
+
+    /// Members whose `rotate_key` derivation is in flight (removed in `on_key_derived`)
+    /// A rotation never adds a member, even if the account left meanwhile
+    pub pending_key_rotations: LookupSet<AccountId>,
+
+    /// Refundable deposits held for others: unspent `Proposal::creator_deposit`s,
+    /// upgrade WASM uploads, member and pending join deposits
+    /// Never credited to the treasury by `reconcile_treasury`
+    pub held_deposits: Balance,
+}
+
+impl Default for PrivateDAO {
+    /// Called by `near_bindgen` when a method runs before `new()` (replaces `PanicOnDefault`)
+    fn default() -> Self {
+        env::panic_str(
+            "PrivateDAO must be initialized with 'new()' before calling any method. \
+             Call new(name, membership_mode, owner, config, outlayer_contract, max_members, secrets_ref) \
+             to initialize.",
+        )
+    }
+}
+
+/// Root state of the initial release, converted by `reset_state`
+///
+/// Its proposals and votes are cleared, so their (baseline) values are never read.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct LegacyPrivateDAO {
+    pub owner: AccountId,
+    pub name: String,
+    pub membership_mode: MembershipMode,
+    pub members: LookupMap<AccountId, BaselineMemberInfo>,
+    pub member_count: u64,
+    pub user_pubkeys: LookupMap<AccountId, String>,
+    pub proposals: UnorderedMap<u64, Vec<u8>>,
+    pub next_proposal_id: u64,
+    pub votes: LookupMap<u64, Vector<Vec<u8>>>,
+}
+
+impl From<LegacyPrivateDAO> for PrivateDAO {
+    /// Current layout keeping the baseline's members and pubkeys (same prefixes); its proposals are cleared
+    ///
+    /// Only the owner gets indexed here, other members by `migrate_index_members`.
+    fn from(mut legacy: LegacyPrivateDAO) -> Self {
+        legacy.proposals.clear();
+
+        let mut dao = Self {
+            owner: legacy.owner.clone(),
+            name: legacy.name,
+            membership_mode: legacy.membership_mode,
+            members: LookupMap::new(StorageKey::Members),
+            member_count: legacy.member_count,
+            member_index: UnorderedSet::new(StorageKey::MemberIndex),
+            user_pubkeys: LookupMap::new(StorageKey::UserPubKeys),
+            user_key_algorithms: LookupMap::new(StorageKey::UserKeyAlgorithms),
+            proposals: UnorderedMap::new(StorageKey::Proposals),
+            // Not reset: ballots of the cleared proposals stay under their IDs
+            next_proposal_id: legacy.next_proposal_id,
+            votes: LookupMap::new(StorageKey::Votes),
+            proposal_templates: UnorderedMap::new(StorageKey::ProposalTemplates),
+            deployed_at: 0,
+            last_upgraded_at: 0,
+            pending_upgrade_wasm: LookupMap::new(StorageKey::PendingUpgradeWasm),
+            whitelist_mode: false,
+            whitelisted: LookupSet::new(StorageKey::Whitelisted),
+            blacklisted: LookupMap::new(StorageKey::Blacklisted),
+            config: DAOConfig::default(),
+            admins: UnorderedSet::new(StorageKey::Admins),
+            veto_approvals: LookupMap::new(StorageKey::VetoApprovals),
+            proposal_pubkeys: LookupMap::new(StorageKey::ProposalPubKeys),
+            block_height_of_last_join_attempt: LookupMap::new(StorageKey::LastJoinAttempt),
+            pending_join_type: LookupMap::new(StorageKey::PendingJoinType),
+            pending_key_derivations: UnorderedMap::new(StorageKey::PendingKeyDerivations),
+            proposal_period_start: 0,
+            proposals_in_period: 0,
+            active_proposal_counts: LookupMap::new(StorageKey::ActiveProposalCounts),
+            proposal_vote_milestones: LookupMap::new(StorageKey::ProposalVoteMilestones),
+            treasury_balance: 0,
+            protocol_fee_collected: 0,
+            delegations: LookupMap::new(StorageKey::Delegations),
+            delegations_received: UnorderedMap::new(StorageKey::DelegationsReceived),
+            outlayer_executions: Vector::new(StorageKey::OutLayerExecutions),
+            outlayer_execution_count: 0,
+            outlayer_executions_completed: 0,
+            outlayer_executions_succeeded: 0,
+            outlayer_contract: DEFAULT_OUTLAYER_CONTRACT_ID.parse().unwrap(),
+            secrets_ref: DEFAULT_SECRETS_REF.to_string(),
+            pending_owner: None,
+            pending_owner_since: 0,
+            pending_deposits: LookupMap::new(StorageKey::PendingDeposits),
+            paused: false,
+            partial_tallies: LookupMap::new(StorageKey::PartialTallies),
+            ballot_indices: LookupMap::new(StorageKey::BallotIndices),
+            max_members: None,
+            pending_invitations: LookupMap::new(StorageKey::PendingInvitations),
+            active_proposal_ids: UnorderedSet::new(StorageKey::ActiveProposalIds),
+            passed_proposal_ids: UnorderedSet::new(StorageKey::PassedProposalIds),
+            rejected_proposal_ids: UnorderedSet::new(StorageKey::RejectedProposalIds),
+            member_deposits: LookupMap::new(StorageKey::MemberDeposits),
+            tag_index: LookupMap::new(StorageKey::TagIndex),
+            key_derivations_in_flight: LookupMap::new(StorageKey::KeyDerivationsInFlight),
+            tallies_in_flight: LookupMap::new(StorageKey::TalliesInFlight),
+            total_votes_cast: 0,
+            dao_paid_tallies: LookupSet::new(StorageKey::DaoPaidTallies),
+            unreconciled_outlayer_spend: 0,
+            upgrade_wasm_uploads: LookupMap::new(StorageKey::UpgradeWasmUploads),
+            // Counted as members get indexed
+            voting_member_count: 0,
+            delegators: LookupMap::new(StorageKey::Delegators),
+            pending_key_rotations: LookupSet::new(StorageKey::PendingKeyRotations),
+            held_deposits: 0,
+        };
+        dao.internal_index_member(&legacy.owner);
+
+        dao
+    }
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
@@ -109,7 +508,46 @@ enum StorageKey {
     Members,
     UserPubKeys,
     Proposals,
-    Votes
+    Votes,
+    ProposalTemplates,
+    PendingUpgradeWasm,
+    // Unused since `ballot_indices` answers "has voted"; kept so later prefixes stay put
+    #[allow(dead_code)]
+    VoterProposals,
+    Whitelisted,
+    Blacklisted,
+    Admins,
+    VetoApprovals,
+    ProposalPubKeys,
+    LastJoinAttempt,
+    PendingJoinType,
+    ActiveProposalCounts,
+    Delegations,
+    DelegationsReceived,
+    // Unused since per-ballot statuses were dropped; kept so later prefixes stay put
+    #[allow(dead_code)]
+    VoteStatuses,
+    ProposalVoteMilestones,
+    OutLayerExecutions,
+    PendingKeyDerivations,
+    UserKeyAlgorithms,
+    MemberIndex,
+    PendingDeposits,
+    PartialTallies,
+    BallotIndices,
+    PendingInvitations,
+    ActiveProposalIds,
+    PassedProposalIds,
+    RejectedProposalIds,
+    MemberDeposits,
+    TagIndex,
+    TagProposalIds { tag: String },
+    KeyDerivationsInFlight,
+    TalliesInFlight,
+    DaoPaidTallies,
+    UpgradeWasmUploads,
+    Delegators,
+    PendingKeyRotations,
 }
 
 #[near_bindgen]
@@ -120,9 +558,28 @@ impl PrivateDAO {
     /// * `name` - DAO name
     /// * `membership_mode` - Public or Private membership
     /// * `owner` - DAO owner/admin account
+    /// * `config` - Optional settings (defaults to `DAOConfig::default()`)
+    /// * `outlayer_contract` - OutLayer contract to call (defaults to `outlayer.testnet`)
+    /// * `max_members` - Optional membership cap (owner included)
+    /// * `secrets_ref` - Keymaster secrets for OutLayer (defaults to the shared testnet keymaster)
     #[init]
-    pub fn new(name: String, membership_mode: MembershipMode, owner: AccountId) -> Self {
-        assert!(!env::state_exists(), "Already initialized");
+    pub fn new(
+        name: String,
+        membership_mode: MembershipMode,
+        owner: AccountId,
+        config: Option<DAOConfig>,
+        outlayer_contract: Option<AccountId>,
+        max_members: Option<u64>,
+        secrets_ref: Option<serde_json::Value>,
+    ) -> Self {
+        Self::assert_not_initialized();
+        if let Some(config) = &config {
+            validate_config(config);
+        }
+        let secrets_ref = match secrets_ref {
+            Some(secrets_ref) => validate_secrets_ref(&secrets_ref),
+            None => DEFAULT_SECRETS_REF.to_string(),
+        };
 
         let mut dao = Self {
             owner: owner.clone(),
@@ -130,16 +587,75 @@ impl PrivateDAO {
             membership_mode,
             members: LookupMap::new(StorageKey::Members),
             member_count: 0,
+            member_index: UnorderedSet::new(StorageKey::MemberIndex),
             user_pubkeys: LookupMap::new(StorageKey::UserPubKeys),
+            user_key_algorithms: LookupMap::new(StorageKey::UserKeyAlgorithms),
             proposals: UnorderedMap::new(StorageKey::Proposals),
             next_proposal_id: 1,
             votes: LookupMap::new(StorageKey::Votes),
+            proposal_templates: UnorderedMap::new(StorageKey::ProposalTemplates),
+            deployed_at: env::block_timestamp(),
+            last_upgraded_at: 0,
+            pending_upgrade_wasm: LookupMap::new(StorageKey::PendingUpgradeWasm),
+            whitelist_mode: false,
+            whitelisted: LookupSet::new(StorageKey::Whitelisted),
+            blacklisted: LookupMap::new(StorageKey::Blacklisted),
+            config: config.unwrap_or_default(),
+            admins: UnorderedSet::new(StorageKey::Admins),
+            veto_approvals: LookupMap::new(StorageKey::VetoApprovals),
+            proposal_pubkeys: LookupMap::new(StorageKey::ProposalPubKeys),
+            block_height_of_last_join_attempt: LookupMap::new(StorageKey::LastJoinAttempt),
+            pending_join_type: LookupMap::new(StorageKey::PendingJoinType),
+            pending_key_derivations: UnorderedMap::new(StorageKey::PendingKeyDerivations),
+            proposal_period_start: 0,
+            proposals_in_period: 0,
+            active_proposal_counts: LookupMap::new(StorageKey::ActiveProposalCounts),
+            proposal_vote_milestones: LookupMap::new(StorageKey::ProposalVoteMilestones),
+            treasury_balance: 0,
+            protocol_fee_collected: 0,
+            delegations: LookupMap::new(StorageKey::Delegations),
+            delegations_received: UnorderedMap::new(StorageKey::DelegationsReceived),
+            outlayer_executions: Vector::new(StorageKey::OutLayerExecutions),
+            outlayer_execution_count: 0,
+            outlayer_executions_completed: 0,
+            outlayer_executions_succeeded: 0,
+            outlayer_contract: outlayer_contract
+                .unwrap_or_else(|| DEFAULT_OUTLAYER_CONTRACT_ID.parse().unwrap()),
+            secrets_ref,
+            pending_owner: None,
+            pending_owner_since: 0,
+            pending_deposits: LookupMap::new(StorageKey::PendingDeposits),
+            paused: false,
+            partial_tallies: LookupMap::new(StorageKey::PartialTallies),
+            ballot_indices: LookupMap::new(StorageKey::BallotIndices),
+            max_members,
+            pending_invitations: LookupMap::new(StorageKey::PendingInvitations),
+            active_proposal_ids: UnorderedSet::new(StorageKey::ActiveProposalIds),
+            passed_proposal_ids: UnorderedSet::new(StorageKey::PassedProposalIds),
+            rejected_proposal_ids: UnorderedSet::new(StorageKey::RejectedProposalIds),
+            member_deposits: LookupMap::new(StorageKey::MemberDeposits),
+            tag_index: LookupMap::new(StorageKey::TagIndex),
+            key_derivations_in_flight: LookupMap::new(StorageKey::KeyDerivationsInFlight),
+            tallies_in_flight: LookupMap::new(StorageKey::TalliesInFlight),
+            total_votes_cast: 0,
+            dao_paid_tallies: LookupSet::new(StorageKey::DaoPaidTallies),
+            unreconciled_outlayer_spend: 0,
+            upgrade_wasm_uploads: LookupMap::new(StorageKey::UpgradeWasmUploads),
+            voting_member_count: 0,
+            delegators: LookupMap::new(StorageKey::Delegators),
+            pending_key_rotations: LookupSet::new(StorageKey::PendingKeyRotations),
+            held_deposits: 0,
         };
 
         // Add owner as first member
         dao.members.insert(&owner, &MemberInfo {
             joined_at: env::block_timestamp(),
+            key_rotated_at: None,
+            last_voted_at: None,
+            proposals_voted: 0,
+            key_epoch: 0,
         });
+        dao.member_index.insert(&owner);
         dao.member_count = 1;
 
         dao
@@ -154,7 +670,7 @@ impl PrivateDAO {
     /// Requires:
     /// - 0.002 NEAR for storage deposit (member record)
     /// - 0.01 NEAR for OutLayer execution (key derivation)
-    /// Total: ~0.012 NEAR
+    /// Total: ~0.012 NEAR, plus the protocol fee if configured
     ///
     /// # Process
     /// 1. User pays and joins DAO
@@ -163,6 +679,7 @@ impl PrivateDAO {
     /// 4. User can now vote with encrypted ballots
     #[payable]
     pub fn join_dao(&mut self) -> Promise {
+        self.assert_not_paused();
         let user = env::predecessor_account_id();
         let attached = env::attached_deposit();
 
@@ -176,22 +693,36 @@ impl PrivateDAO {
             env::panic_str("Private DAO: join by invitation only");
         }
 
-        // Check deposit covers storage + OutLayer execution
-        let required_deposit = STORAGE_DEPOSIT_PER_MEMBER + MIN_OUTLAYER_DEPOSIT;
+        self.assert_not_blacklisted(&user);
+
+        // In whitelist mode, only whitelisted accounts can join
+        if self.whitelist_mode && !self.whitelisted.contains(&user) {
+            env::panic_str("Account is not whitelisted");
+        }
+
+        self.assert_below_member_cap();
+
+        // Check deposit covers storage + OutLayer execution (after the protocol fee)
+        let attached = self.internal_take_protocol_fee(attached);
+        let required_deposit = STORAGE_DEPOSIT_PER_MEMBER + self.config.min_outlayer_deposit.as_yoctonear();
         assert!(
             attached.as_yoctonear() >= required_deposit,
             "Minimum deposit is {} yoctoNEAR (storage + OutLayer execution)",
             required_deposit
         );
 
+        self.internal_record_join_attempt(&user);
+        self.pending_join_type.insert(&user, &JoinType { is_new: true });
+        self.internal_record_pending_key_derivation(&user, STORAGE_DEPOSIT_PER_MEMBER);
+
         log!("User {} requesting to join DAO. Deriving encryption public key via OutLayer", user);
 
         // NOTE: User is NOT added to members yet
         // They will be added in on_key_derived callback after successful key generation
         // This ensures atomicity: user is only a member if they have a valid pubkey
 
-        // Call OutLayer to derive user's public key
-        self.request_key_derivation(user.clone(), attached.as_yoctonear())
+        // Call OutLayer to derive user's public key (the storage deposit stays with the contract)
+        self.request_key_derivation(user.clone(), attached.as_yoctonear() - STORAGE_DEPOSIT_PER_MEMBER, 0)
     }
 
     /// Add member (Private DAO only, owner-only)
@@ -208,686 +739,3329 @@ impl PrivateDAO {
             env::panic_str("Already a member");
         }
 
+        self.assert_below_member_cap();
+
         self.members.insert(&account_id, &MemberInfo {
             joined_at: env::block_timestamp(),
+            key_rotated_at: None,
+            last_voted_at: None,
+            proposals_voted: 0,
+            key_epoch: 0,
         });
+        self.member_index.insert(&account_id);
         self.member_count += 1;
 
         log!("Added {} to private DAO (pre-approved)", account_id);
     }
 
-    /// Leave the DAO (self-removal)
-    ///
-    /// Any member can leave the DAO at any time.
+    /// Invite an account to a private DAO until `expires_at` (owner-only)
     ///
-    /// Removes:
-    /// - Member status
-    /// - Public key (if exists)
-    /// - Does NOT remove votes (preserves historical data)
+    /// The invitee becomes a member once `complete_join` derives their key.
+    /// Unlike `add_member`, nobody is counted as a member before accepting.
     ///
-    /// Note: No refund is provided (storage deposit is forfeited)
-    pub fn leave_dao(&mut self) {
-        let user = env::predecessor_account_id();
+    /// # Payment
+    /// Requires 0.002 NEAR storage deposit (invitation, then member record)
+    #[payable]
+    pub fn create_invitation(&mut self, account_id: AccountId, expires_at: u64) {
+        self.assert_owner();
 
-        // Check if member exists
-        if self.members.get(&user).is_none() {
-            env::panic_str("Not a member");
+        if self.membership_mode == MembershipMode::Public {
+            env::panic_str("Public DAO: users join directly");
         }
 
-        // Remove from members
-        self.members.remove(&user);
-        self.member_count -= 1;
+        if self.members.get(&account_id).is_some() {
+            env::panic_str("Already a member");
+        }
 
-        // Remove pubkey if exists
-        if self.user_pubkeys.get(&user).is_some() {
-            self.user_pubkeys.remove(&user);
+        assert!(
+            expires_at > env::block_timestamp(),
+            "Invitation must expire in the future"
+        );
+        assert!(
+            env::attached_deposit().as_yoctonear() >= STORAGE_DEPOSIT_PER_MEMBER,
+            "Minimum deposit is {} yoctoNEAR for storage",
+            STORAGE_DEPOSIT_PER_MEMBER
+        );
+
+        self.pending_invitations.insert(&account_id, &InvitationInfo {
+            invited_at: env::block_timestamp(),
+            expires_at,
+        });
+
+        log!("Invited {} to private DAO until {}", account_id, expires_at);
+    }
+
+    /// Withdraw an open invitation (owner-only)
+    pub fn revoke_invitation(&mut self, account_id: AccountId) {
+        self.assert_owner();
+
+        if self.pending_invitations.remove(&account_id).is_none() {
+            env::panic_str("No invitation for this account");
         }
 
-        log!("User {} left the DAO", user);
+        log!("Revoked invitation of {}", account_id);
     }
 
-    /// Remove member (owner-only)
-    ///
-    /// **FOR TESTING ONLY**: Removes a member from the DAO.
-    /// In production, this should require governance vote or more strict conditions.
+    /// Ban an account from joining (owner-only)
     ///
-    /// Removes:
-    /// - Member status
-    /// - Public key (if exists)
-    /// - Does NOT remove votes (preserves historical data)
-    pub fn remove_member(&mut self, account_id: AccountId) {
+    /// Existing members are not removed (use `remove_member`), but a blacklisted
+    /// pre-approved member cannot complete their join.
+    pub fn blacklist_member(&mut self, account_id: AccountId, reason: String) {
         self.assert_owner();
 
-        // Check if member exists
-        if self.members.get(&account_id).is_none() {
-            env::panic_str("Not a member");
-        }
+        self.blacklisted.insert(&account_id, &reason);
 
-        // Remove from members
-        self.members.remove(&account_id);
-        self.member_count -= 1;
+        log!("Blacklisted {}: {}", account_id, reason);
+    }
 
-        // Remove pubkey if exists
-        if self.user_pubkeys.get(&account_id).is_some() {
-            self.user_pubkeys.remove(&account_id);
+    /// Lift a ban (owner-only)
+    pub fn remove_from_blacklist(&mut self, account_id: AccountId) {
+        self.assert_owner();
+
+        if self.blacklisted.remove(&account_id).is_none() {
+            env::panic_str("Account is not blacklisted");
         }
 
-        log!("TESTING: Owner removed {} from DAO", account_id);
+        log!("Removed {} from blacklist", account_id);
     }
 
-    /// Manually add member with timestamp (owner-only, for migration)
-    ///
-    /// This is a migration helper to add members with joined_at = 0 after deploying V2.
-    /// Use this to restore members who were in the old contract.
-    ///
-    /// joined_at = 0 means they can vote on all proposals (old and new)
-    pub fn migrate_add_member(&mut self, account_id: AccountId, pubkey: Option<String>) {
+    /// Allow an account to join while whitelist mode is on (owner-only)
+    pub fn whitelist_member(&mut self, account_id: AccountId) {
         self.assert_owner();
 
-        // Add member with joined_at = 0 (can vote on everything)
-        self.members.insert(&account_id, &MemberInfo { joined_at: 0 });
+        self.whitelisted.insert(&account_id);
 
-        // Add pubkey if provided
-        if let Some(pk) = pubkey {
-            self.user_pubkeys.insert(&account_id, &pk);
+        log!("Whitelisted {}", account_id);
+    }
+
+    /// Revoke a whitelist entry (owner-only)
+    pub fn remove_from_whitelist(&mut self, account_id: AccountId) {
+        self.assert_owner();
+
+        if !self.whitelisted.remove(&account_id) {
+            env::panic_str("Account is not whitelisted");
         }
 
-        self.member_count += 1;
+        log!("Removed {} from whitelist", account_id);
+    }
 
-        log!(
-            "Migration: Added member {} with joined_at=0 (can vote on all proposals)",
-            account_id
-        );
+    /// Enable or disable whitelist-only joining for Public DAOs (owner-only)
+    pub fn set_whitelist_mode(&mut self, enabled: bool) {
+        self.assert_owner();
+
+        self.whitelist_mode = enabled;
+
+        log!("Whitelist mode {}", if enabled { "enabled" } else { "disabled" });
     }
 
-    /// Reset state (TESTING ONLY - clears everything)
-    ///
-    /// **FOR TESTING ONLY**: Recreates all storage collections with new format.
-    /// Use this after contract upgrade when state format changed.
-    ///
-    /// WARNING: This will clear all proposals and votes!
-    /// Members and pubkeys are preserved.
+    /// Set or remove (None) the membership cap (owner-only)
     ///
-    /// NOTE: Must be called by contract account itself (use near-cli with --accountId same as contract)
-    #[init(ignore_state)]
-    pub fn reset_state() -> Self {
-        // Get old state
-        let old_state: PrivateDAO = env::state_read().expect("Failed to read state");
+    /// A cap below the current member count evicts nobody; it only blocks new members.
+    pub fn set_max_members(&mut self, max: Option<u64>) {
+        self.assert_owner();
 
-        // Verify caller is owner
-        assert_eq!(
-            env::predecessor_account_id(),
-            old_state.owner,
-            "Only owner can migrate"
-        );
+        self.max_members = max;
 
-        // Create new state - keep members/pubkeys as-is, recreate proposals/votes
-        let new_state = Self {
-            owner: old_state.owner.clone(),
-            name: old_state.name.clone(),
-            membership_mode: old_state.membership_mode.clone(),
-            members: LookupMap::new(StorageKey::Members),
-            member_count: 0,
-            user_pubkeys: LookupMap::new(StorageKey::UserPubKeys),
-            proposals: UnorderedMap::new(StorageKey::Proposals),
-            next_proposal_id: 1,
-            votes: LookupMap::new(StorageKey::Votes),
-        };
+        match max {
+            Some(max) => log!("Membership capped at {} (currently {})", max, self.member_count),
+            None => log!("Membership cap removed"),
+        }
+    }
 
-        log!(
-            "MIGRATION: State migrated. Members: {}, Proposals cleared",
-            new_state.member_count
+    /// Point OutLayer executions at another keymaster secret (owner-only)
+    ///
+    /// Takes effect for key derivations and tallies requested afterwards.
+    /// Members whose keys were derived from the old secret must rotate them.
+    /// Rejected while proposals are active: their ballots were encrypted to keys
+    /// from the old secret and could no longer be decrypted at tally time.
+    pub fn update_secrets_ref(&mut self, secrets_ref: serde_json::Value) {
+        self.assert_owner();
+        assert!(
+            self.active_proposal_ids.is_empty(),
+            "Cannot change secrets_ref while proposals are active"
         );
 
-        new_state
+        self.secrets_ref = validate_secrets_ref(&secrets_ref);
+
+        log!("Secrets ref updated: {}", self.secrets_ref);
     }
 
-    /// Complete join after pre-approval (Private DAO)
+    /// Replace DAO settings (owner-only)
     ///
-    /// After owner adds member, user must call this to derive their key
-    #[payable]
-    pub fn complete_join(&mut self) -> Promise {
-        let user = env::predecessor_account_id();
-        let attached = env::attached_deposit();
+    /// See `DAOConfig` - in particular, never disable `require_pubkey_for_vote`
+    /// in production.
+    pub fn update_config(&mut self, config: DAOConfig) {
+        self.assert_owner();
 
-        // Check if pre-approved
-        if self.members.get(&user).is_none() {
-            env::panic_str("Not pre-approved. Contact DAO owner.");
-        }
+        validate_config(&config);
 
-        // Check if already has pubkey (already completed join)
-        if self.user_pubkeys.get(&user).is_some() {
-            env::panic_str("Already completed join");
-        }
+        self.config = config;
+    }
 
-        // Check deposit for OutLayer execution
-        assert!(
-            attached.as_yoctonear() >= MIN_OUTLAYER_DEPOSIT,
-            "Minimum deposit is {} yoctoNEAR for OutLayer execution",
-            MIN_OUTLAYER_DEPOSIT
-        );
+    /// Grant the Admin role to a member (owner-only)
+    pub fn add_admin(&mut self, account_id: AccountId) {
+        self.assert_owner();
 
-        log!("User {} completing join. Deriving encryption public key", user);
+        if self.members.get(&account_id).is_none() {
+            env::panic_str("Not a member");
+        }
+
+        self.admins.insert(&account_id);
 
-        // Call OutLayer to derive user's public key
-        self.request_key_derivation(user.clone(), attached.as_yoctonear())
+        log!("{} is now an admin", account_id);
     }
 
-    /// Create a new proposal
-    ///
-    /// # Arguments
-    /// * `title` - Proposal title
-    /// * `description` - Proposal description
-    /// * `quorum` - Quorum requirements for passing
-    /// * `deadline` - Optional voting deadline (nanoseconds since epoch). If None, no time limit.
+    /// Revoke the Admin role (owner-only)
     ///
-    /// # Payment
-    /// Requires 0.001 NEAR for storage
-    #[payable]
-    pub fn create_proposal(
-        &mut self,
-        title: String,
-        description: String,
-        quorum: QuorumType,
-        deadline: Option<u64>,
-    ) -> u64 {
-        let creator = env::predecessor_account_id();
+    /// The admin's approvals of vetoes still pending are withdrawn.
+    pub fn remove_admin(&mut self, account_id: AccountId) {
+        self.assert_owner();
 
-        // Only members can create proposals
-        let member_info = self.members.get(&creator)
-            .expect("Only members can create proposals");
+        if !self.internal_revoke_admin(&account_id) {
+            env::panic_str("Not an admin");
+        }
 
-        // Check storage deposit
-        let attached = env::attached_deposit();
-        assert!(
-            attached.as_yoctonear() >= 1_000_000_000_000_000_000_000, // 0.001 NEAR
-            "Minimum deposit is 0.001 NEAR for storage"
-        );
+        log!("{} is no longer an admin", account_id);
+    }
 
-        // Validate deadline is in the future (if provided)
-        if let Some(deadline_ns) = deadline {
-            assert!(
-                deadline_ns > env::block_timestamp(),
-                "Deadline must be in the future"
-            );
+    /// Halt joins and leaves, votes and delegations, proposal creation, edits,
+    /// upgrade uploads and cancellation, tallies and executions (owner-only)
+    ///
+    /// For emergencies such as a discovered bug. Views, admin methods and
+    /// callbacks of OutLayer calls already in flight keep working.
+    pub fn pause(&mut self) {
+        self.assert_owner();
+        assert!(!self.paused, "DAO is already paused");
+
+        self.paused = true;
+        Event::DaoPaused {
+            by: env::predecessor_account_id(),
+            timestamp: env::block_timestamp(),
         }
+        .emit();
+    }
 
-        // Validate creator joined before proposal creation (prevent retroactive voting)
-        // This ensures members can only vote on proposals created AFTER they joined
-        assert!(
-            member_info.joined_at <= env::block_timestamp(),
-            "Invalid member timestamp"
-        );
+    /// Resume normal operation after `pause` (owner-only)
+    pub fn unpause(&mut self) {
+        self.assert_owner();
+        assert!(self.paused, "DAO is not paused");
 
-        let proposal_id = self.next_proposal_id;
-        self.next_proposal_id += 1;
+        self.paused = false;
+        Event::DaoUnpaused {
+            by: env::predecessor_account_id(),
+            timestamp: env::block_timestamp(),
+        }
+        .emit();
+    }
 
-        let proposal = Proposal {
-            id: proposal_id,
-            title,
-            description,
-            creator: creator.clone(),
-            created_at: env::block_timestamp(),
-            deadline,
-            quorum,
-            status: ProposalStatus::Active,
-            tally_result: None,
-        };
+    /// Offer ownership of the DAO to `new_owner` (owner-only)
+    ///
+    /// Nothing changes until `new_owner` calls `accept_ownership`, so a mistyped
+    /// account can't take over. Calling again replaces the pending offer.
+    pub fn transfer_ownership(&mut self, new_owner: AccountId) {
+        self.assert_owner();
+        assert_ne!(new_owner, self.owner, "Already the owner");
 
-        self.proposals.insert(&proposal_id, &proposal);
+        self.pending_owner = Some(new_owner.clone());
+        self.pending_owner_since = env::block_timestamp();
 
-        // Create unique storage key for this proposal's votes
-        let votes_key = format!("v{}", proposal_id);
-        self.votes.insert(&proposal_id, &Vector::new(votes_key.as_bytes()));
+        log!("Ownership transfer to {} pending acceptance", new_owner);
+    }
 
-        log!(
-            "Proposal {} created by {}: '{}'",
-            proposal_id,
-            creator,
-            proposal.title
+    /// Accept a pending ownership transfer (pending owner only)
+    ///
+    /// The previous owner keeps their membership but loses owner rights.
+    pub fn accept_ownership(&mut self) {
+        let caller = env::predecessor_account_id();
+        assert_eq!(
+            self.pending_owner.as_ref(),
+            Some(&caller),
+            "Only the pending owner can accept ownership"
         );
 
-        proposal_id
+        let previous_owner = std::mem::replace(&mut self.owner, caller);
+        self.pending_owner = None;
+        self.pending_owner_since = 0;
+
+        log!("Ownership transferred from {} to {}", previous_owner, self.owner);
     }
 
-    /// Cast a vote on a proposal
-    ///
-    /// # Arguments
-    /// * `proposal_id` - Proposal ID
-    /// * `encrypted_vote` - Encrypted vote ("yes" or "no", encrypted with user's pubkey using ECIES)
-    ///
-    /// # Payment
-    /// Requires 0.002 NEAR for storage
-    ///
-    /// # Returns
-    /// Timestamp (nanoseconds) used for vote hash calculation.
-    /// Vote hash = SHA256(user + timestamp + encrypted_vote)
+    /// Withdraw an ownership offer that wasn't accepted in time (owner-only)
     ///
-    /// # Notes
-    /// - Vote is encrypted client-side using ECIES (secp256k1 + AES-256-GCM)
-    /// - ECIES includes random nonce inside ciphertext (no separate nonce needed)
-    /// - Multiple votes allowed (last real vote counts)
-    /// - User can also send dummy votes for privacy (any ciphertext that doesn't decrypt to "yes"/"no")
-    #[payable]
-    pub fn cast_vote(
-        &mut self,
-        proposal_id: u64,
-        encrypted_vote: String,
-    ) -> u64 {
-        let voter = env::predecessor_account_id();
-        let attached = env::attached_deposit();
-
-        // Only members can vote
-        let member_info = self.members.get(&voter)
-            .expect("Only members can vote");
+    /// Allowed once `DAOConfig::ownership_transfer_timeout_ns` has passed since
+    /// `transfer_ownership`.
+    pub fn cancel_ownership_transfer(&mut self) {
+        self.assert_owner();
+        let pending_owner = self.pending_owner.clone().expect("No pending ownership transfer");
 
-        // Check if user has pubkey (completed join)
+        let elapsed = env::block_timestamp().saturating_sub(self.pending_owner_since);
         assert!(
-            self.user_pubkeys.get(&voter).is_some(),
-            "Complete join first to derive encryption key"
+            elapsed >= self.config.ownership_transfer_timeout_ns,
+            "Ownership transfer can only be cancelled after the timeout"
         );
 
-        // Check storage deposit
-        assert!(
-            attached.as_yoctonear() >= STORAGE_DEPOSIT_PER_VOTE,
-            "Minimum deposit is {} yoctoNEAR for vote storage",
-            STORAGE_DEPOSIT_PER_VOTE
-        );
+        self.pending_owner = None;
+        self.pending_owner_since = 0;
 
-        // Get proposal
-        let proposal = self.proposals.get(&proposal_id)
-            .expect("Proposal not found");
+        log!("Ownership transfer to {} cancelled", pending_owner);
+    }
 
-        // Check member joined BEFORE proposal was created (prevent retroactive voting)
-        // Note: joined_at = 0 means old member from migration (can vote on all proposals)
-        if member_info.joined_at > 0 {
-            assert!(
-                member_info.joined_at < proposal.created_at,
-                "Cannot vote on proposals created before you joined"
-            );
-        }
+    /// Approve a veto of a passed proposal (admins only)
+    ///
+    /// Once `DAOConfig::veto_threshold` distinct admins approve, the proposal
+    /// becomes `Vetoed` with the reason given by the final approval. Vetoed
+    /// proposals are final; a vetoed upgrade's WASM is dropped.
+    pub fn veto_proposal(&mut self, proposal_id: u64, reason: String) {
+        let admin = env::predecessor_account_id();
+        assert!(self.internal_is_admin(&admin), "Only admins can veto proposals");
+
+        let mut proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
 
-        // Check proposal is active
         assert!(
-            proposal.status == ProposalStatus::Active,
-            "Proposal is not active"
+            proposal.status == ProposalStatus::Passed,
+            "Only passed proposals can be vetoed"
         );
 
-        // Check deadline not passed (if deadline is set)
-        if let Some(deadline_ns) = proposal.deadline {
-            assert!(
-                env::block_timestamp() < deadline_ns,
-                "Voting deadline has passed"
-            );
+        let mut approvals = self.veto_approvals.get(&proposal_id).unwrap_or_else(|| {
+            Vector::new(format!("va{}", proposal_id).as_bytes())
+        });
+
+        if approvals.iter().any(|account| account == admin) {
+            env::panic_str("Already approved veto");
         }
 
-        // Create vote with blockchain timestamp
-        let timestamp = env::block_timestamp();
-        let vote = Vote {
-            user: voter.clone(),
-            encrypted_vote,
-            timestamp,
-        };
+        approvals.push(&admin);
+        self.veto_approvals.insert(&proposal_id, &approvals);
 
-        // Add vote to list
-        let mut votes = self.votes.get(&proposal_id).unwrap();
-        votes.push(&vote);
-        self.votes.insert(&proposal_id, &votes);
+        log!(
+            "Veto of proposal {} approved by {} ({}/{}): {}",
+            proposal_id,
+            admin,
+            approvals.len(),
+            self.config.veto_threshold,
+            reason
+        );
 
-        log!("Vote cast by {} on proposal {} at timestamp {}", voter, proposal_id, timestamp);
+        if approvals.len() >= self.config.veto_threshold {
+            let previous_status = std::mem::replace(&mut proposal.status, ProposalStatus::Vetoed { reason });
+            self.proposals.insert(&proposal_id, &proposal);
+            self.internal_index_proposal_status(proposal_id, Some(&previous_status), &proposal.status);
+            if matches!(proposal.proposal_type, ProposalType::UpgradeContract { .. }) {
+                self.internal_drop_upgrade_wasm(proposal_id);
+            }
 
-        // Return timestamp so frontend can compute vote hash immediately
-        // vote_hash = SHA256(user + timestamp + encrypted_vote)
-        timestamp
+            log!("Proposal {} vetoed", proposal_id);
+        }
     }
 
-    /// Finalize a proposal and tally votes in TEE
-    ///
-    /// # Arguments
-    /// * `proposal_id` - Proposal ID
-    ///
-    /// # Payment
-    /// Requires 0.01 NEAR for OutLayer execution
-    ///
-    /// # Process
-    /// 1. User pays for OutLayer execution
-    /// 2. Contract sends all encrypted votes to OutLayer
-    /// 3. OutLayer WASM decrypts votes in TEE
-    /// 4. OutLayer returns tally (yes_count, no_count, total)
-    /// 5. Callback updates proposal status (Passed/Rejected)
+    /// Cancel an active proposal (creator or owner only)
     ///
-    /// # Timing
-    /// - Can be called **any time** after first vote is cast
-    /// - Does NOT require waiting for deadline
-    /// - Deadline only blocks NEW votes, not finalization
-    /// - This allows early finalization if quorum is reached
-    #[payable]
-    pub fn finalize_proposal(&mut self, proposal_id: u64) -> Promise {
+    /// Cancelled proposals are final and can't be voted on or finalized.
+    /// Votes already cast are kept. A tally prepaid by the creator
+    /// (`Proposal::creator_deposit`) is refunded to them.
+    pub fn cancel_proposal(&mut self, proposal_id: u64) {
+        self.assert_not_paused();
         let caller = env::predecessor_account_id();
-        let attached = env::attached_deposit();
-
-        // Check deposit for OutLayer execution
-        assert!(
-            attached.as_yoctonear() >= MIN_OUTLAYER_DEPOSIT,
-            "Minimum deposit is {} yoctoNEAR for OutLayer execution",
-            MIN_OUTLAYER_DEPOSIT
-        );
-
-        // Get proposal
-        let proposal = self.proposals.get(&proposal_id)
+        let mut proposal = self.proposals.get(&proposal_id)
             .expect("Proposal not found");
 
-        // Check proposal is active
         assert!(
-            proposal.status == ProposalStatus::Active,
-            "Proposal is not active"
+            caller == proposal.creator || caller == self.owner,
+            "Only the proposal creator or owner can cancel it"
         );
+        assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
 
-        // Get all votes
-        let votes = self.votes.get(&proposal_id).unwrap();
-        let votes_vec: Vec<Vote> = votes.iter().collect();
+        let vote_count = self.get_vote_count(proposal_id);
+        if vote_count > 0 {
+            log!("Warning: proposal {} cancelled after {} votes were cast", proposal_id, vote_count);
+        }
 
-        // Ensure at least one vote exists
-        assert!(
-            !votes_vec.is_empty(),
-            "No votes to tally. Wait for at least one vote."
-        );
+        proposal.status = ProposalStatus::Cancelled;
+        let refund = std::mem::replace(&mut proposal.creator_deposit, NearToken::from_yoctonear(0));
+        self.held_deposits -= refund.as_yoctonear();
+        self.proposals.insert(&proposal_id, &proposal);
+        self.internal_index_proposal_status(proposal_id, Some(&ProposalStatus::Active), &proposal.status);
+        self.partial_tallies.remove(&proposal_id);
 
-        log!(
-            "Finalizing proposal {} with {} votes. Tallying via OutLayer TEE",
-            proposal_id,
-            votes_vec.len()
-        );
+        self.internal_release_active_proposal(&proposal.creator);
+        if matches!(proposal.proposal_type, ProposalType::UpgradeContract { .. }) {
+            self.internal_drop_upgrade_wasm(proposal_id);
+        }
+        if !refund.is_zero() {
+            Promise::new(proposal.creator.clone()).transfer(refund);
+        }
 
-        // Call OutLayer to tally votes in TEE
-        self.request_vote_tallying(proposal_id, votes_vec, attached.as_yoctonear(), caller)
+        log!("Proposal {} cancelled by {}", proposal_id, caller);
     }
 
-    // ========== Internal methods ==========
+    /// Leave the DAO (self-removal)
+    ///
+    /// Any member can leave the DAO at any time.
+    ///
+    /// Removes:
+    /// - Member status
+    /// - Public key (if exists)
+    /// - Their delegation and delegations to them (those delegators vote for themselves again)
+    /// - Admin role and pending veto approvals
+    /// - Does NOT remove votes (preserves historical data)
+    ///
+    /// The storage deposit paid by `join_dao` is refunded (see `member_deposits`).
+    pub fn leave_dao(&mut self) {
+        self.assert_not_paused();
+        let user = env::predecessor_account_id();
 
-    /// Request key derivation from OutLayer
-    fn request_key_derivation(&self, user: AccountId, attached_deposit: Balance) -> Promise {
-        let code_source = serde_json::json!({
-            "repo": "https://github.com/zavodil/private-dao-ark",
-            "commit": "main",
-            "build_target": "wasm32-wasip1"
-        });
+        // Check if member exists
+        if self.members.get(&user).is_none() {
+            env::panic_str("Not a member");
+        }
 
-        let resource_limits = serde_json::json!({
-            "max_instructions": 1000000000u64,
-            "max_memory_mb": 128u32,
-            "max_execution_seconds": 30u64
-        });
+        // Remove from members
+        self.members.remove(&user);
+        self.member_index.remove(&user);
+        self.member_count -= 1;
 
-        let input_data = serde_json::json!({
-            "action": "derive_pubkey",
-            "dao_account": env::current_account_id(),
-            "user_account": user
-        });
+        // Remove pubkey if exists
+        if self.user_pubkeys.remove(&user).is_some() {
+            self.voting_member_count -= 1;
+        }
 
-        // Call OutLayer with secrets_ref (master secret from keymaster)
-        let secrets_ref = serde_json::json!({
-            "profile": "default",
-            "account_id": "zavodil2.testnet"
-        });
+        self.internal_clear_delegation(&user);
+        self.internal_clear_incoming_delegations(&user);
+        self.internal_revoke_admin(&user);
+        self.internal_refund_member_deposit(&user);
 
-        // Call OutLayer
-        ext_outlayer::ext(OUTLAYER_CONTRACT_ID.parse().unwrap())
+        log!("User {} left the DAO", user);
+        Event::MemberLeft { account_id: user, removed_by: None }.emit();
+    }
+
+    /// Delegate voting right to another member
+    ///
+    /// Replaces any existing delegation. While it is active the caller can't
+    /// vote; the tally credits them with the ballot of the member at the end
+    /// of their delegation chain.
+    ///
+    /// # Panics
+    /// - If `to` already delegates back to the caller (directly or through
+    ///   a chain), or the caller's chain would be longer than `MAX_DELEGATION_DEPTH`
+    /// - While an active proposal is closing (see `internal_assert_delegations_unfrozen`)
+    pub fn delegate_vote(&mut self, to: AccountId) {
+        self.assert_not_paused();
+        let user = env::predecessor_account_id();
+        let delegate = to;
+
+        assert!(self.members.get(&user).is_some(), "Not a member");
+        self.internal_assert_delegations_unfrozen();
+        assert!(self.members.get(&delegate).is_some(), "Delegate is not a member");
+        assert_ne!(user, delegate, "Cannot delegate to yourself");
+
+        if self.delegations.get(&user).as_ref() == Some(&delegate) {
+            return;
+        }
+
+        // Walk the chain the vote would flow through
+        let mut next = delegate.clone();
+        for _ in 1..MAX_DELEGATION_DEPTH {
+            match self.delegations.get(&next) {
+                Some(further) => {
+                    assert_ne!(further, user, "Circular delegation: {} already delegates to you", next);
+                    next = further;
+                }
+                None => break,
+            }
+        }
+        assert!(
+            self.delegations.get(&next).is_none(),
+            "Delegation chain would be longer than {} hops",
+            MAX_DELEGATION_DEPTH
+        );
+
+        self.internal_clear_delegation(&user);
+        self.delegations.insert(&user, &delegate);
+        let received = self.delegations_received.get(&delegate).unwrap_or(0);
+        self.delegations_received.insert(&delegate, &(received + 1));
+        let mut delegators = self.delegators.get(&delegate).unwrap_or_default();
+        delegators.push(user.clone());
+        self.delegators.insert(&delegate, &delegators);
+
+        log!("{} delegated to {}", user, delegate);
+    }
+
+    /// Revoke the caller's delegation (no-op if none)
+    ///
+    /// Not allowed while an active proposal is closing, like `delegate_vote`.
+    pub fn revoke_delegation(&mut self) {
+        self.assert_not_paused();
+        let user = env::predecessor_account_id();
+        if self.delegations.get(&user).is_none() {
+            return;
+        }
+        self.internal_assert_delegations_unfrozen();
+
+        if self.internal_clear_delegation(&user) {
+            log!("{} revoked their delegation", user);
+        }
+    }
+
+    /// Remove member (owner-only)
+    ///
+    /// **FOR TESTING ONLY**: Removes a member from the DAO.
+    /// In production, this should require governance vote or more strict conditions.
+    ///
+    /// Removes:
+    /// - Member status
+    /// - Public key (if exists)
+    /// - Their delegation and delegations to them (those delegators vote for themselves again)
+    /// - Admin role and pending veto approvals
+    /// - Does NOT remove votes (preserves historical data)
+    pub fn remove_member(&mut self, account_id: AccountId) {
+        self.assert_owner();
+
+        // Check if member exists
+        if self.members.get(&account_id).is_none() {
+            env::panic_str("Not a member");
+        }
+
+        // Remove from members
+        self.members.remove(&account_id);
+        self.member_index.remove(&account_id);
+        self.member_count -= 1;
+
+        // Remove pubkey if exists
+        if self.user_pubkeys.remove(&account_id).is_some() {
+            self.voting_member_count -= 1;
+        }
+
+        self.internal_clear_delegation(&account_id);
+        self.internal_clear_incoming_delegations(&account_id);
+        self.internal_revoke_admin(&account_id);
+        self.internal_refund_member_deposit(&account_id);
+
+        log!("TESTING: Owner removed {} from DAO", account_id);
+        Event::MemberLeft {
+            account_id,
+            removed_by: Some(env::predecessor_account_id()),
+        }
+        .emit();
+    }
+
+    /// Manually add member with timestamp (owner-only, for migration)
+    ///
+    /// This is a migration helper to add members with joined_at = 0 after deploying V2.
+    /// Use this to restore members who were in the old contract.
+    ///
+    /// joined_at = 0 means they can vote on all proposals (old and new)
+    pub fn migrate_add_member(&mut self, account_id: AccountId, pubkey: Option<String>) {
+        self.assert_owner();
+
+        // Add member with joined_at = 0 (can vote on everything)
+        self.members.insert(&account_id, &MemberInfo {
+            joined_at: 0,
+            key_rotated_at: None,
+            last_voted_at: None,
+            proposals_voted: 0,
+            key_epoch: 0,
+        });
+        self.member_index.insert(&account_id);
+
+        // Add pubkey if provided
+        if let Some(pk) = pubkey {
+            if self.user_pubkeys.insert(&account_id, &pk).is_none() {
+                self.voting_member_count += 1;
+            }
+        }
+
+        self.member_count += 1;
+
+        log!(
+            "Migration: Added member {} with joined_at=0 (can vote on all proposals)",
+            account_id
+        );
+    }
+
+    /// Add existing members to the `get_members` index (owner-only, for migration)
+    ///
+    /// Members stored before the index existed can't be enumerated on-chain, so the
+    /// owner passes their accounts (in batches if needed). Non-members and accounts
+    /// already indexed are skipped. Returns the number of members added.
+    pub fn migrate_index_members(&mut self, account_ids: Vec<AccountId>) -> u64 {
+        self.assert_owner();
+
+        let mut added = 0;
+        for account_id in account_ids {
+            if self.internal_index_member(&account_id) {
+                added += 1;
+            }
+        }
+
+        log!("Migration: Indexed {} members ({} total)", added, self.member_index.len());
+        added
+    }
+
+    /// Reset state (TESTING ONLY - clears everything)
+    ///
+    /// **FOR TESTING ONLY**: Recreates all storage collections with new format.
+    /// Use this after contract upgrade when state format changed.
+    ///
+    /// WARNING: This will clear all proposals and votes!
+    /// Members, pubkeys and proposal templates are preserved.
+    /// Proposal IDs keep counting, so leftovers of the cleared proposals are never read.
+    ///
+    /// Also converts the initial release's state (`LegacyPrivateDAO`); its members
+    /// must then be indexed with `migrate_index_members`.
+    ///
+    /// NOTE: Must be called by contract account itself (use near-cli with --accountId same as contract)
+    #[init(ignore_state)]
+    pub fn reset_state() -> Self {
+        // Get old state
+        let raw_state = env::storage_read(b"STATE").expect("Failed to read state");
+        let old_state = PrivateDAO::try_from_slice(&raw_state)
+            .or_else(|_| LegacyPrivateDAO::try_from_slice(&raw_state).map(PrivateDAO::from))
+            .unwrap_or_else(|_| env::panic_str("Failed to read state"));
+
+        // Verify caller is owner
+        Self::assert_caller_is(&old_state.owner);
+
+        // Members stored in an older layout need the current one
+        migrate_member_infos(&old_state.member_index);
+
+        // Clear the proposals along with their tag lists (prefixes get reused),
+        // creator counts and tallies still in flight
+        let mut proposals = old_state.proposals;
+        let mut tag_index = old_state.tag_index;
+        let mut active_proposal_counts = old_state.active_proposal_counts;
+        let mut tallies_in_flight = old_state.tallies_in_flight;
+        let mut partial_tallies = old_state.partial_tallies;
+        let mut dao_paid_tallies = old_state.dao_paid_tallies;
+        let mut held_deposits = old_state.held_deposits;
+        for (proposal_id, proposal) in proposals.iter() {
+            for tag in &proposal.tags {
+                if let Some(mut proposal_ids) = tag_index.remove(tag) {
+                    proposal_ids.clear();
+                }
+            }
+            active_proposal_counts.remove(&proposal.creator);
+            tallies_in_flight.remove(&proposal_id);
+            partial_tallies.remove(&proposal_id);
+            dao_paid_tallies.remove(&proposal_id);
+            // Deposits of the cleared proposals and their upgrade WASM are no longer tracked
+            held_deposits -= proposal.creator_deposit.as_yoctonear();
+            if let Some(upload) = old_state.upgrade_wasm_uploads.get(&proposal_id) {
+                held_deposits -= upload.deposit.as_yoctonear();
+            }
+        }
+        proposals.clear();
+        let mut active_proposal_ids = old_state.active_proposal_ids;
+        let mut passed_proposal_ids = old_state.passed_proposal_ids;
+        let mut rejected_proposal_ids = old_state.rejected_proposal_ids;
+        active_proposal_ids.clear();
+        passed_proposal_ids.clear();
+        rejected_proposal_ids.clear();
+
+        // Create new state - keep members/pubkeys as-is, recreate proposals/votes
+        let new_state = Self {
+            owner: old_state.owner.clone(),
+            name: old_state.name.clone(),
+            membership_mode: old_state.membership_mode.clone(),
+            members: LookupMap::new(StorageKey::Members),
+            // Members and pubkeys are kept, so are their counts
+            member_count: old_state.member_count,
+            // Same prefix as `members`, whose entries stay in storage
+            member_index: old_state.member_index,
+            user_pubkeys: LookupMap::new(StorageKey::UserPubKeys),
+            user_key_algorithms: LookupMap::new(StorageKey::UserKeyAlgorithms),
+            proposals,
+            next_proposal_id: old_state.next_proposal_id,
+            votes: LookupMap::new(StorageKey::Votes),
+            proposal_templates: old_state.proposal_templates,
+            deployed_at: old_state.deployed_at,
+            last_upgraded_at: env::block_timestamp(),
+            pending_upgrade_wasm: LookupMap::new(StorageKey::PendingUpgradeWasm),
+            whitelist_mode: old_state.whitelist_mode,
+            whitelisted: LookupSet::new(StorageKey::Whitelisted),
+            blacklisted: LookupMap::new(StorageKey::Blacklisted),
+            config: old_state.config,
+            admins: old_state.admins,
+            veto_approvals: LookupMap::new(StorageKey::VetoApprovals),
+            proposal_pubkeys: LookupMap::new(StorageKey::ProposalPubKeys),
+            block_height_of_last_join_attempt: LookupMap::new(StorageKey::LastJoinAttempt),
+            pending_join_type: LookupMap::new(StorageKey::PendingJoinType),
+            // Keeps deposits of stuck joins refundable across migrations
+            pending_key_derivations: old_state.pending_key_derivations,
+            proposal_period_start: 0,
+            proposals_in_period: 0,
+            active_proposal_counts,
+            proposal_vote_milestones: LookupMap::new(StorageKey::ProposalVoteMilestones),
+            treasury_balance: old_state.treasury_balance,
+            protocol_fee_collected: old_state.protocol_fee_collected,
+            delegations: old_state.delegations,
+            delegations_received: old_state.delegations_received,
+            outlayer_executions: old_state.outlayer_executions,
+            outlayer_execution_count: old_state.outlayer_execution_count,
+            outlayer_executions_completed: old_state.outlayer_executions_completed,
+            outlayer_executions_succeeded: old_state.outlayer_executions_succeeded,
+            outlayer_contract: old_state.outlayer_contract,
+            secrets_ref: old_state.secrets_ref,
+            pending_owner: old_state.pending_owner,
+            pending_owner_since: old_state.pending_owner_since,
+            // Callbacks of in-flight executions may still arrive
+            pending_deposits: old_state.pending_deposits,
+            paused: old_state.paused,
+            partial_tallies,
+            ballot_indices: LookupMap::new(StorageKey::BallotIndices),
+            max_members: old_state.max_members,
+            pending_invitations: old_state.pending_invitations,
+            active_proposal_ids,
+            passed_proposal_ids,
+            rejected_proposal_ids,
+            member_deposits: old_state.member_deposits,
+            tag_index,
+            // Cleared by the callbacks of derivations still in flight (joins and rotations are kept)
+            key_derivations_in_flight: old_state.key_derivations_in_flight,
+            tallies_in_flight,
+            // Counts ballots of the proposals cleared above
+            total_votes_cast: 0,
+            dao_paid_tallies,
+            unreconciled_outlayer_spend: old_state.unreconciled_outlayer_spend,
+            upgrade_wasm_uploads: LookupMap::new(StorageKey::UpgradeWasmUploads),
+            voting_member_count: old_state.voting_member_count,
+            delegators: old_state.delegators,
+            pending_key_rotations: old_state.pending_key_rotations,
+            held_deposits,
+        };
+
+        log!(
+            "MIGRATION: State migrated. Members: {}, Proposals cleared",
+            new_state.member_count
+        );
+
+        new_state
+    }
+
+    /// Complete join after an invitation or pre-approval (Private DAO)
+    ///
+    /// Invitees (`create_invitation`) must call this before the invitation
+    /// expires; the invitation is consumed (re-invite if key derivation fails)
+    /// and they become a member in the key derivation callback. After owner adds member, user must call this
+    /// to derive their key.
+    /// The owner is added as a member in `new()` without a key, so the owner
+    /// must also call this before voting (not restricted to non-owners).
+    #[payable]
+    pub fn complete_join(&mut self) -> Promise {
+        self.assert_not_paused();
+        let user = env::predecessor_account_id();
+        let attached = env::attached_deposit();
+
+        // Invited, or pre-approved by `add_member`
+        let invitation = self.pending_invitations.get(&user);
+        if let Some(invitation) = &invitation {
+            assert!(env::block_timestamp() < invitation.expires_at, "Invitation expired");
+            self.assert_below_member_cap();
+        } else if self.members.get(&user).is_none() {
+            env::panic_str("Not pre-approved. Contact DAO owner.");
+        }
+
+        // Check if already has pubkey (already completed join)
+        if self.user_pubkeys.get(&user).is_some() {
+            env::panic_str("Already completed join");
+        }
+
+        self.assert_not_blacklisted(&user);
+
+        // Check deposit for OutLayer execution
+        assert!(
+            attached >= self.config.min_outlayer_deposit,
+            "Minimum deposit is {} yoctoNEAR for OutLayer execution",
+            self.config.min_outlayer_deposit.as_yoctonear()
+        );
+
+        self.internal_record_join_attempt(&user);
+        if invitation.is_some() {
+            self.pending_invitations.remove(&user);
+        }
+        self.pending_join_type.insert(&user, &JoinType { is_new: invitation.is_some() });
+        // The whole deposit pays for OutLayer: nothing for the contract to refund
+        self.internal_record_pending_key_derivation(&user, 0);
+
+        log!("User {} completing join. Deriving encryption public key", user);
+
+        // Call OutLayer to derive user's public key (pre-approved members keep their epoch)
+        let key_epoch = self.members.get(&user).map_or(0, |member_info| member_info.key_epoch);
+        self.request_key_derivation(user.clone(), attached.as_yoctonear(), key_epoch)
+    }
+
+    /// Re-derive the caller's encryption key (e.g. after a suspected compromise)
+    ///
+    /// The key is derived at the next `MemberInfo::key_epoch`, so the new pubkey is
+    /// unrelated to the old one. The callback replaces the stored pubkey and sets
+    /// `key_rotated_at` and `key_epoch`. Ballots already cast with the old key no
+    /// longer decrypt and should be cast again.
+    ///
+    /// # Payment
+    /// Requires 0.01 NEAR for OutLayer execution
+    #[payable]
+    pub fn rotate_key(&mut self) -> Promise {
+        self.assert_not_paused();
+        let user = env::predecessor_account_id();
+        let attached = env::attached_deposit();
+
+        let member_info = self.members.get(&user).expect("Only members can rotate their key");
+        assert!(
+            self.user_pubkeys.get(&user).is_some(),
+            "No key to rotate, call complete_join first"
+        );
+        self.assert_not_blacklisted(&user);
+
+        assert!(
+            attached >= self.config.min_outlayer_deposit,
+            "Minimum deposit is {} yoctoNEAR for OutLayer execution",
+            self.config.min_outlayer_deposit.as_yoctonear()
+        );
+
+        log!("User {} rotating encryption key", user);
+
+        self.pending_key_rotations.insert(&user);
+        self.request_key_derivation(user, attached.as_yoctonear(), member_info.key_epoch + 1)
+    }
+
+    /// Derive a proposal-scoped encryption key (when `use_proposal_scoped_keys` is on)
+    ///
+    /// Votes on `proposal_id` must then be encrypted with this key instead of
+    /// the DAO-wide key from `get_user_pubkey`.
+    ///
+    /// # Payment
+    /// Requires 0.01 NEAR for OutLayer execution
+    #[payable]
+    pub fn request_proposal_key(&mut self, proposal_id: u64) -> Promise {
+        self.assert_not_paused();
+        let user = env::predecessor_account_id();
+        let attached = env::attached_deposit();
+
+        assert!(
+            self.config.use_proposal_scoped_keys,
+            "Proposal-scoped keys are disabled"
+        );
+
+        if self.members.get(&user).is_none() {
+            env::panic_str("Only members can request proposal keys");
+        }
+
+        let proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+        assert!(
+            proposal.status == ProposalStatus::Active,
+            "Proposal is not active"
+        );
+
+        if self.proposal_pubkeys.get(&account_proposal_key(&user, proposal_id)).is_some() {
+            env::panic_str("Proposal key already derived");
+        }
+
+        assert!(
+            attached >= self.config.min_outlayer_deposit,
+            "Minimum deposit is {} yoctoNEAR for OutLayer execution",
+            self.config.min_outlayer_deposit.as_yoctonear()
+        );
+
+        log!("User {} requesting key for proposal {}", user, proposal_id);
+
+        self.request_proposal_key_derivation(user, proposal_id, attached.as_yoctonear())
+    }
+
+    /// Create a new proposal
+    ///
+    /// # Arguments
+    /// * `title` - Proposal title
+    /// * `description` - Proposal description
+    /// * `quorum` - Quorum requirements for passing
+    /// * `deadline` - Optional voting deadline (nanoseconds since epoch). If None, no time limit.
+    /// * `proposal_type` - Optional proposal category (defaults to Governance)
+    /// * `tags` - Optional tags (up to 5, 1-32 ASCII characters each, stored lowercase)
+    ///
+    /// # Payment
+    /// Requires 0.001 NEAR for storage
+    #[payable]
+    pub fn create_proposal(
+        &mut self,
+        title: String,
+        description: String,
+        quorum: QuorumType,
+        deadline: Option<u64>,
+        proposal_type: Option<ProposalType>,
+        tags: Option<Vec<String>>,
+    ) -> u64 {
+        self.internal_create_proposal(
+            title,
+            description,
+            quorum,
+            deadline,
+            proposal_type.unwrap_or(ProposalType::Governance),
+            tags.unwrap_or_default(),
+        )
+    }
+
+    /// Create a new proposal from a stored template
+    ///
+    /// Description, quorum, deadline, type and tags are taken from the template.
+    /// The deadline is computed as `now + deadline_offset_ns`.
+    ///
+    /// # Payment
+    /// Requires 0.001 NEAR for storage (same as `create_proposal`)
+    #[payable]
+    pub fn create_proposal_from_template(&mut self, template_name: String, title: String) -> u64 {
+        let template = self.proposal_templates.get(&template_name)
+            .expect("Template not found");
+
+        let deadline = template.deadline_offset_ns.map(|offset| {
+            env::block_timestamp()
+                .checked_add(offset)
+                .unwrap_or_else(|| env::panic_str("Template deadline_offset_ns is too large"))
+        });
+
+        self.internal_create_proposal(
+            title,
+            template.description_template,
+            template.quorum,
+            deadline,
+            template.proposal_type,
+            template.tags,
+        )
+    }
+
+    /// Create a proposal where members choose one of `vote_options`
+    ///
+    /// Members encrypt one of the options instead of "yes"/"no"; anything else
+    /// counts as a dummy. The proposal passes if one option has strictly more
+    /// votes than every other (plurality), otherwise it is rejected.
+    /// Otherwise works like `create_proposal` (Governance type).
+    ///
+    /// # Payment
+    /// Requires 0.001 NEAR for storage (same as `create_proposal`)
+    #[payable]
+    pub fn create_multi_option_proposal(
+        &mut self,
+        title: String,
+        description: String,
+        quorum: QuorumType,
+        deadline: Option<u64>,
+        vote_options: Vec<String>,
+        tags: Option<Vec<String>>,
+    ) -> u64 {
+        validate_vote_options(&vote_options);
+
+        let proposal_id = self.internal_create_proposal(
+            title,
+            description,
+            quorum,
+            deadline,
+            ProposalType::Governance,
+            tags.unwrap_or_default(),
+        );
+
+        let mut proposal = self.proposals.get(&proposal_id).unwrap();
+        proposal.vote_options = Some(vote_options);
+        self.proposals.insert(&proposal_id, &proposal);
+
+        proposal_id
+    }
+
+    /// Create a proposal that makes a cross-contract call once it passes
+    ///
+    /// `execution_payload` is a JSON `ExecutionPayload`:
+    /// `{ "receiver_id": "...", "method_name": "...", "args": "...", "deposit": "..." }`
+    /// (`deposit` in yoctoNEAR). After the proposal passes anyone can run it
+    /// with `execute_proposal`. Otherwise works like `create_proposal` (Governance type).
+    ///
+    /// # Payment
+    /// Requires 0.001 NEAR for storage (same as `create_proposal`)
+    #[payable]
+    pub fn create_executable_proposal(
+        &mut self,
+        title: String,
+        description: String,
+        quorum: QuorumType,
+        deadline: Option<u64>,
+        execution_payload: String,
+        tags: Option<Vec<String>>,
+    ) -> u64 {
+        if let Err(e) = serde_json::from_str::<ExecutionPayload>(&execution_payload) {
+            env::panic_str(&format!("Invalid execution payload: {}", e));
+        }
+
+        let proposal_id = self.internal_create_proposal(
+            title,
+            description,
+            quorum,
+            deadline,
+            ProposalType::Governance,
+            tags.unwrap_or_default(),
+        );
+
+        let mut proposal = self.proposals.get(&proposal_id).unwrap();
+        proposal.execution_payload = Some(execution_payload);
+        self.proposals.insert(&proposal_id, &proposal);
+
+        proposal_id
+    }
+
+    /// Run the `execution_payload` of a passed proposal (anyone can call)
+    ///
+    /// Allowed `DAOConfig::execution_delay_ns` after the proposal passed, and
+    /// not while a veto is gathering approvals. The proposal becomes `Executed`
+    /// before the call is made, so it can only run once, even if the called
+    /// method fails. The attached deposit of the call is paid from
+    /// `treasury_balance` and returned to it if the call fails.
+    pub fn execute_proposal(&mut self, proposal_id: u64) -> Promise {
+        self.assert_not_paused();
+        let mut proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+        self.internal_assert_executable(&proposal);
+
+        let payload: ExecutionPayload = serde_json::from_str(
+            proposal.execution_payload.as_deref().expect("Proposal has no execution payload"),
+        )
+        .expect("Invalid execution payload");
+
+        assert!(
+            self.treasury_balance >= payload.deposit.as_yoctonear(),
+            "Treasury balance is too low for the execution deposit (need {})",
+            payload.deposit.exact_amount_display()
+        );
+        self.treasury_balance -= payload.deposit.as_yoctonear();
+
+        proposal.status = ProposalStatus::Executed;
+        self.proposals.insert(&proposal_id, &proposal);
+        self.internal_index_proposal_status(proposal_id, Some(&ProposalStatus::Passed), &proposal.status);
+
+        log!(
+            "Executing proposal {}: {}.{} with {}",
+            proposal_id,
+            payload.receiver_id,
+            payload.method_name,
+            payload.deposit.exact_amount_display()
+        );
+
+        Promise::new(payload.receiver_id)
+            .function_call_weight(
+                payload.method_name,
+                payload.args.into_bytes(),
+                payload.deposit,
+                Gas::from_gas(0),
+                GasWeight(1),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(CALLBACK_GAS)
+                    .on_proposal_executed(proposal_id, payload.deposit),
+            )
+    }
+
+    /// Callback after the call made by `execute_proposal`
+    ///
+    /// Returns the deposit to `treasury_balance` if the call failed (NEAR
+    /// refunds it to this contract). The proposal stays `Executed`.
+    #[private]
+    pub fn on_proposal_executed(&mut self, proposal_id: u64, deposit: NearToken) {
+        if matches!(env::promise_result(0), PromiseResult::Failed) {
+            self.treasury_balance += deposit.as_yoctonear();
+            log!(
+                "Execution of proposal {} failed, {} returned to the treasury",
+                proposal_id,
+                deposit.exact_amount_display()
+            );
+        } else {
+            log!("Proposal {} executed", proposal_id);
+        }
+    }
+
+    /// Deploy the uploaded WASM of a passed UpgradeContract proposal (anyone can call)
+    ///
+    /// Same timing rules as `execute_proposal`. Deploying is charged per byte:
+    /// attach enough gas for the WASM size. The uploader's storage deposit is
+    /// returned.
+    pub fn execute_upgrade(&mut self, proposal_id: u64) -> Promise {
+        self.assert_not_paused();
+        let mut proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+        assert!(
+            matches!(proposal.proposal_type, ProposalType::UpgradeContract { .. }),
+            "Not an UpgradeContract proposal"
+        );
+        self.internal_assert_executable(&proposal);
+        assert!(
+            self.pending_upgrade_wasm.contains_key(&proposal_id),
+            "No upgrade WASM was uploaded for this proposal"
+        );
+
+        proposal.status = ProposalStatus::Executed;
+        self.proposals.insert(&proposal_id, &proposal);
+        self.internal_index_proposal_status(proposal_id, Some(&ProposalStatus::Passed), &proposal.status);
+
+        let wasm = self.internal_drop_upgrade_wasm(proposal_id).unwrap();
+        log!("Deploying contract upgrade from proposal {} ({} bytes)", proposal_id, wasm.len());
+        Promise::new(env::current_account_id()).deploy_contract(wasm)
+    }
+
+    /// Replace an active proposal's description (creator-only)
+    ///
+    /// Only allowed before any vote is cast, unless
+    /// `DAOConfig::allow_proposal_update_after_votes` is on. Edits made after
+    /// voting started get a notice appended so voters can see the text changed.
+    pub fn update_proposal_description(&mut self, proposal_id: u64, description: String) {
+        self.assert_not_paused();
+        let user = env::predecessor_account_id();
+        let mut proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+
+        assert_eq!(proposal.creator, user, "Only the proposal creator can update it");
+        assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
+        validate_proposal_text(&proposal.title, &description, &self.config);
+
+        let vote_count = self.get_vote_count(proposal_id);
+        proposal.description = if vote_count == 0 {
+            description
+        } else {
+            assert!(
+                self.config.allow_proposal_update_after_votes,
+                "Cannot update a proposal after votes were cast"
+            );
+            format!(
+                "{}\n\n[EDITED after vote #{} by {} at {}]",
+                description,
+                vote_count,
+                user,
+                env::block_timestamp()
+            )
+        };
+        proposal.amended_at = Some(env::block_timestamp());
+        self.proposals.insert(&proposal_id, &proposal);
+
+        log!("Proposal {} description updated by {}", proposal_id, user);
+    }
+
+    /// Fix an active proposal's title and/or description (creator-only)
+    ///
+    /// Only allowed before any vote is cast, so nobody voted on other wording.
+    /// Fields left as None are kept.
+    pub fn amend_proposal(&mut self, proposal_id: u64, title: Option<String>, description: Option<String>) {
+        self.assert_not_paused();
+        let user = env::predecessor_account_id();
+        let mut proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+
+        assert_eq!(proposal.creator, user, "Only the proposal creator can amend it");
+        assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
+        assert!(title.is_some() || description.is_some(), "Nothing to amend");
+        assert_eq!(self.get_vote_count(proposal_id), 0, "Cannot amend a proposal after votes were cast");
+
+        let title_changed = title.is_some();
+        let description_changed = description.is_some();
+        if let Some(title) = title {
+            proposal.title = title;
+        }
+        if let Some(description) = description {
+            proposal.description = description;
+        }
+        validate_proposal_text(&proposal.title, &proposal.description, &self.config);
+
+        let amended_at = env::block_timestamp();
+        proposal.amended_at = Some(amended_at);
+        self.proposals.insert(&proposal_id, &proposal);
+
+        log!("Proposal {} amended by {}", proposal_id, user);
+        Event::ProposalAmended {
+            proposal_id,
+            by: user,
+            title_changed,
+            description_changed,
+            amended_at,
+        }
+        .emit();
+    }
+
+    /// Add or replace a proposal template (owner-only)
+    pub fn add_proposal_template(&mut self, template: ProposalTemplate) {
+        self.assert_owner();
+
+        assert!(!template.name.is_empty(), "Template name cannot be empty");
+
+        self.proposal_templates.insert(&template.name, &template);
+
+        log!("Proposal template '{}' saved", template.name);
+    }
+
+    /// Remove a proposal template (owner-only)
+    pub fn remove_proposal_template(&mut self, name: String) {
+        self.assert_owner();
+
+        if self.proposal_templates.remove(&name).is_none() {
+            env::panic_str("Template not found");
+        }
+
+        log!("Proposal template '{}' removed", name);
+    }
+
+    /// Allow a proposal category tag (admin-only)
+    ///
+    /// Once any category exists, proposals may only use listed tags.
+    /// Stored lowercase, like tags.
+    pub fn add_proposal_category(&mut self, category: String) {
+        assert!(
+            self.internal_is_admin(&env::predecessor_account_id()),
+            "Only admins can manage proposal categories"
+        );
+        assert!(!category.is_empty(), "Category cannot be empty");
+        let category = category.to_ascii_lowercase();
+
+        if !self.config.proposal_categories.contains(&category) {
+            self.config.proposal_categories.push(category.clone());
+        }
+
+        log!("Proposal category '{}' added", category);
+    }
+
+    /// Remove a proposal category (admin-only)
+    ///
+    /// Existing proposals keep the tag. Removing the last category allows any tag again.
+    pub fn remove_proposal_category(&mut self, category: String) {
+        assert!(
+            self.internal_is_admin(&env::predecessor_account_id()),
+            "Only admins can manage proposal categories"
+        );
+
+        let category = category.to_ascii_lowercase();
+        let before = self.config.proposal_categories.len();
+        self.config.proposal_categories.retain(|c| *c != category);
+        if self.config.proposal_categories.len() == before {
+            env::panic_str("Category not found");
+        }
+
+        log!("Proposal category '{}' removed", category);
+    }
+
+    /// Refund the deposit of a join whose key derivation never completed (admin-only)
+    ///
+    /// For joins whose callback never ran (failed callbacks refund automatically).
+    /// Only call once the join is clearly stuck (see `get_incomplete_joins`):
+    /// a callback that still succeeds afterwards completes the join without a deposit.
+    pub fn admin_refund_incomplete_join(&mut self, account_id: AccountId) {
+        assert!(
+            self.internal_is_admin(&env::predecessor_account_id()),
+            "Only admins can refund incomplete joins"
+        );
+
+        let pending = self
+            .pending_key_derivations
+            .remove(&account_id)
+            .unwrap_or_else(|| env::panic_str("No incomplete join for this account"));
+        self.held_deposits -= pending.deposit_paid.as_yoctonear();
+        self.pending_join_type.remove(&account_id);
+        self.block_height_of_last_join_attempt.remove(&account_id);
+        self.key_derivations_in_flight.remove(&account_id);
+
+        if !pending.deposit_paid.is_zero() {
+            Promise::new(account_id.clone()).transfer(pending.deposit_paid);
+        }
+
+        log!("Incomplete join of {} refunded: {} yoctoNEAR", account_id, pending.deposit_paid.as_yoctonear());
+    }
+
+    /// Cast a vote on a proposal
+    ///
+    /// # Arguments
+    /// * `proposal_id` - Proposal ID
+    /// * `encrypted_vote` - Encrypted vote ("yes" or "no", encrypted with user's pubkey using ECIES)
+    ///
+    /// # Payment
+    /// Requires 0.002 NEAR for storage
+    ///
+    /// # Returns
+    /// Timestamp (nanoseconds) used for vote hash calculation.
+    /// Vote hash = SHA256(user + timestamp + encrypted_vote)
+    ///
+    /// # Notes
+    /// - Vote is encrypted client-side using ECIES (secp256k1 + AES-256-GCM)
+    /// - ECIES includes random nonce inside ciphertext (no separate nonce needed)
+    /// - Multiple votes allowed (last real vote counts)
+    /// - User can also send dummy votes for privacy (any ciphertext that doesn't decrypt to "yes"/"no")
+    #[payable]
+    pub fn cast_vote(
+        &mut self,
+        proposal_id: u64,
+        encrypted_vote: String,
+    ) -> u64 {
+        self.assert_not_paused();
+        let voter = env::predecessor_account_id();
+        let attached = env::attached_deposit();
+
+        // Only members can vote
+        let mut member_info = self.members.get(&voter)
+            .expect("Only members can vote");
+
+        // Delegated votes are cast by the delegate
+        if let Some(delegate) = self.delegations.get(&voter) {
+            env::panic_str(&format!(
+                "You delegated your vote to {}; call revoke_delegation before voting",
+                delegate
+            ));
+        }
+
+        // Check if user has pubkey (completed join), unless disabled for testing
+        if self.config.require_pubkey_for_vote {
+            if self.config.use_proposal_scoped_keys {
+                assert!(
+                    self.proposal_pubkeys.get(&account_proposal_key(&voter, proposal_id)).is_some(),
+                    "Request a proposal key first (request_proposal_key)"
+                );
+            } else {
+                assert!(
+                    self.user_pubkeys.get(&voter).is_some(),
+                    "Complete join first to derive encryption key"
+                );
+            }
+        }
+
+        // Check storage deposit (after the protocol fee)
+        let attached = self.internal_take_protocol_fee(attached);
+        assert!(
+            attached >= self.config.min_vote_deposit,
+            "Minimum deposit is {} yoctoNEAR for vote storage",
+            self.config.min_vote_deposit.as_yoctonear()
+        );
+
+        // Get proposal
+        let proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+
+        // Check member joined BEFORE proposal was created (prevent retroactive voting)
+        // Note: joined_at = 0 means old member from migration (can vote on all proposals)
+        if member_info.joined_at > 0 {
+            assert!(
+                member_info.joined_at < proposal.created_at,
+                "Cannot vote on proposals created before you joined"
+            );
+        }
+
+        // Check proposal is active
+        assert!(
+            proposal.status == ProposalStatus::Active,
+            "Proposal is not active"
+        );
+        // Check deadline not passed (if deadline is set)
+        if let Some(deadline_ns) = proposal.deadline {
+            assert!(
+                env::block_timestamp() < deadline_ns,
+                "Voting deadline has passed"
+            );
+        }
+
+        // Members who already voted can't change their vote during lock-in
+        if self.internal_is_vote_locked_in(&proposal, &voter) {
+            env::panic_str("Vote lock-in period active, cannot change vote");
+        }
+
+        // Create vote with blockchain timestamp
+        let timestamp = env::block_timestamp();
+        let vote = Vote {
+            user: voter.clone(),
+            encrypted_vote,
+            timestamp,
+            block_height: env::block_height(),
+        };
+
+        // Add vote to list
+        let mut votes = self.votes.get(&proposal_id).unwrap();
+        assert!(
+            votes.len() < self.config.max_votes_per_proposal,
+            "Vote limit for this proposal reached"
+        );
+        votes.push(&vote);
+        self.votes.insert(&proposal_id, &votes);
+        self.total_votes_cast += 1;
+
+        let ballots_key = account_proposal_key(&voter, proposal_id);
+        let mut ballots = self.ballot_indices.get(&ballots_key).unwrap_or_default();
+        // Participation analytics count the first ballot only
+        if ballots.is_empty() {
+            member_info.proposals_voted += 1;
+        }
+        ballots.push(votes.len() - 1);
+        self.ballot_indices.insert(&ballots_key, &ballots);
+
+        self.internal_emit_vote_milestones(&proposal, votes.len());
+        member_info.last_voted_at = Some(timestamp);
+        self.members.insert(&voter, &member_info);
+
+        log!("Vote cast by {} on proposal {} at timestamp {}", voter, proposal_id, timestamp);
+        Event::VoteCast {
+            proposal_id,
+            voter,
+            vote_index: votes.len() - 1,
+            timestamp,
+        }
+        .emit();
+
+        // Return timestamp so frontend can compute vote hash immediately
+        // vote_hash = SHA256(user + timestamp + encrypted_vote)
+        timestamp
+    }
+
+    /// Finalize a proposal and tally votes in TEE
+    ///
+    /// # Arguments
+    /// * `proposal_id` - Proposal ID
+    ///
+    /// # Payment
+    /// Requires 0.01 NEAR for OutLayer execution
+    ///
+    /// # Process
+    /// 1. User pays for OutLayer execution
+    /// 2. Contract sends all encrypted votes to OutLayer
+    /// 3. OutLayer WASM decrypts votes in TEE
+    /// 4. OutLayer returns tally (yes_count, no_count, total)
+    /// 5. Callback updates proposal status (Passed/Rejected)
+    ///
+    /// # Timing
+    /// - Can be called **any time** after first vote is cast
+    /// - Does NOT require waiting for deadline
+    /// - Deadline only blocks NEW votes, not finalization
+    /// - This allows early finalization if quorum is reached
+    /// - Once the deadline passed without any vote, the proposal is rejected
+    ///   on the spot without calling OutLayer and the deposit is returned
+    /// - Only one tally per proposal runs at a time: calls made before the
+    ///   previous tally's callback arrived panic instead of paying OutLayer twice
+    #[payable]
+    pub fn finalize_proposal(&mut self, proposal_id: u64) -> Promise {
+        self.assert_not_paused();
+        let caller = env::predecessor_account_id();
+        let attached = env::attached_deposit();
+
+        // Get proposal
+        let mut proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+
+        // Check proposal is active
+        assert!(
+            proposal.status == ProposalStatus::Active,
+            "Proposal is not active"
+        );
+        assert!(
+            self.partial_tallies.get(&proposal_id).is_none(),
+            "Proposal is being finalized in chunks, use finalize_proposal_chunk or abandon_partial_tally"
+        );
+
+        // Nothing to tally once voting closed without votes: reject without OutLayer
+        let deadline_passed = proposal.deadline.is_some_and(|deadline_ns| env::block_timestamp() >= deadline_ns);
+        if deadline_passed && self.get_vote_count(proposal_id) == 0 {
+            return self.internal_reject_unvoted_proposal(proposal, caller, attached);
+        }
+
+        // Who funds OutLayer (see `DAOConfig::outlayer_payer_mode`)
+        let (deposit, payer) = self.internal_tally_funding(&mut proposal, caller, attached);
+
+        // Get all votes
+        let votes = self.votes.get(&proposal_id).unwrap();
+        let votes_vec: Vec<Vote> = votes.iter().collect();
+
+        // Ensure at least one vote exists
+        assert!(
+            !votes_vec.is_empty(),
+            "No votes to tally. Wait for at least one vote."
+        );
+
+        log!(
+            "Finalizing proposal {} with {} votes. Tallying via OutLayer TEE",
+            proposal_id,
+            votes_vec.len()
+        );
+
+        // Call OutLayer to tally votes in TEE
+        self.request_vote_tallying(proposal_id, votes_vec, deposit.as_yoctonear(), payer)
+    }
+
+    /// Tally the next chunk of a proposal's votes (for proposals too large for `finalize_proposal`)
+    ///
+    /// Only available once the voting deadline has passed. Chunks must be
+    /// tallied in order: `offset` is the `next_offset` of `get_partial_tally`
+    /// (0 for the first chunk), and only one chunk can be in flight at a time.
+    /// Once every vote is covered, `commit_finalization` decides the proposal.
+    /// A stalled run can be dropped with `abandon_partial_tally`.
+    ///
+    /// A member is counted in the chunk holding their last ballot, which is
+    /// sent together with their earlier ballots, so "last real vote wins"
+    /// holds across chunks (a chunk's payload can exceed `limit` votes).
+    ///
+    /// Not supported with TimeWeighted quorum or while any vote is delegated.
+    ///
+    /// # Privacy
+    /// The TEE keeps the running counts sealed between chunks (see
+    /// `PartialTally::sealed_tally`) and only reveals them in
+    /// `commit_finalization` if quorum is met, so chunks of any size, even a
+    /// single vote, reveal nothing about individual ballots.
+    ///
+    /// # Payment
+    /// Requires 0.01 NEAR per chunk for OutLayer execution (see `DAOConfig::outlayer_payer_mode`)
+    #[payable]
+    pub fn finalize_proposal_chunk(&mut self, proposal_id: u64, offset: u64, limit: u64) -> Promise {
+        self.assert_not_paused();
+        let caller = env::predecessor_account_id();
+        let attached = env::attached_deposit();
+
+        let mut proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+        assert!(
+            proposal.status == ProposalStatus::Active,
+            "Proposal is not active"
+        );
+        assert!(
+            proposal.deadline.is_some_and(|deadline_ns| env::block_timestamp() >= deadline_ns),
+            "Chunked finalization is only available after the voting deadline"
+        );
+        assert!(
+            !matches!(proposal.quorum, QuorumType::TimeWeighted { .. }),
+            "Chunked finalization does not support TimeWeighted quorum"
+        );
+        assert!(
+            self.delegations_received.is_empty(),
+            "Chunked finalization does not support delegated votes, use finalize_proposal"
+        );
+        assert!(limit > 0, "limit must be positive");
+
+        assert!(!in_flight(self.tallies_in_flight.get(&proposal_id)), "Tally already in progress");
+        let mut partial = self.partial_tallies.get(&proposal_id).unwrap_or_default();
+        assert!(!partial.chunk_in_flight, "Another chunk of this proposal is being tallied");
+        assert!(
+            offset == partial.next_offset,
+            "Chunks must be tallied in order, next offset is {}",
+            partial.next_offset
+        );
+
+        let votes = self.votes.get(&proposal_id).unwrap();
+        assert!(offset < votes.len(), "No votes left to tally, call commit_finalization");
+        let chunk_end = votes.len().min(offset.saturating_add(limit));
+
+        // Who funds OutLayer (see `DAOConfig::outlayer_payer_mode`)
+        let (deposit, payer) = self.internal_tally_funding(&mut proposal, caller, attached);
+
+        let chunk_votes = self.internal_chunk_ballots(proposal_id, &votes, offset, chunk_end);
+
+        partial.chunk_in_flight = true;
+        partial.updated_at = env::block_timestamp();
+        self.partial_tallies.insert(&proposal_id, &partial);
+
+        log!(
+            "Tallying votes {}..{} of {} for proposal {} ({} ballots sent)",
+            offset,
+            chunk_end,
+            votes.len(),
+            proposal_id,
+            chunk_votes.len()
+        );
+
+        self.request_chunk_tallying(&proposal, &partial, chunk_votes, chunk_end, deposit.as_yoctonear(), payer)
+    }
+
+    /// Decide a proposal once `finalize_proposal_chunk` has covered all its votes
+    ///
+    /// Anyone can call this. The TEE unseals the summed chunk counts and
+    /// checks quorum like `finalize_proposal`, so counts are only published if
+    /// quorum is met. The stored `TallyResult` has no Merkle root or proofs
+    /// (each chunk only hashes its own ballots). If the call fails, the
+    /// partial tally is kept and the commit can be retried.
+    ///
+    /// # Payment
+    /// Requires 0.01 NEAR for OutLayer execution (see `DAOConfig::outlayer_payer_mode`)
+    #[payable]
+    pub fn commit_finalization(&mut self, proposal_id: u64) -> Promise {
+        self.assert_not_paused();
+        let caller = env::predecessor_account_id();
+        let attached = env::attached_deposit();
+
+        let partial = self.partial_tallies.get(&proposal_id)
+            .expect("No chunked finalization in progress");
+        assert!(!partial.chunk_in_flight, "A chunk of this proposal is still being tallied");
+
+        let vote_count = self.get_vote_count(proposal_id);
+        assert!(
+            partial.next_offset == vote_count,
+            "Only {} of {} votes tallied",
+            partial.next_offset,
+            vote_count
+        );
+
+        // Who funds OutLayer (see `DAOConfig::outlayer_payer_mode`)
+        let mut proposal = self.proposals.get(&proposal_id).unwrap();
+        let (deposit, payer) = self.internal_tally_funding(&mut proposal, caller, attached);
+
+        log!("Committing chunked tally of proposal {} ({} votes)", proposal_id, vote_count);
+
+        self.request_chunked_tally_commit(&proposal, &partial.sealed_tally, vote_count, deposit.as_yoctonear(), payer)
+    }
+
+    /// Drop a proposal's chunked finalization so it can be finalized again from scratch
+    ///
+    /// Admins can do this at any time, anyone else once the run made no
+    /// progress for an hour (e.g. a chunk callback never arrived). The result
+    /// of a chunk still in flight is ignored afterwards.
+    pub fn abandon_partial_tally(&mut self, proposal_id: u64) {
+        self.assert_not_paused();
+        let partial = self.partial_tallies.get(&proposal_id)
+            .expect("No chunked finalization in progress");
+
+        let stalled = env::block_timestamp() >= partial.updated_at.saturating_add(PARTIAL_TALLY_TIMEOUT_NS);
+        assert!(
+            stalled || self.internal_is_admin(&env::predecessor_account_id()),
+            "Only admins can abandon a chunked finalization that is still making progress"
+        );
+
+        self.partial_tallies.remove(&proposal_id);
+
+        log!("Chunked finalization of proposal {} abandoned at vote {}", proposal_id, partial.next_offset);
+    }
+
+    /// Add the attached deposit to the treasury that funds DAO-paid tallies
+    #[payable]
+    pub fn fund_treasury(&mut self) {
+        let attached = env::attached_deposit();
+        assert!(!attached.is_zero(), "Attach a deposit to fund the treasury");
+
+        self.treasury_balance += attached.as_yoctonear();
+
+        log!(
+            "{} added {} to the treasury (balance: {})",
+            env::predecessor_account_id(),
+            attached.exact_amount_display(),
+            NearToken::from_yoctonear(self.treasury_balance).exact_amount_display()
+        );
+    }
+
+    /// Credit OutLayer refunds of DAO-paid tallies back to the treasury
+    ///
+    /// OutLayer returns the unused part of a deposit to the contract balance.
+    /// Anyone may call this; at most the amount DAO-paid executions spent is
+    /// credited, and never balance reserved for storage. Also runs before
+    /// every DAO-paid tally. Returns the amount credited.
+    pub fn reconcile_treasury(&mut self) -> NearToken {
+        let credited = self.internal_reconcile_treasury();
+        log!(
+            "Credited {} of OutLayer refunds to the treasury (balance: {})",
+            NearToken::from_yoctonear(credited).exact_amount_display(),
+            NearToken::from_yoctonear(self.treasury_balance).exact_amount_display()
+        );
+        NearToken::from_yoctonear(credited)
+    }
+
+    /// Re-tally a decided proposal using only votes cast before `cutoff_timestamp` (owner-only)
+    ///
+    /// For debugging and disputes ("how would it have ended at time T?").
+    /// The result is returned and logged; the proposal is not changed.
+    ///
+    /// # Privacy
+    /// Replays at cutoffs on either side of a single ballot reveal how that
+    /// member voted (whenever quorum is met both times). That's why this is
+    /// owner-only and only available once voting has ended.
+    ///
+    /// # Payment
+    /// Requires 0.01 NEAR for OutLayer execution
+    #[payable]
+    pub fn finalize_proposal_as_of(&mut self, proposal_id: u64, cutoff_timestamp: u64) -> Promise {
+        self.assert_owner();
+
+        let attached = env::attached_deposit();
+        assert!(
+            attached >= self.config.min_outlayer_deposit,
+            "Minimum deposit is {} yoctoNEAR for OutLayer execution",
+            self.config.min_outlayer_deposit.as_yoctonear()
+        );
+
+        let proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+        assert!(
+            proposal.status != ProposalStatus::Active,
+            "Proposal is still active"
+        );
+
+        let votes: Vec<Vote> = self.votes
+            .get(&proposal_id)
+            .map(|votes| votes.iter().collect())
+            .unwrap_or_default();
+        assert!(!votes.is_empty(), "No votes to tally");
+
+        log!(
+            "Replaying tally of proposal {} as of {} ({} votes submitted in total)",
+            proposal_id,
+            cutoff_timestamp,
+            votes.len()
+        );
+
+        self.request_replay_tally(
+            &proposal,
+            votes,
+            cutoff_timestamp,
+            attached.as_yoctonear(),
+            env::predecessor_account_id(),
+        )
+    }
+
+    /// Upload the new contract code for an UpgradeContract proposal
+    ///
+    /// Anyone can upload; the code must match the proposal's `wasm_hash`.
+    /// Must be done while the proposal is active, once per proposal. The code
+    /// is deployed by `execute_upgrade` if the proposal passes.
+    ///
+    /// # Payment
+    /// Requires storage deposit for the uploaded code. The whole attached
+    /// deposit is returned to the uploader when the code is deployed or
+    /// dropped (proposal rejected, vetoed or cancelled).
+    #[payable]
+    pub fn upload_upgrade_wasm(&mut self, proposal_id: u64, wasm: Base64VecU8) {
+        self.assert_not_paused();
+        let proposal = self.proposals.get(&proposal_id)
+            .expect("Proposal not found");
+
+        assert!(
+            proposal.status == ProposalStatus::Active,
+            "Proposal is not active"
+        );
+
+        let wasm_hash = match &proposal.proposal_type {
+            ProposalType::UpgradeContract { wasm_hash } => wasm_hash.clone(),
+            _ => env::panic_str("Not an UpgradeContract proposal"),
+        };
+
+        assert!(
+            !self.pending_upgrade_wasm.contains_key(&proposal_id),
+            "Upgrade WASM was already uploaded for this proposal"
+        );
+
+        let wasm: Vec<u8> = wasm.into();
+        let uploaded_hash = hex::encode(env::sha256(&wasm));
+        assert!(
+            uploaded_hash == wasm_hash.to_lowercase(),
+            "WASM hash mismatch: expected {}, got {}",
+            wasm_hash,
+            uploaded_hash
+        );
+
+        let storage_before = env::storage_usage();
+        self.pending_upgrade_wasm.insert(&proposal_id, &wasm);
+        self.upgrade_wasm_uploads.insert(&proposal_id, &UpgradeWasmUpload {
+            uploader: env::predecessor_account_id(),
+            deposit: env::attached_deposit(),
+        });
+        self.held_deposits += env::attached_deposit().as_yoctonear();
+        let storage_used = env::storage_usage().saturating_sub(storage_before);
+
+        let required_deposit = env::storage_byte_cost().as_yoctonear() * storage_used as Balance;
+        assert!(
+            env::attached_deposit().as_yoctonear() >= required_deposit,
+            "Minimum deposit is {} yoctoNEAR for WASM storage",
+            required_deposit
+        );
+
+        log!(
+            "Upgrade WASM for proposal {} uploaded ({} bytes, sha256 {})",
+            proposal_id,
+            wasm.len(),
+            uploaded_hash
+        );
+    }
+
+    // ========== Internal methods ==========
+
+    /// Validate and store a new proposal (shared by all create paths)
+    fn internal_create_proposal(
+        &mut self,
+        title: String,
+        description: String,
+        quorum: QuorumType,
+        deadline: Option<u64>,
+        proposal_type: ProposalType,
+        tags: Vec<String>,
+    ) -> u64 {
+        self.assert_not_paused();
+        let creator = env::predecessor_account_id();
+
+        // Only members can create proposals
+        let member_info = self.members.get(&creator)
+            .expect("Only members can create proposals");
+
+        validate_proposal_text(&title, &description, &self.config);
+        let tags = normalize_proposal_tags(tags);
+        validate_proposal_tags(&tags, &self.config);
+
+        // Check storage deposit (after the protocol fee)
+        let attached = self.internal_take_protocol_fee(env::attached_deposit());
+        assert!(
+            attached >= self.config.proposal_deposit,
+            "Minimum deposit is {} for storage",
+            self.config.proposal_deposit.exact_amount_display()
+        );
+
+        // Creator prepays the first tally
+        let creator_pays = self.config.outlayer_payer_mode == OutLayerPayerMode::Proposal { creator_pays: true };
+        let creator_deposit = if creator_pays {
+            let required = self.config.proposal_deposit.saturating_add(self.config.min_outlayer_deposit);
+            assert!(
+                attached >= required,
+                "Minimum deposit is {} (storage + OutLayer execution)",
+                required.exact_amount_display()
+            );
+            attached.saturating_sub(self.config.proposal_deposit)
+        } else {
+            NearToken::from_yoctonear(0)
+        };
+
+        // UpgradeContract proposals must commit to a SHA256 hash
+        if let ProposalType::UpgradeContract { wasm_hash } = &proposal_type {
+            assert!(
+                wasm_hash.len() == 64 && wasm_hash.chars().all(|c| c.is_ascii_hexdigit()),
+                "wasm_hash must be a hex-encoded SHA256 (64 characters)"
+            );
+        }
+
+        match quorum {
+            QuorumType::TimeWeighted { decay_halflife_ns, .. } => {
+                assert!(decay_halflife_ns > 0, "decay_halflife_ns must be greater than 0");
+            }
+            QuorumType::Percentage { min_percent_of_members } => {
+                assert!(
+                    (1..=100).contains(&min_percent_of_members),
+                    "min_percent_of_members must be between 1 and 100"
+                );
+            }
+            QuorumType::SuperMajority { min_yes_percent } | QuorumType::Relative { min_yes_percent } => {
+                assert!(
+                    (1..=100).contains(&min_yes_percent),
+                    "min_yes_percent must be between 1 and 100"
+                );
+            }
+            QuorumType::Absolute { .. } | QuorumType::Unanimous => {}
+        }
+
+        // Validate deadline is in the future (if provided)
+        if let Some(deadline_ns) = deadline {
+            assert!(
+                deadline_ns > env::block_timestamp(),
+                "Deadline must be in the future"
+            );
+        }
+        assert!(
+            deadline.unwrap_or(u64::MAX) - env::block_timestamp() >= self.config.min_voting_period_ns,
+            "Voting period must be at least {} ns",
+            self.config.min_voting_period_ns
+        );
+
+        // Validate creator joined before proposal creation (prevent retroactive voting)
+        // This ensures members can only vote on proposals created AFTER they joined
+        assert!(
+            member_info.joined_at <= env::block_timestamp(),
+            "Invalid member timestamp"
+        );
+
+        // joined_at = 0: migrated member, always eligible
+        if member_info.joined_at > 0 {
+            assert!(
+                env::block_timestamp() - member_info.joined_at >= self.config.min_membership_duration_ns,
+                "Membership too recent to create proposals"
+            );
+        }
+
+        let active_count = self.active_proposal_counts.get(&creator).unwrap_or(0);
+        assert!(
+            active_count < self.config.max_active_proposals_per_creator,
+            "Too many active proposals (max {})",
+            self.config.max_active_proposals_per_creator
+        );
+
+        // Fixed-window rate limit for the whole DAO
+        let now = env::block_timestamp();
+        if now.saturating_sub(self.proposal_period_start) >= self.config.proposal_period_ns {
+            self.proposal_period_start = now;
+            self.proposals_in_period = 0;
+        }
+        assert!(
+            self.proposals_in_period < self.config.max_proposals_per_period,
+            "Proposal limit for this period reached, try again later"
+        );
+        self.proposals_in_period += 1;
+        self.active_proposal_counts.insert(&creator, &(active_count + 1));
+
+        let proposal_id = self.next_proposal_id;
+        self.next_proposal_id += 1;
+
+        let proposal = Proposal {
+            id: proposal_id,
+            title,
+            description,
+            creator: creator.clone(),
+            created_at: env::block_timestamp(),
+            deadline,
+            quorum,
+            status: ProposalStatus::Active,
+            tally_result: None,
+            proposal_type,
+            tags,
+            creator_deposit,
+            eligible_voters: self.voting_member_count,
+            vote_options: None,
+            execution_payload: None,
+            amended_at: None,
+            decided_at: None,
+        };
+
+        self.proposals.insert(&proposal_id, &proposal);
+        self.held_deposits += proposal.creator_deposit.as_yoctonear();
+        self.internal_index_proposal_status(proposal_id, None, &proposal.status);
+        for tag in &proposal.tags {
+            let mut proposal_ids = self
+                .tag_index
+                .get(tag)
+                .unwrap_or_else(|| Vector::new(StorageKey::TagProposalIds { tag: tag.clone() }));
+            proposal_ids.push(&proposal_id);
+            self.tag_index.insert(tag, &proposal_ids);
+        }
+
+        // Create unique storage key for this proposal's votes
+        let votes_key = format!("v{}", proposal_id);
+        self.votes.insert(&proposal_id, &Vector::new(votes_key.as_bytes()));
+
+        log!(
+            "Proposal {} created by {}: '{}'",
+            proposal_id,
+            creator,
+            proposal.title
+        );
+        Event::ProposalCreated {
+            proposal_id,
+            creator,
+            timestamp: proposal.created_at,
+        }
+        .emit();
+
+        proposal_id
+    }
+
+    /// Append a pending OutLayer execution to the history and return its index
+    fn internal_record_outlayer_execution(&mut self, execution_type: &str) -> u64 {
+        let execution_index = self.outlayer_execution_count;
+        let summary = OutLayerExecutionSummary {
+            execution_type: execution_type.to_string(),
+            triggered_by: env::predecessor_account_id(),
+            block_height: env::block_height(),
+            timestamp: env::block_timestamp(),
+            success: None,
+            gas_used: None,
+        };
+
+        if execution_index < MAX_OUTLAYER_EXECUTION_HISTORY {
+            self.outlayer_executions.push(&summary);
+        } else {
+            let dropped = self
+                .outlayer_executions
+                .replace(execution_index % MAX_OUTLAYER_EXECUTION_HISTORY, &summary);
+            if let Some(success) = dropped.success {
+                self.outlayer_executions_completed -= 1;
+                self.outlayer_executions_succeeded -= success as u64;
+            }
+        }
+        self.outlayer_execution_count += 1;
+
+        execution_index
+    }
+
+    /// Store the outcome of an OutLayer execution
+    ///
+    /// No-op for callbacks of requests sent before the history existed (`None`)
+    /// and for executions already overwritten in the ring buffer.
+    fn internal_complete_outlayer_execution(&mut self, execution_index: Option<u64>, success: bool) {
+        let Some(execution_index) = execution_index else {
+            return;
+        };
+        if execution_index + MAX_OUTLAYER_EXECUTION_HISTORY < self.outlayer_execution_count {
+            return;
+        }
+
+        let position = execution_index % MAX_OUTLAYER_EXECUTION_HISTORY;
+        let Some(mut summary) = self.outlayer_executions.get(position) else {
+            return;
+        };
+        if summary.success.is_none() {
+            self.outlayer_executions_completed += 1;
+            self.outlayer_executions_succeeded += success as u64;
+        }
+        summary.success = Some(success);
+        self.outlayer_executions.replace(position, &summary);
+    }
+
+    /// Request key derivation from OutLayer (`key_epoch`: see `MemberInfo::key_epoch`)
+    fn request_key_derivation(&mut self, user: AccountId, attached_deposit: Balance, key_epoch: u32) -> Promise {
+        // A second request would charge OutLayer again and race the first callback
+        if in_flight(self.key_derivations_in_flight.insert(&user, &env::block_timestamp())) {
+            env::panic_str("Key derivation already in progress");
+        }
+        let execution_index = self.internal_record_outlayer_execution("key_derivation");
+        self.internal_record_pending_deposit(execution_index, &user, attached_deposit);
+
+        let code_source = serde_json::json!({
+            "repo": "https://github.com/zavodil/private-dao-ark",
+            "commit": "main",
+            "build_target": "wasm32-wasip1"
+        });
+
+        let resource_limits = serde_json::json!({
+            "max_instructions": 1000000000u64,
+            "max_memory_mb": 128u32,
+            "max_execution_seconds": 30u64
+        });
+
+        let input_data = serde_json::json!({
+            "action": "derive_pubkey",
+            "dao_account": env::current_account_id(),
+            "user_account": user,
+            "key_derivation_algorithm": self.config.key_derivation_algorithm,
+            "key_epoch": key_epoch
+        });
+
+        // Call OutLayer with secrets_ref (master secret from keymaster)
+        let secrets_ref = self.get_secrets_ref();
+
+        // Call OutLayer
+        ext_outlayer::ext(self.outlayer_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(attached_deposit))
+            .with_unused_gas_weight(1)
+            .request_execution(
+                code_source,
+                resource_limits,
+                serde_json::to_string(&input_data).unwrap(),
+                Some(secrets_ref), 
+                "Json".to_string(),
+                Some(user.clone()), // Refund to user
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(CALLBACK_GAS)
+                    .on_key_derived(user, Some(execution_index)),
+            )
+    }
+
+    /// Request proposal-scoped key derivation from OutLayer
+    fn request_proposal_key_derivation(
+        &self,
+        user: AccountId,
+        proposal_id: u64,
+        attached_deposit: Balance,
+    ) -> Promise {
+        let code_source = serde_json::json!({
+            "repo": "https://github.com/zavodil/private-dao-ark",
+            "commit": "main",
+            "build_target": "wasm32-wasip1"
+        });
+
+        let resource_limits = serde_json::json!({
+            "max_instructions": 1000000000u64,
+            "max_memory_mb": 128u32,
+            "max_execution_seconds": 30u64
+        });
+
+        let input_data = serde_json::json!({
+            "action": "derive_proposal_pubkey",
+            "dao_account": env::current_account_id(),
+            "user_account": user,
+            "proposal_id": proposal_id
+        });
+
+        let secrets_ref = self.get_secrets_ref();
+
+        ext_outlayer::ext(self.outlayer_contract.clone())
             .with_attached_deposit(NearToken::from_yoctonear(attached_deposit))
             .with_unused_gas_weight(1)
             .request_execution(
                 code_source,
                 resource_limits,
                 serde_json::to_string(&input_data).unwrap(),
-                Some(secrets_ref), 
+                Some(secrets_ref),
                 "Json".to_string(),
                 Some(user.clone()), // Refund to user
             )
             .then(
                 ext_self::ext(env::current_account_id())
                     .with_static_gas(CALLBACK_GAS)
-                    .on_key_derived(user),
+                    .on_proposal_key_derived(user, proposal_id),
+            )
+    }
+
+    /// Request vote tallying from OutLayer
+    fn request_vote_tallying(
+        &mut self,
+        proposal_id: u64,
+        votes: Vec<Vote>,
+        attached_deposit: Balance,
+        payer: AccountId,
+    ) -> Promise {
+        // Concurrent finalizations would each charge OutLayer for the same tally
+        if in_flight(self.tallies_in_flight.insert(&proposal_id, &env::block_timestamp())) {
+            env::panic_str("Tally already in progress");
+        }
+
+        // Get proposal to pass quorum info to worker
+        let proposal = self.proposals.get(&proposal_id).unwrap();
+        let execution_index = self.internal_record_outlayer_execution("vote_tally");
+        self.internal_record_pending_deposit(execution_index, &payer, attached_deposit);
+
+        let code_source = serde_json::json!({
+            "repo": "https://github.com/zavodil/private-dao-ark",
+            "commit": "main",
+            "build_target": "wasm32-wasip1"
+        });
+
+        let resource_limits = serde_json::json!({
+            "max_instructions": 10000000000u64,
+            "max_memory_mb": 128u32,
+            "max_execution_seconds": 60u64
+        });
+
+        let input_data = serde_json::json!({
+            "action": "tally_votes",
+            "dao_account": env::current_account_id(),
+            "proposal_id": proposal_id,
+            "votes": votes,
+            "quorum": proposal.quorum,
+            "proposal_created_at": proposal.created_at,
+            "proposal_deadline": proposal.deadline,
+            "use_proposal_scoped_keys": self.config.use_proposal_scoped_keys,
+            "key_derivation_algorithms": self.internal_key_derivation_algorithms(&votes),
+            "key_epochs": self.internal_key_epochs(&votes),
+            "delegations": self.internal_active_delegations(),
+            "eligible_voters": proposal.eligible_voters,
+            "vote_options": proposal.vote_options,
+            "current_timestamp": env::block_timestamp()
+        });
+
+        // Call OutLayer with secrets_ref (master secret from keymaster)
+        let secrets_ref = self.get_secrets_ref();
+
+        ext_outlayer::ext(self.outlayer_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(attached_deposit))
+            .with_unused_gas_weight(1)
+            .request_execution(
+                code_source,
+                resource_limits,
+                serde_json::to_string(&input_data).unwrap(),
+                Some(secrets_ref), // Master secret from keymaster
+                "Json".to_string(),
+                Some(payer), // Refund to payer
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(CALLBACK_GAS)
+                    .on_votes_tallied(proposal_id, Some(execution_index)),
+            )
+    }
+
+    /// Request a tally of one chunk of votes ("tally_votes_chunk" action) from OutLayer
+    fn request_chunk_tallying(
+        &mut self,
+        proposal: &Proposal,
+        partial: &PartialTally,
+        votes: Vec<Vote>,
+        chunk_end: u64,
+        attached_deposit: Balance,
+        payer: AccountId,
+    ) -> Promise {
+        let execution_index = self.internal_record_outlayer_execution("vote_tally_chunk");
+        self.internal_record_pending_deposit(execution_index, &payer, attached_deposit);
+
+        let code_source = serde_json::json!({
+            "repo": "https://github.com/zavodil/private-dao-ark",
+            "commit": "main",
+            "build_target": "wasm32-wasip1"
+        });
+
+        let resource_limits = serde_json::json!({
+            "max_instructions": 10000000000u64,
+            "max_memory_mb": 128u32,
+            "max_execution_seconds": 60u64
+        });
+
+        let input_data = serde_json::json!({
+            "action": "tally_votes_chunk",
+            "dao_account": env::current_account_id(),
+            "proposal_id": proposal.id,
+            "votes": votes,
+            "sealed_tally": Some(&partial.sealed_tally).filter(|sealed| !sealed.is_empty()),
+            "chunk_offset": partial.next_offset,
+            "chunk_end": chunk_end,
+            "proposal_created_at": proposal.created_at,
+            "use_proposal_scoped_keys": self.config.use_proposal_scoped_keys,
+            "key_derivation_algorithms": self.internal_key_derivation_algorithms(&votes),
+            "key_epochs": self.internal_key_epochs(&votes),
+            "vote_options": proposal.vote_options,
+            "current_timestamp": env::block_timestamp()
+        });
+
+        let secrets_ref = self.get_secrets_ref();
+
+        ext_outlayer::ext(self.outlayer_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(attached_deposit))
+            .with_unused_gas_weight(1)
+            .request_execution(
+                code_source,
+                resource_limits,
+                serde_json::to_string(&input_data).unwrap(),
+                Some(secrets_ref),
+                "Json".to_string(),
+                Some(payer),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(CALLBACK_GAS)
+                    .on_votes_tallied_chunk(proposal.id, chunk_end, Some(execution_index)),
+            )
+    }
+
+    /// Request the decision of a chunked tally ("commit_chunked_tally" action) from OutLayer
+    ///
+    /// The result is handled like a full tally, by `on_votes_tallied`.
+    fn request_chunked_tally_commit(
+        &mut self,
+        proposal: &Proposal,
+        sealed_tally: &str,
+        vote_count: u64,
+        attached_deposit: Balance,
+        payer: AccountId,
+    ) -> Promise {
+        if in_flight(self.tallies_in_flight.insert(&proposal.id, &env::block_timestamp())) {
+            env::panic_str("Tally already in progress");
+        }
+
+        let execution_index = self.internal_record_outlayer_execution("vote_tally_commit");
+        self.internal_record_pending_deposit(execution_index, &payer, attached_deposit);
+
+        let code_source = serde_json::json!({
+            "repo": "https://github.com/zavodil/private-dao-ark",
+            "commit": "main",
+            "build_target": "wasm32-wasip1"
+        });
+
+        let resource_limits = serde_json::json!({
+            "max_instructions": 10000000000u64,
+            "max_memory_mb": 128u32,
+            "max_execution_seconds": 60u64
+        });
+
+        let input_data = serde_json::json!({
+            "action": "commit_chunked_tally",
+            "dao_account": env::current_account_id(),
+            "proposal_id": proposal.id,
+            "sealed_tally": sealed_tally,
+            "vote_count": vote_count,
+            "quorum": proposal.quorum,
+            "eligible_voters": proposal.eligible_voters,
+            "vote_options": proposal.vote_options,
+            "current_timestamp": env::block_timestamp()
+        });
+
+        let secrets_ref = self.get_secrets_ref();
+
+        ext_outlayer::ext(self.outlayer_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(attached_deposit))
+            .with_unused_gas_weight(1)
+            .request_execution(
+                code_source,
+                resource_limits,
+                serde_json::to_string(&input_data).unwrap(),
+                Some(secrets_ref),
+                "Json".to_string(),
+                Some(payer),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(CALLBACK_GAS)
+                    .on_votes_tallied(proposal.id, Some(execution_index)),
+            )
+    }
+
+    /// Request a partial re-tally ("replay_tally" action) from OutLayer
+    fn request_replay_tally(
+        &self,
+        proposal: &Proposal,
+        votes: Vec<Vote>,
+        cutoff_timestamp: u64,
+        attached_deposit: Balance,
+        payer: AccountId,
+    ) -> Promise {
+        let code_source = serde_json::json!({
+            "repo": "https://github.com/zavodil/private-dao-ark",
+            "commit": "main",
+            "build_target": "wasm32-wasip1"
+        });
+
+        let resource_limits = serde_json::json!({
+            "max_instructions": 10000000000u64,
+            "max_memory_mb": 128u32,
+            "max_execution_seconds": 60u64
+        });
+
+        let input_data = serde_json::json!({
+            "action": "replay_tally",
+            "dao_account": env::current_account_id(),
+            "proposal_id": proposal.id,
+            "votes": votes,
+            "quorum": proposal.quorum,
+            "use_proposal_scoped_keys": self.config.use_proposal_scoped_keys,
+            "key_derivation_algorithms": self.internal_key_derivation_algorithms(&votes),
+            "key_epochs": self.internal_key_epochs(&votes),
+            "delegations": self.internal_active_delegations(),
+            "eligible_voters": proposal.eligible_voters,
+            "vote_options": proposal.vote_options,
+            "include_votes_before_timestamp": cutoff_timestamp
+        });
+
+        let secrets_ref = self.get_secrets_ref();
+
+        ext_outlayer::ext(self.outlayer_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(attached_deposit))
+            .with_unused_gas_weight(1)
+            .request_execution(
+                code_source,
+                resource_limits,
+                serde_json::to_string(&input_data).unwrap(),
+                Some(secrets_ref),
+                "Json".to_string(),
+                Some(payer),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(CALLBACK_GAS)
+                    .on_replay_tallied(proposal.id, cutoff_timestamp),
             )
     }
 
-    /// Request vote tallying from OutLayer
-    fn request_vote_tallying(
+    /// Callback after key derivation
+    ///
+    /// Failures are logged and recorded in the OutLayer execution history
+    /// instead of panicking (a panic would revert the history entry).
+    #[private]
+    pub fn on_key_derived(
+        &mut self,
+        user: AccountId,
+        execution_index: Option<u64>,
+        #[callback_result] result: Result<Option<OutLayerResponse>, PromiseError>,
+    ) {
+        self.internal_settle_pending_deposit(execution_index, result.is_err());
+        self.key_derivations_in_flight.remove(&user);
+        let is_rotation = self.pending_key_rotations.remove(&user);
+
+        match result {
+            Ok(Some(outlayer_response)) => {
+                log!("OutLayer response for {}: success={}", user, outlayer_response.success);
+
+                // Check if execution was successful
+                if !outlayer_response.success {
+                    let error_msg = outlayer_response.error.unwrap_or_else(|| "Unknown error".to_string());
+                    log!("OutLayer execution failed for {}: {}", user, error_msg);
+                    self.internal_refund_failed_join(&user);
+                    self.internal_complete_outlayer_execution(execution_index, false);
+                    return;
+                }
+
+                // Parse result field to get DeriveKeyResponse
+                let key_response: DeriveKeyResponse = match serde_json::from_value(outlayer_response.result) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        log!("Failed to parse key derivation result for {}: {}", user, e);
+                        self.internal_refund_failed_join(&user);
+                        self.internal_complete_outlayer_execution(execution_index, false);
+                        return;
+                    }
+                };
+
+                log!("Public key derived for {}: {}", user, key_response.pubkey);
+
+                // The member left or was removed while the rotation was in flight
+                if is_rotation && self.members.get(&user).is_none() {
+                    log!("{} is no longer a member, rotated key dropped", user);
+                    self.internal_complete_outlayer_execution(execution_index, true);
+                    return;
+                }
+
+                // Store pubkey (and how it was derived, absent = HKDF)
+                let previous_pubkey = self.user_pubkeys.insert(&user, &key_response.pubkey);
+                if previous_pubkey.is_none() {
+                    self.voting_member_count += 1;
+                }
+                if key_response.key_derivation_algorithm == KeyDerivationAlgorithm::HKDF {
+                    self.user_key_algorithms.remove(&user);
+                } else {
+                    self.user_key_algorithms.insert(&user, &key_response.key_derivation_algorithm);
+                }
+                self.block_height_of_last_join_attempt.remove(&user);
+                if let Some(pending) = self.pending_key_derivations.remove(&user) {
+                    let paid = pending.deposit_paid.as_yoctonear();
+                    if paid > 0 {
+                        let previous = self.member_deposits.get(&user).unwrap_or(0);
+                        self.member_deposits.insert(&user, &(previous + paid));
+                    }
+                }
+
+                // Requests sent before join types were recorded: infer from membership
+                let join_type = self.pending_join_type.remove(&user).unwrap_or(JoinType {
+                    is_new: self.members.get(&user).is_none(),
+                });
+
+                // Membership start of a completed join (None for key rotations)
+                let joined_at = match self.members.get(&user) {
+                    // Add as member NOW (after successful key derivation)
+                    // This ensures user is only added if they have a valid pubkey
+                    None => {
+                        self.members.insert(&user, &MemberInfo {
+                            joined_at: env::block_timestamp(),
+                            key_rotated_at: None,
+                            last_voted_at: None,
+                            proposals_voted: 0,
+                            key_epoch: key_response.key_epoch,
+                        });
+                        self.member_index.insert(&user);
+                        self.member_count += 1;
+                        log!("User {} added to DAO with encryption key at {}", user, env::block_timestamp());
+                        Some(env::block_timestamp())
+                    }
+                    // Membership predates this key: keep the original joined_at
+                    Some(mut member_info) if previous_pubkey.is_some() => {
+                        log!("User {} encryption key re-derived (member since {})", user, member_info.joined_at);
+                        member_info.key_rotated_at = Some(env::block_timestamp());
+                        member_info.key_epoch = key_response.key_epoch;
+                        self.members.insert(&user, &member_info);
+                        None
+                    }
+                    Some(member_info) if join_type.is_new => {
+                        log!("User {} was added as a member while joining (member since {})", user, member_info.joined_at);
+                        Some(member_info.joined_at)
+                    }
+                    Some(member_info) => {
+                        log!("User {} completed join as pre-approved member (member since {})", user, member_info.joined_at);
+                        Some(member_info.joined_at)
+                    }
+                };
+                if let Some(joined_at) = joined_at {
+                    Event::MemberJoined { account_id: user.clone(), joined_at }.emit();
+                }
+
+                log!("User {} can now vote with encrypted ballots", user);
+                self.internal_complete_outlayer_execution(execution_index, true);
+            }
+            Ok(None) => {
+                log!("OutLayer execution failed for user {}", user);
+                self.internal_refund_failed_join(&user);
+                self.internal_complete_outlayer_execution(execution_index, false);
+            }
+            Err(e) => {
+                log!("Promise error for user {}: {:?}", user, e);
+                self.internal_refund_failed_join(&user);
+                self.internal_complete_outlayer_execution(execution_index, false);
+            }
+        }
+    }
+
+    /// Callback after proposal-scoped key derivation
+    #[private]
+    pub fn on_proposal_key_derived(
+        &mut self,
+        user: AccountId,
+        proposal_id: u64,
+        #[callback_result] result: Result<Option<OutLayerResponse>, PromiseError>,
+    ) {
+        match result {
+            Ok(Some(outlayer_response)) => {
+                if !outlayer_response.success {
+                    let error_msg = outlayer_response.error.unwrap_or_else(|| "Unknown error".to_string());
+                    log!("OutLayer execution failed for {}: {}", user, error_msg);
+                    env::panic_str(&format!("OutLayer error: {}", error_msg));
+                }
+
+                let key_response: DeriveKeyResponse = match serde_json::from_value(outlayer_response.result) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        log!("Failed to parse key derivation result for {}: {}", user, e);
+                        env::panic_str(&format!("Invalid result format: {}", e));
+                    }
+                };
+
+                self.proposal_pubkeys.insert(&account_proposal_key(&user, proposal_id), &key_response.pubkey);
+
+                log!("Proposal {} key derived for {}: {}", proposal_id, user, key_response.pubkey);
+            }
+            Ok(None) => {
+                log!("OutLayer execution failed for user {}", user);
+                env::panic_str("Failed to derive proposal key");
+            }
+            Err(e) => {
+                log!("Promise error for user {}: {:?}", user, e);
+                env::panic_str(&format!("Promise error: {:?}", e));
+            }
+        }
+    }
+
+    /// Callback after a replayed tally (read-only: returns the result, stores nothing)
+    #[private]
+    pub fn on_replay_tallied(
         &self,
         proposal_id: u64,
-        votes: Vec<Vote>,
-        attached_deposit: Balance,
-        payer: AccountId,
-    ) -> Promise {
-        // Get proposal to pass quorum info to worker
-        let proposal = self.proposals.get(&proposal_id).unwrap();
-
-        let code_source = serde_json::json!({
-            "repo": "https://github.com/zavodil/private-dao-ark",
-            "commit": "main",
-            "build_target": "wasm32-wasip1"
-        });
-
-        let resource_limits = serde_json::json!({
-            "max_instructions": 10000000000u64,
-            "max_memory_mb": 128u32,
-            "max_execution_seconds": 60u64
-        });
+        cutoff_timestamp: u64,
+        #[callback_result] result: Result<Option<OutLayerResponse>, PromiseError>,
+    ) -> TallyResponse {
+        let outlayer_response = match result {
+            Ok(Some(response)) => response,
+            Ok(None) => env::panic_str("Replay tally failed"),
+            Err(e) => env::panic_str(&format!("Promise error: {:?}", e)),
+        };
 
-        let input_data = serde_json::json!({
-            "action": "tally_votes",
-            "dao_account": env::current_account_id(),
-            "proposal_id": proposal_id,
-            "votes": votes,
-            "quorum": proposal.quorum
-        });
+        if !outlayer_response.success {
+            let error_msg = outlayer_response.error.unwrap_or_else(|| "Unknown error".to_string());
+            env::panic_str(&format!("OutLayer error: {}", error_msg));
+        }
 
-        // Call OutLayer with secrets_ref (master secret from keymaster)
-        let secrets_ref = serde_json::json!({
-            "profile": "default",
-            "account_id": "zavodil2.testnet"
-        });
+        let response: TallyResponse = serde_json::from_value(outlayer_response.result)
+            .unwrap_or_else(|e| env::panic_str(&format!("Invalid result format: {}", e)));
+
+        match tallied_vote_counts(&response) {
+            Some(vote_counts) => log!(
+                "Replay of proposal {} as of {}: {}, TOTAL={}",
+                proposal_id,
+                cutoff_timestamp,
+                format_vote_counts(&vote_counts),
+                response.total_votes
+            ),
+            None => log!(
+                "Replay of proposal {} as of {}: QUORUM NOT MET (counts hidden)",
+                proposal_id,
+                cutoff_timestamp
+            ),
+        }
 
-        ext_outlayer::ext(OUTLAYER_CONTRACT_ID.parse().unwrap())
-            .with_attached_deposit(NearToken::from_yoctonear(attached_deposit))
-            .with_unused_gas_weight(1)
-            .request_execution(
-                code_source,
-                resource_limits,
-                serde_json::to_string(&input_data).unwrap(),
-                Some(secrets_ref), // Master secret from keymaster
-                "Json".to_string(),
-                Some(payer), // Refund to payer
-            )
-            .then(
-                ext_self::ext(env::current_account_id())
-                    .with_static_gas(CALLBACK_GAS)
-                    .on_votes_tallied(proposal_id),
-            )
+        response
     }
 
-    /// Callback after key derivation
+    /// Callback after vote tallying
+    ///
+    /// Failures are logged and recorded in the OutLayer execution history;
+    /// the proposal is left unchanged so it can be finalized again. Results
+    /// for a proposal that is no longer active are ignored.
     #[private]
-    pub fn on_key_derived(
+    pub fn on_votes_tallied(
         &mut self,
-        user: AccountId,
+        proposal_id: u64,
+        execution_index: Option<u64>,
         #[callback_result] result: Result<Option<OutLayerResponse>, PromiseError>,
     ) {
+        if self.internal_settle_pending_deposit(execution_index, result.is_err()) {
+            // The failed call cost the treasury nothing: its retry may be DAO-paid too
+            self.dao_paid_tallies.remove(&proposal_id);
+        }
+        self.tallies_in_flight.remove(&proposal_id);
+
         match result {
             Ok(Some(outlayer_response)) => {
-                log!("OutLayer response for {}: success={}", user, outlayer_response.success);
+                log!("OutLayer response for proposal {}: success={}", proposal_id, outlayer_response.success);
 
                 // Check if execution was successful
                 if !outlayer_response.success {
                     let error_msg = outlayer_response.error.unwrap_or_else(|| "Unknown error".to_string());
-                    log!("OutLayer execution failed for {}: {}", user, error_msg);
-                    env::panic_str(&format!("OutLayer error: {}", error_msg));
+                    log!("OutLayer execution failed for proposal {}: {}", proposal_id, error_msg);
+                    self.internal_complete_outlayer_execution(execution_index, false);
+                    return;
                 }
 
-                // Parse result field to get DeriveKeyResponse
-                let key_response: DeriveKeyResponse = match serde_json::from_value(outlayer_response.result) {
+                // Parse result field to get TallyResponse
+                let response: TallyResponse = match serde_json::from_value(outlayer_response.result) {
                     Ok(r) => r,
                     Err(e) => {
-                        log!("Failed to parse key derivation result for {}: {}", user, e);
-                        env::panic_str(&format!("Invalid result format: {}", e));
+                        log!("Failed to parse tally result for proposal {}: {}", proposal_id, e);
+                        self.internal_complete_outlayer_execution(execution_index, false);
+                        return;
+                    }
+                };
+
+                // Get proposal; a late result must not replace a decision taken meanwhile
+                let Some(proposal) = self
+                    .proposals
+                    .get(&proposal_id)
+                    .filter(|proposal| proposal.status == ProposalStatus::Active)
+                else {
+                    match self.proposals.get(&proposal_id).map(|proposal| proposal.status) {
+                        Some(ProposalStatus::Cancelled) => {
+                            log!("Proposal {} was cancelled while tallying, result ignored", proposal_id)
+                        }
+                        _ => log!("Proposal {} is no longer active, tally result ignored", proposal_id),
+                    }
+                    self.internal_complete_outlayer_execution(execution_index, true);
+                    return;
+                };
+
+                // Check if vote counts are present (quorum met in TEE)
+                let tally_result = if let Some(vote_counts) = tallied_vote_counts(&response) {
+                    log!(
+                        "Votes tallied for proposal {}: {}, TOTAL={}, QUORUM MET",
+                        proposal_id,
+                        format_vote_counts(&vote_counts),
+                        response.total_votes
+                    );
+
+                    // Store full results
+                    TallyResult {
+                        quorum_met: true,
+                        yes_count: response.yes_count,
+                        no_count: response.no_count,
+                        abstain_count: response.abstain_count,
+                        vote_counts,
+                        total_votes: response.total_votes,
+                        tee_attestation: response.tee_attestation,
+                        votes_merkle_root: response.votes_merkle_root.clone(),
+                        merkle_proofs: response.merkle_proofs.clone(),
+                        merkle_proofs_available: response.merkle_proofs_available,
+                        counted_votes_root: response.counted_votes_root,
+                    }
+                } else {
+                    log!(
+                        "Votes tallied for proposal {}: TOTAL={}, QUORUM NOT MET (counts hidden)",
+                        proposal_id,
+                        response.total_votes
+                    );
+
+                    TallyResult {
+                        quorum_met: false,
+                        yes_count: None,
+                        no_count: None,
+                        abstain_count: None,
+                        vote_counts: HashMap::new(),
+                        total_votes: response.total_votes,
+                        tee_attestation: response.tee_attestation,
+                        votes_merkle_root: response.votes_merkle_root.clone(),
+                        merkle_proofs: response.merkle_proofs.clone(),
+                        merkle_proofs_available: response.merkle_proofs_available,
+                        counted_votes_root: String::new(),
                     }
                 };
 
-                log!("Public key derived for {}: {}", user, key_response.pubkey);
+                self.internal_complete_outlayer_execution(execution_index, true);
+                self.partial_tallies.remove(&proposal_id);
+                self.internal_decide_proposal(proposal, tally_result);
+            }
+            Ok(None) => {
+                log!("OutLayer execution failed for proposal {}", proposal_id);
+                self.internal_complete_outlayer_execution(execution_index, false);
+            }
+            Err(e) => {
+                log!("Promise error for proposal {}: {:?}", proposal_id, e);
+                self.internal_complete_outlayer_execution(execution_index, false);
+            }
+        }
+    }
+
+    /// Callback after tallying one chunk of votes (see `finalize_proposal_chunk`)
+    ///
+    /// Stores the new sealed running tally in the proposal's `PartialTally`.
+    /// On failure the chunk can be requested again with the same offset.
+    #[private]
+    pub fn on_votes_tallied_chunk(
+        &mut self,
+        proposal_id: u64,
+        chunk_end: u64,
+        execution_index: Option<u64>,
+        #[callback_result] result: Result<Option<OutLayerResponse>, PromiseError>,
+    ) {
+        if self.internal_settle_pending_deposit(execution_index, result.is_err()) {
+            self.dao_paid_tallies.remove(&proposal_id);
+        }
+
+        let Some(mut partial) = self.partial_tallies.get(&proposal_id).filter(|partial| partial.chunk_in_flight) else {
+            log!("Chunked finalization of proposal {} was cancelled or abandoned, result ignored", proposal_id);
+            self.internal_complete_outlayer_execution(execution_index, result.is_ok());
+            return;
+        };
+        partial.chunk_in_flight = false;
+        partial.updated_at = env::block_timestamp();
+
+        let response = match result {
+            Ok(Some(outlayer_response)) if outlayer_response.success => {
+                serde_json::from_value::<ChunkTallyResponse>(outlayer_response.result)
+                    .map_err(|e| format!("Failed to parse chunk tally result: {}", e))
+                    .and_then(|response| match response.next_offset == chunk_end {
+                        true => Ok(response),
+                        false => Err(format!("Sealed tally covers {} votes", response.next_offset)),
+                    })
+            }
+            Ok(Some(outlayer_response)) => {
+                Err(outlayer_response.error.unwrap_or_else(|| "Unknown error".to_string()))
+            }
+            Ok(None) => Err("OutLayer execution failed".to_string()),
+            Err(e) => Err(format!("Promise error: {:?}", e)),
+        };
+
+        let response = match response {
+            Ok(response) => response,
+            Err(error) => {
+                log!("Chunk ending at {} of proposal {} failed: {}", chunk_end, proposal_id, error);
+                self.partial_tallies.insert(&proposal_id, &partial);
+                self.internal_complete_outlayer_execution(execution_index, false);
+                return;
+            }
+        };
+
+        partial.sealed_tally = response.sealed_tally;
+        partial.next_offset = chunk_end;
+        self.partial_tallies.insert(&proposal_id, &partial);
+
+        self.internal_complete_outlayer_execution(execution_index, true);
+
+        log!("Chunk of proposal {} tallied up to vote {}", proposal_id, chunk_end);
+    }
+
+    /// Set the status and result of a tallied proposal and run the follow-ups of a decision
+    ///
+    /// Passes if quorum was met and "yes" (or, with vote options, any single
+    /// option) has the most votes. Without quorum it's rejected once the
+    /// deadline has passed and otherwise stays active to allow more votes.
+    fn internal_decide_proposal(&mut self, mut proposal: Proposal, tally_result: TallyResult) {
+        let proposal_id = proposal.id;
+        let was_active = proposal.status == ProposalStatus::Active;
+
+        if tally_result.quorum_met {
+            // Abstentions are neutral: a yes/no proposal passes on more yes than no
+            let passed = match proposal.vote_options {
+                Some(_) => plurality_winner(&tally_result.vote_counts).is_some(),
+                None => option_count(&tally_result.vote_counts, "yes") > option_count(&tally_result.vote_counts, "no"),
+            };
+
+            proposal.status = if passed {
+                ProposalStatus::Passed
+            } else {
+                ProposalStatus::Rejected
+            };
+        } else {
+            // Quorum not met - check if deadline passed
+            let deadline_passed = if let Some(deadline_ns) = proposal.deadline {
+                env::block_timestamp() >= deadline_ns
+            } else {
+                false // No deadline = never passed
+            };
+
+            if deadline_passed {
+                // Deadline passed + no quorum = Rejected
+                proposal.status = ProposalStatus::Rejected;
+                log!("Proposal {} rejected: deadline passed without reaching quorum", proposal_id);
+            } else {
+                // Deadline not passed or no deadline - keep Active to allow more votes
+                log!("Proposal {} remains active: quorum not met but deadline not passed", proposal_id);
+            }
+        }
+
+        proposal.tally_result = Some(tally_result);
+        self.internal_record_decision(proposal, was_active);
+    }
+
+    /// Store a proposal after its status was decided and run the follow-ups of leaving Active
+    fn internal_record_decision(&mut self, mut proposal: Proposal, was_active: bool) {
+        let proposal_id = proposal.id;
+        if was_active && proposal.status != ProposalStatus::Active {
+            proposal.decided_at = Some(env::block_timestamp());
+        }
+        self.proposals.insert(&proposal_id, &proposal);
+
+        if was_active && proposal.status != ProposalStatus::Active {
+            self.internal_index_proposal_status(proposal_id, Some(&ProposalStatus::Active), &proposal.status);
+            self.internal_release_active_proposal(&proposal.creator);
+        }
+
+        // Passed upgrades keep their WASM for `execute_upgrade`
+        if matches!(proposal.proposal_type, ProposalType::UpgradeContract { .. })
+            && !matches!(proposal.status, ProposalStatus::Active | ProposalStatus::Passed)
+        {
+            self.internal_drop_upgrade_wasm(proposal_id);
+        }
+
+        log!(
+            "Proposal {} finalized: {}",
+            proposal_id,
+            match proposal.status {
+                ProposalStatus::Passed => "PASSED",
+                ProposalStatus::Rejected => "REJECTED",
+                _ => "UNKNOWN"
+            }
+        );
+
+        if proposal.status != ProposalStatus::Active {
+            Event::ProposalFinalized {
+                proposal_id,
+                total_votes: proposal.tally_result.as_ref().map_or(0, |result| result.total_votes),
+                status: proposal.status,
+            }
+            .emit();
+        }
+    }
+
+    /// Reject a proposal whose deadline passed without any vote
+    ///
+    /// Returns the caller's attached deposit; a tally prepaid by the creator
+    /// (`Proposal::creator_deposit`) goes back to them.
+    fn internal_reject_unvoted_proposal(&mut self, mut proposal: Proposal, caller: AccountId, attached: NearToken) -> Promise {
+        log!("Proposal {} rejected: deadline passed without votes", proposal.id);
+
+        proposal.status = ProposalStatus::Rejected;
+        let creator_refund = std::mem::replace(&mut proposal.creator_deposit, NearToken::from_yoctonear(0));
+        self.held_deposits -= creator_refund.as_yoctonear();
+        let creator = proposal.creator.clone();
+        self.internal_record_decision(proposal, true);
+
+        if !creator_refund.is_zero() {
+            Promise::new(creator).transfer(creator_refund);
+        }
+        Promise::new(caller).transfer(attached)
+    }
+
+    /// Ballots to send for the chunk of votes `offset..chunk_end`
+    ///
+    /// Each voter goes with the chunk holding their last ballot, together with
+    /// all their earlier ballots. Ballots whose voter votes again later are left
+    /// for a later chunk.
+    fn internal_chunk_ballots(&self, proposal_id: u64, votes: &Vector<Vote>, offset: u64, chunk_end: u64) -> Vec<Vote> {
+        let mut ballots = Vec::new();
+
+        for index in offset..chunk_end {
+            let vote = votes.get(index).unwrap();
+            let indices = self.ballot_indices
+                .get(&account_proposal_key(&vote.user, proposal_id))
+                .unwrap_or_else(|| {
+                    env::panic_str(&format!("No ballot index for {}, use finalize_proposal", vote.user))
+                });
+
+            if indices.last() == Some(&index) {
+                ballots.extend(indices.iter().map(|&ballot| votes.get(ballot).unwrap()));
+            }
+        }
+
+        ballots
+    }
+
+    /// Remove a proposal's upgrade WASM and return the uploader's storage deposit
+    fn internal_drop_upgrade_wasm(&mut self, proposal_id: u64) -> Option<Vec<u8>> {
+        let wasm = self.pending_upgrade_wasm.remove(&proposal_id)?;
+
+        if let Some(upload) = self.upgrade_wasm_uploads.remove(&proposal_id) {
+            self.held_deposits -= upload.deposit.as_yoctonear();
+            if !upload.deposit.is_zero() {
+                Promise::new(upload.uploader.clone()).transfer(upload.deposit);
+            }
+            log!(
+                "Upgrade WASM of proposal {} released, {} returned to {}",
+                proposal_id,
+                upload.deposit.exact_amount_display(),
+                upload.uploader
+            );
+        }
+        Some(wasm)
+    }
+
+    /// Panic unless a passed proposal may be executed now
+    ///
+    /// Waits `DAOConfig::execution_delay_ns` after the decision and for
+    /// pending veto approvals to be resolved.
+    fn internal_assert_executable(&self, proposal: &Proposal) {
+        assert!(
+            proposal.status != ProposalStatus::Executed,
+            "Proposal was already executed"
+        );
+        assert_eq!(proposal.status, ProposalStatus::Passed, "Proposal has not passed");
+
+        let executable_at = proposal.decided_at.unwrap_or(0).saturating_add(self.config.execution_delay_ns);
+        assert!(
+            env::block_timestamp() >= executable_at,
+            "Proposal can be executed from {}",
+            executable_at
+        );
+        assert!(
+            self.veto_approvals.get(&proposal.id).is_none_or(|approvals| approvals.is_empty()),
+            "Proposal has pending veto approvals"
+        );
+    }
+
+    /// True if `account_id` already voted and the proposal is inside its lock-in window
+    fn internal_is_vote_locked_in(&self, proposal: &Proposal, account_id: &AccountId) -> bool {
+        let (Some(lock_in_ns), Some(deadline_ns)) = (self.config.vote_change_deadline_ns, proposal.deadline) else {
+            return false;
+        };
+
+        let in_lock_in = deadline_ns.saturating_sub(env::block_timestamp()) <= lock_in_ns;
+        let has_voted = self.ballot_indices.contains_key(&account_proposal_key(account_id, proposal.id));
+
+        in_lock_in && has_voted
+    }
+
+    /// Panic if an active proposal is inside its lock-in window or past its deadline
+    ///
+    /// Tallies read `delegations` when they run, so a change made after voting
+    /// closed (or during lock-in, when voters can no longer react) would move
+    /// votes of a proposal that is already decided in all but name.
+    fn internal_assert_delegations_unfrozen(&self) {
+        let now = env::block_timestamp();
+        let lock_in_ns = self.config.vote_change_deadline_ns.unwrap_or(0);
+
+        for proposal_id in self.active_proposal_ids.iter() {
+            let Some(deadline_ns) = self.proposals.get(&proposal_id).and_then(|proposal| proposal.deadline) else {
+                continue;
+            };
+            assert!(
+                now < deadline_ns.saturating_sub(lock_in_ns),
+                "Delegations are frozen until proposal {} is finalized",
+                proposal_id
+            );
+        }
+    }
+
+    /// Timeline entries of a proposal's votes matching `filter`
+    fn internal_vote_timeline(
+        &self,
+        proposal_id: u64,
+        filter: impl Fn(&Vote) -> bool,
+    ) -> Vec<VoteTimelineEntry> {
+        let votes = match self.votes.get(&proposal_id) {
+            Some(votes) => votes,
+            None => return Vec::new(),
+        };
+
+        votes
+            .iter()
+            .enumerate()
+            .filter(|(_, vote)| filter(vote))
+            .map(|(index, vote)| VoteTimelineEntry {
+                vote_index: index as u64,
+                user: vote.user,
+                timestamp: vote.timestamp,
+                block_height: vote.block_height,
+            })
+            .collect()
+    }
+
+    /// Add a member to `member_index`, converting a record stored in an older layout first
+    /// Returns false for non-members and members already indexed
+    fn internal_index_member(&mut self, account_id: &AccountId) -> bool {
+        if migrate_member_info(account_id) && self.user_pubkeys.get(account_id).is_some() {
+            // Baseline members predate `voting_member_count`
+            self.voting_member_count += 1;
+        }
+        self.members.get(account_id).is_some() && self.member_index.insert(account_id)
+    }
+
+    /// Owner or holder of the Admin role
+    fn internal_is_admin(&self, account_id: &AccountId) -> bool {
+        *account_id == self.owner || self.admins.contains(account_id)
+    }
+
+    /// Drop `account_id`'s Admin role and its approvals of pending vetoes
+    /// Returns false if it wasn't an admin
+    fn internal_revoke_admin(&mut self, account_id: &AccountId) -> bool {
+        if !self.admins.remove(account_id) {
+            return false;
+        }
+
+        // Approvals only matter while a proposal is Passed (vetoed and executed ones are final)
+        for proposal_id in self.passed_proposal_ids.iter() {
+            let Some(mut approvals) = self.veto_approvals.get(&proposal_id) else {
+                continue;
+            };
+            if let Some(position) = approvals.iter().position(|admin| admin == *account_id) {
+                approvals.swap_remove(position as u64);
+                if approvals.is_empty() {
+                    self.veto_approvals.remove(&proposal_id);
+                } else {
+                    self.veto_approvals.insert(&proposal_id, &approvals);
+                }
+                log!("Veto approval of {} on proposal {} withdrawn", account_id, proposal_id);
+            }
+        }
+        true
+    }
+
+    /// Decrement `creator`'s active proposal count once a proposal is decided
+    fn internal_release_active_proposal(&mut self, creator: &AccountId) {
+        match self.active_proposal_counts.get(creator).unwrap_or(0) {
+            0 | 1 => {
+                self.active_proposal_counts.remove(creator);
+            }
+            count => {
+                self.active_proposal_counts.insert(creator, &(count - 1));
+            }
+        }
+    }
+
+    /// Emit `VoteMilestoneReached` for every milestone `vote_count` newly crossed
+    ///
+    /// Quorums with a fixed vote target only (not TimeWeighted, SuperMajority or Relative).
+    fn internal_emit_vote_milestones(&mut self, proposal: &Proposal, vote_count: u64) {
+        let min_votes = match proposal.quorum {
+            QuorumType::Absolute { min_votes } => min_votes,
+            QuorumType::Unanimous => proposal.eligible_voters,
+            QuorumType::Percentage { min_percent_of_members } => {
+                (min_percent_of_members as u64 * proposal.eligible_voters).div_ceil(100)
+            }
+            QuorumType::TimeWeighted { .. } | QuorumType::SuperMajority { .. } | QuorumType::Relative { .. } => {
+                return
+            }
+        };
+        if min_votes == 0 {
+            return;
+        }
+
+        let last = self.proposal_vote_milestones.get(&proposal.id).unwrap_or(0);
+        let reached: Vec<u8> = VOTE_MILESTONES
+            .into_iter()
+            .filter(|&milestone| milestone > last)
+            .filter(|&milestone| vote_count as u128 * 100 >= milestone as u128 * min_votes as u128)
+            .collect();
+
+        for &milestone in &reached {
+            Event::VoteMilestoneReached {
+                proposal_id: proposal.id,
+                milestone_percent: milestone,
+                current_votes: vote_count,
+                quorum_min: min_votes,
+                timestamp: env::block_timestamp(),
+            }
+            .emit();
+        }
+
+        if let Some(&highest) = reached.last() {
+            self.proposal_vote_milestones.insert(&proposal.id, &highest);
+        }
+    }
+
+    /// Resolve the OutLayer deposit and refund account for a tally of `proposal`
+    ///
+    /// Debits the treasury or consumes the creator's deposit as configured
+    /// (saving `proposal` in the latter case). When the caller doesn't pay,
+    /// anything they attach tops up the treasury.
+    ///
+    /// The treasury pays for one tally per proposal, and only once its voting
+    /// deadline has passed: early-quorum attempts, retries and further chunks
+    /// are paid by their caller, so repeated calls can't drain the treasury.
+    fn internal_tally_funding(
+        &mut self,
+        proposal: &mut Proposal,
+        caller: AccountId,
+        attached: NearToken,
+    ) -> (NearToken, AccountId) {
+        let min_deposit = self.config.min_outlayer_deposit;
+
+        let voting_closed = proposal.deadline.is_none_or(|deadline_ns| env::block_timestamp() >= deadline_ns);
+
+        match self.config.outlayer_payer_mode {
+            OutLayerPayerMode::DAO if voting_closed && !self.dao_paid_tallies.contains(&proposal.id) => {
+                self.internal_reconcile_treasury();
+                self.treasury_balance += attached.as_yoctonear();
+                assert!(
+                    self.treasury_balance >= min_deposit.as_yoctonear(),
+                    "Treasury balance is too low for OutLayer execution (need {})",
+                    min_deposit.exact_amount_display()
+                );
+                self.treasury_balance -= min_deposit.as_yoctonear();
+                self.dao_paid_tallies.insert(&proposal.id);
+                return (min_deposit, env::current_account_id());
+            }
+            OutLayerPayerMode::Proposal { creator_pays: true } if !proposal.creator_deposit.is_zero() => {
+                self.treasury_balance += attached.as_yoctonear();
+                let deposit = std::mem::replace(&mut proposal.creator_deposit, NearToken::from_yoctonear(0));
+                self.held_deposits -= deposit.as_yoctonear();
+                self.proposals.insert(&proposal.id, proposal);
+                return (deposit, proposal.creator.clone());
+            }
+            _ => {}
+        }
+
+        // Caller pays (also: Proposal mode once the creator's deposit is spent,
+        // DAO mode before the deadline or once the DAO-paid tally was used)
+        assert!(
+            attached >= min_deposit,
+            "Minimum deposit is {} yoctoNEAR for OutLayer execution",
+            min_deposit.as_yoctonear()
+        );
+        (attached, caller)
+    }
 
-                // Store pubkey
-                self.user_pubkeys.insert(&user, &key_response.pubkey);
-
-                // Add as member NOW (after successful key derivation)
-                // This ensures user is only added if they have a valid pubkey
-                if self.members.get(&user).is_none() {
-                    self.members.insert(&user, &MemberInfo {
-                        joined_at: env::block_timestamp(),
-                    });
-                    self.member_count += 1;
-                    log!("User {} added to DAO with encryption key at {}", user, env::block_timestamp());
-                } else {
-                    log!("User {} pubkey updated (was pre-approved in private DAO)", user);
-                }
+    /// Remove `delegator`'s delegation and update the reverse index
+    /// Returns false if there was none
+    fn internal_clear_delegation(&mut self, delegator: &AccountId) -> bool {
+        let Some(delegate) = self.delegations.remove(delegator) else {
+            return false;
+        };
 
-                log!("User {} can now vote with encrypted ballots", user);
+        match self.delegations_received.get(&delegate).unwrap_or(0) {
+            0 | 1 => {
+                self.delegations_received.remove(&delegate);
             }
-            Ok(None) => {
-                log!("OutLayer execution failed for user {}", user);
-                env::panic_str("Failed to derive encryption key");
+            received => {
+                self.delegations_received.insert(&delegate, &(received - 1));
             }
-            Err(e) => {
-                log!("Promise error for user {}: {:?}", user, e);
-                env::panic_str(&format!("Promise error: {:?}", e));
+        }
+
+        if let Some(mut delegators) = self.delegators.get(&delegate) {
+            delegators.retain(|account_id| account_id != delegator);
+            if delegators.is_empty() {
+                self.delegators.remove(&delegate);
+            } else {
+                self.delegators.insert(&delegate, &delegators);
             }
         }
+        true
     }
 
-    /// Callback after vote tallying
-    #[private]
-    pub fn on_votes_tallied(
-        &mut self,
-        proposal_id: u64,
-        #[callback_result] result: Result<Option<OutLayerResponse>, PromiseError>,
-    ) {
-        match result {
-            Ok(Some(outlayer_response)) => {
-                log!("OutLayer response for proposal {}: success={}", proposal_id, outlayer_response.success);
+    /// Drop every delegation to `delegate` (its delegators vote for themselves again)
+    fn internal_clear_incoming_delegations(&mut self, delegate: &AccountId) {
+        for delegator in self.delegators.remove(delegate).unwrap_or_default() {
+            self.delegations.remove(&delegator);
+            log!("Delegation of {} to {} cleared", delegator, delegate);
+        }
+        self.delegations_received.remove(delegate);
+    }
 
-                // Check if execution was successful
-                if !outlayer_response.success {
-                    let error_msg = outlayer_response.error.unwrap_or_else(|| "Unknown error".to_string());
-                    log!("OutLayer execution failed for proposal {}: {}", proposal_id, error_msg);
-                    env::panic_str(&format!("OutLayer error: {}", error_msg));
-                }
+    /// Reject a second join attempt in the same block, then record this one
+    fn internal_record_join_attempt(&mut self, user: &AccountId) {
+        let block_height = env::block_height();
+        if self.block_height_of_last_join_attempt.get(user) == Some(block_height) {
+            env::panic_str("Please wait for the next block before retrying join");
+        }
+        self.block_height_of_last_join_attempt.insert(user, &block_height);
+    }
 
-                // Parse result field to get TallyResponse
-                let response: TallyResponse = match serde_json::from_value(outlayer_response.result) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        log!("Failed to parse tally result for proposal {}: {}", proposal_id, e);
-                        env::panic_str(&format!("Invalid result format: {}", e));
-                    }
-                };
+    /// Take the protocol fee from `attached` and return what's left
+    ///
+    /// The fee goes to `fee_recipient`, or to `treasury_balance` when that is this contract.
+    fn internal_take_protocol_fee(&mut self, attached: NearToken) -> NearToken {
+        let Some(recipient) = self.config.fee_recipient.clone() else {
+            return attached;
+        };
+        let fee = attached.as_yoctonear() * self.config.protocol_fee_basis_points as u128 / 10_000;
+        if fee == 0 {
+            return attached;
+        }
+
+        if recipient == env::current_account_id() {
+            self.treasury_balance += fee;
+        } else {
+            Promise::new(recipient.clone()).transfer(NearToken::from_yoctonear(fee));
+        }
+        self.protocol_fee_collected += fee;
 
-                // Get proposal
-                let mut proposal = self.proposals.get(&proposal_id).unwrap();
+        log!("Protocol fee of {} yoctoNEAR to {}", fee, recipient);
+        NearToken::from_yoctonear(attached.as_yoctonear() - fee)
+    }
 
-                // Check if vote counts are present (quorum met in TEE)
-                let quorum_met = response.yes_count.is_some();
+    /// Deposit that leaves at least `net` after `internal_take_protocol_fee`
+    fn internal_with_protocol_fee(&self, net: Balance) -> NearToken {
+        if self.config.fee_recipient.is_none() {
+            return NearToken::from_yoctonear(net);
+        }
+        let kept_basis_points = 10_000 - self.config.protocol_fee_basis_points as u128;
+        NearToken::from_yoctonear((net * 10_000).div_ceil(kept_basis_points))
+    }
 
-                if quorum_met {
-                    let yes_count = response.yes_count.unwrap();
-                    let no_count = response.no_count.unwrap();
+    /// Voters in `votes` whose keys weren't derived with HKDF (for the tally input)
+    fn internal_key_derivation_algorithms(
+        &self,
+        votes: &[Vote],
+    ) -> std::collections::BTreeMap<AccountId, KeyDerivationAlgorithm> {
+        votes
+            .iter()
+            .filter_map(|vote| {
+                self.user_key_algorithms
+                    .get(&vote.user)
+                    .map(|algorithm| (vote.user.clone(), algorithm))
+            })
+            .collect()
+    }
 
-                    log!(
-                        "Votes tallied for proposal {}: YES={}, NO={}, TOTAL={}, QUORUM MET",
-                        proposal_id,
-                        yes_count,
-                        no_count,
-                        response.total_votes
-                    );
+    /// Voters in `votes` who rotated their key (for the tally input)
+    fn internal_key_epochs(&self, votes: &[Vote]) -> std::collections::BTreeMap<AccountId, u32> {
+        votes
+            .iter()
+            .filter_map(|vote| {
+                self.members
+                    .get(&vote.user)
+                    .filter(|member_info| member_info.key_epoch > 0)
+                    .map(|member_info| (vote.user.clone(), member_info.key_epoch))
+            })
+            .collect()
+    }
 
-                    // Determine if passed (quorum met AND more yes than no)
-                    let passed = yes_count > no_count;
+    /// Every delegation of an indexed member (for the tally input)
+    ///
+    /// Only members in `member_index` are covered; index members added before
+    /// it existed with `migrate_index_members`.
+    fn internal_active_delegations(&self) -> std::collections::BTreeMap<AccountId, AccountId> {
+        self.member_index
+            .iter()
+            .filter_map(|member| self.delegations.get(&member).map(|delegate| (member, delegate)))
+            .collect()
+    }
 
-                    proposal.status = if passed {
-                        ProposalStatus::Passed
-                    } else {
-                        ProposalStatus::Rejected
-                    };
+    /// Remember who funded an OutLayer execution until its callback runs
+    fn internal_record_pending_deposit(&mut self, execution_index: u64, payer: &AccountId, amount: Balance) {
+        self.pending_deposits.insert(&execution_index, &PendingDeposit {
+            payer: payer.clone(),
+            amount: NearToken::from_yoctonear(amount),
+        });
+    }
 
-                    // Store full results
-                    proposal.tally_result = Some(TallyResult {
-                        quorum_met: true,
-                        yes_count: Some(yes_count),
-                        no_count: Some(no_count),
-                        total_votes: response.total_votes,
-                        tee_attestation: response.tee_attestation,
-                        votes_merkle_root: response.votes_merkle_root.clone(),
-                        merkle_proofs: response.merkle_proofs.clone(),
-                    });
-                } else {
-                    log!(
-                        "Votes tallied for proposal {}: TOTAL={}, QUORUM NOT MET (counts hidden)",
-                        proposal_id,
-                        response.total_votes
-                    );
+    /// Forget the deposit of a finished execution, refunding it if the OutLayer call failed
+    ///
+    /// A failed call returns the attached deposit to this contract; otherwise
+    /// OutLayer settles with the payer itself. DAO-paid deposits go back to the
+    /// treasury; for successful DAO-paid calls OutLayer's refund of the unused
+    /// part lands on the contract balance and is picked up by `reconcile_treasury`.
+    ///
+    /// Returns true if a DAO-paid deposit went back to the treasury.
+    fn internal_settle_pending_deposit(&mut self, execution_index: Option<u64>, call_failed: bool) -> bool {
+        let Some(pending) = execution_index.and_then(|index| self.pending_deposits.remove(&index)) else {
+            return false;
+        };
+        let dao_paid = pending.payer == env::current_account_id();
+        if pending.amount.is_zero() {
+            return false;
+        }
+        if !call_failed {
+            if dao_paid {
+                self.unreconciled_outlayer_spend += pending.amount.as_yoctonear();
+            }
+            return false;
+        }
 
-                    // Quorum not met - check if deadline passed
-                    let deadline_passed = if let Some(deadline_ns) = proposal.deadline {
-                        env::block_timestamp() >= deadline_ns
-                    } else {
-                        false // No deadline = never passed
-                    };
-
-                    if deadline_passed {
-                        // Deadline passed + no quorum = Rejected
-                        proposal.status = ProposalStatus::Rejected;
-                        log!("Proposal {} rejected: deadline passed without reaching quorum", proposal_id);
-                    } else {
-                        // Deadline not passed or no deadline - keep Active to allow more votes
-                        log!("Proposal {} remains active: quorum not met but deadline not passed", proposal_id);
-                    }
+        if dao_paid {
+            self.treasury_balance += pending.amount.as_yoctonear();
+        } else {
+            Promise::new(pending.payer.clone()).transfer(pending.amount);
+        }
+        log!("OutLayer call failed, refunded {} to {}", pending.amount.exact_amount_display(), pending.payer);
+        dao_paid
+    }
 
-                    proposal.tally_result = Some(TallyResult {
-                        quorum_met: false,
-                        yes_count: None,
-                        no_count: None,
-                        total_votes: response.total_votes,
-                        tee_attestation: response.tee_attestation,
-                        votes_merkle_root: response.votes_merkle_root.clone(),
-                        merkle_proofs: response.merkle_proofs.clone(),
-                    });
-                }
+    /// Credit OutLayer refunds of DAO-paid executions back to the treasury
+    ///
+    /// OutLayer doesn't report refunds, so this credits the balance not held
+    /// for storage, the treasury or `held_deposits`, up to what DAO-paid executions spent.
+    /// Returns the amount credited.
+    fn internal_reconcile_treasury(&mut self) -> Balance {
+        let storage_cost = env::storage_byte_cost().as_yoctonear() * env::storage_usage() as u128;
+        let free_balance = env::account_balance()
+            .as_yoctonear()
+            .saturating_sub(storage_cost)
+            .saturating_sub(self.treasury_balance)
+            .saturating_sub(self.held_deposits);
+        let credited = free_balance.min(self.unreconciled_outlayer_spend);
+
+        self.unreconciled_outlayer_spend -= credited;
+        self.treasury_balance += credited;
+        credited
+    }
 
-                self.proposals.insert(&proposal_id, &proposal);
+    /// Drop a join whose key derivation failed and refund the storage deposit kept for it
+    fn internal_refund_failed_join(&mut self, user: &AccountId) {
+        let Some(pending) = self.pending_key_derivations.remove(user) else {
+            return;
+        };
+        self.held_deposits -= pending.deposit_paid.as_yoctonear();
+        self.pending_join_type.remove(user);
+        self.block_height_of_last_join_attempt.remove(user);
 
-                log!(
-                    "Proposal {} finalized: {}",
-                    proposal_id,
-                    match proposal.status {
-                        ProposalStatus::Passed => "PASSED",
-                        ProposalStatus::Rejected => "REJECTED",
-                        _ => "UNKNOWN"
-                    }
-                );
-            }
-            Ok(None) => {
-                log!("OutLayer execution failed for proposal {}", proposal_id);
-                env::panic_str("Failed to tally votes");
-            }
-            Err(e) => {
-                log!("Promise error for proposal {}: {:?}", proposal_id, e);
-                env::panic_str(&format!("Promise error: {:?}", e));
-            }
+        if !pending.deposit_paid.is_zero() {
+            Promise::new(user.clone()).transfer(pending.deposit_paid);
+            log!("Join of {} failed, refunded {}", user, pending.deposit_paid.exact_amount_display());
+        }
+    }
+
+    /// Track a join until its key is derived
+    ///
+    /// No retry can start while the previous derivation is in flight, so the
+    /// entry never holds the deposit of an earlier attempt.
+    fn internal_record_pending_key_derivation(&mut self, user: &AccountId, retained_deposit: Balance) {
+        self.pending_key_derivations.insert(user, &PendingKeyDerivation {
+            initiated_at: env::block_timestamp(),
+            deposit_paid: NearToken::from_yoctonear(retained_deposit),
+        });
+        self.held_deposits += retained_deposit;
+    }
+
+    /// Return the storage deposit a departing member paid to join (nothing if none is recorded)
+    fn internal_refund_member_deposit(&mut self, account_id: &AccountId) {
+        if let Some(deposit) = self.member_deposits.remove(account_id) {
+            self.held_deposits -= deposit;
+            log!("Refunding {} yoctoNEAR storage deposit to {}", deposit, account_id);
+            Promise::new(account_id.clone()).transfer(NearToken::from_yoctonear(deposit));
+        }
+    }
+
+    /// ID index of a proposal status (None for statuses without one)
+    fn proposal_status_index(&self, status: &ProposalStatus) -> Option<&UnorderedSet<u64>> {
+        match status {
+            ProposalStatus::Active => Some(&self.active_proposal_ids),
+            ProposalStatus::Passed => Some(&self.passed_proposal_ids),
+            ProposalStatus::Rejected => Some(&self.rejected_proposal_ids),
+            _ => None,
+        }
+    }
+
+    fn proposal_status_index_mut(&mut self, status: &ProposalStatus) -> Option<&mut UnorderedSet<u64>> {
+        match status {
+            ProposalStatus::Active => Some(&mut self.active_proposal_ids),
+            ProposalStatus::Passed => Some(&mut self.passed_proposal_ids),
+            ProposalStatus::Rejected => Some(&mut self.rejected_proposal_ids),
+            _ => None,
+        }
+    }
+
+    /// Move a proposal between status indices (`from` is None for a new proposal)
+    fn internal_index_proposal_status(&mut self, proposal_id: u64, from: Option<&ProposalStatus>, to: &ProposalStatus) {
+        if let Some(ids) = from.and_then(|from| self.proposal_status_index_mut(from)) {
+            ids.remove(&proposal_id);
+        }
+        if let Some(ids) = self.proposal_status_index_mut(to) {
+            ids.insert(&proposal_id);
+        }
+    }
+
+    fn assert_not_paused(&self) {
+        assert!(!self.paused, "DAO is paused");
+    }
+
+    /// Panic if `max_members` leaves no room for another member
+    fn assert_below_member_cap(&self) {
+        if let Some(max) = self.max_members {
+            assert!(self.member_count < max, "DAO is full: member limit of {} reached", max);
+        }
+    }
+
+    /// Panic if `account_id` is blacklisted
+    fn assert_not_blacklisted(&self, account_id: &AccountId) {
+        if let Some(reason) = self.blacklisted.get(account_id) {
+            env::panic_str(&format!("Account is blacklisted: {}", reason));
         }
     }
 
+    /// Assert `new()` has not run yet (contract state must not exist)
+    fn assert_not_initialized() {
+        assert!(!env::state_exists(), "Already initialized");
+    }
+
     /// Assert caller is owner
     fn assert_owner(&self) {
+        Self::assert_caller_is(&self.owner);
+    }
+
+    /// Assert caller is `expected` (shared by all owner checks, including `reset_state`
+    /// which runs before `self` exists)
+    fn assert_caller_is(expected: &AccountId) {
         assert_eq!(
-            env::predecessor_account_id(),
-            self.owner,
+            &env::predecessor_account_id(),
+            expected,
             "Only owner can call this method"
         );
     }
@@ -901,12 +4075,357 @@ impl PrivateDAO {
             owner: self.owner.clone(),
             membership_mode: self.membership_mode.clone(),
             member_count: self.member_count,
+            max_members: self.max_members,
+        }
+    }
+
+    /// Get contract version
+    pub fn get_version(&self) -> String {
+        CONTRACT_VERSION.to_string()
+    }
+
+    /// Get deployment info (version, deploy and last upgrade timestamps)
+    pub fn get_deploy_info(&self) -> DeployInfo {
+        DeployInfo {
+            version: CONTRACT_VERSION.to_string(),
+            deployed_at: self.deployed_at,
+            last_upgraded_at: self.last_upgraded_at,
+        }
+    }
+
+    /// Get core state counters (diagnostics after upgrades and migrations)
+    ///
+    /// Reads every proposal ID to count vote Vectors: may run out of view gas
+    /// on DAOs with many thousands of proposals.
+    pub fn get_state_summary(&self) -> StateSummary {
+        StateSummary {
+            owner: self.owner.clone(),
+            member_count: self.member_count,
+            proposal_count: self.proposals.len(),
+            vote_vector_count: self
+                .proposals
+                .keys()
+                .filter(|proposal_id| self.votes.contains_key(proposal_id))
+                .count() as u64,
+            next_proposal_id: self.next_proposal_id,
+            membership_mode: self.membership_mode.clone(),
+            contract_version: CONTRACT_VERSION.to_string(),
+            is_paused: self.paused,
+        }
+    }
+
+    /// List detected state inconsistencies (empty if none found)
+    ///
+    /// Only checks what can be verified without iterating lookup-only collections.
+    pub fn verify_state_consistency(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        if self.next_proposal_id < self.proposals.len() {
+            issues.push(format!(
+                "next_proposal_id {} is below the proposal count {}",
+                self.next_proposal_id,
+                self.proposals.len()
+            ));
+        }
+
+        let mut active_by_creator: std::collections::BTreeMap<AccountId, u64> = Default::default();
+        for (proposal_id, proposal) in self.proposals.iter() {
+            if proposal.id != proposal_id {
+                issues.push(format!("Proposal stored under ID {} has id {}", proposal_id, proposal.id));
+            }
+            if proposal_id >= self.next_proposal_id {
+                issues.push(format!(
+                    "Proposal {} is not below next_proposal_id {}",
+                    proposal_id, self.next_proposal_id
+                ));
+            }
+            let active = active_by_creator.entry(proposal.creator.clone()).or_default();
+            if proposal.status == ProposalStatus::Active {
+                *active += 1;
+            }
+        }
+        for (creator, active) in active_by_creator {
+            let recorded = self.active_proposal_counts.get(&creator).unwrap_or(0);
+            if recorded != active {
+                issues.push(format!(
+                    "active_proposal_counts for {} is {} but {} proposals are active",
+                    creator, recorded, active
+                ));
+            }
+        }
+
+        if self.treasury_balance > env::account_balance().as_yoctonear() {
+            issues.push(format!(
+                "treasury_balance {} exceeds the account balance {}",
+                self.treasury_balance,
+                env::account_balance().as_yoctonear()
+            ));
+        }
+
+        let stored_executions = self.outlayer_execution_count.min(MAX_OUTLAYER_EXECUTION_HISTORY);
+        if self.outlayer_executions.len() != stored_executions {
+            issues.push(format!(
+                "outlayer_executions holds {} entries, expected {}",
+                self.outlayer_executions.len(),
+                stored_executions
+            ));
+        }
+        if self.outlayer_executions_succeeded > self.outlayer_executions_completed
+            || self.outlayer_executions_completed > self.outlayer_executions.len()
+        {
+            issues.push(format!(
+                "OutLayer outcome counters out of range: {} succeeded, {} completed, {} stored",
+                self.outlayer_executions_succeeded,
+                self.outlayer_executions_completed,
+                self.outlayer_executions.len()
+            ));
+        }
+
+        issues
+    }
+
+    /// Check if account is a member
+    pub fn is_member(&self, account_id: AccountId) -> bool {
+        self.members.get(&account_id).is_some()
+    }
+
+    /// Check if account has the Admin role (the owner always does)
+    pub fn is_admin(&self, account_id: AccountId) -> bool {
+        self.internal_is_admin(&account_id)
+    }
+
+    /// Get admins granted via `add_admin` (excludes the owner)
+    pub fn get_admins(&self) -> Vec<AccountId> {
+        self.admins.to_vec()
+    }
+
+    /// Get allowed proposal tags (empty = any tag allowed)
+    pub fn get_proposal_categories(&self) -> Vec<String> {
+        self.config.proposal_categories.clone()
+    }
+
+    /// Get the treasury available for DAO-paid tallies
+    pub fn get_treasury_balance(&self) -> NearToken {
+        NearToken::from_yoctonear(self.treasury_balance)
+    }
+
+    /// Get the total protocol fees taken since deployment
+    pub fn get_protocol_fee_collected(&self) -> NearToken {
+        NearToken::from_yoctonear(self.protocol_fee_collected)
+    }
+
+    /// Get the member an account delegated to
+    pub fn get_delegate(&self, account_id: AccountId) -> Option<AccountId> {
+        self.delegations.get(&account_id)
+    }
+
+    /// Get delegation concentration statistics
+    ///
+    /// `gini_coefficient` is computed over all members (members without
+    /// delegations count as 0), normalized so one member holding every
+    /// delegation gives 1.0.
+    pub fn get_delegation_stats(&self) -> DelegationStats {
+        let counts: Vec<u64> = self.delegations_received.values().collect();
+
+        DelegationStats {
+            total_delegations: counts.iter().sum(),
+            unique_delegates: counts.len() as u64,
+            max_delegations_to_one_person: counts.iter().copied().max().unwrap_or(0),
+            gini_coefficient: gini_coefficient(counts, self.member_count),
+        }
+    }
+
+    /// Get DAO-level participation metrics
+    ///
+    /// Status counts come from the status indexes, so executed proposals are
+    /// not counted as passed. `total_proposals` includes cancelled ones.
+    pub fn get_governance_stats(&self) -> GovernanceStats {
+        let total_proposals = self.proposals.len();
+
+        GovernanceStats {
+            total_proposals,
+            active_proposals: self.active_proposal_ids.len(),
+            passed_proposals: self.passed_proposal_ids.len(),
+            rejected_proposals: self.rejected_proposal_ids.len(),
+            total_votes_cast: self.total_votes_cast,
+            avg_votes_per_proposal: self.total_votes_cast.checked_div(total_proposals).unwrap_or(0),
         }
     }
 
-    /// Check if account is a member
-    pub fn is_member(&self, account_id: AccountId) -> bool {
-        self.members.get(&account_id).is_some()
+    /// Get the `limit` members with the most delegations received (most first)
+    pub fn get_top_delegates(&self, limit: u64) -> Vec<(AccountId, u64)> {
+        let mut delegates = self.delegations_received.to_vec();
+        delegates.sort_by(|(a_id, a), (b_id, b)| b.cmp(a).then_with(|| a_id.cmp(b_id)));
+        delegates.truncate(limit as usize);
+        delegates
+    }
+
+    /// Get admins who approved vetoing a proposal
+    pub fn get_veto_approvals(&self, proposal_id: u64) -> Vec<AccountId> {
+        self.veto_approvals
+            .get(&proposal_id)
+            .map(|approvals| approvals.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Check if a member can no longer change their vote on a proposal
+    /// (already voted and inside `DAOConfig::vote_change_deadline_ns` of the deadline)
+    pub fn is_vote_locked_in(&self, proposal_id: u64, account_id: AccountId) -> bool {
+        self.proposals
+            .get(&proposal_id)
+            .is_some_and(|proposal| self.internal_is_vote_locked_in(&proposal, &account_id))
+    }
+
+    /// Estimate the deposit to attach to `finalize_proposal`
+    ///
+    /// `min_outlayer_deposit` + `per_vote_outlayer_cost` × vote count + callback gas
+    /// at the minimum gas price. An estimate only - OutLayer refunds unused deposit.
+    pub fn estimate_finalization_cost(&self, proposal_id: u64) -> NearToken {
+        let vote_count = self.get_vote_count(proposal_id) as Balance;
+        let per_vote = self.config.per_vote_outlayer_cost.as_yoctonear();
+        let callback_cost = CALLBACK_GAS.as_gas() as Balance * ESTIMATED_GAS_PRICE;
+
+        NearToken::from_yoctonear(
+            self.config.min_outlayer_deposit.as_yoctonear()
+                .saturating_add(per_vote.saturating_mul(vote_count))
+                .saturating_add(callback_cost),
+        )
+    }
+
+    /// Estimate whether a proposal has reached quorum without running the TEE tally
+    ///
+    /// Counts distinct voters (re-votes once). Ballots may be dummies, so
+    /// `definitely_met` assumes none are; only `finalize_proposal` gives the
+    /// real answer. Mirrors `check_early_quorum` in the worker.
+    pub fn estimate_quorum_status(&self, proposal_id: u64) -> EarlyQuorumStatus {
+        let proposal = self.proposals.get(&proposal_id).expect("Proposal not found");
+
+        let voters: std::collections::HashSet<AccountId> = self
+            .votes
+            .get(&proposal_id)
+            .map(|votes| votes.iter().map(|vote| vote.user).collect())
+            .unwrap_or_default();
+
+        early_quorum_status(&proposal.quorum, voters.len() as u64, proposal.eligible_voters)
+    }
+
+    /// Minimum deposit for `join_dao` (member storage + OutLayer key derivation + protocol fee)
+    pub fn get_min_join_cost(&self) -> NearToken {
+        self.internal_with_protocol_fee(STORAGE_DEPOSIT_PER_MEMBER + self.config.min_outlayer_deposit.as_yoctonear())
+    }
+
+    /// Minimum deposit for `cast_vote` (vote storage + protocol fee)
+    pub fn get_min_vote_cost(&self) -> NearToken {
+        self.internal_with_protocol_fee(self.config.min_vote_deposit.as_yoctonear())
+    }
+
+    /// Estimate the storage deposit for a proposal with this text (+20% margin)
+    ///
+    /// Worst case for the fields not given (longest creator account ID, largest
+    /// quorum variant, deadline set). The tally result stored at finalization is
+    /// not included, nor are the options of multi-option proposals. Attachments
+    /// don't exist yet; `tags` is the only other variable-size field.
+    pub fn estimate_proposal_storage_deposit(
+        &self,
+        title: String,
+        description: String,
+        tags: Option<Vec<String>>,
+    ) -> NearToken {
+        let proposal = Proposal {
+            id: 0,
+            title,
+            description,
+            creator: worst_case_account_id(),
+            created_at: 0,
+            deadline: Some(0),
+            quorum: QuorumType::TimeWeighted { base_votes: 0, decay_halflife_ns: 0 },
+            status: ProposalStatus::Active,
+            tally_result: None,
+            proposal_type: ProposalType::Governance,
+            tags: tags.unwrap_or_default(),
+            creator_deposit: NearToken::from_yoctonear(0),
+            eligible_voters: 0,
+            vote_options: None,
+            execution_payload: None,
+            amended_at: None,
+            decided_at: Some(0),
+        };
+
+        // UnorderedMap keeps three records per entry: key → index, index → key, index → value
+        let value_bytes = borsh::to_vec(&proposal).unwrap().len() as u64;
+        let index_bytes = 2 * (STORAGE_RECORD_OVERHEAD + STORAGE_KEY_BYTES + 8);
+
+        storage_deposit_with_margin(STORAGE_RECORD_OVERHEAD + STORAGE_KEY_BYTES + value_bytes + index_bytes)
+    }
+
+    /// Estimate the storage deposit for a vote with a ciphertext of `encrypted_vote_len`
+    /// hex characters (+20% margin)
+    ///
+    /// Includes the voter's participation record. Compare with `get_min_vote_cost`,
+    /// which is what `cast_vote` currently enforces.
+    pub fn estimate_vote_storage_deposit(&self, encrypted_vote_len: u64) -> NearToken {
+        // Serialized with an empty ciphertext; its bytes are added below without allocating them
+        let vote = Vote {
+            user: worst_case_account_id(),
+            encrypted_vote: String::new(),
+            timestamp: 0,
+            block_height: 0,
+        };
+
+        let empty_vote_bytes = borsh::to_vec(&vote).unwrap().len() as u64;
+        // Vote index appended to `ballot_indices`
+        let participation_bytes = 8;
+
+        let bytes = (STORAGE_RECORD_OVERHEAD + STORAGE_KEY_BYTES + empty_vote_bytes + participation_bytes)
+            .checked_add(encrypted_vote_len)
+            .unwrap_or_else(|| env::panic_str("encrypted_vote_len is too large"));
+
+        storage_deposit_with_margin(bytes)
+    }
+
+    /// Get user's proposal-scoped public key (hex)
+    pub fn get_proposal_pubkey(&self, proposal_id: u64, account_id: AccountId) -> Option<String> {
+        self.proposal_pubkeys.get(&account_proposal_key(&account_id, proposal_id))
+    }
+
+    /// Get DAO settings
+    pub fn get_config(&self) -> DAOConfig {
+        self.config.clone()
+    }
+
+    /// Whether the owner has paused the DAO (see `pause`)
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Get the OutLayer contract this DAO sends executions to
+    pub fn get_outlayer_contract(&self) -> AccountId {
+        self.outlayer_contract.clone()
+    }
+
+    /// Get the keymaster secrets reference sent with every OutLayer execution
+    pub fn get_secrets_ref(&self) -> serde_json::Value {
+        serde_json::from_str(&self.secrets_ref).expect("Stored secrets_ref is valid JSON")
+    }
+
+    /// Get the account a pending ownership transfer is waiting on
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+
+    /// Check if account is banned from joining
+    pub fn is_blacklisted(&self, account_id: AccountId) -> bool {
+        self.blacklisted.contains_key(&account_id)
+    }
+
+    /// Check if account is on the whitelist
+    pub fn is_whitelisted(&self, account_id: AccountId) -> bool {
+        self.whitelisted.contains(&account_id)
+    }
+
+    /// Get the open invitation of an account (may already be expired)
+    pub fn get_invitation(&self, account_id: AccountId) -> Option<InvitationInfo> {
+        self.pending_invitations.get(&account_id)
     }
 
     /// Get member info (joined_at timestamp)
@@ -914,16 +4433,153 @@ impl PrivateDAO {
         self.members.get(&account_id)
     }
 
+    /// Members whose latest ballot was cast at or after `since_ns`
+    ///
+    /// Scans every member, so large DAOs may hit the view gas limit.
+    pub fn get_active_member_count(&self, since_ns: u64) -> u64 {
+        self.member_index
+            .iter()
+            .filter_map(|account_id| self.members.get(&account_id))
+            .filter(|info| info.last_voted_at.is_some_and(|voted_at| voted_at >= since_ns))
+            .count() as u64
+    }
+
+    /// List members with their join timestamps, `limit` capped at 100
+    ///
+    /// Order is stable between calls but changes when members leave
+    /// (the last member takes the removed one's place).
+    pub fn get_members(&self, from_index: u64, limit: u64) -> Vec<(AccountId, MemberInfo)> {
+        let accounts = self.member_index.as_vector();
+        let end = from_index
+            .saturating_add(limit.min(MAX_MEMBERS_PAGE_SIZE))
+            .min(accounts.len());
+
+        (from_index.min(end)..end)
+            .filter_map(|index| accounts.get(index))
+            .filter_map(|account_id| {
+                let info = self.members.get(&account_id)?;
+                Some((account_id, info))
+            })
+            .collect()
+    }
+
     /// Get user's public key
     pub fn get_user_pubkey(&self, account_id: AccountId) -> Option<String> {
         self.user_pubkeys.get(&account_id)
     }
 
+    /// Get the algorithm the user's public key was derived with (HKDF if not recorded)
+    pub fn get_user_key_algorithm(&self, account_id: AccountId) -> KeyDerivationAlgorithm {
+        self.user_key_algorithms.get(&account_id).unwrap_or_default()
+    }
+
     /// Get proposal
     pub fn get_proposal(&self, proposal_id: u64) -> Option<Proposal> {
         self.proposals.get(&proposal_id)
     }
 
+    /// ID the next `create_proposal` call will get
+    ///
+    /// Only a prediction: if another member's `create_proposal` lands first
+    /// (even in the same block), the caller's proposal gets a later ID.
+    /// Use the ID returned by `create_proposal` as the source of truth.
+    pub fn get_next_proposal_id(&self) -> u64 {
+        self.next_proposal_id
+    }
+
+    /// Number of stored proposals
+    ///
+    /// Currently equals `get_next_proposal_id() - 1` because proposals are never
+    /// deleted, but use this for counting rather than relying on that.
+    pub fn get_proposal_count(&self) -> u64 {
+        self.proposals.len()
+    }
+
+    /// Check if a proposal passed (executed proposals count as passed)
+    ///
+    /// Lightweight integration point for other contracts (see `ext::ext_dao`).
+    /// Returns false for unknown proposals.
+    pub fn is_proposal_passed(&self, proposal_id: u64) -> bool {
+        self.proposals
+            .get(&proposal_id)
+            .map(|proposal| matches!(proposal.status, ProposalStatus::Passed | ProposalStatus::Executed))
+            .unwrap_or(false)
+    }
+
+    /// Get just the status of a proposal (see `ext::ext_dao`)
+    pub fn get_proposal_status(&self, proposal_id: u64) -> Option<ProposalStatus> {
+        self.proposals.get(&proposal_id).map(|proposal| proposal.status)
+    }
+
+    /// Verify an externally supplied Merkle proof against the proposal's stored root
+    ///
+    /// Unlike `get_vote_proofs` (which returns the worker's stored proofs), this
+    /// checks a proof computed independently, e.g. by recomputing the tree from
+    /// `get_votes`. Uses the same format as the WASI worker: hex-encoded SHA256
+    /// hashes, parents = SHA256(left_hex || right_hex).
+    ///
+    /// # Arguments
+    /// * `vote_index` - Leaf position (determines left/right order at each level)
+    /// * `vote_hash` - Leaf hash, SHA256(user || timestamp_le || encrypted_vote)
+    /// * `proof_path` - Sibling hashes from leaf to root
+    ///
+    /// Returns false if the proposal doesn't exist or hasn't been tallied.
+    pub fn verify_submitted_vote_proof(
+        &self,
+        proposal_id: u64,
+        vote_index: u64,
+        vote_hash: String,
+        proof_path: Vec<String>,
+    ) -> bool {
+        let root = match self.proposals.get(&proposal_id).and_then(|p| p.tally_result) {
+            Some(result) => result.votes_merkle_root,
+            None => return false,
+        };
+
+        merkle_root_from_path(vote_hash, vote_index, &proof_path) == root
+    }
+
+    /// Verify that a stored vote is included in the proposal's tallied Merkle root
+    ///
+    /// Unlike `verify_submitted_vote_proof`, the leaf is not taken on trust: it is
+    /// recomputed as SHA256(voter || timestamp_le || encrypted_vote) from the vote
+    /// stored at `proof.vote_index`, which must match `proof.voter`,
+    /// `proof.timestamp` and `proof.vote_hash`. The path walk is O(log2(vote_count)).
+    ///
+    /// Returns false (never panics) for unknown or untallied proposals and
+    /// malformed proofs.
+    pub fn verify_vote_inclusion(&self, proposal_id: u64, proof: MerkleProof) -> bool {
+        let root = match self.proposals.get(&proposal_id).and_then(|p| p.tally_result) {
+            Some(result) => result.votes_merkle_root,
+            None => return false,
+        };
+        let vote = match self.votes.get(&proposal_id).and_then(|votes| votes.get(proof.vote_index)) {
+            Some(vote) => vote,
+            None => return false,
+        };
+        if vote.user.as_str() != proof.voter || vote.timestamp != proof.timestamp {
+            return false;
+        }
+
+        let leaf = [
+            vote.user.as_bytes(),
+            &vote.timestamp.to_le_bytes(),
+            vote.encrypted_vote.as_bytes(),
+        ]
+        .concat();
+        let leaf_hash = hex::encode(env::sha256(&leaf));
+        if leaf_hash != proof.vote_hash {
+            return false;
+        }
+
+        merkle_root_from_path(leaf_hash, proof.vote_index, &proof.proof_path) == root
+    }
+
+    /// Running tally of a chunked finalization (None if none is in progress)
+    pub fn get_partial_tally(&self, proposal_id: u64) -> Option<PartialTally> {
+        self.partial_tallies.get(&proposal_id)
+    }
+
     /// Get all proposals
     pub fn get_proposals(&self, from_index: u64, limit: u64) -> Vec<Proposal> {
         self.proposals
@@ -933,7 +4589,93 @@ impl PrivateDAO {
             .collect()
     }
 
+    /// List proposals with `status`, `limit` capped at 50
+    ///
+    /// Active, Passed and Rejected proposals are read from per-status indices.
+    /// Order is by entry into the status, except that a proposal leaving it
+    /// is replaced by the last one. Other statuses scan all proposals
+    /// (a `Vetoed` reason in `status` is ignored).
+    pub fn get_proposals_by_status(&self, status: ProposalStatus, from_index: u64, limit: u64) -> Vec<Proposal> {
+        let limit = limit.min(MAX_PROPOSALS_PAGE_SIZE) as usize;
+
+        match self.proposal_status_index(&status) {
+            Some(ids) => ids
+                .as_vector()
+                .iter()
+                .skip(from_index as usize)
+                .take(limit)
+                .filter_map(|proposal_id| self.proposals.get(&proposal_id))
+                .collect(),
+            None => self
+                .proposals
+                .values()
+                .filter(|proposal| std::mem::discriminant(&proposal.status) == std::mem::discriminant(&status))
+                .skip(from_index as usize)
+                .take(limit)
+                .collect(),
+        }
+    }
+
+    /// List proposals tagged `tag` (any case) in creation order, `limit` capped at 50
+    pub fn get_proposals_by_tag(&self, tag: String, from_index: u64, limit: u64) -> Vec<Proposal> {
+        let Some(proposal_ids) = self.tag_index.get(&tag.to_ascii_lowercase()) else {
+            return Vec::new();
+        };
+
+        proposal_ids
+            .iter()
+            .skip(from_index as usize)
+            .take(limit.min(MAX_PROPOSALS_PAGE_SIZE) as usize)
+            .filter_map(|proposal_id| self.proposals.get(&proposal_id))
+            .collect()
+    }
+
+    /// Get a proposal and one page of its encrypted votes in a single call
+    ///
+    /// `votes_limit` defaults to 20 and is capped at 50.
+    pub fn get_proposal_with_votes(
+        &self,
+        proposal_id: u64,
+        votes_from: u64,
+        votes_limit: Option<u64>,
+    ) -> Option<ProposalWithVotes> {
+        let proposal = self.proposals.get(&proposal_id)?;
+        let limit = votes_limit
+            .unwrap_or(DEFAULT_VOTES_PAGE_SIZE)
+            .min(MAX_VOTES_PAGE_SIZE);
+
+        let (votes, total_vote_count) = match self.votes.get(&proposal_id) {
+            Some(votes) => {
+                let page = (votes_from..votes.len().min(votes_from.saturating_add(limit)))
+                    .filter_map(|index| votes.get(index))
+                    .collect();
+                (page, votes.len())
+            }
+            None => (Vec::new(), 0),
+        };
+
+        Some(ProposalWithVotes {
+            proposal,
+            votes,
+            total_vote_count,
+        })
+    }
+
+    /// Get proposal template by name
+    pub fn get_proposal_template(&self, name: String) -> Option<ProposalTemplate> {
+        self.proposal_templates.get(&name)
+    }
+
+    /// Get all proposal templates
+    pub fn get_all_templates(&self) -> Vec<ProposalTemplate> {
+        self.proposal_templates.values().collect()
+    }
+
     /// Get votes for a proposal (encrypted)
+    ///
+    /// Deprecated: reads every vote, so large proposals exceed the view gas limit.
+    /// Use `get_votes_paginated`.
+    #[deprecated(note = "use get_votes_paginated")]
     pub fn get_votes(&self, proposal_id: u64) -> Vec<Vote> {
         self.votes
             .get(&proposal_id)
@@ -941,6 +4683,23 @@ impl PrivateDAO {
             .unwrap_or_default()
     }
 
+    /// Get one page of a proposal's votes (encrypted), `limit` capped at 200
+    ///
+    /// Gas is O(limit), not O(total votes): only the requested entries are read.
+    pub fn get_votes_paginated(&self, proposal_id: u64, from_index: u64, limit: u64) -> Vec<Vote> {
+        let Some(votes) = self.votes.get(&proposal_id) else {
+            return Vec::new();
+        };
+        let end = votes.len().min(from_index.saturating_add(limit.min(MAX_VOTES_PAGINATED_LIMIT)));
+
+        (from_index..end).filter_map(|index| votes.get(index)).collect()
+    }
+
+    /// Get the vote at `index` of a proposal's votes (encrypted)
+    pub fn get_vote_at(&self, proposal_id: u64, index: u64) -> Option<Vote> {
+        self.votes.get(&proposal_id)?.get(index)
+    }
+
     /// Get vote count for a proposal
     pub fn get_vote_count(&self, proposal_id: u64) -> u64 {
         self.votes
@@ -949,12 +4708,163 @@ impl PrivateDAO {
             .unwrap_or(0)
     }
 
+    /// Ballots (real and dummy) `account_id` submitted on a proposal
+    ///
+    /// Read from `ballot_indices`, so it doesn't scan the proposal's votes.
+    pub fn get_vote_count_by_user(&self, proposal_id: u64, account_id: AccountId) -> u64 {
+        self.ballot_indices
+            .get(&account_proposal_key(&account_id, proposal_id))
+            .map_or(0, |indices| indices.len() as u64)
+    }
+
+    /// Whether `account_id` submitted any ballot on a proposal (it may be a dummy)
+    pub fn has_voted(&self, proposal_id: u64, account_id: AccountId) -> bool {
+        self.get_vote_count_by_user(proposal_id, account_id) > 0
+    }
+
+    /// Get joins whose key derivation hasn't succeeded, pending for at least `older_than_ns`
+    ///
+    /// Includes joins whose callback is still in flight; pass a few minutes to
+    /// only see stuck ones. Joins whose callback reported a failure were
+    /// already refunded and aren't listed.
+    pub fn get_incomplete_joins(&self, older_than_ns: u64) -> Vec<IncompleteJoinInfo> {
+        let now = env::block_timestamp();
+
+        self.pending_key_derivations
+            .iter()
+            .map(|(account_id, pending)| IncompleteJoinInfo {
+                account_id,
+                initiated_at: pending.initiated_at,
+                deposit_paid: pending.deposit_paid,
+                age_ns: now.saturating_sub(pending.initiated_at),
+            })
+            .filter(|join| join.age_ns >= older_than_ns)
+            .collect()
+    }
+
+    /// Get recorded OutLayer executions, oldest first
+    ///
+    /// Only the last `MAX_OUTLAYER_EXECUTION_HISTORY` (10,000) executions are kept;
+    /// `from_index` counts from the oldest one still stored. `limit` is capped at 100.
+    pub fn get_outlayer_execution_history(&self, from_index: u64, limit: u64) -> Vec<OutLayerExecutionSummary> {
+        let stored = self.outlayer_executions.len();
+        let oldest = self.outlayer_execution_count - stored;
+        let end = from_index
+            .saturating_add(limit.min(MAX_OUTLAYER_HISTORY_PAGE_SIZE))
+            .min(stored);
+
+        (from_index.min(end)..end)
+            .filter_map(|i| self.outlayer_executions.get((oldest + i) % MAX_OUTLAYER_EXECUTION_HISTORY))
+            .collect()
+    }
+
+    /// Share of completed OutLayer executions in the stored history that succeeded
+    ///
+    /// Pending executions are ignored; 0.0 if none has completed.
+    pub fn get_outlayer_success_rate(&self) -> f32 {
+        if self.outlayer_executions_completed == 0 {
+            return 0.0;
+        }
+        self.outlayer_executions_succeeded as f32 / self.outlayer_executions_completed as f32
+    }
+
+    /// Get submissions for a proposal with `start_ns <= timestamp <= end_ns`
+    ///
+    /// # Privacy
+    /// Vote contents stay encrypted, but submission timing is metadata: bursts of
+    /// votes right after an announcement, or a member voting again just before the
+    /// deadline, can hint at coordination or a changed mind. All of this is already
+    /// public through `get_votes` and the chain itself; these views only make it
+    /// convenient to query. Members who care should vote early and send dummy
+    /// messages at random times.
+    pub fn get_vote_submissions_in_timerange(
+        &self,
+        proposal_id: u64,
+        start_ns: u64,
+        end_ns: u64,
+    ) -> Vec<VoteTimelineEntry> {
+        self.internal_vote_timeline(proposal_id, |vote| {
+            (start_ns..=end_ns).contains(&vote.timestamp)
+        })
+    }
+
+    /// Get submissions for a proposal with `from_block <= block_height <= to_block`
+    ///
+    /// Same privacy caveats as `get_vote_submissions_in_timerange`.
+    pub fn get_votes_by_block_height_range(
+        &self,
+        proposal_id: u64,
+        from_block: u64,
+        to_block: u64,
+    ) -> Vec<VoteTimelineEntry> {
+        self.internal_vote_timeline(proposal_id, |vote| {
+            (from_block..=to_block).contains(&vote.block_height)
+        })
+    }
+
+    /// Get participation statistics for a member over one page of proposals, `limit` capped at 50
+    ///
+    /// # Privacy
+    /// Only participation is reported: how many eligible proposals the member
+    /// cast a ballot on. The yes/no breakdown is intentionally NOT available -
+    /// ballots are encrypted and only the TEE sees individual votes, so exposing
+    /// per-member choices would defeat the anonymous voting scheme. Note that a
+    /// ballot may be a dummy message, so `total_votes_cast` counts participation,
+    /// not real votes.
+    ///
+    /// Eligible proposals are those created after the member joined
+    /// (all proposals for migrated members with `joined_at = 0`). Pages follow
+    /// `get_proposals` order; sum the counts over pages up to `get_proposal_count`
+    /// for the member's overall participation.
+    pub fn get_member_voting_analytics(
+        &self,
+        account_id: AccountId,
+        from_index: u64,
+        limit: u64,
+    ) -> MemberVotingAnalytics {
+        let joined_at = self.members
+            .get(&account_id)
+            .map(|info| info.joined_at)
+            .unwrap_or(u64::MAX);
+
+        let eligible: Vec<u64> = self.proposals
+            .values()
+            .skip(from_index as usize)
+            .take(limit.min(MAX_PROPOSALS_PAGE_SIZE) as usize)
+            .filter(|proposal| joined_at == 0 || joined_at < proposal.created_at)
+            .map(|proposal| proposal.id)
+            .collect();
+
+        let total_proposals_eligible = eligible.len() as u64;
+        let total_votes_cast = eligible
+            .iter()
+            .filter(|&&proposal_id| {
+                self.ballot_indices.contains_key(&account_proposal_key(&account_id, proposal_id))
+            })
+            .count() as u64;
+
+        let participation_rate = if total_proposals_eligible > 0 {
+            total_votes_cast as f64 / total_proposals_eligible as f64
+        } else {
+            0.0
+        };
+
+        MemberVotingAnalytics {
+            account_id,
+            total_proposals_eligible,
+            total_votes_cast,
+            participation_rate,
+        }
+    }
+
     /// Get merkle proofs for user's votes in a proposal
     ///
     /// Returns proofs for all votes cast by the specified account in the proposal.
     /// Use this to verify that votes were included in the tally.
     ///
     /// Returns empty Vec if proposal not finalized or user has no votes.
+    /// Also empty if the worker omitted proofs (`merkle_proofs_available = false`);
+    /// in that case recompute them off-chain from `get_votes`.
     pub fn get_vote_proofs(&self, proposal_id: u64, account_id: AccountId) -> Vec<MerkleProof> {
         // Get proposal
         let proposal = match self.proposals.get(&proposal_id) {
@@ -975,3 +4885,518 @@ impl PrivateDAO {
             .collect()
     }
 }
+
+/// Panic unless `secrets_ref` names a keymaster profile; returns it as stored
+fn validate_secrets_ref(secrets_ref: &serde_json::Value) -> String {
+    let is_set = |field: &str| {
+        secrets_ref.get(field).and_then(|value| value.as_str()).is_some_and(|value| !value.is_empty())
+    };
+    assert!(
+        is_set("profile") && is_set("account_id"),
+        "secrets_ref must have non-empty \"profile\" and \"account_id\" strings"
+    );
+    secrets_ref.to_string()
+}
+
+/// Panic if `config` values are out of range
+fn validate_config(config: &DAOConfig) {
+    assert!(config.veto_threshold >= 1, "veto_threshold must be at least 1");
+    assert!(
+        config.max_title_length >= MAX_TITLE_LENGTH_FLOOR,
+        "max_title_length must be at least {}",
+        MAX_TITLE_LENGTH_FLOOR
+    );
+    assert!(
+        config.max_description_length >= MAX_DESCRIPTION_LENGTH_FLOOR,
+        "max_description_length must be at least {}",
+        MAX_DESCRIPTION_LENGTH_FLOOR
+    );
+    assert!(
+        config.min_title_length <= config.max_title_length,
+        "min_title_length exceeds max_title_length"
+    );
+    assert!(
+        config.min_description_length <= config.max_description_length,
+        "min_description_length exceeds max_description_length"
+    );
+    assert!(config.proposal_period_ns > 0, "proposal_period_ns must be greater than 0");
+    assert!(config.max_proposals_per_period >= 1, "max_proposals_per_period must be at least 1");
+    assert!(config.max_votes_per_proposal >= 1, "max_votes_per_proposal must be at least 1");
+    assert!(
+        config.max_active_proposals_per_creator >= 1,
+        "max_active_proposals_per_creator must be at least 1"
+    );
+    assert!(
+        config.protocol_fee_basis_points <= MAX_PROTOCOL_FEE_BASIS_POINTS,
+        "protocol_fee_basis_points must be at most {}",
+        MAX_PROTOCOL_FEE_BASIS_POINTS
+    );
+    if let KeyDerivationAlgorithm::PBKDF2 { iterations } = config.key_derivation_algorithm {
+        assert!(iterations >= 1, "PBKDF2 iterations must be at least 1");
+    }
+
+    if !config.require_pubkey_for_vote {
+        log!("WARNING: pubkey requirement for voting disabled (testing only)");
+    }
+}
+
+/// Panic unless title and description lengths (in characters) are within `config` limits
+fn validate_proposal_text(title: &str, description: &str, config: &DAOConfig) {
+    let title_length = title.chars().count();
+    assert!(
+        (config.min_title_length..=config.max_title_length).contains(&title_length),
+        "Title must be {}-{} characters",
+        config.min_title_length,
+        config.max_title_length
+    );
+
+    let description_length = description.chars().count();
+    assert!(
+        (config.min_description_length..=config.max_description_length).contains(&description_length),
+        "Description must be {}-{} characters",
+        config.min_description_length,
+        config.max_description_length
+    );
+}
+
+/// Lowercased, deduplicated `tags`; panics on more than 5 or on tags that aren't 1-32 ASCII characters
+fn normalize_proposal_tags(tags: Vec<String>) -> Vec<String> {
+    assert!(tags.len() <= MAX_PROPOSAL_TAGS, "A proposal can have at most {} tags", MAX_PROPOSAL_TAGS);
+
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        assert!(
+            !tag.is_empty() && tag.len() <= MAX_TAG_LENGTH && tag.is_ascii(),
+            "Tags must be 1-{} ASCII characters",
+            MAX_TAG_LENGTH
+        );
+        let tag = tag.to_ascii_lowercase();
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Panics unless every tag is an allowed category (any tag if none are configured)
+fn validate_proposal_tags(tags: &[String], config: &DAOConfig) {
+    if config.proposal_categories.is_empty() {
+        return;
+    }
+
+    if let Some(tag) = tags.iter().find(|tag| !config.proposal_categories.contains(tag)) {
+        env::panic_str(&format!(
+            "Unknown proposal category '{}', allowed: {}",
+            tag,
+            config.proposal_categories.join(", ")
+        ));
+    }
+}
+
+/// Panics unless `vote_options` are 2-10 distinct, non-empty strings of at most 64 characters
+fn validate_vote_options(vote_options: &[String]) {
+    assert!(
+        (2..=MAX_VOTE_OPTIONS).contains(&vote_options.len()),
+        "A proposal needs between 2 and {} vote options",
+        MAX_VOTE_OPTIONS
+    );
+
+    for (i, option) in vote_options.iter().enumerate() {
+        assert!(
+            !option.is_empty() && option.chars().count() <= MAX_VOTE_OPTION_LENGTH,
+            "Vote options must be 1-{} characters",
+            MAX_VOTE_OPTION_LENGTH
+        );
+        assert!(!vote_options[..i].contains(option), "Duplicate vote option '{}'", option);
+    }
+}
+
+/// Votes per option of a tally that met quorum (None if counts are hidden)
+///
+/// Workers without multi-option support only report `yes_count` / `no_count`.
+fn tallied_vote_counts(response: &TallyResponse) -> Option<HashMap<String, u64>> {
+    if !response.vote_counts.is_empty() {
+        return Some(response.vote_counts.clone());
+    }
+
+    let (yes_count, no_count) = response.yes_count.zip(response.no_count)?;
+    Some(HashMap::from([("yes".to_string(), yes_count), ("no".to_string(), no_count)]))
+}
+
+/// Votes for `option` (0 if it isn't in the tally)
+fn option_count(vote_counts: &HashMap<String, u64>, option: &str) -> u64 {
+    vote_counts.get(option).copied().unwrap_or(0)
+}
+
+/// Option with strictly more votes than any other (None on a tie or without votes)
+fn plurality_winner(vote_counts: &HashMap<String, u64>) -> Option<&str> {
+    let (winner, &most) = vote_counts.iter().max_by_key(|(_, &count)| count)?;
+    let tied = vote_counts.values().filter(|&&count| count == most).count() > 1;
+
+    (most > 0 && !tied).then_some(winner.as_str())
+}
+
+/// "option=count" pairs sorted by option, for logs
+fn format_vote_counts(vote_counts: &HashMap<String, u64>) -> String {
+    let mut counts: Vec<_> = vote_counts.iter().collect();
+    counts.sort();
+    counts
+        .into_iter()
+        .map(|(option, count)| format!("{}={}", option, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether an OutLayer request made at `requested_at` still awaits its callback
+/// (false once `IN_FLIGHT_TIMEOUT_NS` passed: the callback is presumed lost)
+fn in_flight(requested_at: Option<u64>) -> bool {
+    requested_at.is_some_and(|requested_at| env::block_timestamp() < requested_at.saturating_add(IN_FLIGHT_TIMEOUT_NS))
+}
+
+/// Longest possible account ID, for worst-case storage estimates
+fn worst_case_account_id() -> AccountId {
+    "a".repeat(MAX_ACCOUNT_ID_LEN).parse().unwrap()
+}
+
+/// Deposit covering `bytes` of storage plus `STORAGE_ESTIMATE_MARGIN_PERCENT`
+fn storage_deposit_with_margin(bytes: u64) -> NearToken {
+    let cost = env::storage_byte_cost().as_yoctonear()
+        .checked_mul(bytes as u128)
+        .and_then(|cost| cost.checked_mul(STORAGE_ESTIMATE_MARGIN_PERCENT))
+        .unwrap_or_else(|| env::panic_str("Storage estimate overflow"));
+    NearToken::from_yoctonear(cost / 100)
+}
+
+/// Quorum outlook from the number of distinct voters (see `estimate_quorum_status`)
+///
+/// TimeWeighted votes weigh at most 1.0 and SuperMajority / Relative depend on how members
+/// voted, so those quorums are never definitely met early.
+fn early_quorum_status(quorum: &QuorumType, voter_count: u64, eligible_voters: u64) -> EarlyQuorumStatus {
+    match quorum {
+        QuorumType::Absolute { min_votes } => {
+            let met = voter_count >= *min_votes;
+            EarlyQuorumStatus { definitely_met: met, possibly_met: met }
+        }
+        QuorumType::TimeWeighted { base_votes, .. } => EarlyQuorumStatus {
+            definitely_met: false,
+            possibly_met: voter_count >= *base_votes,
+        },
+        QuorumType::Unanimous => {
+            let met = voter_count >= eligible_voters;
+            EarlyQuorumStatus { definitely_met: met, possibly_met: met }
+        }
+        QuorumType::Percentage { min_percent_of_members } => {
+            let met = voter_count as u128 * 100 >= *min_percent_of_members as u128 * eligible_voters as u128;
+            EarlyQuorumStatus { definitely_met: met, possibly_met: met }
+        }
+        QuorumType::SuperMajority { .. } | QuorumType::Relative { .. } => EarlyQuorumStatus {
+            definitely_met: false,
+            possibly_met: voter_count > 0,
+        },
+    }
+}
+
+/// Normalized Gini coefficient of delegation counts across `population` members
+///
+/// Members missing from `counts` are treated as having 0 delegations.
+/// 0.0 = perfectly equal (or no delegations), 1.0 = one member has all of them.
+fn gini_coefficient(mut counts: Vec<u64>, population: u64) -> f32 {
+    let total: u64 = counts.iter().sum();
+    let n = population.max(counts.len() as u64);
+    if total == 0 || n < 2 {
+        return 0.0;
+    }
+
+    // Ascending order; the zero-count members come first
+    counts.sort_unstable();
+    let zeros = n - counts.len() as u64;
+    let weighted: f64 = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| (zeros + i as u64 + 1) as f64 * count as f64)
+        .sum();
+
+    let n = n as f64;
+    let gini = 2.0 * weighted / (n * total as f64) - (n + 1.0) / n;
+    (gini * n / (n - 1.0)) as f32
+}
+
+/// Walk a Merkle proof path from a leaf to the root (WASI worker format)
+///
+/// Hashes are hex strings; parents are SHA256(left_hex || right_hex), with the
+/// leaf index deciding left/right order at each level.
+fn merkle_root_from_path(leaf_hash: String, leaf_index: u64, proof_path: &[String]) -> String {
+    proof_path
+        .iter()
+        .fold((leaf_hash, leaf_index), |(current, index), sibling| {
+            let combined = if index % 2 == 0 {
+                [current.as_bytes(), sibling.as_bytes()].concat()
+            } else {
+                [sibling.as_bytes(), current.as_bytes()].concat()
+            };
+            (hex::encode(env::sha256(&combined)), index / 2)
+        })
+        .0
+}
+
+/// Rewrite older `members` records of indexed members as `MemberInfo` (see `migrate_member_info`)
+fn migrate_member_infos(member_index: &UnorderedSet<AccountId>) {
+    for account_id in member_index.iter() {
+        migrate_member_info(&account_id);
+    }
+}
+
+/// Rewrite a `BaselineMemberInfo` / `LegacyMemberInfo` / `PreEpochMemberInfo` record of `members` as `MemberInfo`
+///
+/// Returns true if it was a `BaselineMemberInfo` (whose member `voting_member_count` doesn't include yet).
+fn migrate_member_info(account_id: &AccountId) -> bool {
+    let key = [borsh::to_vec(&StorageKey::Members).unwrap(), borsh::to_vec(account_id).unwrap()].concat();
+    let Some(raw) = env::storage_read(&key) else {
+        return false;
+    };
+    if MemberInfo::try_from_slice(&raw).is_ok() {
+        return false;
+    }
+
+    let (member_info, is_baseline) = match BaselineMemberInfo::try_from_slice(&raw) {
+        Ok(baseline) => (MemberInfo::from(baseline), true),
+        Err(_) => {
+            let member_info = PreEpochMemberInfo::try_from_slice(&raw)
+                .map(MemberInfo::from)
+                .or_else(|_| LegacyMemberInfo::try_from_slice(&raw).map(MemberInfo::from))
+                .unwrap_or_else(|_| env::panic_str(&format!("Cannot migrate member info of {}", account_id)));
+            (member_info, false)
+        }
+    };
+    env::storage_write(&key, &borsh::to_vec(&member_info).unwrap());
+    is_baseline
+}
+
+/// Storage key for `PrivateDAO::proposal_pubkeys`
+fn account_proposal_key(account_id: &AccountId, proposal_id: u64) -> String {
+    format!("{}:{}", account_id, proposal_id)
+}
+
+#[cfg(test)]
+impl PrivateDAO {
+    /// `create_proposal` with `config_override` in effect for this call only
+    pub fn create_proposal_with_config(
+        &mut self,
+        title: String,
+        description: String,
+        quorum: QuorumType,
+        deadline: Option<u64>,
+        config_override: Option<DAOConfig>,
+    ) -> u64 {
+        let saved_config = config_override.map(|config| std::mem::replace(&mut self.config, config));
+        let proposal_id = self.create_proposal(title, description, quorum, deadline, None, None);
+        if let Some(config) = saved_config {
+            self.config = config;
+        }
+        proposal_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    const NOW: u64 = 1_000_000;
+
+    fn account(name: &str) -> AccountId {
+        format!("{}.testnet", name).parse().unwrap()
+    }
+
+    fn set_context(predecessor: &str, deposit: NearToken) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account(predecessor))
+            .attached_deposit(deposit)
+            .block_timestamp(NOW)
+            .build());
+    }
+
+    /// Private DAO owned by `owner` with member `alice` (has a pubkey)
+    fn setup_dao() -> PrivateDAO {
+        set_context("owner", NearToken::from_yoctonear(0));
+        let mut dao = PrivateDAO::new(
+            "Test DAO".to_string(),
+            MembershipMode::Private,
+            account("owner"),
+            None,
+            None,
+            None,
+            None,
+        );
+        dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+        dao
+    }
+
+    fn create_proposal_as(dao: &mut PrivateDAO, caller: &str, deadline: Option<u64>) -> u64 {
+        set_context(caller, NearToken::from_millinear(1));
+        dao.create_proposal(
+            "Fund the meetup".to_string(),
+            "Venue and snacks".to_string(),
+            QuorumType::Absolute { min_votes: 1 },
+            deadline,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "PrivateDAO must be initialized with 'new()'")]
+    fn test_default_panics_with_init_hint() {
+        set_context("owner", NearToken::from_yoctonear(0));
+        let _ = PrivateDAO::default();
+    }
+
+    #[test]
+    #[should_panic(expected = "Already initialized")]
+    fn test_new_rejects_existing_state() {
+        let dao = setup_dao();
+        env::state_write(&dao);
+        PrivateDAO::new("Again".to_string(), MembershipMode::Public, account("owner"), None, None, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only members can create proposals")]
+    fn test_create_proposal_by_non_member_panics() {
+        let mut dao = setup_dao();
+        create_proposal_as(&mut dao, "mallory", None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Deadline must be in the future")]
+    fn test_create_proposal_with_past_deadline_panics() {
+        let mut dao = setup_dao();
+        create_proposal_as(&mut dao, "alice", Some(NOW - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Voting period must be at least 86400000000000 ns")]
+    fn test_create_proposal_with_short_voting_period_panics() {
+        let mut dao = setup_dao();
+        create_proposal_as(&mut dao, "alice", Some(NOW + 86_400_000_000_000 - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Minimum deposit is 0.001 NEAR for storage")]
+    fn test_create_proposal_insufficient_deposit_panics() {
+        let mut dao = setup_dao();
+        set_context("alice", NearToken::from_micronear(999));
+        dao.create_proposal(
+            "Fund the meetup".to_string(),
+            String::new(),
+            QuorumType::Absolute { min_votes: 1 },
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_create_proposal_succeeds() {
+        let mut dao = setup_dao();
+
+        let id = create_proposal_as(&mut dao, "alice", Some(NOW + 86_400_000_000_000));
+
+        let proposal = dao.get_proposal(id).unwrap();
+        assert_eq!(proposal.creator, account("alice"));
+        assert_eq!(proposal.created_at, NOW);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.proposal_type, ProposalType::Governance);
+        assert_eq!(dao.get_next_proposal_id(), id + 1);
+        assert_eq!(dao.get_proposal_count(), 1);
+    }
+
+    fn create_with_config(dao: &mut PrivateDAO, config: DAOConfig) -> u64 {
+        dao.create_proposal_with_config(
+            "Fund the meetup".to_string(),
+            String::new(),
+            QuorumType::Absolute { min_votes: 1 },
+            None,
+            Some(config),
+        )
+    }
+
+    #[test]
+    fn test_config_override_applies_to_one_call() {
+        let mut dao = setup_dao();
+        set_context("alice", NearToken::from_micronear(500));
+
+        let cheap = DAOConfig {
+            proposal_deposit: NearToken::from_micronear(500),
+            ..DAOConfig::default()
+        };
+        create_with_config(&mut dao, cheap);
+
+        assert_eq!(dao.get_config().proposal_deposit, NearToken::from_millinear(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many active proposals (max 1)")]
+    fn test_active_proposals_per_creator_limit() {
+        let mut dao = setup_dao();
+        let config = DAOConfig { max_active_proposals_per_creator: 1, ..DAOConfig::default() };
+
+        set_context("alice", NearToken::from_millinear(1));
+        create_with_config(&mut dao, config.clone());
+        create_with_config(&mut dao, config);
+    }
+
+    #[test]
+    fn test_proposal_rate_limit_window() {
+        let mut dao = setup_dao();
+        dao.migrate_add_member(account("bob"), None);
+        let config = DAOConfig { max_proposals_per_period: 1, ..DAOConfig::default() };
+
+        set_context("alice", NearToken::from_millinear(1));
+        create_with_config(&mut dao, config.clone());
+
+        set_context("bob", NearToken::from_millinear(1));
+        let limited = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            create_with_config(&mut dao, config.clone())
+        }));
+        assert!(limited.is_err());
+
+        // Next window
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account("bob"))
+            .attached_deposit(NearToken::from_millinear(1))
+            .block_timestamp(NOW + config.proposal_period_ns)
+            .build());
+        create_with_config(&mut dao, config);
+    }
+
+    #[test]
+    #[should_panic(expected = "Membership too recent to create proposals")]
+    fn test_min_membership_duration() {
+        let mut dao = setup_dao();
+        dao.members.insert(&account("alice"), &MemberInfo {
+            joined_at: NOW - 10,
+            key_rotated_at: None,
+            last_voted_at: None,
+            proposals_voted: 0,
+            key_epoch: 0,
+        });
+
+        set_context("alice", NearToken::from_millinear(1));
+        create_with_config(&mut dao, DAOConfig { min_membership_duration_ns: 11, ..DAOConfig::default() });
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal is not active")]
+    fn test_cast_vote_on_inactive_proposal_panics() {
+        let mut dao = setup_dao();
+        let id = create_proposal_as(&mut dao, "alice", None);
+
+        let mut proposal = dao.proposals.get(&id).unwrap();
+        proposal.status = ProposalStatus::Rejected;
+        dao.proposals.insert(&id, &proposal);
+
+        set_context("alice", NearToken::from_millinear(2));
+        dao.cast_vote(id, "ab".repeat(113));
+    }
+}
@@ -1,8 +1,27 @@
+use std::collections::HashMap;
+
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::AccountId;
+use near_sdk::{AccountId, NearToken};
 use schemars::JsonSchema;
 
+// Field descriptions in the JSON schema come from the doc comments below.
+
+/// Schema example for `Vote::encrypted_vote`
+fn example_encrypted_vote() -> &'static str {
+    "04a1b2c3d4e5f6...(hex-encoded ECIES ciphertext, ~226 chars for \"yes\")"
+}
+
+/// Schema example for `tee_attestation`
+fn example_tee_attestation() -> &'static str {
+    "mvp-attestation:3f9a1c...(hex SHA256 of the sorted votes)"
+}
+
+/// Schema example for `votes_merkle_root`
+fn example_merkle_root() -> &'static str {
+    "9e107d9d372bb6826bd81d3542a419d6e277d4d731479571562a6d5a1a9b7c10"
+}
+
 /// Membership mode for the DAO
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
 #[borsh(crate = "near_sdk::borsh")]
@@ -15,12 +34,41 @@ pub enum MembershipMode {
 }
 
 /// Quorum requirements for proposal passing
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
 #[borsh(crate = "near_sdk::borsh")]
 #[serde(crate = "near_sdk::serde")]
 pub enum QuorumType {
     /// Minimum absolute number of votes required
-    Absolute { min_votes: u64 },
+    Absolute {
+        /// Valid yes/no votes needed for quorum (dummies don't count)
+        min_votes: u64,
+    },
+    /// Minimum sum of time-decayed vote weights
+    /// Each vote weighs `2^(-(vote_time - created_at) / decay_halflife_ns)`, so early votes count more
+    TimeWeighted {
+        /// Weighted vote sum needed for quorum
+        base_votes: u64,
+        /// Time after proposal creation at which a vote's weight halves (nanoseconds, > 0)
+        decay_halflife_ns: u64,
+    },
+    /// Every eligible member must cast a real vote (`Proposal::eligible_voters`)
+    Unanimous,
+    /// Minimum share of eligible members (`Proposal::eligible_voters`) that must vote
+    Percentage {
+        /// Percent of members needed for quorum (1-100)
+        min_percent_of_members: u8,
+    },
+    /// Minimum share of yes votes among valid votes
+    SuperMajority {
+        /// yes / (yes + no) needed to pass, in percent (1-100)
+        min_yes_percent: u8,
+    },
+    /// Minimum share of yes votes among all votes cast, abstentions included
+    /// (SuperMajority leaves them out), with no turnout requirement beyond a single vote
+    Relative {
+        /// yes * 100 / (yes + no + abstain), rounded down, needed to pass (1-100)
+        min_yes_percent: u8,
+    },
 }
 
 /// Proposal status
@@ -28,67 +76,460 @@ pub enum QuorumType {
 #[borsh(crate = "near_sdk::borsh")]
 #[serde(crate = "near_sdk::serde")]
 pub enum ProposalStatus {
+    /// Accepting votes
     Active,
+    /// Tallied: quorum met and yes > no
     Passed,
+    /// Tallied: quorum not met or yes <= no
     Rejected,
+    /// Passed, then blocked by admins (final - cannot be re-opened)
+    Vetoed {
+        /// Reason given by the admin who completed the veto
+        reason: String,
+    },
+    /// Withdrawn by its creator or the owner before finalization (final)
+    Cancelled,
+    /// Passed and its `execution_payload` was dispatched by `execute_proposal` (final)
+    Executed,
+}
+
+/// Proposal category
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalType {
+    /// General governance decision (default)
+    Governance,
+    /// Request for funds
+    Funding,
+    /// Change to the DAO's rules
+    Constitution,
+    /// Replace the contract code once passed (via `execute_upgrade`)
+    /// `wasm_hash` is the hex SHA256 of the new WASM (uploaded via `upload_upgrade_wasm`)
+    UpgradeContract { wasm_hash: String },
+}
+
+/// Who funds the OutLayer execution of `finalize_proposal` (and receives the refund)
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug, Default)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum OutLayerPayerMode {
+    /// Whoever calls `finalize_proposal` attaches the deposit (default)
+    #[default]
+    Caller,
+    /// The contract pays `min_outlayer_deposit` from `treasury_balance` (see `fund_treasury`)
+    /// for one tally per proposal, once voting closed; other attempts fall back to the caller
+    DAO,
+    /// With `creator_pays`, the creator attaches the tally deposit when creating the
+    /// proposal and it funds the first finalization; later attempts (quorum not yet
+    /// met) fall back to the caller. Without it, the caller pays as in `Caller`.
+    Proposal { creator_pays: bool },
+}
+
+/// How members' encryption keys are derived by the worker (`DAOConfig::key_derivation_algorithm`)
+#[allow(clippy::upper_case_acronyms)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Debug, Default)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum KeyDerivationAlgorithm {
+    /// HKDF-SHA256 (default)
+    #[default]
+    HKDF,
+    /// PBKDF2-HMAC-SHA256 with `iterations` rounds, for frameworks that mandate it
+    PBKDF2 { iterations: u32 },
+}
+
+/// Reusable defaults for common proposal types
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalTemplate {
+    /// Unique template name (used as lookup key)
+    pub name: String,
+    /// Description used for proposals created from this template
+    pub description_template: String,
+    pub quorum: QuorumType,
+    /// Deadline relative to creation time (nanoseconds). If None, no time limit.
+    pub deadline_offset_ns: Option<u64>,
+    pub proposal_type: ProposalType,
+    pub tags: Vec<String>,
 }
 
 /// A proposal in the DAO
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
 #[borsh(crate = "near_sdk::borsh")]
 #[serde(crate = "near_sdk::serde")]
 pub struct Proposal {
+    /// Sequential proposal ID (starts at 0)
     pub id: u64,
+    /// Short proposal title
     pub title: String,
+    /// Full proposal text
     pub description: String,
+    /// Member who created the proposal
     #[schemars(with = "String")]
     pub creator: AccountId,
+    /// Block timestamp at creation (nanoseconds since epoch)
     pub created_at: u64,
     /// Optional deadline (nanoseconds since epoch). If None, proposal has no time limit.
     pub deadline: Option<u64>,
+    /// Quorum required for the proposal to pass
     pub quorum: QuorumType,
+    /// Current lifecycle status
     pub status: ProposalStatus,
+    /// Set once the votes have been tallied by OutLayer
     pub tally_result: Option<TallyResult>,
+    /// Proposal category
+    pub proposal_type: ProposalType,
+    /// Free-form labels for filtering (up to 5, lowercase ASCII, see `get_proposals_by_tag`)
+    pub tags: Vec<String>,
+    /// Deposit the creator attached beyond `proposal_deposit` to fund the first tally
+    /// (`OutLayerPayerMode::Proposal { creator_pays: true }`, otherwise 0). Spent on first finalization.
+    #[schemars(with = "String")]
+    pub creator_deposit: NearToken,
+    /// Members with a pubkey when the proposal was created (later joiners can't vote on it)
+    /// Denominator of Unanimous and Percentage quorum; sent to the worker as `eligible_voters`
+    /// Members who leave afterwards stay in it: their ballots still count, but if
+    /// they hadn't voted, Unanimous can no longer be met and Percentage gets harder.
+    pub eligible_voters: u64,
+    /// Ballot choices of a multi-option proposal (None = "yes" / "no")
+    pub vote_options: Option<Vec<String>>,
+    /// JSON `ExecutionPayload` run by `execute_proposal` once passed (None = nothing to execute)
+    pub execution_payload: Option<String>,
+    /// Timestamp of the last `amend_proposal` / `update_proposal_description` (None = never edited)
+    pub amended_at: Option<u64>,
+    /// When the proposal left Active (None while active); starts `DAOConfig::execution_delay_ns`
+    pub decided_at: Option<u64>,
+}
+
+/// Cross-contract call made by `execute_proposal` (stored JSON-encoded in `Proposal::execution_payload`)
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExecutionPayload {
+    /// Contract to call
+    #[schemars(with = "String")]
+    pub receiver_id: AccountId,
+    pub method_name: String,
+    /// Call arguments, passed through as-is (usually JSON)
+    pub args: String,
+    /// Attached deposit in yoctoNEAR, paid from the treasury
+    #[schemars(with = "String")]
+    pub deposit: NearToken,
 }
 
 /// An encrypted vote
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
 #[borsh(crate = "near_sdk::borsh")]
 #[serde(crate = "near_sdk::serde")]
 pub struct Vote {
+    /// Member who submitted the ballot
     #[schemars(with = "String")]
     pub user: AccountId,
+    /// Hex-encoded ECIES ciphertext of "yes", "no" or a dummy value
+    #[schemars(example = "example_encrypted_vote")]
     pub encrypted_vote: String,
+    /// Block timestamp of submission (nanoseconds since epoch)
+    pub timestamp: u64,
+    /// Block height of submission
+    pub block_height: u64,
+}
+
+/// One OutLayer execution requested by the contract (see `get_outlayer_execution_history`)
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct OutLayerExecutionSummary {
+    /// "key_derivation" or "vote_tally"
+    pub execution_type: String,
+    /// Account whose call triggered the execution
+    #[schemars(with = "String")]
+    pub triggered_by: AccountId,
+    pub block_height: u64,
+    pub timestamp: u64,
+    /// None while the callback is pending
+    pub success: Option<bool>,
+    /// Not reported by OutLayer yet (always None)
+    pub gas_used: Option<u64>,
+}
+
+/// When a vote was submitted (no ciphertext)
+///
+/// Submission timing is metadata in its own right: see `get_vote_submissions_in_timerange`.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VoteTimelineEntry {
+    /// Position of the vote in the proposal's vote list (Merkle leaf index)
+    pub vote_index: u64,
+    #[schemars(with = "String")]
+    pub user: AccountId,
+    /// Block timestamp of submission (nanoseconds since epoch)
     pub timestamp: u64,
+    /// Block height of submission
+    pub block_height: u64,
 }
 
 /// Merkle proof for vote verification
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
 #[borsh(crate = "near_sdk::borsh")]
 #[serde(crate = "near_sdk::serde")]
 pub struct MerkleProof {
+    /// Account that submitted the vote
     pub voter: String,
+    /// Position of the vote in the proposal's vote list
     pub vote_index: u64,
+    /// Hex SHA256 of the vote leaf
     pub vote_hash: String,
+    /// Hex sibling hashes from the leaf up to the root
     pub proof_path: Vec<String>,
+    /// Vote submission timestamp (nanoseconds since epoch)
     pub timestamp: u64,
 }
 
 /// Tally result from OutLayer
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
 #[borsh(crate = "near_sdk::borsh")]
 #[serde(crate = "near_sdk::serde")]
 pub struct TallyResult {
+    /// Whether the proposal's quorum was reached
     pub quorum_met: bool,
     /// Only present if quorum was met (privacy protection)
     pub yes_count: Option<u64>,
     /// Only present if quorum was met (privacy protection)
     pub no_count: Option<u64>,
+    /// Abstentions: count towards quorum, neutral for the outcome (only if quorum met)
+    #[serde(default)]
+    pub abstain_count: Option<u64>,
+    /// Votes per option (empty if quorum not met)
+    pub vote_counts: HashMap<String, u64>,
+    /// Valid votes, one per voter (0 if quorum not met)
     pub total_votes: u64,
+    /// TEE attestation over the tallied votes
+    #[schemars(example = "example_tee_attestation")]
     pub tee_attestation: String,
+    /// Hex Merkle root of all submitted votes
+    #[schemars(example = "example_merkle_root")]
     pub votes_merkle_root: String,
     /// Merkle proofs for vote verification
     pub merkle_proofs: Vec<MerkleProof>,
+    /// False if the worker omitted proofs to stay under OutLayer output limits
+    pub merkle_proofs_available: bool,
+    /// Blinded Merkle root over the counted ballots (empty if quorum not met)
+    ///
+    /// Binds the TEE to which ballots it counted without revealing which
+    /// submissions were dummies or overridden.
+    #[serde(default)]
+    pub counted_votes_root: String,
+}
+
+/// Running result of a chunked finalization (see `PrivateDAO::finalize_proposal_chunk`)
+///
+/// Removed once `commit_finalization` decides the proposal, by
+/// `abandon_partial_tally` or when the proposal is cancelled.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug, Default)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct PartialTally {
+    /// Votes (by index) covered by the chunks tallied so far; the next chunk starts here
+    pub next_offset: u64,
+    /// Counts of the tallied chunks, sealed by the TEE (hex, empty before the first chunk)
+    /// Only the worker can read them, so no chunk's counts are ever published
+    pub sealed_tally: String,
+    /// A chunk is being tallied by OutLayer (only one at a time)
+    pub chunk_in_flight: bool,
+    /// When the last chunk was requested or tallied (nanoseconds)
+    pub updated_at: u64,
+}
+
+/// Owner-adjustable DAO settings
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct DAOConfig {
+    /// Require a derived pubkey (completed join) before `cast_vote` (default: true)
+    ///
+    /// WARNING: only disable for testing the proposal lifecycle without OutLayer.
+    /// With `false`, members without a key can submit votes that look valid
+    /// on-chain but can never be decrypted - the TEE counts them as dummies.
+    pub require_pubkey_for_vote: bool,
+
+    /// Admin approvals needed to veto a passed proposal (default: 1)
+    pub veto_threshold: u64,
+
+    /// Members encrypt each proposal's vote with a proposal-scoped key
+    /// (obtained via `request_proposal_key`) instead of their DAO-wide key (default: false)
+    ///
+    /// Only change while no proposal is active: tallying uses the current value.
+    pub use_proposal_scoped_keys: bool,
+
+    /// Lock-in window before the deadline (nanoseconds) during which members who
+    /// already voted can't send further ballots. First-time voters are always allowed.
+    /// None = votes can change until the deadline (default).
+    pub vote_change_deadline_ns: Option<u64>,
+
+    /// Extra OutLayer cost per vote used by `estimate_finalization_cost` (default: 0)
+    /// Tune as the DAO learns actual tally costs.
+    #[schemars(with = "String")]
+    pub per_vote_outlayer_cost: NearToken,
+
+    /// Shortest allowed proposal title in characters (default: 1)
+    pub min_title_length: usize,
+
+    /// Longest allowed proposal title in characters (default: 200, at least 5)
+    pub max_title_length: usize,
+
+    /// Shortest allowed proposal description in characters (default: 0)
+    pub min_description_length: usize,
+
+    /// Longest allowed proposal description in characters (default: 10000, at least 10)
+    pub max_description_length: usize,
+
+    /// Proposals the whole DAO can create per `proposal_period_ns` window (default: 100)
+    pub max_proposals_per_period: u64,
+
+    /// Length of the proposal rate-limit window in nanoseconds (default: 1 day)
+    pub proposal_period_ns: u64,
+
+    /// How long an account must have been a member before creating proposals
+    /// (nanoseconds, default: 0). Migrated members (joined_at = 0) always qualify.
+    pub min_membership_duration_ns: u64,
+
+    /// Storage deposit required by `create_proposal` (default: 0.001 NEAR)
+    #[schemars(with = "String")]
+    pub proposal_deposit: NearToken,
+
+    /// Maximum ballots (including dummies and re-votes) per proposal (default: 10000)
+    /// Keeps the tally input within OutLayer limits.
+    pub max_votes_per_proposal: u64,
+
+    /// Active proposals a single member can have at once (default: 5)
+    pub max_active_proposals_per_creator: u64,
+
+    /// Storage deposit required by `cast_vote` (default: 0.002 NEAR)
+    #[schemars(with = "String")]
+    pub min_vote_deposit: NearToken,
+
+    /// Deposit required for each OutLayer execution (default: 0.01 NEAR)
+    #[schemars(with = "String")]
+    pub min_outlayer_deposit: NearToken,
+
+    /// Allowed proposal tags, e.g. ["governance", "treasury"] (default: empty = any tag)
+    /// Only checked at creation; existing proposals keep their tags.
+    pub proposal_categories: Vec<String>,
+
+    /// Who funds tallying in `finalize_proposal` (default: Caller)
+    pub outlayer_payer_mode: OutLayerPayerMode,
+
+    /// Let creators edit a proposal's description after votes were cast (default: false)
+    /// Such edits get a visible "[EDITED after vote #N ...]" notice appended.
+    pub allow_proposal_update_after_votes: bool,
+
+    /// Key derivation for new member keys (default: HKDF)
+    /// Existing keys keep the algorithm they were derived with.
+    pub key_derivation_algorithm: KeyDerivationAlgorithm,
+
+    /// Receives protocol fees (default: None = no fees)
+    /// The contract's own account adds them to `treasury_balance` instead.
+    #[schemars(with = "Option<String>")]
+    pub fee_recipient: Option<AccountId>,
+
+    /// Share of `join_dao`, `create_proposal` and `cast_vote` deposits taken as a fee,
+    /// in basis points (0-1000 = 0-10%, default: 0). The rest must still cover the minimums.
+    pub protocol_fee_basis_points: u16,
+
+    /// How long a `transfer_ownership` waits for `accept_ownership` before the owner
+    /// can cancel it (nanoseconds, default: 7 days)
+    pub ownership_transfer_timeout_ns: u64,
+
+    /// Shortest time between proposal creation and its deadline (nanoseconds, default: 1 day)
+    /// Stops proposals that close before members notice them.
+    pub min_voting_period_ns: u64,
+
+    /// Time between a proposal passing and `execute_proposal` being allowed
+    /// (nanoseconds, default: 1 day). Gives admins a window to veto.
+    pub execution_delay_ns: u64,
+}
+
+impl Default for DAOConfig {
+    fn default() -> Self {
+        Self {
+            require_pubkey_for_vote: true,
+            veto_threshold: 1,
+            use_proposal_scoped_keys: false,
+            vote_change_deadline_ns: None,
+            per_vote_outlayer_cost: NearToken::from_yoctonear(0),
+            min_title_length: 1,
+            max_title_length: crate::DEFAULT_MAX_TITLE_LENGTH,
+            min_description_length: 0,
+            max_description_length: crate::DEFAULT_MAX_DESCRIPTION_LENGTH,
+            max_proposals_per_period: 100,
+            proposal_period_ns: 86_400_000_000_000,
+            min_membership_duration_ns: 0,
+            proposal_deposit: NearToken::from_yoctonear(crate::PROPOSAL_STORAGE_DEPOSIT),
+            max_votes_per_proposal: 10_000,
+            max_active_proposals_per_creator: 5,
+            min_vote_deposit: NearToken::from_yoctonear(crate::STORAGE_DEPOSIT_PER_VOTE),
+            min_outlayer_deposit: NearToken::from_yoctonear(crate::MIN_OUTLAYER_DEPOSIT),
+            proposal_categories: Vec::new(),
+            outlayer_payer_mode: OutLayerPayerMode::Caller,
+            allow_proposal_update_after_votes: false,
+            key_derivation_algorithm: KeyDerivationAlgorithm::HKDF,
+            fee_recipient: None,
+            protocol_fee_basis_points: 0,
+            ownership_transfer_timeout_ns: 7 * 86_400_000_000_000,
+            min_voting_period_ns: 86_400_000_000_000,
+            execution_delay_ns: 86_400_000_000_000,
+        }
+    }
+}
+
+/// Who uploaded an UpgradeContract proposal's WASM (see `upload_upgrade_wasm`)
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct UpgradeWasmUpload {
+    pub uploader: AccountId,
+    /// Storage deposit attached to the upload, returned when the WASM is removed
+    pub deposit: NearToken,
+}
+
+/// How a pending key derivation was requested
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct JoinType {
+    /// true: `join_dao` (member is added once the key is derived)
+    /// false: `complete_join` (member was pre-approved by the owner)
+    pub is_new: bool,
+}
+
+/// Join whose key derivation hasn't succeeded yet (see `get_incomplete_joins`)
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct PendingKeyDerivation {
+    /// Block timestamp of the (latest) join call
+    pub initiated_at: u64,
+    /// Part of the join deposits kept by the contract (the rest went to OutLayer)
+    pub deposit_paid: NearToken,
+}
+
+/// OutLayer deposit attached to an in-flight execution (see `PrivateDAO::pending_deposits`)
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct PendingDeposit {
+    /// Who funded the execution (this contract for DAO-paid tallies)
+    pub payer: AccountId,
+    pub amount: NearToken,
+}
+
+/// Pending join reported by `get_incomplete_joins`
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IncompleteJoinInfo {
+    #[schemars(with = "String")]
+    pub account_id: AccountId,
+    pub initiated_at: u64,
+    /// Refundable via `admin_refund_incomplete_join`
+    #[schemars(with = "String")]
+    pub deposit_paid: NearToken,
+    pub age_ns: u64,
 }
 
 /// Member information
@@ -98,17 +539,199 @@ pub struct TallyResult {
 pub struct MemberInfo {
     /// Timestamp when member joined (nanoseconds)
     pub joined_at: u64,
+    /// Timestamp of the last `rotate_key` that replaced the member's pubkey
+    pub key_rotated_at: Option<u64>,
+    /// Timestamp of the member's latest ballot (real or dummy)
+    pub last_voted_at: Option<u64>,
+    /// Proposals the member cast at least one ballot on
+    pub proposals_voted: u64,
+    /// Number of `rotate_key` calls; the member's key is derived at this epoch
+    #[serde(default)]
+    pub key_epoch: u32,
+}
+
+/// `MemberInfo` layout before key epochs (converted by `reset_state`)
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct PreEpochMemberInfo {
+    pub joined_at: u64,
+    pub key_rotated_at: Option<u64>,
+    pub last_voted_at: Option<u64>,
+    pub proposals_voted: u64,
+}
+
+impl From<PreEpochMemberInfo> for MemberInfo {
+    fn from(old: PreEpochMemberInfo) -> Self {
+        Self {
+            joined_at: old.joined_at,
+            key_rotated_at: old.key_rotated_at,
+            last_voted_at: old.last_voted_at,
+            proposals_voted: old.proposals_voted,
+            key_epoch: 0,
+        }
+    }
+}
+
+/// `MemberInfo` layout before vote activity was tracked (converted by `reset_state`)
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct LegacyMemberInfo {
+    pub joined_at: u64,
+    pub key_rotated_at: Option<u64>,
+}
+
+impl From<LegacyMemberInfo> for MemberInfo {
+    fn from(legacy: LegacyMemberInfo) -> Self {
+        Self {
+            joined_at: legacy.joined_at,
+            key_rotated_at: legacy.key_rotated_at,
+            last_voted_at: None,
+            proposals_voted: 0,
+            key_epoch: 0,
+        }
+    }
+}
+
+/// `MemberInfo` layout of the initial release (converted when the member is indexed)
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct BaselineMemberInfo {
+    pub joined_at: u64,
+}
+
+impl From<BaselineMemberInfo> for MemberInfo {
+    fn from(baseline: BaselineMemberInfo) -> Self {
+        Self {
+            joined_at: baseline.joined_at,
+            key_rotated_at: None,
+            last_voted_at: None,
+            proposals_voted: 0,
+            key_epoch: 0,
+        }
+    }
+}
+
+/// Owner's invitation to join a private DAO (see `create_invitation`)
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct InvitationInfo {
+    /// Timestamp when the invitation was created (nanoseconds)
+    pub invited_at: u64,
+    /// `complete_join` must be called before this timestamp (nanoseconds)
+    pub expires_at: u64,
+}
+
+/// A proposal together with one page of its encrypted votes
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalWithVotes {
+    pub proposal: Proposal,
+    pub votes: Vec<Vote>,
+    /// Total votes stored for the proposal (use to detect further pages)
+    pub total_vote_count: u64,
+}
+
+/// Participation statistics for a single member
+///
+/// Only whether a member voted is tracked, never how they voted.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MemberVotingAnalytics {
+    #[schemars(with = "String")]
+    pub account_id: AccountId,
+    /// Proposals in the page created after the member joined
+    pub total_proposals_eligible: u64,
+    /// Eligible proposals in the page the member cast at least one ballot on (dummy messages included)
+    pub total_votes_cast: u64,
+    /// `total_votes_cast / total_proposals_eligible` (0.0 if nothing eligible)
+    pub participation_rate: f64,
+}
+
+/// Quorum outlook from submission counts alone (see `estimate_quorum_status`)
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EarlyQuorumStatus {
+    /// Quorum is met if every voter's ballot is a real vote (not a dummy)
+    pub definitely_met: bool,
+    /// Quorum could be met; false means finalizing now is certain to fail quorum
+    pub possibly_met: bool,
+}
+
+/// Delegation concentration (see `get_delegation_stats`)
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelegationStats {
+    /// Members currently delegating
+    pub total_delegations: u64,
+    /// Members receiving at least one delegation
+    pub unique_delegates: u64,
+    /// Most delegations held by a single member
+    pub max_delegations_to_one_person: u64,
+    /// 0.0 = delegations spread evenly across members, 1.0 = one member holds all
+    pub gini_coefficient: f32,
+}
+
+/// DAO-level participation metrics (see `get_governance_stats`)
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GovernanceStats {
+    /// Proposals ever created (cancelled ones included)
+    pub total_proposals: u64,
+    pub active_proposals: u64,
+    /// Passed and not yet executed
+    pub passed_proposals: u64,
+    pub rejected_proposals: u64,
+    /// Ballots cast, re-votes included
+    pub total_votes_cast: u64,
+    /// `total_votes_cast / total_proposals`, rounded down (0 without proposals)
+    pub avg_votes_per_proposal: u64,
 }
 
 /// DAO information
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct DAOInfo {
+    /// DAO display name
     pub name: String,
+    /// Account that controls membership and settings
     #[schemars(with = "String")]
     pub owner: AccountId,
+    /// How new members join
     pub membership_mode: MembershipMode,
+    /// Current number of members
     pub member_count: u64,
+    /// Membership cap (None = unlimited)
+    pub max_members: Option<u64>,
+}
+
+/// Contract deployment info (for indexers and auditors)
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DeployInfo {
+    pub version: String,
+    /// Block timestamp of the initial `new()` call (nanoseconds)
+    pub deployed_at: u64,
+    /// Block timestamp of the last state migration (nanoseconds), 0 if never migrated
+    pub last_upgraded_at: u64,
+}
+
+/// Core state counters for debugging migrations (see `get_state_summary`)
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StateSummary {
+    #[schemars(with = "String")]
+    pub owner: AccountId,
+    pub member_count: u64,
+    /// Proposals stored in `proposals`
+    pub proposal_count: u64,
+    /// Proposals with a votes Vector (created with each proposal; lower means lost vote storage)
+    pub vote_vector_count: u64,
+    pub next_proposal_id: u64,
+    pub membership_mode: MembershipMode,
+    pub contract_version: String,
+    /// See `pause`
+    pub is_paused: bool,
 }
 
 /// OutLayer execution response wrapper
@@ -125,20 +748,55 @@ pub struct OutLayerResponse {
 #[serde(crate = "near_sdk::serde")]
 pub struct DeriveKeyResponse {
     pub pubkey: String,
+    /// Missing from workers that only support HKDF
+    #[serde(default)]
+    pub key_derivation_algorithm: KeyDerivationAlgorithm,
+    /// Epoch the key was derived at (missing from workers without key epochs)
+    #[serde(default)]
+    pub key_epoch: u32,
+}
+
+/// Response from OutLayer for one chunk of a chunked finalization
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChunkTallyResponse {
+    /// Proposal the chunk belongs to
+    pub proposal_id: u64,
+    /// Counts of every chunk so far, sealed by the TEE (hex)
+    pub sealed_tally: String,
+    /// Votes covered by `sealed_tally`
+    pub next_offset: u64,
 }
 
 /// Response from OutLayer vote tallying
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TallyResponse {
+    /// Proposal the tally belongs to
     pub proposal_id: u64,
     /// Only present if quorum met (privacy protection)
     pub yes_count: Option<u64>,
     /// Only present if quorum met (privacy protection)
     pub no_count: Option<u64>,
+    /// Only present if quorum met (missing from workers without abstain support)
+    #[serde(default)]
+    pub abstain_count: Option<u64>,
+    /// Votes per option (empty if quorum not met, or from a worker without multi-option support)
+    #[serde(default)]
+    pub vote_counts: HashMap<String, u64>,
+    /// Valid votes, one per voter (0 if quorum not met)
     pub total_votes: u64,
+    /// TEE attestation over the tallied votes
+    #[schemars(example = "example_tee_attestation")]
     pub tee_attestation: String,
+    /// Hex Merkle root of all submitted votes
+    #[schemars(example = "example_merkle_root")]
     pub votes_merkle_root: String,
     /// Merkle proofs for vote verification
     pub merkle_proofs: Vec<MerkleProof>,
+    /// False if the worker omitted proofs to stay under OutLayer output limits
+    pub merkle_proofs_available: bool,
+    /// TEE commitment to the set of counted ballots (empty if quorum not met)
+    #[serde(default)]
+    pub counted_votes_root: String,
 }
@@ -0,0 +1,64 @@
+//! Abstentions count towards quorum but don't decide yes/no proposals
+
+mod common;
+
+use common::{caller, dao, outlayer_success};
+use private_dao_contract::types::*;
+
+/// Tally a yes/no proposal with the given counts and return the stored proposal
+fn tally(quorum: QuorumType, yes: u64, no: u64, abstain: u64) -> Proposal {
+    caller("owner").set();
+    let mut dao = dao().private().build();
+    dao.create_proposal("Abstain".to_string(), String::new(), quorum, None, None, None);
+
+    caller("dao").set();
+    dao.on_votes_tallied(
+        1,
+        None,
+        outlayer_success(serde_json::json!({
+            "proposal_id": 1,
+            "yes_count": yes,
+            "no_count": no,
+            "abstain_count": abstain,
+            "vote_counts": { "yes": yes, "no": no, "abstain": abstain },
+            "total_votes": yes + no + abstain,
+            "tee_attestation": "",
+            "votes_merkle_root": "",
+            "merkle_proofs": [],
+            "merkle_proofs_available": false,
+        })),
+    );
+
+    dao.get_proposal(1).unwrap()
+}
+
+#[test]
+fn abstain_majority_does_not_block_yes() {
+    let proposal = tally(QuorumType::Absolute { min_votes: 5 }, 2, 1, 3);
+
+    assert_eq!(proposal.status, ProposalStatus::Passed);
+    let result = proposal.tally_result.unwrap();
+    assert_eq!((result.abstain_count, result.total_votes), (Some(3), 6));
+}
+
+#[test]
+fn abstain_does_not_break_a_tie() {
+    let proposal = tally(QuorumType::Absolute { min_votes: 1 }, 1, 1, 1);
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+}
+
+#[test]
+fn worker_without_abstain_support() {
+    let response: TallyResponse = serde_json::from_value(serde_json::json!({
+        "proposal_id": 1,
+        "yes_count": 1,
+        "no_count": 0,
+        "total_votes": 1,
+        "tee_attestation": "",
+        "votes_merkle_root": "",
+        "merkle_proofs": [],
+        "merkle_proofs_available": false,
+    }))
+    .unwrap();
+    assert_eq!(response.abstain_count, None);
+}
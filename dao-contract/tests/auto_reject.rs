@@ -0,0 +1,82 @@
+//! `finalize_proposal` rejects a proposal whose deadline passed without votes, skipping OutLayer
+
+mod common;
+
+use common::{account, caller, dao_with_paid_proposal};
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::get_created_receipts;
+use near_sdk::{AccountId, NearToken};
+use private_dao_contract::types::*;
+
+const DAY_NS: u64 = 86_400_000_000_000;
+const DEADLINE: u64 = 1_000 + 2 * DAY_NS;
+
+/// (receiver, amount) of every transfer and whether OutLayer was called
+fn outgoing() -> (Vec<(AccountId, NearToken)>, bool) {
+    let mut transfers = Vec::new();
+    let mut outlayer_called = false;
+    for receipt in get_created_receipts() {
+        for action in receipt.actions {
+            match action {
+                MockAction::Transfer { deposit, .. } => transfers.push((receipt.receiver_id.clone(), deposit)),
+                MockAction::FunctionCallWeight { method_name, .. } if method_name == b"request_execution" => {
+                    outlayer_called = true
+                }
+                _ => {}
+            }
+        }
+    }
+    (transfers, outlayer_called)
+}
+
+#[test]
+fn expired_without_votes_is_rejected_locally() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), Some(DEADLINE));
+
+    caller("bob").timestamp(DEADLINE).set();
+    let _ = dao.finalize_proposal(1);
+
+    let proposal = dao.get_proposal(1).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+    assert!(proposal.tally_result.is_none());
+    assert_eq!(dao.get_proposals_by_status(ProposalStatus::Rejected, 0, 10).len(), 1);
+    assert_eq!(outgoing(), (vec![(account("bob"), NearToken::from_millinear(20))], false));
+}
+
+#[test]
+fn prepaid_tally_returns_to_creator() {
+    let prepaid = DAOConfig::default().min_outlayer_deposit;
+    let creation_deposit = NearToken::from_millinear(1).saturating_add(prepaid);
+    let mut dao =
+        dao_with_paid_proposal(OutLayerPayerMode::Proposal { creator_pays: true }, creation_deposit, Some(DEADLINE));
+
+    caller("bob").timestamp(DEADLINE + 1).set();
+    let _ = dao.finalize_proposal(1);
+
+    assert!(dao.get_proposal(1).unwrap().creator_deposit.is_zero());
+    let (transfers, outlayer_called) = outgoing();
+    assert!(!outlayer_called);
+    assert!(transfers.contains(&(account("alice"), prepaid)));
+}
+
+#[test]
+fn expired_with_votes_goes_to_outlayer() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), Some(DEADLINE));
+    caller("alice").deposit(NearToken::from_millinear(2)).timestamp(2_000).set();
+    dao.cast_vote(1, "ab".repeat(113));
+
+    caller("bob").timestamp(DEADLINE + 1).set();
+    let _ = dao.finalize_proposal(1);
+
+    assert_eq!(dao.get_proposal(1).unwrap().status, ProposalStatus::Active);
+    assert!(outgoing().1);
+}
+
+#[test]
+#[should_panic(expected = "No votes to tally")]
+fn open_proposal_without_votes_still_waits() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), Some(DEADLINE));
+
+    caller("bob").timestamp(DEADLINE - 1).set();
+    let _ = dao.finalize_proposal(1);
+}
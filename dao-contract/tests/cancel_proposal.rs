@@ -0,0 +1,112 @@
+//! `cancel_proposal` withdraws an active proposal
+
+mod common;
+
+use common::{account, caller, dao_with_paid_proposal, outlayer_success};
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::{get_created_receipts, get_logs};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn cancel_as(dao: &mut PrivateDAO, name: &str) {
+    caller(name).set();
+    dao.cancel_proposal(1);
+}
+
+#[test]
+fn creator_cancels() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), None);
+
+    cancel_as(&mut dao, "alice");
+
+    assert_eq!(dao.get_proposal_status(1), Some(ProposalStatus::Cancelled));
+    assert!(get_logs().iter().all(|log| !log.starts_with("Warning")));
+    assert!(get_created_receipts().is_empty());
+    assert!(dao.verify_state_consistency().is_empty());
+}
+
+#[test]
+fn owner_cancels_with_warning_if_voted() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), None);
+    caller("bob").deposit(NearToken::from_millinear(2)).set();
+    dao.cast_vote(1, "ab".repeat(113));
+
+    cancel_as(&mut dao, "owner");
+
+    assert_eq!(dao.get_proposal_status(1), Some(ProposalStatus::Cancelled));
+    assert!(get_logs().contains(&"Warning: proposal 1 cancelled after 1 votes were cast".to_string()));
+    assert_eq!(dao.get_vote_count(1), 1);
+}
+
+#[test]
+fn prepaid_tally_refunded_to_creator() {
+    let config = DAOConfig::default();
+    let creation_deposit = config.proposal_deposit.saturating_add(config.min_outlayer_deposit);
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Proposal { creator_pays: true }, creation_deposit, None);
+
+    cancel_as(&mut dao, "owner");
+
+    let refund = get_created_receipts()
+        .into_iter()
+        .find(|receipt| receipt.receiver_id == account("alice"))
+        .and_then(|receipt| {
+            receipt.actions.into_iter().find_map(|action| match action {
+                MockAction::Transfer { deposit, .. } => Some(deposit),
+                _ => None,
+            })
+        });
+    assert_eq!(refund, Some(config.min_outlayer_deposit));
+    assert!(dao.get_proposal(1).unwrap().creator_deposit.is_zero());
+}
+
+#[test]
+fn tally_arriving_after_cancel_is_ignored() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), None);
+    cancel_as(&mut dao, "alice");
+
+    caller("dao").set();
+    dao.on_votes_tallied(
+        1,
+        None,
+        outlayer_success(serde_json::json!({
+            "proposal_id": 1,
+            "yes_count": 1,
+            "no_count": 0,
+            "total_votes": 1,
+            "tee_attestation": "",
+            "votes_merkle_root": "",
+            "merkle_proofs": [],
+            "merkle_proofs_available": true
+        })),
+    );
+
+    assert!(get_logs().contains(&"Proposal 1 was cancelled while tallying, result ignored".to_string()));
+    assert_eq!(dao.get_proposal_status(1), Some(ProposalStatus::Cancelled));
+    assert!(dao.get_proposal(1).unwrap().tally_result.is_none());
+}
+
+#[test]
+#[should_panic(expected = "Only the proposal creator or owner can cancel it")]
+fn other_member_cannot_cancel() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), None);
+    cancel_as(&mut dao, "bob");
+}
+
+#[test]
+#[should_panic(expected = "Proposal is not active")]
+fn cancelled_proposal_cannot_be_voted_on() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), None);
+    cancel_as(&mut dao, "alice");
+
+    caller("bob").deposit(NearToken::from_millinear(2)).set();
+    dao.cast_vote(1, "ab".repeat(113));
+}
+
+#[test]
+#[should_panic(expected = "Proposal is not active")]
+fn cannot_cancel_twice() {
+    let mut dao = dao_with_paid_proposal(OutLayerPayerMode::Caller, NearToken::from_millinear(1), None);
+    cancel_as(&mut dao, "alice");
+    cancel_as(&mut dao, "alice");
+}
@@ -0,0 +1,220 @@
+//! `finalize_proposal_chunk` / `commit_finalization` tally large proposals in slices
+
+mod common;
+
+use common::{caller, dao, outlayer_input, outlayer_success, yes_no_tally};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// Shortest allowed voting period (1 day)
+const DEADLINE: u64 = 86_400_000_000_000;
+
+/// One hour after the deadline: stalled runs can be abandoned by anyone
+const STALLED: u64 = DEADLINE + 3_600_000_000_000;
+
+/// Ballots: alice (0), bob (1), alice (2), carol (3); voting closed
+fn dao_with_closed_votes() -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(2)).set();
+    let mut dao = dao().private().members(&["alice", "bob", "carol"]).build();
+    dao.create_proposal(
+        "Chunked".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 2 },
+        Some(DEADLINE),
+        None,
+        None,
+    );
+
+    for voter in ["alice", "bob", "alice", "carol"] {
+        caller(voter).deposit(NearToken::from_millinear(2)).set();
+        dao.cast_vote(1, format!("{}-ballot", voter));
+    }
+
+    dao
+}
+
+/// Request a chunk after the deadline and return the users of the ballots sent to OutLayer
+fn request_chunk(dao: &mut PrivateDAO, offset: u64, limit: u64) -> Vec<String> {
+    caller("alice").timestamp(DEADLINE).set();
+    let _ = dao.finalize_proposal_chunk(1, offset, limit);
+
+    let input = outlayer_input();
+    assert_eq!(input["action"], "tally_votes_chunk");
+    assert_eq!(input["chunk_offset"], offset);
+
+    input["votes"].as_array().unwrap().iter().map(|vote| vote["user"].as_str().unwrap().to_string()).collect()
+}
+
+fn on_chunk_tallied(dao: &mut PrivateDAO, chunk_end: u64) {
+    caller("dao").timestamp(DEADLINE).set();
+    dao.on_votes_tallied_chunk(
+        1,
+        chunk_end,
+        None,
+        outlayer_success(serde_json::json!({
+            "proposal_id": 1,
+            "sealed_tally": format!("sealed-{}", chunk_end),
+            "next_offset": chunk_end,
+        })),
+    );
+}
+
+#[test]
+fn voters_go_with_their_last_ballot() {
+    let mut dao = dao_with_closed_votes();
+
+    // alice's first ballot is left for the chunk holding her last one
+    assert_eq!(request_chunk(&mut dao, 0, 2), vec!["bob.testnet"]);
+    assert!(outlayer_input()["sealed_tally"].is_null());
+    on_chunk_tallied(&mut dao, 2);
+
+    assert_eq!(request_chunk(&mut dao, 2, 2), vec!["alice.testnet", "alice.testnet", "carol.testnet"]);
+    assert_eq!(outlayer_input()["sealed_tally"], "sealed-2");
+}
+
+#[test]
+fn chunk_counts_stay_sealed_until_commit() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 1);
+    on_chunk_tallied(&mut dao, 1);
+    request_chunk(&mut dao, 1, 10);
+    on_chunk_tallied(&mut dao, 4);
+
+    let partial = dao.get_partial_tally(1).unwrap();
+    assert_eq!((partial.next_offset, partial.sealed_tally.as_str()), (4, "sealed-4"));
+    assert_eq!(dao.get_proposal(1).unwrap().tally_result, None);
+
+    caller("bob").timestamp(DEADLINE).set();
+    let _ = dao.commit_finalization(1);
+    let input = outlayer_input();
+    assert_eq!(input["action"], "commit_chunked_tally");
+    assert_eq!((input["sealed_tally"].as_str(), input["vote_count"].as_u64()), (Some("sealed-4"), Some(4)));
+    assert_eq!(input["quorum"], serde_json::json!({ "Absolute": { "min_votes": 2 } }));
+
+    caller("dao").timestamp(DEADLINE).set();
+    dao.on_votes_tallied(1, None, yes_no_tally(1, 2, 1));
+
+    let proposal = dao.get_proposal(1).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Passed);
+    assert_eq!(proposal.tally_result.unwrap().total_votes, 3);
+    assert!(dao.get_partial_tally(1).is_none());
+}
+
+#[test]
+fn failed_commit_keeps_partial_tally() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 10);
+    on_chunk_tallied(&mut dao, 4);
+    caller("bob").timestamp(DEADLINE).set();
+    let _ = dao.commit_finalization(1);
+
+    caller("dao").timestamp(DEADLINE).set();
+    dao.on_votes_tallied(1, None, Ok(None));
+
+    assert_eq!(dao.get_proposal(1).unwrap().status, ProposalStatus::Active);
+    assert_eq!(dao.get_partial_tally(1).unwrap().sealed_tally, "sealed-4");
+    caller("bob").timestamp(DEADLINE).set();
+    let _ = dao.commit_finalization(1);
+}
+
+#[test]
+#[should_panic(expected = "Chunked finalization is only available after the voting deadline")]
+fn chunks_wait_for_deadline() {
+    let mut dao = dao_with_closed_votes();
+
+    caller("alice").timestamp(DEADLINE - 1).set();
+    let _ = dao.finalize_proposal_chunk(1, 0, 2);
+}
+
+#[test]
+fn failed_chunk_can_be_retried() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 2);
+    caller("dao").timestamp(DEADLINE).set();
+    dao.on_votes_tallied_chunk(1, 2, None, Ok(None));
+
+    let partial = dao.get_partial_tally(1).unwrap();
+    assert_eq!(partial.next_offset, 0);
+    assert!(!partial.chunk_in_flight);
+    request_chunk(&mut dao, 0, 2);
+}
+
+#[test]
+fn chunk_result_must_match_requested_range() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 2);
+    caller("dao").timestamp(DEADLINE).set();
+    dao.on_votes_tallied_chunk(
+        1,
+        2,
+        None,
+        outlayer_success(serde_json::json!({ "proposal_id": 1, "sealed_tally": "sealed-3", "next_offset": 3 })),
+    );
+
+    let partial = dao.get_partial_tally(1).unwrap();
+    assert_eq!((partial.next_offset, partial.sealed_tally.as_str()), (0, ""));
+}
+
+#[test]
+#[should_panic(expected = "Chunks must be tallied in order, next offset is 2")]
+fn chunks_must_be_sequential() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 2);
+    on_chunk_tallied(&mut dao, 2);
+    request_chunk(&mut dao, 3, 1);
+}
+
+#[test]
+#[should_panic(expected = "Only 2 of 4 votes tallied")]
+fn commit_requires_every_vote() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 2);
+    on_chunk_tallied(&mut dao, 2);
+    let _ = dao.commit_finalization(1);
+}
+
+#[test]
+fn admin_can_abandon_and_late_chunk_is_ignored() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 2);
+    caller("owner").timestamp(DEADLINE).set();
+    dao.abandon_partial_tally(1);
+    assert!(dao.get_partial_tally(1).is_none());
+
+    on_chunk_tallied(&mut dao, 2);
+    assert!(dao.get_partial_tally(1).is_none());
+
+    // Nothing blocks a regular finalization any more
+    caller("alice").timestamp(DEADLINE).set();
+    let _ = dao.finalize_proposal(1);
+    assert_eq!(outlayer_input()["action"], "tally_votes");
+}
+
+#[test]
+#[should_panic(expected = "Only admins can abandon a chunked finalization that is still making progress")]
+fn members_cannot_abandon_active_run() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 2);
+    caller("bob").timestamp(STALLED - 1).set();
+    dao.abandon_partial_tally(1);
+}
+
+#[test]
+fn anyone_can_abandon_stalled_run() {
+    let mut dao = dao_with_closed_votes();
+
+    request_chunk(&mut dao, 0, 2);
+    caller("bob").timestamp(STALLED).set();
+    dao.abandon_partial_tally(1);
+
+    assert!(dao.get_partial_tally(1).is_none());
+}
@@ -0,0 +1,284 @@
+//! Fixtures shared by the integration tests (`mod common;` in each test file)
+//!
+//! Every test crate uses a different subset, hence the `dead_code` allowance.
+#![allow(dead_code)]
+
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+use near_sdk::{testing_env, AccountId, NearToken, PromiseError, PromiseResult};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// `<name>.testnet`
+pub fn account(name: &str) -> AccountId {
+    format!("{}.testnet", name).parse().unwrap()
+}
+
+/// Context of a call from `name` to the DAO (deployed at `dao.testnet`)
+///
+/// Attaches 20 mNEAR unless `deposit` says otherwise; takes effect on `set`.
+pub fn caller(name: &str) -> Context {
+    let mut builder = VMContextBuilder::new();
+    builder
+        .current_account_id(account("dao"))
+        .predecessor_account_id(account(name))
+        .attached_deposit(NearToken::from_millinear(20));
+    Context(builder)
+}
+
+pub struct Context(VMContextBuilder);
+
+impl Context {
+    pub fn deposit(mut self, deposit: NearToken) -> Self {
+        self.0.attached_deposit(deposit);
+        self
+    }
+
+    pub fn balance(mut self, balance: NearToken) -> Self {
+        self.0.account_balance(balance);
+        self
+    }
+
+    pub fn timestamp(mut self, block_timestamp: u64) -> Self {
+        self.0.block_timestamp(block_timestamp);
+        self
+    }
+
+    pub fn block_height(mut self, block_height: u64) -> Self {
+        self.0.block_height(block_height);
+        self
+    }
+
+    /// Block `block_height`, produced at `block_height` × 1000 ns
+    pub fn block(self, block_height: u64) -> Self {
+        self.block_height(block_height).timestamp(block_height * 1_000)
+    }
+
+    pub fn set(self) {
+        testing_env!(self.0.build());
+    }
+
+    /// `set` for a callback whose single promise ended with `result`
+    pub fn set_with_promise_result(self, result: PromiseResult) {
+        testing_env!(
+            self.0.build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+    }
+}
+
+/// `PrivateDAO::new` arguments: a public "Test DAO" owned by `owner.testnet` with default settings
+///
+/// `build` doesn't touch the call context, set it first.
+pub fn dao() -> DaoBuilder {
+    DaoBuilder {
+        membership_mode: MembershipMode::Public,
+        owner: account("owner"),
+        config: None,
+        outlayer_contract: None,
+        max_members: None,
+        secrets_ref: None,
+        members: vec![],
+    }
+}
+
+pub struct DaoBuilder {
+    membership_mode: MembershipMode,
+    owner: AccountId,
+    config: Option<DAOConfig>,
+    outlayer_contract: Option<AccountId>,
+    max_members: Option<u64>,
+    secrets_ref: Option<serde_json::Value>,
+    members: Vec<AccountId>,
+}
+
+impl DaoBuilder {
+    pub fn membership_mode(mut self, membership_mode: MembershipMode) -> Self {
+        self.membership_mode = membership_mode;
+        self
+    }
+
+    pub fn private(self) -> Self {
+        self.membership_mode(MembershipMode::Private)
+    }
+
+    pub fn owner(mut self, name: &str) -> Self {
+        self.owner = account(name);
+        self
+    }
+
+    pub fn config(mut self, config: DAOConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn outlayer_contract(mut self, outlayer_contract: AccountId) -> Self {
+        self.outlayer_contract = Some(outlayer_contract);
+        self
+    }
+
+    pub fn max_members(mut self, max_members: u64) -> Self {
+        self.max_members = Some(max_members);
+        self
+    }
+
+    pub fn secrets_ref(mut self, secrets_ref: serde_json::Value) -> Self {
+        self.secrets_ref = Some(secrets_ref);
+        self
+    }
+
+    /// Members with pubkey `02abcd`, added by the owner (`migrate_add_member`) after `new`
+    pub fn members(mut self, names: &[&str]) -> Self {
+        self.members = names.iter().map(|name| account(name)).collect();
+        self
+    }
+
+    pub fn build(self) -> PrivateDAO {
+        let mut dao = PrivateDAO::new(
+            "Test DAO".to_string(),
+            self.membership_mode,
+            self.owner,
+            self.config,
+            self.outlayer_contract,
+            self.max_members,
+            self.secrets_ref,
+        );
+        for member in self.members {
+            dao.migrate_add_member(member, Some("02abcd".to_string()));
+        }
+        dao
+    }
+}
+
+/// Public DAO with default settings, created in the owner's call context (left set)
+pub fn new_dao() -> PrivateDAO {
+    caller("owner").set();
+    dao().build()
+}
+
+/// Private DAO with `members` and proposal 1 by the owner requiring `quorum`, without deadline
+///
+/// The owner's call context (2 mNEAR attached) is left set.
+pub fn dao_with_proposal(members: &[&str], quorum: QuorumType) -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(2)).set();
+    let mut dao = dao().private().members(members).build();
+    dao.create_proposal("Proposal 1".to_string(), String::new(), quorum, None, None, None);
+    dao
+}
+
+/// Private DAO in payer `mode` with members alice and bob, and proposal 1 by alice
+/// (attaching `creation_deposit`, due at `deadline` if any)
+pub fn dao_with_paid_proposal(
+    mode: OutLayerPayerMode,
+    creation_deposit: NearToken,
+    deadline: Option<u64>,
+) -> PrivateDAO {
+    caller("owner").set();
+    let config = DAOConfig { outlayer_payer_mode: mode, ..DAOConfig::default() };
+    let mut dao = dao().private().config(config).members(&["alice", "bob"]).build();
+    caller("alice").deposit(creation_deposit).set();
+    dao.create_proposal(
+        "Proposal 1".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        deadline,
+        None,
+        None,
+    );
+    dao
+}
+
+/// `dao_with_proposal` with a ballot from each of `voters`, in order
+pub fn dao_with_votes(members: &[&str], voters: &[&str], quorum: QuorumType) -> PrivateDAO {
+    let mut dao = dao_with_proposal(members, quorum);
+    cast_votes(&mut dao, 1, voters);
+    dao
+}
+
+/// A ballot on `proposal_id` from each of `voters`, in order (2 mNEAR attached)
+pub fn cast_votes(dao: &mut PrivateDAO, proposal_id: u64, voters: &[&str]) {
+    for voter in voters {
+        caller(voter).deposit(NearToken::from_millinear(2)).set();
+        dao.cast_vote(proposal_id, "ab".repeat(113));
+    }
+}
+
+/// Transfers scheduled so far, as (receiver, amount)
+pub fn transfers() -> Vec<(AccountId, NearToken)> {
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| {
+            let receiver_id = receipt.receiver_id.clone();
+            receipt.actions.into_iter().filter_map(move |action| match action {
+                MockAction::Transfer { deposit, .. } => Some((receiver_id.clone(), deposit)),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+/// Active yes/no proposal `id` by `owner.testnet`, for tests that store proposals directly
+pub fn proposal(id: u64) -> Proposal {
+    Proposal {
+        id,
+        title: format!("Proposal {}", id),
+        description: String::new(),
+        creator: account("owner"),
+        created_at: 0,
+        deadline: None,
+        quorum: QuorumType::Absolute { min_votes: 1 },
+        status: ProposalStatus::Active,
+        tally_result: None,
+        proposal_type: ProposalType::Governance,
+        tags: vec![],
+        creator_deposit: NearToken::from_yoctonear(0),
+        eligible_voters: 1,
+        vote_options: None,
+        execution_payload: None,
+        amended_at: None,
+        decided_at: None,
+    }
+}
+
+/// Successful OutLayer execution returning `result`
+pub fn outlayer_success(result: serde_json::Value) -> Result<Option<OutLayerResponse>, PromiseError> {
+    Ok(Some(OutLayerResponse { success: true, result, error: None }))
+}
+
+/// Key derivation result with pubkey `02abcd`
+pub fn derived_key() -> Result<Option<OutLayerResponse>, PromiseError> {
+    outlayer_success(serde_json::json!({ "pubkey": "02abcd" }))
+}
+
+/// Tally result of a yes/no proposal that reached quorum
+pub fn yes_no_tally(proposal_id: u64, yes: u64, no: u64) -> Result<Option<OutLayerResponse>, PromiseError> {
+    outlayer_success(serde_json::json!({
+        "proposal_id": proposal_id,
+        "yes_count": yes,
+        "no_count": no,
+        "vote_counts": { "yes": yes, "no": no },
+        "total_votes": yes + no,
+        "tee_attestation": "",
+        "votes_merkle_root": "",
+        "merkle_proofs": [],
+        "merkle_proofs_available": false,
+    }))
+}
+
+/// `input_data` of the OutLayer `request_execution` call created by the last method
+pub fn outlayer_input() -> serde_json::Value {
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| receipt.actions)
+        .find_map(|action| match action {
+            MockAction::FunctionCallWeight { method_name, args, .. } if method_name == b"request_execution" => {
+                let args: serde_json::Value = serde_json::from_slice(&args).unwrap();
+                Some(serde_json::from_str(args["input_data"].as_str().unwrap()).unwrap())
+            }
+            _ => None,
+        })
+        .expect("request_execution call")
+}
@@ -0,0 +1,137 @@
+//! UpgradeContract proposals: `upload_upgrade_wasm` then `execute_upgrade` once passed
+
+mod common;
+
+use common::{account, caller, dao, transfers, yes_no_tally};
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::get_created_receipts;
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// `DAOConfig::execution_delay_ns` default
+const EXECUTION_DELAY: u64 = 86_400_000_000_000;
+
+const WASM: &[u8] = b"\0asm new contract code";
+
+fn wasm_hash() -> String {
+    hex::encode(near_sdk::env::sha256(WASM))
+}
+
+/// DAO with UpgradeContract proposal 1, its WASM uploaded by carol (1 NEAR deposit)
+fn dao_with_upload() -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().private().build();
+    dao.create_proposal(
+        "Upgrade".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        Some(ProposalType::UpgradeContract { wasm_hash: wasm_hash() }),
+        None,
+    );
+
+    caller("carol").deposit(NearToken::from_near(1)).set();
+    dao.upload_upgrade_wasm(1, Base64VecU8::from(WASM.to_vec()));
+    dao
+}
+
+/// Decide proposal 1 at time 0 with `yes` / `no` votes
+fn decide(dao: &mut PrivateDAO, yes: u64, no: u64) {
+    caller("dao").set();
+    dao.on_votes_tallied(1, None, yes_no_tally(1, yes, no));
+}
+
+fn deployed_code() -> Option<Vec<u8>> {
+    get_created_receipts().into_iter().flat_map(|receipt| receipt.actions).find_map(|action| match action {
+        MockAction::DeployContract { code, .. } => Some(code),
+        _ => None,
+    })
+}
+
+#[test]
+fn passing_keeps_wasm_until_executed() {
+    let mut dao = dao_with_upload();
+    decide(&mut dao, 1, 0);
+
+    assert_eq!(dao.get_proposal(1).unwrap().status, ProposalStatus::Passed);
+    assert!(deployed_code().is_none());
+    assert!(dao.pending_upgrade_wasm.contains_key(&1));
+}
+
+#[test]
+fn execute_upgrade_deploys_and_refunds_uploader() {
+    let mut dao = dao_with_upload();
+    decide(&mut dao, 1, 0);
+
+    caller("anyone").timestamp(EXECUTION_DELAY).set();
+    let _ = dao.execute_upgrade(1);
+
+    assert_eq!(deployed_code(), Some(WASM.to_vec()));
+    assert_eq!(transfers(), vec![(account("carol"), NearToken::from_near(1))]);
+    assert_eq!(dao.get_proposal(1).unwrap().status, ProposalStatus::Executed);
+    assert!(!dao.pending_upgrade_wasm.contains_key(&1));
+}
+
+#[test]
+#[should_panic(expected = "Proposal can be executed from 86400000000000")]
+fn execute_upgrade_waits_for_delay() {
+    let mut dao = dao_with_upload();
+    decide(&mut dao, 1, 0);
+
+    caller("anyone").timestamp(EXECUTION_DELAY - 1).set();
+    let _ = dao.execute_upgrade(1);
+}
+
+#[test]
+fn rejected_upgrade_refunds_uploader() {
+    let mut dao = dao_with_upload();
+    decide(&mut dao, 0, 1);
+
+    assert_eq!(dao.get_proposal(1).unwrap().status, ProposalStatus::Rejected);
+    assert!(deployed_code().is_none());
+    assert_eq!(transfers(), vec![(account("carol"), NearToken::from_near(1))]);
+}
+
+#[test]
+fn vetoed_upgrade_refunds_uploader() {
+    let mut dao = dao_with_upload();
+    decide(&mut dao, 1, 0);
+
+    caller("owner").set();
+    dao.veto_proposal(1, "Unaudited code".to_string());
+
+    assert!(!dao.pending_upgrade_wasm.contains_key(&1));
+    assert_eq!(transfers(), vec![(account("carol"), NearToken::from_near(1))]);
+}
+
+#[test]
+fn cancelled_upgrade_refunds_uploader() {
+    let mut dao = dao_with_upload();
+
+    caller("owner").set();
+    dao.cancel_proposal(1);
+
+    assert!(!dao.pending_upgrade_wasm.contains_key(&1));
+    assert_eq!(transfers(), vec![(account("carol"), NearToken::from_near(1))]);
+}
+
+#[test]
+#[should_panic(expected = "Upgrade WASM was already uploaded for this proposal")]
+fn upload_only_once() {
+    let mut dao = dao_with_upload();
+
+    caller("dave").deposit(NearToken::from_near(1)).set();
+    dao.upload_upgrade_wasm(1, Base64VecU8::from(WASM.to_vec()));
+}
+
+#[test]
+#[should_panic(expected = "Not an UpgradeContract proposal")]
+fn execute_upgrade_needs_upgrade_proposal() {
+    caller("owner").set();
+    let mut dao = dao().private().build();
+    dao.create_proposal("Plain".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+
+    let _ = dao.execute_upgrade(1);
+}
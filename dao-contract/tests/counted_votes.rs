@@ -0,0 +1,74 @@
+//! Commitment to the counted ballots stored with the tally
+
+mod common;
+
+use common::{account, caller, dao_with_votes, outlayer_success, yes_no_tally};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+const MEMBERS: &[&str] = &["alice", "bob", "carol"];
+
+/// Ballots: alice (0), bob (1), carol (2), alice (3)
+const BALLOTS: &[&str] = &["alice", "bob", "carol", "alice"];
+
+/// Deliver a tally callback; `counted_votes_root` is None when quorum wasn't met
+fn on_votes_tallied(dao: &mut PrivateDAO, counted_votes_root: Option<&str>) {
+    let quorum_met = counted_votes_root.is_some();
+    caller("dao").set();
+    dao.on_votes_tallied(
+        1,
+        None,
+        outlayer_success(serde_json::json!({
+            "proposal_id": 1,
+            "yes_count": quorum_met.then_some(2),
+            "no_count": quorum_met.then_some(0),
+            "total_votes": if quorum_met { 2 } else { 0 },
+            "tee_attestation": "",
+            "votes_merkle_root": "",
+            "merkle_proofs": [],
+            "merkle_proofs_available": false,
+            "counted_votes_root": counted_votes_root.unwrap_or_default(),
+        })),
+    );
+}
+
+fn counted_votes_root(dao: &PrivateDAO) -> String {
+    dao.get_proposal(1).unwrap().tally_result.unwrap().counted_votes_root
+}
+
+#[test]
+fn root_stored_with_tally() {
+    let mut dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Absolute { min_votes: 2 });
+    on_votes_tallied(&mut dao, Some("c0ffee"));
+
+    assert_eq!(counted_votes_root(&dao), "c0ffee");
+}
+
+#[test]
+fn no_root_without_quorum() {
+    let mut dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Absolute { min_votes: 2 });
+    on_votes_tallied(&mut dao, None);
+
+    assert_eq!(counted_votes_root(&dao), "");
+}
+
+#[test]
+fn older_worker_output_still_parses() {
+    let mut dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Absolute { min_votes: 2 });
+    caller("dao").set();
+    dao.on_votes_tallied(1, None, yes_no_tally(1, 2, 0));
+
+    assert_eq!(dao.get_proposal(1).unwrap().status, ProposalStatus::Passed);
+    assert_eq!(counted_votes_root(&dao), "");
+}
+
+#[test]
+fn ballots_per_user() {
+    let dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Absolute { min_votes: 2 });
+
+    assert_eq!(dao.get_vote_count_by_user(1, account("alice")), 2);
+    assert_eq!(dao.get_vote_count_by_user(1, account("bob")), 1);
+    assert!(dao.has_voted(1, account("carol")));
+    assert!(!dao.has_voted(1, account("owner")));
+    assert!(!dao.has_voted(2, account("alice")));
+}
@@ -0,0 +1,255 @@
+//! Vote delegation and concentration views
+
+mod common;
+
+use common::{account, caller, dao, dao_with_proposal, outlayer_input};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// DAO with `owner` plus the given members
+fn dao_with_members(members: &[&str]) -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().private().build();
+    for member in members {
+        dao.migrate_add_member(account(member), None);
+    }
+    dao
+}
+
+fn delegate(dao: &mut PrivateDAO, from: &str, to: &str) {
+    caller(from).set();
+    dao.delegate_vote(account(to));
+}
+
+#[test]
+fn no_delegations() {
+    let dao = dao_with_members(&["alice", "bob"]);
+
+    let stats = dao.get_delegation_stats();
+    assert_eq!(stats.total_delegations, 0);
+    assert_eq!(stats.unique_delegates, 0);
+    assert_eq!(stats.max_delegations_to_one_person, 0);
+    assert_eq!(stats.gini_coefficient, 0.0);
+    assert!(dao.get_top_delegates(10).is_empty());
+}
+
+#[test]
+fn one_delegate_holds_everything() {
+    let mut dao = dao_with_members(&["alice", "bob", "carol"]);
+    for member in ["owner", "bob", "carol"] {
+        delegate(&mut dao, member, "alice");
+    }
+
+    let stats = dao.get_delegation_stats();
+    assert_eq!(stats.total_delegations, 3);
+    assert_eq!(stats.unique_delegates, 1);
+    assert_eq!(stats.max_delegations_to_one_person, 3);
+    assert!((stats.gini_coefficient - 1.0).abs() < 1e-6, "gini = {}", stats.gini_coefficient);
+}
+
+#[test]
+fn spread_delegations_have_lower_gini() {
+    // Two of four members receive one delegation each
+    let mut dao = dao_with_members(&["alice", "bob", "carol"]);
+    delegate(&mut dao, "owner", "alice");
+    delegate(&mut dao, "bob", "carol");
+
+    let stats = dao.get_delegation_stats();
+    assert_eq!(stats.unique_delegates, 2);
+    assert!((stats.gini_coefficient - 2.0 / 3.0).abs() < 1e-6, "gini = {}", stats.gini_coefficient);
+}
+
+#[test]
+fn top_delegates_ordered_and_limited() {
+    let mut dao = dao_with_members(&["alice", "bob", "carol", "dave"]);
+    delegate(&mut dao, "owner", "alice");
+    delegate(&mut dao, "carol", "alice");
+    delegate(&mut dao, "dave", "bob");
+
+    assert_eq!(dao.get_top_delegates(1), vec![(account("alice"), 2)]);
+    assert_eq!(dao.get_top_delegates(10), vec![(account("alice"), 2), (account("bob"), 1)]);
+}
+
+#[test]
+fn leaving_delegate_clears_incoming_delegations() {
+    let mut dao = dao_with_members(&["alice", "bob", "carol"]);
+    delegate(&mut dao, "bob", "alice");
+    delegate(&mut dao, "carol", "alice");
+
+    caller("alice").set();
+    dao.leave_dao();
+
+    assert_eq!(dao.get_delegate(account("bob")), None);
+    assert_eq!(dao.get_delegate(account("carol")), None);
+    assert_eq!(dao.get_delegation_stats().total_delegations, 0);
+    assert!(dao.get_top_delegates(10).is_empty());
+}
+
+#[test]
+fn removed_delegate_loses_only_its_delegators() {
+    let mut dao = dao_with_members(&["alice", "bob", "carol", "dave"]);
+    delegate(&mut dao, "bob", "alice");
+    delegate(&mut dao, "carol", "alice");
+    delegate(&mut dao, "carol", "dave");
+
+    caller("owner").set();
+    dao.remove_member(account("alice"));
+
+    assert_eq!(dao.get_delegate(account("bob")), None);
+    assert_eq!(dao.get_delegate(account("carol")), Some(account("dave")));
+    assert_eq!(dao.get_top_delegates(10), vec![(account("dave"), 1)]);
+
+    // bob can delegate again
+    delegate(&mut dao, "bob", "dave");
+    assert_eq!(dao.get_top_delegates(10), vec![(account("dave"), 2)]);
+}
+
+#[test]
+fn redelegating_and_leaving_update_reverse_index() {
+    let mut dao = dao_with_members(&["alice", "bob", "carol"]);
+    delegate(&mut dao, "carol", "alice");
+    delegate(&mut dao, "carol", "bob");
+
+    assert_eq!(dao.get_delegate(account("carol")), Some(account("bob")));
+    assert_eq!(dao.get_top_delegates(10), vec![(account("bob"), 1)]);
+
+    caller("carol").set();
+    dao.leave_dao();
+
+    assert_eq!(dao.get_delegate(account("carol")), None);
+    assert_eq!(dao.get_delegation_stats().total_delegations, 0);
+}
+
+#[test]
+#[should_panic(expected = "Cannot delegate to yourself")]
+fn cannot_delegate_to_self() {
+    let mut dao = dao_with_members(&["alice"]);
+    delegate(&mut dao, "alice", "alice");
+}
+
+#[test]
+#[should_panic(expected = "Circular delegation: alice.testnet already delegates to you")]
+fn circular_delegation_rejected() {
+    let mut dao = dao_with_members(&["alice", "bob"]);
+    delegate(&mut dao, "owner", "alice");
+    delegate(&mut dao, "alice", "bob");
+    delegate(&mut dao, "bob", "owner");
+}
+
+#[test]
+#[should_panic(expected = "Delegation chain would be longer than 10 hops")]
+fn over_long_chain_rejected() {
+    // m0 → m1 → ... → m10 is the longest allowed chain
+    let names: Vec<String> = (0..=11).map(|i| format!("m{}", i)).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let mut dao = dao_with_members(&names);
+    for pair in names[..11].windows(2).rev() {
+        delegate(&mut dao, pair[0], pair[1]);
+    }
+
+    delegate(&mut dao, "m11", "m0");
+}
+
+fn cast_vote(dao: &mut PrivateDAO, voter: &str) {
+    caller(voter).deposit(NearToken::from_millinear(2)).set();
+    dao.cast_vote(1, "ab".repeat(113));
+}
+
+#[test]
+#[should_panic(expected = "You delegated your vote to alice.testnet; call revoke_delegation before voting")]
+fn delegator_cannot_vote() {
+    let mut dao = dao_with_proposal(&["alice", "bob"], QuorumType::Absolute { min_votes: 1 });
+    delegate(&mut dao, "bob", "alice");
+
+    cast_vote(&mut dao, "bob");
+}
+
+#[test]
+fn revoked_delegator_can_vote() {
+    let mut dao = dao_with_proposal(&["alice", "bob"], QuorumType::Absolute { min_votes: 1 });
+    delegate(&mut dao, "bob", "alice");
+    caller("bob").set();
+    dao.revoke_delegation();
+
+    cast_vote(&mut dao, "bob");
+    assert_eq!(dao.get_delegate(account("bob")), None);
+}
+
+#[test]
+fn delegations_sent_to_tally() {
+    let mut dao = dao_with_proposal(&["alice", "bob"], QuorumType::Absolute { min_votes: 1 });
+    delegate(&mut dao, "bob", "alice");
+    cast_vote(&mut dao, "alice");
+
+    caller("alice").deposit(NearToken::from_millinear(20)).set();
+    let _ = dao.finalize_proposal(1);
+
+    let input = outlayer_input();
+    assert_eq!(input["delegations"], serde_json::json!({ "bob.testnet": "alice.testnet" }));
+}
+
+/// Shortest allowed voting period (1 day)
+const DEADLINE: u64 = 86_400_000_000_000;
+
+/// One-hour lock-in before the deadline
+const LOCK_IN: u64 = 3_600_000_000_000;
+
+/// `dao_with_proposal`, but proposal 1 closes at `DEADLINE` after a `LOCK_IN` window
+fn dao_with_closing_proposal() -> PrivateDAO {
+    caller("owner").set();
+    let config = DAOConfig { vote_change_deadline_ns: Some(LOCK_IN), ..DAOConfig::default() };
+    let mut dao = dao().private().config(config).build();
+    for member in ["alice", "bob", "carol"] {
+        dao.migrate_add_member(account(member), Some("02abcd".to_string()));
+    }
+    caller("alice").deposit(NearToken::from_millinear(2)).set();
+    dao.create_proposal(
+        "Budget".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        Some(DEADLINE),
+        None,
+        None,
+    );
+    dao
+}
+
+#[test]
+fn delegating_allowed_before_lock_in() {
+    let mut dao = dao_with_closing_proposal();
+
+    caller("bob").timestamp(DEADLINE - LOCK_IN - 1).set();
+    dao.delegate_vote(account("alice"));
+    assert_eq!(dao.get_delegate(account("bob")), Some(account("alice")));
+}
+
+#[test]
+#[should_panic(expected = "Delegations are frozen until proposal 1 is finalized")]
+fn delegating_frozen_during_lock_in() {
+    let mut dao = dao_with_closing_proposal();
+
+    caller("bob").timestamp(DEADLINE - LOCK_IN).set();
+    dao.delegate_vote(account("alice"));
+}
+
+#[test]
+#[should_panic(expected = "Delegations are frozen until proposal 1 is finalized")]
+fn revoking_frozen_until_finalized() {
+    let mut dao = dao_with_closing_proposal();
+    delegate(&mut dao, "bob", "alice");
+
+    caller("bob").timestamp(DEADLINE + 1).set();
+    dao.revoke_delegation();
+}
+
+#[test]
+fn delegating_allowed_after_finalization() {
+    let mut dao = dao_with_closing_proposal();
+    caller("alice").timestamp(DEADLINE).set();
+    dao.cancel_proposal(1);
+
+    caller("bob").timestamp(DEADLINE).set();
+    dao.delegate_vote(account("alice"));
+    assert_eq!(dao.get_delegate(account("bob")), Some(account("alice")));
+}
@@ -0,0 +1,125 @@
+//! Quorum estimate from submission counts (no TEE tally)
+
+mod common;
+
+use common::{account, caller, dao, dao_with_votes};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+
+/// carol has a key but doesn't vote; the owner has no key and isn't eligible
+const MEMBERS: &[&str] = &["alice", "bob", "carol"];
+
+/// Ballots from alice, bob and alice again (2 distinct voters)
+const BALLOTS: &[&str] = &["alice", "bob", "alice"];
+
+#[test]
+fn absolute_met() {
+    let dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Absolute { min_votes: 2 });
+    assert_eq!(
+        dao.estimate_quorum_status(1),
+        EarlyQuorumStatus { definitely_met: true, possibly_met: true }
+    );
+}
+
+#[test]
+fn absolute_counts_distinct_voters() {
+    // Three submissions, but alice's second one replaces her first
+    let dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Absolute { min_votes: 3 });
+    assert_eq!(
+        dao.estimate_quorum_status(1),
+        EarlyQuorumStatus { definitely_met: false, possibly_met: false }
+    );
+}
+
+#[test]
+fn time_weighted_is_never_definite() {
+    let dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::TimeWeighted { base_votes: 2, decay_halflife_ns: 1_000 });
+    assert_eq!(
+        dao.estimate_quorum_status(1),
+        EarlyQuorumStatus { definitely_met: false, possibly_met: true }
+    );
+}
+
+#[test]
+fn unanimous_needs_every_member_at_creation() {
+    // alice, bob and carol had keys when the proposal was created; carol hasn't voted
+    let mut dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Unanimous);
+    assert_eq!(dao.get_proposal(1).unwrap().eligible_voters, 3);
+    assert_eq!(
+        dao.estimate_quorum_status(1),
+        EarlyQuorumStatus { definitely_met: false, possibly_met: false }
+    );
+
+    // Members added after creation don't raise the bar
+    caller("owner").deposit(NearToken::from_millinear(2)).set();
+    dao.migrate_add_member(account("dave"), Some("02abcd".to_string()));
+    assert_eq!(dao.get_proposal(1).unwrap().eligible_voters, 3);
+
+    // carol's ballot completes the count
+    caller("carol").deposit(NearToken::from_millinear(2)).set();
+    dao.cast_vote(1, "ab".repeat(113));
+    assert_eq!(
+        dao.estimate_quorum_status(1),
+        EarlyQuorumStatus { definitely_met: true, possibly_met: true }
+    );
+}
+
+#[test]
+fn members_without_key_are_not_eligible() {
+    caller("owner").deposit(NearToken::from_millinear(2)).set();
+    let mut dao = dao().private().build();
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+    // Pre-approved but never completed the join
+    dao.add_member(account("bob"));
+
+    dao.create_proposal("Quorum".to_string(), String::new(), QuorumType::Unanimous, None, None, None);
+    assert_eq!(dao.get_dao_info().member_count, 3);
+    assert_eq!(dao.get_proposal(1).unwrap().eligible_voters, 1);
+}
+
+#[test]
+fn percentage_met() {
+    // 2 of 3 eligible members voted
+    let dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Percentage { min_percent_of_members: 66 });
+    assert_eq!(
+        dao.estimate_quorum_status(1),
+        EarlyQuorumStatus { definitely_met: true, possibly_met: true }
+    );
+}
+
+#[test]
+fn percentage_below_share_of_members() {
+    let dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Percentage { min_percent_of_members: 67 });
+    assert_eq!(
+        dao.estimate_quorum_status(1),
+        EarlyQuorumStatus { definitely_met: false, possibly_met: false }
+    );
+}
+
+#[test]
+fn super_majority_is_never_definite() {
+    let dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::SuperMajority { min_yes_percent: 67 });
+    assert_eq!(
+        dao.estimate_quorum_status(1),
+        EarlyQuorumStatus { definitely_met: false, possibly_met: true }
+    );
+}
+
+#[test]
+#[should_panic(expected = "min_percent_of_members must be between 1 and 100")]
+fn percentage_above_100_rejected() {
+    dao_with_votes(MEMBERS, BALLOTS, QuorumType::Percentage { min_percent_of_members: 101 });
+}
+
+#[test]
+#[should_panic(expected = "min_yes_percent must be between 1 and 100")]
+fn super_majority_zero_rejected() {
+    dao_with_votes(MEMBERS, BALLOTS, QuorumType::SuperMajority { min_yes_percent: 0 });
+}
+
+#[test]
+#[should_panic(expected = "Proposal not found")]
+fn unknown_proposal() {
+    let dao = dao_with_votes(MEMBERS, BALLOTS, QuorumType::Absolute { min_votes: 1 });
+    dao.estimate_quorum_status(42);
+}
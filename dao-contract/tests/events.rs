@@ -0,0 +1,110 @@
+//! NEP-297 events for membership, proposal and vote changes
+
+mod common;
+
+use common::{account, caller, derived_key, new_dao, outlayer_success};
+use near_sdk::test_utils::get_logs;
+use private_dao_contract::types::*;
+
+/// Events emitted by the last call, as (event name, data)
+fn events() -> Vec<(String, serde_json::Value)> {
+    get_logs()
+        .iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|json| {
+            let event: serde_json::Value = serde_json::from_str(json).unwrap();
+            assert_eq!(event["standard"], "private_dao");
+            (event["event"].as_str().unwrap().to_string(), event["data"].clone())
+        })
+        .collect()
+}
+
+#[test]
+fn join_and_leave() {
+    let mut dao = new_dao();
+
+    caller("alice").timestamp(200).set();
+    let _ = dao.join_dao();
+    caller("dao").timestamp(300).set();
+    dao.on_key_derived(account("alice"), None, derived_key());
+    assert_eq!(
+        events(),
+        vec![("member_joined".to_string(), serde_json::json!({ "account_id": "alice.testnet", "joined_at": 300 }))]
+    );
+
+    // Key rotation is not a join
+    caller("alice").timestamp(400).set();
+    let _ = dao.rotate_key();
+    caller("dao").timestamp(500).set();
+    dao.on_key_derived(account("alice"), None, derived_key());
+    assert!(events().is_empty());
+
+    caller("alice").timestamp(600).set();
+    dao.leave_dao();
+    assert_eq!(
+        events(),
+        vec![("member_left".to_string(), serde_json::json!({ "account_id": "alice.testnet", "removed_by": null }))]
+    );
+}
+
+#[test]
+fn owner_removal() {
+    let mut dao = new_dao();
+    dao.migrate_add_member(account("bob"), Some("02abcd".to_string()));
+
+    dao.remove_member(account("bob"));
+    assert_eq!(
+        events(),
+        vec![(
+            "member_left".to_string(),
+            serde_json::json!({ "account_id": "bob.testnet", "removed_by": "owner.testnet" })
+        )]
+    );
+}
+
+#[test]
+fn proposal_lifecycle() {
+    let mut dao = new_dao();
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+
+    caller("alice").timestamp(1_000).set();
+    dao.create_proposal("Events".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    assert_eq!(
+        events(),
+        vec![(
+            "proposal_created".to_string(),
+            serde_json::json!({ "proposal_id": 1, "creator": "alice.testnet", "timestamp": 1_000 })
+        )]
+    );
+
+    caller("alice").timestamp(2_000).set();
+    dao.cast_vote(1, "ab".repeat(113));
+    let (_, data) = events().into_iter().find(|(name, _)| name == "vote_cast").unwrap();
+    assert_eq!(
+        data,
+        serde_json::json!({ "proposal_id": 1, "voter": "alice.testnet", "vote_index": 0, "timestamp": 2_000 })
+    );
+
+    caller("dao").timestamp(3_000).set();
+    dao.on_votes_tallied(
+        1,
+        None,
+        outlayer_success(serde_json::json!({
+            "proposal_id": 1,
+            "yes_count": 1,
+            "no_count": 0,
+            "total_votes": 1,
+            "tee_attestation": "",
+            "votes_merkle_root": "",
+            "merkle_proofs": [],
+            "merkle_proofs_available": false,
+        })),
+    );
+    assert_eq!(
+        events(),
+        vec![(
+            "proposal_finalized".to_string(),
+            serde_json::json!({ "proposal_id": 1, "status": "Passed", "total_votes": 1 })
+        )]
+    );
+}
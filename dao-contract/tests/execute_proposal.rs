@@ -0,0 +1,159 @@
+//! `execute_proposal` runs the execution payload of a passed proposal once
+
+mod common;
+
+use common::{account, caller, dao};
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::get_created_receipts;
+use near_sdk::{NearToken, PromiseResult};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn payload(deposit: &str) -> String {
+    serde_json::json!({
+        "receiver_id": "grants.testnet",
+        "method_name": "pay",
+        "args": "{\"amount\":\"5\"}",
+        "deposit": deposit
+    })
+    .to_string()
+}
+
+/// Private DAO with executable proposal 1 (`execution_payload`) by the owner
+fn dao_with_executable_proposal(execution_payload: String) -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(1)).set();
+    let mut dao = dao().private().build();
+    dao.create_executable_proposal(
+        "Pay grant".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        execution_payload,
+        None,
+    );
+    dao
+}
+
+/// `DAOConfig::execution_delay_ns` default
+const EXECUTION_DELAY: u64 = 86_400_000_000_000;
+
+/// Mark `proposal_id` as passed at time 0 and move the clock past the execution delay
+fn mark_passed(dao: &mut PrivateDAO, proposal_id: u64) {
+    let mut proposal = dao.get_proposal(proposal_id).unwrap();
+    proposal.status = ProposalStatus::Passed;
+    proposal.decided_at = Some(0);
+    dao.proposals.insert(&proposal_id, &proposal);
+    caller("anyone").timestamp(EXECUTION_DELAY).set();
+}
+
+#[test]
+fn passed_proposal_calls_receiver() {
+    let mut dao = dao_with_executable_proposal(payload("1000"));
+    caller("owner").deposit(NearToken::from_yoctonear(1000)).set();
+    dao.fund_treasury();
+    mark_passed(&mut dao, 1);
+
+    let _ = dao.execute_proposal(1);
+
+    let (receiver, call) = get_created_receipts()
+        .into_iter()
+        .find_map(|receipt| {
+            let receiver = receipt.receiver_id.clone();
+            receipt.actions.into_iter().find_map(|action| match action {
+                MockAction::FunctionCallWeight { method_name, args, attached_deposit, .. } => {
+                    Some((receiver.clone(), (method_name, args, attached_deposit)))
+                }
+                _ => None,
+            })
+        })
+        .unwrap();
+    assert_eq!(receiver, account("grants"));
+    assert_eq!(call, (b"pay".to_vec(), b"{\"amount\":\"5\"}".to_vec(), NearToken::from_yoctonear(1000)));
+
+    assert_eq!(dao.get_proposal(1).unwrap().status, ProposalStatus::Executed);
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_yoctonear(0));
+    assert!(dao.is_proposal_passed(1));
+}
+
+#[test]
+#[should_panic(expected = "Proposal was already executed")]
+fn executes_only_once() {
+    let mut dao = dao_with_executable_proposal(payload("0"));
+    mark_passed(&mut dao, 1);
+
+    let _ = dao.execute_proposal(1);
+    let _ = dao.execute_proposal(1);
+}
+
+#[test]
+#[should_panic(expected = "Proposal has not passed")]
+fn active_proposal_not_executable() {
+    let mut dao = dao_with_executable_proposal(payload("0"));
+    let _ = dao.execute_proposal(1);
+}
+
+#[test]
+#[should_panic(expected = "Treasury balance is too low for the execution deposit")]
+fn deposit_comes_from_treasury() {
+    let mut dao = dao_with_executable_proposal(payload("1000"));
+    mark_passed(&mut dao, 1);
+    let _ = dao.execute_proposal(1);
+}
+
+#[test]
+#[should_panic(expected = "Proposal has no execution payload")]
+fn plain_proposal_has_nothing_to_execute() {
+    let mut dao = dao_with_executable_proposal(payload("0"));
+    dao.create_proposal("Plain".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    mark_passed(&mut dao, 2);
+
+    let _ = dao.execute_proposal(2);
+}
+
+#[test]
+#[should_panic(expected = "Invalid execution payload")]
+fn malformed_payload_rejected_at_creation() {
+    dao_with_executable_proposal("{\"receiver_id\":\"grants.testnet\"}".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Proposal can be executed from 86400000000000")]
+fn waits_for_execution_delay() {
+    let mut dao = dao_with_executable_proposal(payload("0"));
+    mark_passed(&mut dao, 1);
+
+    caller("anyone").timestamp(EXECUTION_DELAY - 1).set();
+    let _ = dao.execute_proposal(1);
+}
+
+#[test]
+#[should_panic(expected = "Proposal has pending veto approvals")]
+fn pending_veto_blocks_execution() {
+    let mut dao = dao_with_executable_proposal(payload("0"));
+    let mut config = dao.get_config();
+    config.veto_threshold = 2;
+    dao.update_config(config);
+    mark_passed(&mut dao, 1);
+
+    caller("owner").timestamp(EXECUTION_DELAY).set();
+    dao.veto_proposal(1, "Wrong recipient".to_string());
+    let _ = dao.execute_proposal(1);
+}
+
+#[test]
+fn failed_call_returns_deposit_to_treasury() {
+    let mut dao = dao_with_executable_proposal(payload("1000"));
+    caller("owner").deposit(NearToken::from_yoctonear(1000)).set();
+    dao.fund_treasury();
+    mark_passed(&mut dao, 1);
+    let _ = dao.execute_proposal(1);
+
+    caller("dao").set_with_promise_result(PromiseResult::Successful(Vec::new()));
+    dao.on_proposal_executed(1, NearToken::from_yoctonear(1000));
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_yoctonear(0));
+
+    caller("dao").set_with_promise_result(PromiseResult::Failed);
+    dao.on_proposal_executed(1, NearToken::from_yoctonear(1000));
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_yoctonear(1000));
+    assert_eq!(dao.get_proposal(1).unwrap().status, ProposalStatus::Executed);
+}
@@ -0,0 +1,85 @@
+//! `get_governance_stats` counts proposals by status and ballots cast
+
+mod common;
+
+use common::{caller, dao, outlayer_success};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn tallied(
+    proposal_id: u64,
+    yes_count: u64,
+    no_count: u64,
+) -> Result<Option<OutLayerResponse>, near_sdk::PromiseError> {
+    outlayer_success(serde_json::json!({
+        "proposal_id": proposal_id,
+        "yes_count": yes_count,
+        "no_count": no_count,
+        "total_votes": yes_count + no_count,
+        "tee_attestation": "",
+        "votes_merkle_root": "",
+        "merkle_proofs": [],
+        "merkle_proofs_available": false,
+    }))
+}
+
+/// Public DAO with voting members alice and bob
+fn dao_with_members() -> PrivateDAO {
+    caller("owner").set();
+    dao().members(&["alice", "bob"]).build()
+}
+
+fn propose(dao: &mut PrivateDAO, title: &str) {
+    caller("alice").set();
+    dao.create_proposal(title.to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+}
+
+fn vote(dao: &mut PrivateDAO, voter: &str, proposal_id: u64) {
+    caller(voter).set();
+    dao.cast_vote(proposal_id, "ab".repeat(113));
+}
+
+#[test]
+fn empty_dao() {
+    let dao = dao_with_members();
+    assert_eq!(
+        dao.get_governance_stats(),
+        GovernanceStats {
+            total_proposals: 0,
+            active_proposals: 0,
+            passed_proposals: 0,
+            rejected_proposals: 0,
+            total_votes_cast: 0,
+            avg_votes_per_proposal: 0,
+        }
+    );
+}
+
+#[test]
+fn counts_by_status_and_ballots() {
+    let mut dao = dao_with_members();
+    for title in ["Passes", "Fails", "Open"] {
+        propose(&mut dao, title);
+    }
+    vote(&mut dao, "alice", 1);
+    vote(&mut dao, "bob", 1);
+    // Re-votes are ballots too
+    vote(&mut dao, "bob", 1);
+    vote(&mut dao, "alice", 2);
+
+    caller("dao").set();
+    dao.on_votes_tallied(1, None, tallied(1, 2, 0));
+    dao.on_votes_tallied(2, None, tallied(2, 0, 1));
+
+    assert_eq!(
+        dao.get_governance_stats(),
+        GovernanceStats {
+            total_proposals: 3,
+            active_proposals: 1,
+            passed_proposals: 1,
+            rejected_proposals: 1,
+            total_votes_cast: 4,
+            avg_votes_per_proposal: 1,
+        }
+    );
+}
@@ -0,0 +1,151 @@
+//! Failed joins are refunded right away; joins whose callback never ran stay listed until an admin refunds them
+
+mod common;
+
+use common::{account, caller, dao};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::get_created_receipts;
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+const STORAGE_DEPOSIT: NearToken = NearToken::from_millinear(2);
+
+fn key_response(success: bool) -> Result<Option<OutLayerResponse>, near_sdk::PromiseError> {
+    Ok(Some(OutLayerResponse {
+        success,
+        result: serde_json::json!({ "pubkey": "02abcd" }),
+        error: (!success).then(|| "TEE unavailable".to_string()),
+    }))
+}
+
+/// Public DAO where alice's join at block 10 is still waiting for its callback
+fn dao_with_pending_join() -> PrivateDAO {
+    caller("owner").block(1).set();
+    let mut dao = dao().build();
+
+    caller("alice").block(10).set();
+    let _ = dao.join_dao();
+
+    dao
+}
+
+/// Attached deposits of the receipts created so far, by method (or "transfer")
+fn created_deposits() -> Vec<(String, NearToken)> {
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| receipt.actions)
+        .filter_map(|action| match action {
+            MockAction::FunctionCallWeight { method_name, attached_deposit, .. } => {
+                Some((String::from_utf8(method_name).unwrap(), attached_deposit))
+            }
+            MockAction::Transfer { deposit, .. } => Some(("transfer".to_string(), deposit)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn join_keeps_storage_deposit_and_forwards_the_rest() {
+    caller("owner").block(1).set();
+    let mut dao = dao().build();
+
+    caller("alice").block(10).set();
+    let _ = dao.join_dao();
+
+    assert_eq!(
+        created_deposits()[0],
+        ("request_execution".to_string(), NearToken::from_millinear(18))
+    );
+}
+
+#[test]
+fn failed_join_refunds_storage_deposit() {
+    let mut dao = dao_with_pending_join();
+
+    caller("dao").block(11).set();
+    dao.on_key_derived(account("alice"), Some(0), key_response(false));
+
+    // OutLayer settles its own deposit when the execution ran
+    assert_eq!(created_deposits(), vec![("transfer".to_string(), STORAGE_DEPOSIT)]);
+    assert!(dao.get_incomplete_joins(0).is_empty());
+    assert!(!dao.is_member(account("alice")));
+}
+
+#[test]
+fn failed_outlayer_call_refunds_whole_join_deposit() {
+    let mut dao = dao_with_pending_join();
+
+    caller("dao").block(11).set();
+    dao.on_key_derived(account("alice"), Some(0), Err(near_sdk::PromiseError::Failed));
+
+    let mut refunds = created_deposits();
+    refunds.sort();
+    assert_eq!(
+        refunds,
+        vec![
+            ("transfer".to_string(), STORAGE_DEPOSIT),
+            ("transfer".to_string(), NearToken::from_millinear(18)),
+        ]
+    );
+    assert!(dao.get_incomplete_joins(0).is_empty());
+}
+
+#[test]
+fn pending_join_is_listed_by_age() {
+    let mut dao = dao_with_pending_join();
+
+    caller("bob").block(50).set();
+    assert_eq!(
+        dao.get_incomplete_joins(30_000),
+        vec![IncompleteJoinInfo {
+            account_id: account("alice"),
+            initiated_at: 10_000,
+            deposit_paid: STORAGE_DEPOSIT,
+            age_ns: 40_000,
+        }]
+    );
+    assert!(dao.get_incomplete_joins(40_001).is_empty());
+
+    // No retry while the derivation is in flight; success clears the entry
+    caller("alice").block(60).set();
+    let retry = catch_unwind(AssertUnwindSafe(|| {
+        let _ = dao.join_dao();
+    }));
+    assert!(retry.is_err());
+    assert_eq!(dao.get_incomplete_joins(0)[0].deposit_paid, STORAGE_DEPOSIT);
+
+    caller("dao").block(61).set();
+    dao.on_key_derived(account("alice"), None, key_response(true));
+    assert!(dao.get_incomplete_joins(0).is_empty());
+}
+
+#[test]
+fn admin_refunds_stored_deposit() {
+    let mut dao = dao_with_pending_join();
+
+    caller("owner").block(100).set();
+    dao.admin_refund_incomplete_join(account("alice"));
+
+    assert_eq!(created_deposits(), vec![("transfer".to_string(), STORAGE_DEPOSIT)]);
+    assert!(dao.get_incomplete_joins(0).is_empty());
+
+    // Alice can join again right away, and the refund can't be repeated
+    caller("alice").block(100).set();
+    let _ = dao.join_dao();
+    caller("owner").block(101).set();
+    dao.admin_refund_incomplete_join(account("alice"));
+    let result = catch_unwind(AssertUnwindSafe(|| dao.admin_refund_incomplete_join(account("alice"))));
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Only admins can refund incomplete joins")]
+fn refund_requires_admin() {
+    let mut dao = dao_with_pending_join();
+
+    caller("alice").block(100).set();
+    dao.admin_refund_incomplete_join(account("alice"));
+}
@@ -0,0 +1,72 @@
+//! Private DAO invitations: `create_invitation` → `complete_join` before expiry
+
+mod common;
+
+use common::{account, caller, dao, derived_key};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// Private DAO where the owner invited bob at 100, valid until 1_000
+fn dao_with_invitation() -> PrivateDAO {
+    caller("owner").timestamp(100).set();
+    let mut dao = dao().private().build();
+    dao.create_invitation(account("bob"), 1_000);
+    dao
+}
+
+#[test]
+fn invitee_joins_on_key_derivation() {
+    let mut dao = dao_with_invitation();
+    assert_eq!(
+        dao.get_invitation(account("bob")),
+        Some(InvitationInfo { invited_at: 100, expires_at: 1_000 })
+    );
+    assert!(!dao.is_member(account("bob")));
+
+    caller("bob").timestamp(500).set();
+    let _ = dao.complete_join();
+    assert!(dao.get_invitation(account("bob")).is_none());
+
+    caller("dao").timestamp(600).set();
+    dao.on_key_derived(
+        account("bob"),
+        None,
+        derived_key(),
+    );
+
+    assert_eq!(dao.get_member_info(account("bob")).unwrap().joined_at, 600);
+    assert_eq!(dao.get_dao_info().member_count, 2);
+}
+
+#[test]
+#[should_panic(expected = "Invitation expired")]
+fn expired_invitation_rejected() {
+    let mut dao = dao_with_invitation();
+    caller("bob").timestamp(1_000).set();
+    let _ = dao.complete_join();
+}
+
+#[test]
+#[should_panic(expected = "Not pre-approved")]
+fn revoked_invitation_rejected() {
+    let mut dao = dao_with_invitation();
+    dao.revoke_invitation(account("bob"));
+
+    caller("bob").timestamp(500).set();
+    let _ = dao.complete_join();
+}
+
+#[test]
+#[should_panic(expected = "Invitation must expire in the future")]
+fn expiry_must_be_in_the_future() {
+    let mut dao = dao_with_invitation();
+    dao.create_invitation(account("carol"), 100);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can call this method")]
+fn only_owner_invites() {
+    let mut dao = dao_with_invitation();
+    caller("bob").timestamp(200).set();
+    dao.create_invitation(account("carol"), 1_000);
+}
@@ -0,0 +1,41 @@
+//! Duplicate joins must not trigger a second OutLayer call
+
+mod common;
+
+use common::{account, caller, dao};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[test]
+fn join_twice_in_same_block_is_rejected() {
+    caller("owner").block_height(10).set();
+    let mut dao = dao().build();
+
+    caller("alice").block_height(10).set();
+    let _ = dao.join_dao();
+
+    let err = catch_unwind(AssertUnwindSafe(|| {
+        let _ = dao.join_dao();
+    }))
+    .expect_err("second join must panic");
+    let message = err.downcast_ref::<String>().cloned().unwrap_or_default();
+    assert!(message.contains("Please wait for the next block"), "unexpected message: {}", message);
+
+    // Next block is fine once the key derivation failed
+    dao.on_key_derived(account("alice"), None, Err(near_sdk::PromiseError::Failed));
+    caller("alice").block_height(11).set();
+    let _ = dao.join_dao();
+    assert_eq!(dao.block_height_of_last_join_attempt.get(&account("alice")), Some(11));
+}
+
+#[test]
+#[should_panic(expected = "Key derivation already in progress")]
+fn join_while_key_derivation_in_flight_is_rejected() {
+    caller("owner").block_height(10).set();
+    let mut dao = dao().build();
+
+    caller("alice").block_height(10).set();
+    let _ = dao.join_dao();
+
+    caller("alice").block_height(11).set();
+    let _ = dao.join_dao();
+}
@@ -0,0 +1,78 @@
+//! Keys derived with a non-default algorithm are recorded and passed to tallies
+
+mod common;
+
+use common::{account, caller, dao, derived_key, outlayer_input, outlayer_success};
+use private_dao_contract::types::*;
+
+const PBKDF2: KeyDerivationAlgorithm = KeyDerivationAlgorithm::PBKDF2 { iterations: 100_000 };
+
+fn derived_key_using(algorithm: serde_json::Value) -> Result<Option<OutLayerResponse>, near_sdk::PromiseError> {
+    outlayer_success(serde_json::json!({ "pubkey": "02abcd", "key_derivation_algorithm": algorithm }))
+}
+
+#[test]
+fn pbkdf2_keys_are_recorded_and_sent_to_tally() {
+    caller("owner").block_height(1).set();
+    let mut dao = dao().build();
+    dao.update_config(DAOConfig { key_derivation_algorithm: PBKDF2, ..DAOConfig::default() });
+
+    caller("alice").block_height(10).set();
+    let _ = dao.join_dao();
+    assert_eq!(
+        outlayer_input()["key_derivation_algorithm"],
+        serde_json::json!({ "PBKDF2": { "iterations": 100_000 } })
+    );
+
+    caller("dao").block_height(11).set();
+    dao.on_key_derived(account("alice"), None, derived_key_using(serde_json::json!({ "PBKDF2": { "iterations": 100_000 } })));
+    assert_eq!(dao.get_user_key_algorithm(account("alice")), PBKDF2);
+
+    // Bob joined with an HKDF key before the switch (migrated: nothing recorded)
+    caller("owner").block_height(12).set();
+    dao.migrate_add_member(account("bob"), Some("03abcd".to_string()));
+    assert_eq!(dao.get_user_key_algorithm(account("bob")), KeyDerivationAlgorithm::HKDF);
+
+    caller("alice").block_height(13).set();
+    dao.create_proposal("Switch KDF".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    for voter in ["alice", "bob"] {
+        caller(voter).block_height(14).set();
+        dao.cast_vote(1, "ab".repeat(113));
+    }
+
+    caller("carol").block_height(15).set();
+    let _ = dao.finalize_proposal(1);
+    assert_eq!(
+        outlayer_input()["key_derivation_algorithms"],
+        serde_json::json!({ "alice.testnet": { "PBKDF2": { "iterations": 100_000 } } })
+    );
+}
+
+#[test]
+fn older_worker_response_means_hkdf() {
+    caller("owner").block_height(1).set();
+    let mut dao = dao().build();
+
+    caller("alice").block_height(10).set();
+    let _ = dao.join_dao();
+    caller("dao").block_height(11).set();
+    dao.on_key_derived(
+        account("alice"),
+        None,
+        derived_key(),
+    );
+
+    assert_eq!(dao.get_user_pubkey(account("alice")), Some("02abcd".to_string()));
+    assert_eq!(dao.get_user_key_algorithm(account("alice")), KeyDerivationAlgorithm::HKDF);
+}
+
+#[test]
+#[should_panic(expected = "PBKDF2 iterations must be at least 1")]
+fn zero_pbkdf2_iterations_rejected() {
+    caller("owner").block_height(1).set();
+    let mut dao = dao().build();
+    dao.update_config(DAOConfig {
+        key_derivation_algorithm: KeyDerivationAlgorithm::PBKDF2 { iterations: 0 },
+        ..DAOConfig::default()
+    });
+}
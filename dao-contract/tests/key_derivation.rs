@@ -0,0 +1,121 @@
+//! `on_key_derived` distinguishes new joins, pre-approved members and key rotations
+
+mod common;
+
+use common::{account, caller, dao, derived_key, outlayer_input, outlayer_success};
+use near_sdk::test_utils::get_logs;
+use near_sdk::AccountId;
+use private_dao_contract::types::QuorumType;
+use private_dao_contract::PrivateDAO;
+
+/// Deliver the OutLayer callback for `user` at `block_timestamp`
+fn on_key_derived(dao: &mut PrivateDAO, user: AccountId, block_timestamp: u64) {
+    caller("dao").timestamp(block_timestamp).set();
+    dao.on_key_derived(user, None, derived_key());
+}
+
+#[test]
+fn public_join_adds_member_on_callback() {
+    caller("owner").timestamp(100).set();
+    let mut dao = dao().build();
+
+    caller("alice").timestamp(200).set();
+    let _ = dao.join_dao();
+    assert!(!dao.is_member(account("alice")));
+
+    on_key_derived(&mut dao, account("alice"), 300);
+
+    assert_eq!(dao.members.get(&account("alice")).unwrap().joined_at, 300);
+    assert!(dao.pending_join_type.get(&account("alice")).is_none());
+    assert!(get_logs().iter().any(|log| log.contains("added to DAO")));
+}
+
+#[test]
+fn complete_join_keeps_pre_approval_time() {
+    caller("owner").timestamp(100).set();
+    let mut dao = dao().private().build();
+    dao.add_member(account("bob"));
+
+    caller("bob").timestamp(200).set();
+    let _ = dao.complete_join();
+    on_key_derived(&mut dao, account("bob"), 300);
+
+    assert_eq!(dao.members.get(&account("bob")).unwrap().joined_at, 100);
+    assert_eq!(dao.get_dao_info().member_count, 2);
+    assert!(get_logs().iter().any(|log| log.contains("completed join as pre-approved member (member since 100)")));
+}
+
+#[test]
+fn rotate_key_replaces_pubkey_of_existing_member() {
+    caller("owner").timestamp(100).set();
+    let mut dao = dao().private().build();
+    dao.migrate_add_member(account("bob"), Some("02old".to_string()));
+
+    caller("bob").timestamp(200).set();
+    let _ = dao.rotate_key();
+    on_key_derived(&mut dao, account("bob"), 300);
+
+    let member = dao.members.get(&account("bob")).unwrap();
+    assert_eq!(member.joined_at, 0);
+    assert_eq!(member.key_rotated_at, Some(300));
+    assert_eq!(dao.user_pubkeys.get(&account("bob")), Some("02abcd".to_string()));
+    assert_eq!(dao.get_dao_info().member_count, 2);
+}
+
+#[test]
+fn rotate_key_derives_at_next_epoch() {
+    caller("owner").timestamp(100).set();
+    let mut dao = dao().private().build();
+    dao.migrate_add_member(account("bob"), Some("02old".to_string()));
+
+    for epoch in 1..=2 {
+        caller("bob").timestamp(200).set();
+        let _ = dao.rotate_key();
+        assert_eq!(outlayer_input()["key_epoch"], epoch);
+
+        caller("dao").timestamp(300).set();
+        dao.on_key_derived(
+            account("bob"),
+            None,
+            outlayer_success(serde_json::json!({ "pubkey": format!("02new{}", epoch), "key_epoch": epoch })),
+        );
+        assert_eq!(dao.members.get(&account("bob")).unwrap().key_epoch, epoch);
+    }
+
+    // Tallies re-derive bob's key at his current epoch
+    caller("owner").timestamp(400).set();
+    dao.create_proposal("Rotated".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    caller("bob").timestamp(500).set();
+    dao.cast_vote(1, "ab".repeat(113));
+    caller("carol").timestamp(600).set();
+    let _ = dao.finalize_proposal(1);
+    assert_eq!(outlayer_input()["key_epochs"], serde_json::json!({ "bob.testnet": 2 }));
+}
+
+#[test]
+fn rotation_does_not_readd_member_who_left() {
+    caller("owner").timestamp(100).set();
+    let mut dao = dao().private().build();
+    dao.migrate_add_member(account("bob"), Some("02old".to_string()));
+
+    caller("bob").timestamp(200).set();
+    let _ = dao.rotate_key();
+    dao.leave_dao();
+    on_key_derived(&mut dao, account("bob"), 300);
+
+    assert!(!dao.is_member(account("bob")));
+    assert_eq!(dao.user_pubkeys.get(&account("bob")), None);
+    assert_eq!(dao.get_dao_info().member_count, 1);
+    assert!(get_logs().iter().any(|log| log.contains("no longer a member, rotated key dropped")));
+}
+
+#[test]
+#[should_panic(expected = "No key to rotate, call complete_join first")]
+fn rotate_key_requires_existing_key() {
+    caller("owner").timestamp(100).set();
+    let mut dao = dao().private().build();
+    dao.add_member(account("bob"));
+
+    caller("bob").timestamp(200).set();
+    let _ = dao.rotate_key();
+}
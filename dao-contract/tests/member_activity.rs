@@ -0,0 +1,173 @@
+//! `MemberInfo` vote activity and its migration from the legacy layout
+
+mod common;
+
+use common::{account, caller, dao};
+use near_sdk::borsh;
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::{env, AccountId, NearToken};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn dao_with_proposals() -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(100).set();
+    let mut dao = dao().private().build();
+    for member in ["alice", "bob"] {
+        dao.migrate_add_member(account(member), Some("02abcd".to_string()));
+    }
+    for title in ["First", "Second"] {
+        dao.create_proposal(title.to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    }
+    dao
+}
+
+#[test]
+fn cast_vote_records_activity() {
+    let mut dao = dao_with_proposals();
+
+    for (proposal_id, timestamp) in [(1, 200), (1, 300), (2, 400)] {
+        caller("alice").deposit(NearToken::from_millinear(2)).timestamp(timestamp).set();
+        dao.cast_vote(proposal_id, "ab".repeat(113));
+    }
+
+    let alice = dao.get_member_info(account("alice")).unwrap();
+    assert_eq!((alice.last_voted_at, alice.proposals_voted), (Some(400), 2));
+    let bob = dao.get_member_info(account("bob")).unwrap();
+    assert_eq!((bob.last_voted_at, bob.proposals_voted), (None, 0));
+
+    assert_eq!(dao.get_active_member_count(400), 1);
+    assert_eq!(dao.get_active_member_count(401), 0);
+}
+
+#[test]
+fn reset_state_converts_legacy_member_info() {
+    let dao = dao_with_proposals();
+    env::state_write(&dao);
+
+    // `members` uses the first storage prefix (0)
+    let key = [vec![0u8], borsh::to_vec(&account("alice")).unwrap()].concat();
+    let legacy = LegacyMemberInfo { joined_at: 0, key_rotated_at: Some(50) };
+    env::storage_write(&key, &borsh::to_vec(&legacy).unwrap());
+
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(500).set();
+    let dao = PrivateDAO::reset_state();
+
+    let alice = dao.get_member_info(account("alice")).unwrap();
+    assert_eq!(alice.key_rotated_at, Some(50));
+    assert_eq!((alice.last_voted_at, alice.proposals_voted), (None, 0));
+    assert!(dao.get_member_info(account("bob")).is_some());
+}
+
+#[test]
+fn reset_state_converts_pre_epoch_member_info() {
+    let dao = dao_with_proposals();
+    env::state_write(&dao);
+
+    let key = [vec![0u8], borsh::to_vec(&account("alice")).unwrap()].concat();
+    let old = PreEpochMemberInfo { joined_at: 0, key_rotated_at: Some(50), last_voted_at: Some(60), proposals_voted: 3 };
+    env::storage_write(&key, &borsh::to_vec(&old).unwrap());
+
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(500).set();
+    let dao = PrivateDAO::reset_state();
+
+    let alice = dao.get_member_info(account("alice")).unwrap();
+    assert_eq!((alice.key_rotated_at, alice.last_voted_at, alice.proposals_voted), (Some(50), Some(60), 3));
+    assert_eq!(alice.key_epoch, 0);
+}
+
+#[test]
+fn reset_state_keeps_member_counts_and_clears_tallies_in_flight() {
+    let mut dao = dao_with_proposals();
+    dao.tallies_in_flight.insert(&1, &100);
+    let (member_count, voting_member_count) = (dao.member_count, dao.voting_member_count);
+    env::state_write(&dao);
+
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(500).set();
+    let mut dao = PrivateDAO::reset_state();
+
+    assert_eq!((dao.member_count, dao.voting_member_count), (member_count, voting_member_count));
+    assert_eq!(dao.member_count, dao.member_index.len());
+    assert_eq!(dao.proposals.len(), 0);
+    assert!(dao.tallies_in_flight.get(&1).is_none());
+    // IDs of the cleared proposals aren't handed out again
+    let proposal_id = dao.create_proposal(
+        "Third".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        None,
+        None,
+    );
+    assert_eq!(proposal_id, 3);
+}
+
+#[test]
+fn reset_state_converts_baseline_state() {
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(500).set();
+
+    // Root state of the initial release, with its storage prefixes 0..=3 and 8-byte `MemberInfo { joined_at }`
+    let mut members = LookupMap::<AccountId, u64>::new(vec![0u8]);
+    let mut user_pubkeys = LookupMap::<AccountId, String>::new(vec![1u8]);
+    for (member, joined_at) in [("owner", 100), ("alice", 200), ("bob", 300)] {
+        members.insert(&account(member), &joined_at);
+    }
+    for member in ["owner", "alice"] {
+        user_pubkeys.insert(&account(member), &"02abcd".to_string());
+    }
+    let mut proposals = UnorderedMap::<u64, Vec<u8>>::new(vec![2u8]);
+    proposals.insert(&1, &vec![1, 2, 3]);
+    proposals.insert(&2, &vec![4, 5, 6]);
+    let votes = LookupMap::<u64, Vec<u8>>::new(vec![3u8]);
+    let baseline = (
+        account("owner"),
+        "Baseline DAO".to_string(),
+        MembershipMode::Private,
+        members,
+        3u64,
+        user_pubkeys,
+        proposals,
+        3u64,
+        votes,
+    );
+    env::storage_write(b"STATE", &borsh::to_vec(&baseline).unwrap());
+
+    let mut dao = PrivateDAO::reset_state();
+
+    assert_eq!((dao.name.as_str(), dao.owner.clone()), ("Baseline DAO", account("owner")));
+    assert_eq!(dao.membership_mode, MembershipMode::Private);
+    assert_eq!(dao.proposals.len(), 0);
+    assert_eq!(dao.next_proposal_id, 3);
+    // Only the owner is indexed (and counted as a voter) until the others are backfilled
+    assert_eq!((dao.member_count, dao.member_index.len(), dao.voting_member_count), (3, 1, 1));
+    assert_eq!(dao.get_member_info(account("owner")).unwrap().joined_at, 100);
+
+    assert_eq!(dao.migrate_index_members(vec![account("alice"), account("bob")]), 2);
+    assert_eq!((dao.member_count, dao.member_index.len(), dao.voting_member_count), (3, 3, 2));
+    let alice = dao.get_member_info(account("alice")).unwrap();
+    assert_eq!((alice.joined_at, alice.proposals_voted, alice.key_epoch), (200, 0, 0));
+    assert_eq!(dao.user_pubkeys.get(&account("alice")), Some("02abcd".to_string()));
+}
+
+#[test]
+fn voting_analytics_pages_over_proposals() {
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(100).set();
+    let mut dao = dao().private().build();
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(200).set();
+    for title in ["First", "Second", "Third"] {
+        dao.create_proposal(title.to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    }
+    for proposal_id in [1, 3] {
+        caller("alice").deposit(NearToken::from_millinear(2)).timestamp(300).set();
+        dao.cast_vote(proposal_id, "ab".repeat(113));
+    }
+
+    let first = dao.get_member_voting_analytics(account("alice"), 0, 2);
+    assert_eq!((first.total_proposals_eligible, first.total_votes_cast), (2, 1));
+    let second = dao.get_member_voting_analytics(account("alice"), 2, 2);
+    assert_eq!((second.total_proposals_eligible, second.total_votes_cast), (1, 1));
+    assert_eq!(second.participation_rate, 1.0);
+
+    let past_end = dao.get_member_voting_analytics(account("alice"), 3, 2);
+    assert_eq!((past_end.total_proposals_eligible, past_end.participation_rate), (0, 0.0));
+}
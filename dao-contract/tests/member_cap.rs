@@ -0,0 +1,53 @@
+//! `max_members` caps `join_dao` and `add_member` without evicting anyone
+
+mod common;
+
+use common::{account, caller, dao};
+use private_dao_contract::PrivateDAO;
+
+/// Private DAO of the owner and alice, capped at `max_members`
+fn capped_dao(max_members: u64) -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().private().max_members(max_members).build();
+    dao.add_member(account("alice"));
+    dao
+}
+
+#[test]
+#[should_panic(expected = "DAO is full: member limit of 2 reached")]
+fn add_member_rejected_at_cap() {
+    let mut dao = capped_dao(2);
+    dao.add_member(account("bob"));
+}
+
+#[test]
+#[should_panic(expected = "DAO is full: member limit of 1 reached")]
+fn join_rejected_at_cap() {
+    caller("owner").set();
+    let mut dao = dao().max_members(1).build();
+
+    caller("bob").set();
+    let _ = dao.join_dao();
+}
+
+#[test]
+fn lowering_cap_keeps_members() {
+    let mut dao = capped_dao(3);
+    dao.set_max_members(Some(1));
+
+    let info = dao.get_dao_info();
+    assert_eq!((info.member_count, info.max_members), (2, Some(1)));
+    assert!(dao.is_member(account("alice")));
+
+    dao.set_max_members(None);
+    dao.add_member(account("bob"));
+    assert_eq!(dao.get_dao_info().member_count, 3);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can call this method")]
+fn only_owner_sets_cap() {
+    let mut dao = capped_dao(3);
+    caller("alice").set();
+    dao.set_max_members(None);
+}
@@ -0,0 +1,57 @@
+//! Storage deposits paid by `join_dao` are refunded on `leave_dao` / `remove_member`
+
+mod common;
+
+use common::{account, caller, dao, derived_key, transfers};
+use near_sdk::NearToken;
+use private_dao_contract::PrivateDAO;
+
+/// Public DAO that alice joined through `join_dao`
+fn dao_with_joined_member() -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().build();
+
+    caller("alice").set();
+    let _ = dao.join_dao();
+    caller("dao").set();
+    dao.on_key_derived(
+        account("alice"),
+        None,
+        derived_key(),
+    );
+    dao
+}
+
+#[test]
+fn leave_refunds_join_deposit() {
+    let mut dao = dao_with_joined_member();
+    assert_eq!(dao.member_deposits.get(&account("alice")), Some(NearToken::from_millinear(2).as_yoctonear()));
+
+    caller("alice").set();
+    dao.leave_dao();
+
+    assert_eq!(transfers(), vec![(account("alice"), NearToken::from_millinear(2))]);
+    assert!(dao.member_deposits.get(&account("alice")).is_none());
+}
+
+#[test]
+fn removed_member_gets_refund() {
+    let mut dao = dao_with_joined_member();
+
+    caller("owner").set();
+    dao.remove_member(account("alice"));
+
+    assert_eq!(transfers(), vec![(account("alice"), NearToken::from_millinear(2))]);
+}
+
+#[test]
+fn migrated_member_leaves_without_refund() {
+    caller("owner").set();
+    let mut dao = dao().build();
+    dao.migrate_add_member(account("bob"), Some("02abcd".to_string()));
+
+    caller("bob").set();
+    dao.leave_dao();
+
+    assert!(transfers().is_empty());
+}
@@ -0,0 +1,82 @@
+//! Paginated member roster (`get_members`)
+
+mod common;
+
+use common::{account, caller, dao};
+use near_sdk::AccountId;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// Private DAO created (and joined by its owner) at timestamp 1000
+fn private_dao() -> PrivateDAO {
+    caller("owner").timestamp(1_000).set();
+    dao().private().build()
+}
+
+fn member_names(members: &[(AccountId, MemberInfo)]) -> Vec<String> {
+    members.iter().map(|(account_id, _)| account_id.to_string()).collect()
+}
+
+#[test]
+fn lists_members_with_join_timestamps() {
+    let mut dao = private_dao();
+    caller("owner").timestamp(2_000).set();
+    dao.add_member(account("alice"));
+    dao.migrate_add_member(account("bob"), None);
+
+    let members = dao.get_members(0, 10);
+    assert_eq!(member_names(&members), ["owner.testnet", "alice.testnet", "bob.testnet"]);
+    let joined_at: Vec<u64> = members.iter().map(|(_, info)| info.joined_at).collect();
+    assert_eq!(joined_at, [1_000, 2_000, 0]);
+
+    assert_eq!(member_names(&dao.get_members(1, 1)), ["alice.testnet"]);
+    assert!(dao.get_members(3, 10).is_empty());
+    assert!(dao.get_members(u64::MAX, u64::MAX).is_empty());
+}
+
+#[test]
+fn removed_members_are_not_listed() {
+    let mut dao = private_dao();
+    for member in ["alice", "bob", "carol"] {
+        dao.add_member(account(member));
+    }
+
+    dao.remove_member(account("alice"));
+    caller("bob").timestamp(1_000).set();
+    dao.leave_dao();
+
+    assert_eq!(member_names(&dao.get_members(0, 10)), ["owner.testnet", "carol.testnet"]);
+}
+
+#[test]
+fn limit_capped_at_100() {
+    let mut dao = private_dao();
+    for i in 0..120 {
+        // Fresh context every 20 calls to stay under the mocked gas limit
+        if i % 20 == 0 {
+            caller("owner").timestamp(1_000).set();
+        }
+        dao.add_member(account(&format!("member{}", i)));
+    }
+
+    assert_eq!(dao.get_members(0, 1_000).len(), 100);
+    assert_eq!(dao.get_members(100, 1_000).len(), 21);
+}
+
+#[test]
+fn migrate_index_members_backfills_only_unindexed_members() {
+    let mut dao = private_dao();
+    dao.add_member(account("alice"));
+
+    // Simulate a member stored before the index existed
+    dao.add_member(account("bob"));
+    dao.member_index.remove(&account("bob"));
+    assert_eq!(member_names(&dao.get_members(0, 10)), ["owner.testnet", "alice.testnet"]);
+
+    let added = dao.migrate_index_members(vec![account("alice"), account("bob"), account("stranger")]);
+    assert_eq!(added, 1);
+    assert_eq!(
+        member_names(&dao.get_members(0, 10)),
+        ["owner.testnet", "alice.testnet", "bob.testnet"]
+    );
+}
@@ -0,0 +1,79 @@
+//! `verify_submitted_vote_proof` must accept the proofs produced by the WASI worker
+//!
+//! `fixtures/tally_three_votes.json` is real worker output for three votes.
+
+mod common;
+
+use common::{caller, dao, proposal};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+const PROPOSAL_ID: u64 = 1;
+
+fn tallied_dao() -> (PrivateDAO, TallyResponse) {
+    caller("owner").set();
+    let mut dao = dao().build();
+    let response: TallyResponse = serde_json::from_str(include_str!("fixtures/tally_three_votes.json")).unwrap();
+
+    dao.proposals.insert(
+        &PROPOSAL_ID,
+        &Proposal {
+            quorum: QuorumType::Absolute { min_votes: 0 },
+            status: ProposalStatus::Passed,
+            tally_result: Some(TallyResult {
+                quorum_met: true,
+                yes_count: response.yes_count,
+                no_count: response.no_count,
+                abstain_count: response.abstain_count,
+                vote_counts: response.vote_counts.clone(),
+                total_votes: response.total_votes,
+                tee_attestation: response.tee_attestation.clone(),
+                votes_merkle_root: response.votes_merkle_root.clone(),
+                merkle_proofs: response.merkle_proofs.clone(),
+                merkle_proofs_available: true,
+                counted_votes_root: String::new(),
+            }),
+            ..proposal(PROPOSAL_ID)
+        },
+    );
+
+    (dao, response)
+}
+
+#[test]
+fn stored_proofs_verify() {
+    let (dao, response) = tallied_dao();
+    assert_eq!(response.merkle_proofs.len(), 3);
+
+    for proof in &response.merkle_proofs {
+        assert!(
+            dao.verify_submitted_vote_proof(
+                PROPOSAL_ID,
+                proof.vote_index,
+                proof.vote_hash.clone(),
+                proof.proof_path.clone(),
+            ),
+            "proof for {} rejected",
+            proof.voter
+        );
+    }
+}
+
+#[test]
+fn tampered_proofs_rejected() {
+    let (dao, response) = tallied_dao();
+    let proof = &response.merkle_proofs[0];
+
+    // Wrong leaf
+    let other_leaf = response.merkle_proofs[1].vote_hash.clone();
+    assert!(!dao.verify_submitted_vote_proof(PROPOSAL_ID, 0, other_leaf, proof.proof_path.clone()));
+
+    // Wrong position (swaps left/right order)
+    assert!(!dao.verify_submitted_vote_proof(PROPOSAL_ID, 1, proof.vote_hash.clone(), proof.proof_path.clone()));
+
+    // Truncated path
+    assert!(!dao.verify_submitted_vote_proof(PROPOSAL_ID, 0, proof.vote_hash.clone(), proof.proof_path[..1].to_vec(),));
+
+    // Unknown proposal
+    assert!(!dao.verify_submitted_vote_proof(99, 0, proof.vote_hash.clone(), proof.proof_path.clone()));
+}
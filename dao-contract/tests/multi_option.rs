@@ -0,0 +1,124 @@
+//! Multi-option proposals are decided by plurality
+
+mod common;
+
+use common::{account, caller, dao, outlayer_input, outlayer_success};
+use std::collections::HashMap;
+
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn options(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+/// DAO with member alice, who created proposal 1 with `vote_options` and voted on it
+fn dao_with_poll(vote_options: Vec<String>) -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().private().members(&["alice"]).build();
+
+    caller("alice").deposit(NearToken::from_millinear(2)).set();
+    dao.create_multi_option_proposal(
+        "Pick a venue".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        vote_options,
+        None,
+    );
+    dao.cast_vote(1, "ab".repeat(113));
+    dao
+}
+
+fn on_votes_tallied(dao: &mut PrivateDAO, result: serde_json::Value) {
+    let mut result = result;
+    for (key, value) in [
+        ("proposal_id", serde_json::json!(1)),
+        ("tee_attestation", serde_json::json!("")),
+        ("votes_merkle_root", serde_json::json!("")),
+        ("merkle_proofs", serde_json::json!([])),
+        ("merkle_proofs_available", serde_json::json!(false)),
+    ] {
+        result[key] = value;
+    }
+
+    caller("dao").set();
+    dao.on_votes_tallied(1, None, outlayer_success(result));
+}
+
+#[test]
+fn options_stored_and_sent_to_worker() {
+    let mut dao = dao_with_poll(options(&["park", "hall", "online"]));
+    assert_eq!(dao.get_proposal(1).unwrap().vote_options, Some(options(&["park", "hall", "online"])));
+
+    caller("alice").set();
+    let _ = dao.finalize_proposal(1);
+
+    let input = outlayer_input();
+    assert_eq!(input["vote_options"], serde_json::json!(["park", "hall", "online"]));
+}
+
+#[test]
+fn plurality_winner_passes() {
+    let mut dao = dao_with_poll(options(&["park", "hall", "online"]));
+
+    on_votes_tallied(
+        &mut dao,
+        serde_json::json!({ "vote_counts": { "park": 3, "hall": 5, "online": 4 }, "total_votes": 12 }),
+    );
+
+    let proposal = dao.get_proposal(1).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Passed);
+    let tally = proposal.tally_result.unwrap();
+    assert_eq!(tally.vote_counts["hall"], 5);
+    assert_eq!(tally.yes_count, None);
+}
+
+#[test]
+fn tie_for_first_rejects() {
+    let mut dao = dao_with_poll(options(&["park", "hall", "online"]));
+
+    on_votes_tallied(
+        &mut dao,
+        serde_json::json!({ "vote_counts": { "park": 5, "hall": 5, "online": 1 }, "total_votes": 11 }),
+    );
+
+    assert_eq!(dao.get_proposal_status(1), Some(ProposalStatus::Rejected));
+}
+
+#[test]
+fn binary_tally_from_worker_without_vote_counts() {
+    caller("owner").set();
+    let mut dao = dao().private().build();
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+    caller("alice").deposit(NearToken::from_millinear(2)).set();
+    dao.create_proposal("Yes or no".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+
+    on_votes_tallied(&mut dao, serde_json::json!({ "yes_count": 2, "no_count": 1, "total_votes": 3 }));
+
+    let proposal = dao.get_proposal(1).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Passed);
+    assert_eq!(
+        proposal.tally_result.unwrap().vote_counts,
+        HashMap::from([("yes".to_string(), 2), ("no".to_string(), 1)])
+    );
+}
+
+#[test]
+#[should_panic(expected = "A proposal needs between 2 and 10 vote options")]
+fn single_option_rejected() {
+    dao_with_poll(options(&["park"]));
+}
+
+#[test]
+#[should_panic(expected = "Duplicate vote option 'park'")]
+fn duplicate_options_rejected() {
+    dao_with_poll(options(&["park", "hall", "park"]));
+}
+
+#[test]
+#[should_panic(expected = "Vote options must be 1-64 characters")]
+fn empty_option_rejected() {
+    dao_with_poll(options(&["park", ""]));
+}
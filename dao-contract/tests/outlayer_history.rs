@@ -0,0 +1,128 @@
+//! OutLayer executions are recorded when requested and updated by their callbacks
+
+mod common;
+
+use common::{account, caller, dao, new_dao};
+use near_sdk::test_utils::get_created_receipts;
+use near_sdk::AccountId;
+use private_dao_contract::types::*;
+
+fn key_response(success: bool) -> Result<Option<OutLayerResponse>, near_sdk::PromiseError> {
+    Ok(Some(OutLayerResponse {
+        success,
+        result: serde_json::json!({ "pubkey": "02abcd" }),
+        error: (!success).then(|| "TEE unavailable".to_string()),
+    }))
+}
+
+#[test]
+fn outlayer_contract_defaults_to_testnet() {
+    let dao = new_dao();
+    assert_eq!(dao.get_outlayer_contract(), "outlayer.testnet".parse::<AccountId>().unwrap());
+}
+
+#[test]
+fn executions_sent_to_configured_outlayer_contract() {
+    caller("owner").block(1).set();
+    let mut dao = dao().outlayer_contract(account("mock-outlayer")).build();
+    assert_eq!(dao.get_outlayer_contract(), account("mock-outlayer"));
+
+    caller("alice").block(10).set();
+    let _ = dao.join_dao();
+
+    assert_eq!(get_created_receipts()[0].receiver_id, account("mock-outlayer"));
+}
+
+#[test]
+fn join_records_pending_execution_until_callback() {
+    let mut dao = new_dao();
+
+    caller("alice").block(10).set();
+    let _ = dao.join_dao();
+
+    assert_eq!(
+        dao.get_outlayer_execution_history(0, 10),
+        vec![OutLayerExecutionSummary {
+            execution_type: "key_derivation".to_string(),
+            triggered_by: account("alice"),
+            block_height: 10,
+            timestamp: 10_000,
+            success: None,
+            gas_used: None,
+        }]
+    );
+    assert_eq!(dao.get_outlayer_success_rate(), 0.0);
+
+    caller("dao").block(11).set();
+    dao.on_key_derived(account("alice"), Some(0), key_response(true));
+
+    assert_eq!(dao.get_outlayer_execution_history(0, 10)[0].success, Some(true));
+    assert_eq!(dao.get_outlayer_success_rate(), 1.0);
+}
+
+#[test]
+fn failed_callback_is_recorded_without_panicking() {
+    let mut dao = new_dao();
+
+    for (index, user) in ["alice", "bob"].into_iter().enumerate() {
+        caller(user).block(10).set();
+        let _ = dao.join_dao();
+
+        caller("dao").block(11).set();
+        dao.on_key_derived(account(user), Some(index as u64), key_response(user == "alice"));
+    }
+
+    let history = dao.get_outlayer_execution_history(0, 10);
+    assert_eq!(history.iter().map(|e| e.success).collect::<Vec<_>>(), vec![Some(true), Some(false)]);
+    assert!(dao.is_member(account("alice")));
+    assert!(!dao.is_member(account("bob")));
+    assert_eq!(dao.get_outlayer_success_rate(), 0.5);
+
+    // Pagination within the stored history
+    assert_eq!(dao.get_outlayer_execution_history(1, 10), history[1..].to_vec());
+    assert!(dao.get_outlayer_execution_history(5, 10).is_empty());
+}
+
+#[test]
+fn history_is_capped_at_10000_entries() {
+    let mut dao = new_dao();
+    let old_entry = OutLayerExecutionSummary {
+        execution_type: "vote_tally".to_string(),
+        triggered_by: account("owner"),
+        block_height: 1,
+        timestamp: 1_000,
+        success: Some(true),
+        gas_used: None,
+    };
+    for chunk in 0..20 {
+        // Fresh context per chunk to stay under the mocked gas limit
+        caller("owner").block(2 + chunk).set();
+        for _ in 0..500 {
+            dao.outlayer_executions.push(&old_entry);
+        }
+    }
+    dao.outlayer_execution_count = 10_000;
+    dao.outlayer_executions_completed = 10_000;
+    dao.outlayer_executions_succeeded = 10_000;
+
+    // Execution 10,000 overwrites execution 0 and becomes the newest entry
+    caller("alice").block(10).set();
+    let _ = dao.join_dao();
+
+    assert_eq!(dao.outlayer_executions.len(), 10_000);
+    let newest = dao.get_outlayer_execution_history(9_999, 1);
+    assert_eq!(newest[0].triggered_by, account("alice"));
+    assert_eq!(dao.get_outlayer_execution_history(0, 1)[0], old_entry);
+
+    // The callback of an execution that was dropped from the history is ignored
+    caller("dao").block(11).set();
+    dao.on_key_derived(account("alice"), Some(0), key_response(false));
+    assert_eq!(dao.get_outlayer_execution_history(9_999, 1)[0].success, None);
+
+    assert_eq!(dao.outlayer_executions_completed, 9_999);
+
+    dao.on_key_derived(account("alice"), Some(10_000), key_response(false));
+    assert_eq!(dao.get_outlayer_execution_history(9_999, 1)[0].success, Some(false));
+    assert_eq!(dao.get_outlayer_success_rate(), 9_999.0 / 10_000.0);
+    assert_eq!(dao.get_outlayer_execution_history(0, 500).len(), 100);
+}
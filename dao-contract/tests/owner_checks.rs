@@ -0,0 +1,87 @@
+//! Every owner-restricted method must reject non-owners with the same message
+
+mod common;
+
+use common::{account, caller, dao, new_dao};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use near_sdk::env;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+const OWNER_ERROR: &str = "Only owner can call this method";
+
+type OwnerCall = Box<dyn FnOnce(&mut PrivateDAO)>;
+
+fn template() -> ProposalTemplate {
+    ProposalTemplate {
+        name: "grant".to_string(),
+        description_template: "Grant request".to_string(),
+        quorum: QuorumType::Absolute { min_votes: 1 },
+        deadline_offset_ns: None,
+        proposal_type: ProposalType::Funding,
+        tags: vec![],
+    }
+}
+
+/// Run `call` as a non-owner and return the panic message
+fn panic_message_as_stranger(call: impl FnOnce()) -> String {
+    caller("stranger").set();
+    let err = catch_unwind(AssertUnwindSafe(call)).expect_err("non-owner call must panic");
+    err.downcast_ref::<String>()
+        .cloned()
+        .or_else(|| err.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_default()
+}
+
+#[test]
+fn owner_methods_reject_non_owner() {
+    // Owner check must come before any other validation, so arguments don't need to be valid
+    let cases: Vec<(&str, OwnerCall)> = vec![
+        ("add_member", Box::new(|dao| dao.add_member(account("stranger")))),
+        ("remove_member", Box::new(|dao| dao.remove_member(account("owner")))),
+        ("migrate_add_member", Box::new(|dao| dao.migrate_add_member(account("stranger"), None))),
+        ("migrate_index_members", Box::new(|dao| {
+            dao.migrate_index_members(vec![account("owner")]);
+        })),
+        ("add_proposal_template", Box::new(|dao| dao.add_proposal_template(template()))),
+        ("remove_proposal_template", Box::new(|dao| dao.remove_proposal_template("grant".to_string()))),
+        ("blacklist_member", Box::new(|dao| dao.blacklist_member(account("stranger"), "spam".to_string()))),
+        ("remove_from_blacklist", Box::new(|dao| dao.remove_from_blacklist(account("stranger")))),
+        ("whitelist_member", Box::new(|dao| dao.whitelist_member(account("stranger")))),
+        ("remove_from_whitelist", Box::new(|dao| dao.remove_from_whitelist(account("stranger")))),
+        ("set_whitelist_mode", Box::new(|dao| dao.set_whitelist_mode(true))),
+        ("update_config", Box::new(|dao| dao.update_config(DAOConfig::default()))),
+        ("add_admin", Box::new(|dao| dao.add_admin(account("owner")))),
+        ("remove_admin", Box::new(|dao| dao.remove_admin(account("owner")))),
+        ("transfer_ownership", Box::new(|dao| dao.transfer_ownership(account("stranger")))),
+        ("cancel_ownership_transfer", Box::new(|dao| dao.cancel_ownership_transfer())),
+        ("finalize_proposal_as_of", Box::new(|dao| {
+            let _ = dao.finalize_proposal_as_of(1, 0);
+        })),
+    ];
+
+    for (method, call) in cases {
+        caller("owner").set();
+        let mut dao = dao().private().build();
+
+        let message = panic_message_as_stranger(|| call(&mut dao));
+        assert!(
+            message.contains(OWNER_ERROR),
+            "{} panicked with unexpected message: {}",
+            method,
+            message
+        );
+    }
+}
+
+#[test]
+fn reset_state_rejects_non_owner() {
+    let dao = new_dao();
+    env::state_write(&dao);
+
+    let message = panic_message_as_stranger(|| {
+        PrivateDAO::reset_state();
+    });
+    assert!(message.contains(OWNER_ERROR), "unexpected message: {}", message);
+}
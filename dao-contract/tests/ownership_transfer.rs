@@ -0,0 +1,90 @@
+//! Two-step ownership transfer (`transfer_ownership` + `accept_ownership`)
+
+mod common;
+
+use common::{account, caller, dao};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+const DAY_NS: u64 = 86_400_000_000_000;
+
+/// DAO owned by `owner` with a pending transfer to `alice` made at day 1
+fn dao_with_pending_transfer() -> PrivateDAO {
+    caller("owner").timestamp(0).set();
+    let mut dao = dao().private().build();
+
+    caller("owner").timestamp(DAY_NS).set();
+    dao.transfer_ownership(account("alice"));
+    dao
+}
+
+#[test]
+fn transfer_completes_on_accept() {
+    let mut dao = dao_with_pending_transfer();
+    assert_eq!(dao.get_pending_owner(), Some(account("alice")));
+    assert_eq!(dao.owner, account("owner"));
+
+    caller("alice").timestamp(2 * DAY_NS).set();
+    dao.accept_ownership();
+
+    assert_eq!(dao.owner, account("alice"));
+    assert_eq!(dao.get_pending_owner(), None);
+
+    // The new owner has owner rights, the previous one doesn't
+    dao.update_config(DAOConfig::default());
+    assert!(!dao.is_admin(account("owner")));
+}
+
+#[test]
+#[should_panic(expected = "Only the pending owner can accept ownership")]
+fn only_pending_owner_can_accept() {
+    let mut dao = dao_with_pending_transfer();
+    caller("mallory").timestamp(2 * DAY_NS).set();
+    dao.accept_ownership();
+}
+
+#[test]
+#[should_panic(expected = "Only the pending owner can accept ownership")]
+fn new_offer_replaces_pending_one() {
+    let mut dao = dao_with_pending_transfer();
+    dao.transfer_ownership(account("bob"));
+    assert_eq!(dao.get_pending_owner(), Some(account("bob")));
+
+    caller("alice").timestamp(2 * DAY_NS).set();
+    dao.accept_ownership();
+}
+
+#[test]
+fn cancel_after_timeout() {
+    let mut dao = dao_with_pending_transfer();
+
+    caller("owner").timestamp(8 * DAY_NS).set();
+    dao.cancel_ownership_transfer();
+
+    assert_eq!(dao.get_pending_owner(), None);
+    assert_eq!(dao.owner, account("owner"));
+}
+
+#[test]
+#[should_panic(expected = "Ownership transfer can only be cancelled after the timeout")]
+fn cancel_before_timeout_rejected() {
+    let mut dao = dao_with_pending_transfer();
+
+    caller("owner").timestamp(8 * DAY_NS - 1).set();
+    dao.cancel_ownership_transfer();
+}
+
+#[test]
+#[should_panic(expected = "No pending ownership transfer")]
+fn cancel_without_pending_transfer() {
+    caller("owner").timestamp(0).set();
+    let mut dao = dao().private().build();
+    dao.cancel_ownership_transfer();
+}
+
+#[test]
+#[should_panic(expected = "Already the owner")]
+fn transfer_to_self_rejected() {
+    let mut dao = dao_with_pending_transfer();
+    dao.transfer_ownership(account("owner"));
+}
@@ -0,0 +1,151 @@
+//! Owner emergency pause of user-facing methods
+
+mod common;
+
+use common::{account, caller, dao};
+use near_sdk::test_utils::get_logs;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn paused_dao() -> PrivateDAO {
+    caller("owner").timestamp(7_000).set();
+    let mut dao = dao().build();
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+    dao.create_proposal(
+        "Before pause".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        None,
+        None,
+    );
+    dao.pause();
+    dao
+}
+
+#[test]
+fn pause_and_unpause_emit_events() {
+    let mut dao = paused_dao();
+    assert!(dao.is_paused());
+    let paused: serde_json::Value =
+        serde_json::from_str(get_logs().last().unwrap().strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+    assert_eq!(paused["event"], "dao_paused");
+    assert_eq!(paused["data"], serde_json::json!({ "by": "owner.testnet", "timestamp": 7_000 }));
+
+    dao.unpause();
+    assert!(!dao.is_paused());
+    assert!(get_logs().last().unwrap().contains("\"event\":\"dao_unpaused\""));
+
+    caller("alice").timestamp(7_000).set();
+    dao.cast_vote(1, "ab".repeat(113));
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn cast_vote_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    dao.cast_vote(1, "ab".repeat(113));
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn join_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("bob").timestamp(7_000).set();
+    let _ = dao.join_dao();
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn create_proposal_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    dao.create_proposal("Paused".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn finalize_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    let _ = dao.finalize_proposal(1);
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn commit_finalization_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    let _ = dao.commit_finalization(1);
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn delegate_vote_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    dao.delegate_vote(account("owner"));
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn revoke_delegation_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    dao.revoke_delegation();
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn leave_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    dao.leave_dao();
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn cancel_proposal_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("owner").timestamp(7_000).set();
+    dao.cancel_proposal(1);
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn update_proposal_description_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("owner").timestamp(7_000).set();
+    dao.update_proposal_description(1, "Edited".to_string());
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn upload_upgrade_wasm_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    dao.upload_upgrade_wasm(1, vec![0u8; 8].into());
+}
+
+#[test]
+#[should_panic(expected = "DAO is paused")]
+fn abandon_partial_tally_rejected_while_paused() {
+    let mut dao = paused_dao();
+    caller("owner").timestamp(7_000).set();
+    dao.abandon_partial_tally(1);
+}
+
+#[test]
+fn state_summary_reports_pause() {
+    let dao = paused_dao();
+    assert!(dao.get_state_summary().is_paused);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can call this method")]
+fn only_owner_can_pause() {
+    let mut dao = paused_dao();
+    caller("alice").timestamp(7_000).set();
+    dao.unpause();
+}
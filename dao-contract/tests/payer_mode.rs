@@ -0,0 +1,285 @@
+//! `DAOConfig::outlayer_payer_mode` decides who funds (and is refunded for) tallies
+
+mod common;
+
+use common::{account, caller, dao};
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::get_created_receipts;
+use near_sdk::{AccountId, NearToken};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn min_outlayer_deposit() -> NearToken {
+    DAOConfig::default().min_outlayer_deposit
+}
+
+/// DAO in `mode` with proposal 1 by `alice` (attaching `creation_deposit`) and one vote
+fn dao_with_vote(mode: OutLayerPayerMode, creation_deposit: NearToken) -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().private().build();
+    dao.update_config(DAOConfig { outlayer_payer_mode: mode, ..DAOConfig::default() });
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+
+    caller("alice").deposit(creation_deposit).set();
+    dao.create_proposal(
+        "Fund the meetup".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        None,
+        None,
+    );
+
+    caller("alice").deposit(NearToken::from_millinear(2)).set();
+    dao.cast_vote(1, "ab".repeat(113));
+    dao
+}
+
+/// Finalize as `name` and return the (deposit, payer_account_id) sent to OutLayer
+fn finalize(dao: &mut PrivateDAO, name: &str, attached: NearToken) -> (NearToken, AccountId) {
+    caller(name).deposit(attached).set();
+    let _ = dao.finalize_proposal(1);
+
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| receipt.actions)
+        .find_map(|action| match action {
+            MockAction::FunctionCallWeight { method_name, args, attached_deposit, .. }
+                if method_name == b"request_execution" =>
+            {
+                let args: serde_json::Value = serde_json::from_slice(&args).unwrap();
+                let payer = args["payer_account_id"].as_str().unwrap().parse().unwrap();
+                Some((attached_deposit, payer))
+            }
+            _ => None,
+        })
+        .expect("request_execution call")
+}
+
+#[test]
+fn caller_pays() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::Caller, NearToken::from_millinear(1));
+
+    assert_eq!(
+        finalize(&mut dao, "bob", NearToken::from_millinear(20)),
+        (NearToken::from_millinear(20), account("bob"))
+    );
+}
+
+#[test]
+fn dao_pays_from_treasury() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::DAO, NearToken::from_millinear(1));
+    caller("owner").deposit(NearToken::from_millinear(25)).set();
+    dao.fund_treasury();
+
+    assert_eq!(finalize(&mut dao, "bob", NearToken::from_yoctonear(0)), (min_outlayer_deposit(), account("dao")));
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_millinear(25).saturating_sub(min_outlayer_deposit()));
+}
+
+#[test]
+#[should_panic(expected = "Treasury balance is too low")]
+fn dao_pays_requires_treasury() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::DAO, NearToken::from_millinear(1));
+    finalize(&mut dao, "bob", NearToken::from_yoctonear(0));
+}
+
+#[test]
+fn creator_deposit_funds_first_tally() {
+    let creation_deposit = NearToken::from_millinear(1).saturating_add(min_outlayer_deposit());
+    let mut dao = dao_with_vote(OutLayerPayerMode::Proposal { creator_pays: true }, creation_deposit);
+    assert_eq!(dao.get_proposal(1).unwrap().creator_deposit, min_outlayer_deposit());
+
+    assert_eq!(finalize(&mut dao, "bob", NearToken::from_yoctonear(0)), (min_outlayer_deposit(), account("alice")));
+    assert!(dao.get_proposal(1).unwrap().creator_deposit.is_zero());
+
+    // Spent: the next attempt (once the first tally failed) is paid by its caller
+    refunds_after_failed_tally(&mut dao, Ok(None));
+    assert_eq!(
+        finalize(&mut dao, "bob", NearToken::from_millinear(10)),
+        (NearToken::from_millinear(10), account("bob"))
+    );
+}
+
+#[test]
+#[should_panic(expected = "storage + OutLayer execution")]
+fn creator_pays_requires_tally_deposit_at_creation() {
+    dao_with_vote(OutLayerPayerMode::Proposal { creator_pays: true }, NearToken::from_millinear(1));
+}
+
+#[test]
+fn proposal_mode_without_creator_pays_charges_caller() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::Proposal { creator_pays: false }, NearToken::from_millinear(1));
+
+    assert_eq!(
+        finalize(&mut dao, "bob", NearToken::from_millinear(10)),
+        (NearToken::from_millinear(10), account("bob"))
+    );
+}
+
+/// Deliver the tally callback for proposal 1 and return the transfers it created
+fn refunds_after_failed_tally(
+    dao: &mut PrivateDAO,
+    result: Result<Option<OutLayerResponse>, near_sdk::PromiseError>,
+) -> Vec<(AccountId, NearToken)> {
+    caller("dao").set();
+    dao.on_votes_tallied(1, Some(0), result);
+
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| {
+            let receiver_id = receipt.receiver_id.clone();
+            receipt.actions.into_iter().filter_map(move |action| match action {
+                MockAction::Transfer { deposit, .. } => Some((receiver_id.clone(), deposit)),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn failed_outlayer_call_refunds_caller() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::Caller, NearToken::from_millinear(1));
+    finalize(&mut dao, "bob", NearToken::from_millinear(20));
+
+    assert_eq!(
+        refunds_after_failed_tally(&mut dao, Err(near_sdk::PromiseError::Failed)),
+        vec![(account("bob"), NearToken::from_millinear(20))]
+    );
+
+    // The deposit is only refunded once
+    assert!(refunds_after_failed_tally(&mut dao, Err(near_sdk::PromiseError::Failed)).is_empty());
+}
+
+#[test]
+fn failed_execution_is_settled_by_outlayer() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::Caller, NearToken::from_millinear(1));
+    finalize(&mut dao, "bob", NearToken::from_millinear(20));
+
+    let failed = OutLayerResponse {
+        success: false,
+        result: serde_json::Value::Null,
+        error: Some("TEE unavailable".to_string()),
+    };
+    assert!(refunds_after_failed_tally(&mut dao, Ok(Some(failed))).is_empty());
+}
+
+#[test]
+fn failed_dao_paid_call_returns_deposit_to_treasury() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::DAO, NearToken::from_millinear(1));
+    caller("owner").deposit(NearToken::from_millinear(25)).set();
+    dao.fund_treasury();
+    finalize(&mut dao, "bob", NearToken::from_yoctonear(0));
+
+    assert!(refunds_after_failed_tally(&mut dao, Err(near_sdk::PromiseError::Failed)).is_empty());
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_millinear(25));
+}
+
+#[test]
+fn failed_dao_paid_call_can_be_retried_at_dao_expense() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::DAO, NearToken::from_millinear(1));
+    caller("owner").deposit(NearToken::from_millinear(25)).set();
+    dao.fund_treasury();
+    finalize(&mut dao, "bob", NearToken::from_yoctonear(0));
+    refunds_after_failed_tally(&mut dao, Err(near_sdk::PromiseError::Failed));
+
+    assert_eq!(finalize(&mut dao, "bob", NearToken::from_yoctonear(0)), (min_outlayer_deposit(), account("dao")));
+}
+
+#[test]
+fn dao_pays_one_tally_per_proposal() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::DAO, NearToken::from_millinear(1));
+    caller("owner").deposit(NearToken::from_millinear(25)).set();
+    dao.fund_treasury();
+    finalize(&mut dao, "bob", NearToken::from_yoctonear(0));
+
+    // OutLayer ran (and charged) but the tally failed: the retry is on the caller
+    refunds_after_failed_tally(&mut dao, Ok(None));
+    assert_eq!(
+        finalize(&mut dao, "bob", NearToken::from_millinear(10)),
+        (NearToken::from_millinear(10), account("bob"))
+    );
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_millinear(25).saturating_sub(min_outlayer_deposit()));
+}
+
+#[test]
+fn dao_pays_only_after_deadline() {
+    let deadline = 86_400_000_000_000;
+    caller("owner").set();
+    let mut dao = dao().private().build();
+    dao.update_config(DAOConfig { outlayer_payer_mode: OutLayerPayerMode::DAO, ..DAOConfig::default() });
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+    dao.fund_treasury();
+    caller("alice").deposit(NearToken::from_millinear(1)).set();
+    dao.create_proposal(
+        "Early".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        Some(deadline),
+        None,
+        None,
+    );
+    caller("alice").deposit(NearToken::from_millinear(2)).set();
+    dao.cast_vote(1, "ab".repeat(113));
+
+    // Early-quorum attempts are paid by their caller
+    assert_eq!(
+        finalize(&mut dao, "bob", NearToken::from_millinear(10)),
+        (NearToken::from_millinear(10), account("bob"))
+    );
+    refunds_after_failed_tally(&mut dao, Ok(None));
+
+    caller("bob").deposit(NearToken::from_yoctonear(0)).timestamp(deadline).set();
+    let _ = dao.finalize_proposal(1);
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_millinear(20).saturating_sub(min_outlayer_deposit()));
+}
+
+#[test]
+fn reconcile_credits_outlayer_refunds() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::DAO, NearToken::from_millinear(1));
+    caller("owner").deposit(NearToken::from_millinear(25)).set();
+    dao.fund_treasury();
+    finalize(&mut dao, "bob", NearToken::from_yoctonear(0));
+    refunds_after_failed_tally(&mut dao, Ok(None));
+    let treasury = dao.get_treasury_balance();
+
+    // Only balance beyond storage and the treasury can be a refund
+    let carol = || caller("carol").deposit(NearToken::from_yoctonear(0));
+    carol().set();
+    let storage_cost = near_sdk::env::storage_byte_cost().saturating_mul(near_sdk::env::storage_usage() as u128);
+    carol().balance(storage_cost.saturating_add(treasury).saturating_add(NearToken::from_yoctonear(5))).set();
+    assert_eq!(dao.reconcile_treasury(), NearToken::from_yoctonear(5));
+
+    // Never more than the DAO-paid executions spent
+    carol().balance(NearToken::from_near(100)).set();
+    assert_eq!(dao.reconcile_treasury(), min_outlayer_deposit().saturating_sub(NearToken::from_yoctonear(5)));
+    assert_eq!(dao.reconcile_treasury(), NearToken::from_yoctonear(0));
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_millinear(25));
+}
+
+#[test]
+fn reconcile_leaves_pending_creator_deposits() {
+    let mut dao = dao_with_vote(OutLayerPayerMode::DAO, NearToken::from_millinear(1));
+    caller("owner").deposit(NearToken::from_millinear(25)).set();
+    dao.fund_treasury();
+    finalize(&mut dao, "bob", NearToken::from_yoctonear(0));
+    refunds_after_failed_tally(&mut dao, Ok(None));
+
+    // Proposal 2 holds a creator deposit that isn't spent yet
+    caller("owner").set();
+    dao.update_config(DAOConfig {
+        outlayer_payer_mode: OutLayerPayerMode::Proposal { creator_pays: true },
+        ..DAOConfig::default()
+    });
+    let creator_deposit = min_outlayer_deposit();
+    caller("alice").deposit(DAOConfig::default().proposal_deposit.saturating_add(creator_deposit)).set();
+    dao.create_proposal("Second".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    let treasury = dao.get_treasury_balance();
+
+    let carol = || caller("carol").deposit(NearToken::from_yoctonear(0));
+    carol().set();
+    let storage_cost = near_sdk::env::storage_byte_cost().saturating_mul(near_sdk::env::storage_usage() as u128);
+    carol().balance(storage_cost.saturating_add(treasury).saturating_add(creator_deposit)).set();
+    assert_eq!(dao.reconcile_treasury(), NearToken::from_yoctonear(0));
+    assert_eq!(dao.get_treasury_balance(), treasury);
+    assert_eq!(dao.get_proposal(2).unwrap().creator_deposit, creator_deposit);
+}
@@ -0,0 +1,71 @@
+//! `DAOConfig::proposal_categories` restricts proposal tags
+
+mod common;
+
+use common::{caller, new_dao};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// `new_dao` allowing only `categories` as proposal tags (any if empty)
+fn dao_with_categories(categories: &[&str]) -> PrivateDAO {
+    let mut dao = new_dao();
+    for category in categories {
+        dao.add_proposal_category(category.to_string());
+    }
+    dao
+}
+
+fn create_tagged(dao: &mut PrivateDAO, tags: &[&str]) -> u64 {
+    dao.create_proposal(
+        "Tagged".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        None,
+        Some(tags.iter().map(|tag| tag.to_string()).collect()),
+    )
+}
+
+#[test]
+#[should_panic(expected = "Unknown proposal category 'memes', allowed: governance, treasury")]
+fn invalid_category_rejected() {
+    let mut dao = dao_with_categories(&["governance", "treasury"]);
+    create_tagged(&mut dao, &["treasury", "memes"]);
+}
+
+#[test]
+fn valid_category_accepted() {
+    let mut dao = dao_with_categories(&["governance", "treasury"]);
+
+    let id = create_tagged(&mut dao, &["treasury"]);
+    assert_eq!(dao.get_proposal(id).unwrap().tags, vec!["treasury"]);
+}
+
+#[test]
+fn any_tag_without_categories() {
+    let mut dao = dao_with_categories(&[]);
+
+    assert!(dao.get_proposal_categories().is_empty());
+    create_tagged(&mut dao, &["anything", "goes"]);
+}
+
+#[test]
+fn removing_category_grandfathers_existing_proposals() {
+    let mut dao = dao_with_categories(&["governance", "social"]);
+    let id = create_tagged(&mut dao, &["social"]);
+
+    dao.remove_proposal_category("social".to_string());
+
+    assert_eq!(dao.get_proposal_categories(), vec!["governance"]);
+    assert_eq!(dao.get_proposal(id).unwrap().tags, vec!["social"]);
+}
+
+#[test]
+#[should_panic(expected = "Only admins can manage proposal categories")]
+fn only_admins_manage_categories() {
+    let mut dao = dao_with_categories(&[]);
+
+    caller("stranger").deposit(NearToken::from_millinear(1)).set();
+    dao.add_proposal_category("governance".to_string());
+}
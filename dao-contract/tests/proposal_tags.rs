@@ -0,0 +1,76 @@
+//! Proposal tags are normalized at creation and indexed for `get_proposals_by_tag`
+
+mod common;
+
+use common::{caller, dao};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// DAO whose owner may keep up to 100 proposals active
+fn dao_without_proposal_cap() -> PrivateDAO {
+    caller("owner").set();
+    dao().config(DAOConfig { max_active_proposals_per_creator: 100, ..DAOConfig::default() }).build()
+}
+
+fn create_tagged(dao: &mut PrivateDAO, tags: &[&str]) -> u64 {
+    caller("owner").deposit(NearToken::from_millinear(1)).set();
+    dao.create_proposal(
+        "Tagged".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        None,
+        Some(tags.iter().map(|tag| tag.to_string()).collect()),
+    )
+}
+
+fn ids(proposals: Vec<Proposal>) -> Vec<u64> {
+    proposals.into_iter().map(|proposal| proposal.id).collect()
+}
+
+#[test]
+fn tags_lowercased_and_indexed() {
+    let mut dao = dao_without_proposal_cap();
+    create_tagged(&mut dao, &["Treasury", "treasury", "Q3"]);
+    create_tagged(&mut dao, &["governance"]);
+    create_tagged(&mut dao, &["TREASURY"]);
+
+    assert_eq!(dao.get_proposal(1).unwrap().tags, vec!["treasury", "q3"]);
+    assert_eq!(ids(dao.get_proposals_by_tag("treasury".to_string(), 0, 10)), vec![1, 3]);
+    assert_eq!(ids(dao.get_proposals_by_tag("Treasury".to_string(), 1, 10)), vec![3]);
+    assert_eq!(ids(dao.get_proposals_by_tag("governance".to_string(), 0, 10)), vec![2]);
+    assert!(dao.get_proposals_by_tag("social".to_string(), 0, 10).is_empty());
+}
+
+#[test]
+fn page_capped_at_50() {
+    let mut dao = dao_without_proposal_cap();
+    for _ in 0..55 {
+        create_tagged(&mut dao, &["social"]);
+    }
+
+    assert_eq!(dao.get_proposals_by_tag("social".to_string(), 0, 100).len(), 50);
+    assert_eq!(ids(dao.get_proposals_by_tag("social".to_string(), 50, 100)), vec![51, 52, 53, 54, 55]);
+}
+
+#[test]
+#[should_panic(expected = "A proposal can have at most 5 tags")]
+fn too_many_tags_rejected() {
+    let mut dao = dao_without_proposal_cap();
+    create_tagged(&mut dao, &["a", "b", "c", "d", "e", "f"]);
+}
+
+#[test]
+#[should_panic(expected = "Tags must be 1-32 ASCII characters")]
+fn long_tag_rejected() {
+    let mut dao = dao_without_proposal_cap();
+    create_tagged(&mut dao, &[&"x".repeat(33)]);
+}
+
+#[test]
+#[should_panic(expected = "Tags must be 1-32 ASCII characters")]
+fn non_ascii_tag_rejected() {
+    let mut dao = dao_without_proposal_cap();
+    create_tagged(&mut dao, &["trésor"]);
+}
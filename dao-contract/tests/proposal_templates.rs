@@ -0,0 +1,46 @@
+//! `create_proposal_from_template` takes everything but the title from a stored template
+
+mod common;
+
+use common::{caller, dao};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// One day, the shortest allowed voting period
+const DAY: u64 = 86_400_000_000_000;
+
+fn dao_with_template(deadline_offset_ns: Option<u64>) -> PrivateDAO {
+    caller("owner").timestamp(1_000).set();
+    let mut dao = dao().build();
+    dao.add_proposal_template(ProposalTemplate {
+        name: "grant".to_string(),
+        description_template: "Grant request".to_string(),
+        quorum: QuorumType::Absolute { min_votes: 1 },
+        deadline_offset_ns,
+        proposal_type: ProposalType::Funding,
+        tags: vec![],
+    });
+    dao
+}
+
+#[test]
+fn deadline_is_offset_from_now() {
+    let mut dao = dao_with_template(Some(DAY));
+
+    caller("owner").deposit(NearToken::from_millinear(1)).timestamp(1_000).set();
+    let proposal_id = dao.create_proposal_from_template("grant".to_string(), "Grant".to_string());
+
+    let proposal = dao.get_proposal(proposal_id).unwrap();
+    assert_eq!(proposal.deadline, Some(1_000 + DAY));
+    assert_eq!(proposal.description, "Grant request");
+}
+
+#[test]
+#[should_panic(expected = "Template deadline_offset_ns is too large")]
+fn overflowing_offset_rejected() {
+    let mut dao = dao_with_template(Some(u64::MAX));
+
+    caller("owner").deposit(NearToken::from_millinear(1)).timestamp(1_000).set();
+    dao.create_proposal_from_template("grant".to_string(), "Grant".to_string());
+}
@@ -0,0 +1,67 @@
+//! Owner-configurable proposal title/description length limits
+
+mod common;
+
+use common::new_dao;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn create(dao: &mut PrivateDAO, title: &str, description: &str) -> Result<u64, String> {
+    catch_unwind(AssertUnwindSafe(|| {
+        dao.create_proposal(
+            title.to_string(),
+            description.to_string(),
+            QuorumType::Absolute { min_votes: 1 },
+            None,
+            None,
+            None,
+        )
+    }))
+    .map_err(|err| err.downcast_ref::<String>().cloned().unwrap_or_default())
+}
+
+#[test]
+fn default_limits_apply() {
+    let mut dao = new_dao();
+
+    assert!(create(&mut dao, "Fund the meetup", "").is_ok());
+    assert!(create(&mut dao, "", "No title").unwrap_err().contains("Title must be 1-200"));
+    assert!(create(&mut dao, &"x".repeat(201), "").is_err());
+    // Limits count characters, not bytes
+    assert!(create(&mut dao, &"é".repeat(200), "").is_ok());
+}
+
+#[test]
+fn configured_limits_apply() {
+    let mut dao = new_dao();
+    dao.update_config(DAOConfig {
+        min_title_length: 5,
+        max_title_length: 10,
+        min_description_length: 10,
+        max_description_length: 20,
+        ..DAOConfig::default()
+    });
+
+    assert!(create(&mut dao, "Short", "Long enough text").is_ok());
+    assert!(create(&mut dao, "Tiny", "Long enough text").is_err());
+    assert!(create(&mut dao, "Way too long title", "Long enough text").is_err());
+    assert!(create(&mut dao, "Short", "Too short")
+        .unwrap_err()
+        .contains("Description must be 10-20"));
+}
+
+#[test]
+fn update_config_rejects_limits_below_floor() {
+    let invalid = [
+        DAOConfig { max_title_length: 4, ..DAOConfig::default() },
+        DAOConfig { max_description_length: 9, ..DAOConfig::default() },
+        DAOConfig { min_title_length: 50, max_title_length: 20, ..DAOConfig::default() },
+    ];
+
+    for config in invalid {
+        let mut dao = new_dao();
+        assert!(catch_unwind(AssertUnwindSafe(|| dao.update_config(config))).is_err());
+    }
+}
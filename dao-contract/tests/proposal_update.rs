@@ -0,0 +1,114 @@
+//! Editing a proposal's description before and after voting starts
+
+mod common;
+
+use common::{caller, dao};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+const NOW: u64 = 5_000;
+
+/// Proposal 1 by `owner`, optionally with one ballot from `alice`
+fn dao_with_editable_proposal(allow_after_votes: bool, votes: usize) -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(NOW).set();
+    let config = DAOConfig { allow_proposal_update_after_votes: allow_after_votes, ..DAOConfig::default() };
+    let mut dao = dao().private().config(config).members(&["alice"]).build();
+    dao.create_proposal(
+        "Fund the meetup".to_string(),
+        "Venue and snaks".to_string(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        None,
+        None,
+    );
+
+    caller("alice").deposit(NearToken::from_millinear(2)).timestamp(NOW).set();
+    for _ in 0..votes {
+        dao.cast_vote(1, "ab".repeat(113));
+    }
+
+    caller("owner").deposit(NearToken::from_millinear(2)).timestamp(NOW).set();
+    dao
+}
+
+fn description(dao: &PrivateDAO) -> String {
+    dao.get_proposal(1).unwrap().description
+}
+
+#[test]
+fn update_without_votes() {
+    let mut dao = dao_with_editable_proposal(false, 0);
+
+    dao.update_proposal_description(1, "Venue and snacks".to_string());
+    assert_eq!(description(&dao), "Venue and snacks");
+}
+
+#[test]
+fn update_after_vote_rejected_by_default() {
+    let mut dao = dao_with_editable_proposal(false, 1);
+
+    let err = catch_unwind(AssertUnwindSafe(|| {
+        dao.update_proposal_description(1, "Venue and snacks".to_string())
+    }))
+    .expect_err("update after a vote must panic");
+    let message = err
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| err.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_default();
+    assert!(message.contains("Cannot update a proposal after votes were cast"), "{}", message);
+    assert_eq!(description(&dao), "Venue and snaks");
+}
+
+#[test]
+fn update_after_vote_allowed_with_notice() {
+    let mut dao = dao_with_editable_proposal(true, 1);
+
+    dao.update_proposal_description(1, "Venue and snacks".to_string());
+    assert_eq!(
+        description(&dao),
+        format!("Venue and snacks\n\n[EDITED after vote #1 by owner.testnet at {}]", NOW)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Only the proposal creator can update it")]
+fn only_creator_can_update() {
+    let mut dao = dao_with_editable_proposal(false, 0);
+
+    caller("alice").deposit(NearToken::from_millinear(2)).timestamp(NOW).set();
+    dao.update_proposal_description(1, "Hijacked".to_string());
+}
+
+#[test]
+fn amend_title_and_description() {
+    let mut dao = dao_with_editable_proposal(false, 0);
+
+    dao.amend_proposal(1, Some("Fund the spring meetup".to_string()), None);
+
+    let proposal = dao.get_proposal(1).unwrap();
+    assert_eq!(proposal.title, "Fund the spring meetup");
+    assert_eq!(proposal.description, "Venue and snaks");
+    assert_eq!(proposal.amended_at, Some(NOW));
+    let event = near_sdk::test_utils::get_logs().pop().unwrap();
+    assert!(event.starts_with("EVENT_JSON:") && event.contains("\"event\":\"proposal_amended\""));
+}
+
+#[test]
+#[should_panic(expected = "Cannot amend a proposal after votes were cast")]
+fn amend_rejected_after_votes() {
+    // Even where description updates after votes are allowed
+    let mut dao = dao_with_editable_proposal(true, 1);
+    dao.amend_proposal(1, None, Some("Venue and snacks".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Only the proposal creator can amend it")]
+fn only_creator_amends() {
+    let mut dao = dao_with_editable_proposal(false, 0);
+    caller("alice").deposit(NearToken::from_millinear(2)).timestamp(NOW).set();
+    dao.amend_proposal(1, None, Some("Venue and snacks".to_string()));
+}
@@ -0,0 +1,91 @@
+//! `get_proposals_by_status` reads per-status ID indices
+
+mod common;
+
+use common::{caller, dao, outlayer_success};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+fn on_votes_tallied(dao: &mut PrivateDAO, proposal_id: u64, yes: u64, no: u64) {
+    caller("dao").deposit(NearToken::from_millinear(2)).set();
+    dao.on_votes_tallied(
+        proposal_id,
+        None,
+        outlayer_success(serde_json::json!({
+            "proposal_id": proposal_id,
+            "yes_count": yes,
+            "no_count": no,
+            "total_votes": yes + no,
+            "tee_attestation": "",
+            "votes_merkle_root": "",
+            "merkle_proofs": [],
+            "merkle_proofs_available": false,
+        })),
+    );
+    caller("owner").deposit(NearToken::from_millinear(2)).set();
+}
+
+fn ids(proposals: Vec<Proposal>) -> Vec<u64> {
+    proposals.into_iter().map(|proposal| proposal.id).collect()
+}
+
+/// Proposals 1-4: 1 passed, 2 rejected, 3 cancelled, 4 active
+fn dao_with_proposals() -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(2)).set();
+    let mut dao = dao().private().build();
+    for title in ["First", "Second", "Third", "Fourth"] {
+        dao.create_proposal(title.to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    }
+
+    on_votes_tallied(&mut dao, 1, 2, 1);
+    on_votes_tallied(&mut dao, 2, 0, 1);
+    dao.cancel_proposal(3);
+    dao
+}
+
+#[test]
+fn proposals_listed_by_status() {
+    let dao = dao_with_proposals();
+
+    assert_eq!(ids(dao.get_proposals_by_status(ProposalStatus::Active, 0, 10)), vec![4]);
+    assert_eq!(ids(dao.get_proposals_by_status(ProposalStatus::Passed, 0, 10)), vec![1]);
+    assert_eq!(ids(dao.get_proposals_by_status(ProposalStatus::Rejected, 0, 10)), vec![2]);
+    assert_eq!(ids(dao.get_proposals_by_status(ProposalStatus::Cancelled, 0, 10)), vec![3]);
+}
+
+#[test]
+fn executed_proposal_leaves_passed_index() {
+    let mut dao = dao_with_proposals();
+    dao.create_executable_proposal(
+        "Pay".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        serde_json::json!({ "receiver_id": "grants.testnet", "method_name": "pay", "args": "{}", "deposit": "0" })
+            .to_string(),
+        None,
+    );
+    on_votes_tallied(&mut dao, 5, 1, 0);
+    assert_eq!(ids(dao.get_proposals_by_status(ProposalStatus::Passed, 0, 10)), vec![1, 5]);
+
+    // Once `DAOConfig::execution_delay_ns` passed
+    caller("anyone").timestamp(DAOConfig::default().execution_delay_ns).set();
+    let _ = dao.execute_proposal(5);
+    assert_eq!(ids(dao.get_proposals_by_status(ProposalStatus::Passed, 0, 10)), vec![1]);
+    assert_eq!(ids(dao.get_proposals_by_status(ProposalStatus::Executed, 0, 10)), vec![5]);
+}
+
+#[test]
+fn pages_capped_at_fifty() {
+    caller("owner").deposit(NearToken::from_millinear(2)).set();
+    let config = DAOConfig { max_active_proposals_per_creator: 100, ..DAOConfig::default() };
+    let mut dao = dao().private().config(config).build();
+    for index in 0..55 {
+        caller("owner").deposit(NearToken::from_millinear(2)).set();
+        dao.create_proposal(format!("Proposal {}", index), String::new(), QuorumType::Unanimous, None, None, None);
+    }
+
+    assert_eq!(dao.get_proposals_by_status(ProposalStatus::Active, 0, 100).len(), 50);
+    assert_eq!(ids(dao.get_proposals_by_status(ProposalStatus::Active, 53, 10)), vec![54, 55]);
+}
@@ -0,0 +1,182 @@
+//! Property-based round-trip tests for contract types
+//!
+//! `Proposal` is stored with Borsh and returned to clients with serde JSON.
+//! Both paths must reproduce the original value exactly.
+
+use near_sdk::borsh::{self, BorshDeserialize};
+use near_sdk::{AccountId, NearToken};
+use private_dao_contract::types::*;
+use proptest::prelude::*;
+
+fn arb_text(max_len: usize) -> impl Strategy<Value = String> {
+    prop::collection::vec(any::<char>(), 0..=max_len).prop_map(String::from_iter)
+}
+
+fn arb_account_id() -> impl Strategy<Value = AccountId> {
+    "[a-z0-9]{2,16}([_-][a-z0-9]{1,8})?(\\.[a-z0-9]{2,12}){0,2}"
+        .prop_map(|s| s.parse().expect("regex only generates valid account IDs"))
+}
+
+fn arb_quorum() -> impl Strategy<Value = QuorumType> {
+    prop_oneof![
+        any::<u64>().prop_map(|min_votes| QuorumType::Absolute { min_votes }),
+        (any::<u64>(), 1..u64::MAX)
+            .prop_map(|(base_votes, decay_halflife_ns)| { QuorumType::TimeWeighted { base_votes, decay_halflife_ns } }),
+        Just(QuorumType::Unanimous),
+        (1..=100u8).prop_map(|min_percent_of_members| QuorumType::Percentage { min_percent_of_members }),
+        (1..=100u8).prop_map(|min_yes_percent| QuorumType::SuperMajority { min_yes_percent }),
+        (1..=100u8).prop_map(|min_yes_percent| QuorumType::Relative { min_yes_percent }),
+    ]
+}
+
+fn arb_status() -> impl Strategy<Value = ProposalStatus> {
+    prop_oneof![
+        Just(ProposalStatus::Active),
+        Just(ProposalStatus::Passed),
+        Just(ProposalStatus::Rejected),
+        arb_text(100).prop_map(|reason| ProposalStatus::Vetoed { reason }),
+        Just(ProposalStatus::Cancelled),
+        Just(ProposalStatus::Executed),
+    ]
+}
+
+fn arb_proposal_type() -> impl Strategy<Value = ProposalType> {
+    prop_oneof![
+        Just(ProposalType::Governance),
+        Just(ProposalType::Funding),
+        Just(ProposalType::Constitution),
+        "[0-9a-f]{64}".prop_map(|wasm_hash| ProposalType::UpgradeContract { wasm_hash }),
+    ]
+}
+
+fn arb_merkle_proof() -> impl Strategy<Value = MerkleProof> {
+    (arb_text(64), any::<u64>(), "[0-9a-f]{64}", prop::collection::vec("[0-9a-f]{64}", 0..16), any::<u64>()).prop_map(
+        |(voter, vote_index, vote_hash, proof_path, timestamp)| MerkleProof {
+            voter,
+            vote_index,
+            vote_hash,
+            proof_path,
+            timestamp,
+        },
+    )
+}
+
+fn arb_tally_result() -> impl Strategy<Value = TallyResult> {
+    (
+        any::<bool>(),
+        any::<Option<u64>>(),
+        any::<Option<u64>>(),
+        any::<Option<u64>>(),
+        prop::collection::hash_map(arb_text(64), any::<u64>(), 0..10),
+        any::<u64>(),
+        arb_text(100),
+        "[0-9a-f]{0,64}",
+        prop::collection::vec(arb_merkle_proof(), 0..8),
+        any::<bool>(),
+        "[0-9a-f]{0,64}",
+    )
+        .prop_map(
+            |(
+                quorum_met,
+                yes_count,
+                no_count,
+                abstain_count,
+                vote_counts,
+                total_votes,
+                tee_attestation,
+                votes_merkle_root,
+                merkle_proofs,
+                merkle_proofs_available,
+                counted_votes_root,
+            )| TallyResult {
+                quorum_met,
+                yes_count,
+                no_count,
+                abstain_count,
+                vote_counts,
+                total_votes,
+                tee_attestation,
+                votes_merkle_root,
+                merkle_proofs,
+                merkle_proofs_available,
+                counted_votes_root,
+            },
+        )
+}
+
+fn arb_proposal() -> impl Strategy<Value = Proposal> {
+    (
+        (
+            any::<u64>(),
+            arb_text(200),
+            arb_text(1000),
+            arb_account_id(),
+            any::<u64>(),
+            any::<u128>().prop_map(NearToken::from_yoctonear),
+            any::<u64>(),
+            prop::option::of(arb_text(200)),
+        ),
+        (
+            any::<Option<u64>>(),
+            arb_quorum(),
+            arb_status(),
+            prop::option::of(arb_tally_result()),
+            arb_proposal_type(),
+            prop::collection::vec(arb_text(32), 0..5),
+            prop::option::of(prop::collection::vec(arb_text(64), 2..10)),
+            any::<Option<u64>>(),
+            any::<Option<u64>>(),
+        ),
+    )
+        .prop_map(
+            |(
+                (id, title, description, creator, created_at, creator_deposit, eligible_voters, execution_payload),
+                (deadline, quorum, status, tally_result, proposal_type, tags, vote_options, amended_at, decided_at),
+            )| Proposal {
+                id,
+                title,
+                description,
+                creator,
+                created_at,
+                deadline,
+                quorum,
+                status,
+                tally_result,
+                proposal_type,
+                tags,
+                creator_deposit,
+                eligible_voters,
+                vote_options,
+                execution_payload,
+                amended_at,
+                decided_at,
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn proposal_borsh_round_trip(proposal in arb_proposal()) {
+        let bytes = borsh::to_vec(&proposal).unwrap();
+        let decoded = Proposal::try_from_slice(&bytes).unwrap();
+        prop_assert_eq!(decoded, proposal);
+    }
+
+    #[test]
+    fn proposal_json_round_trip(proposal in arb_proposal()) {
+        let json = serde_json::to_string(&proposal).unwrap();
+        let decoded: Proposal = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(decoded, proposal);
+    }
+
+    #[test]
+    fn proposal_borsh_then_json_round_trip(proposal in arb_proposal()) {
+        // Value read from storage must serialize to the same JSON as the original
+        let bytes = borsh::to_vec(&proposal).unwrap();
+        let from_storage = Proposal::try_from_slice(&bytes).unwrap();
+        prop_assert_eq!(
+            serde_json::to_value(&from_storage).unwrap(),
+            serde_json::to_value(&proposal).unwrap()
+        );
+    }
+}
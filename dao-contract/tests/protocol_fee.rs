@@ -0,0 +1,131 @@
+//! `DAOConfig::protocol_fee_basis_points` takes a share of deposits for `fee_recipient`
+
+mod common;
+
+use common::{account, caller, dao, transfers};
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::get_created_receipts;
+use near_sdk::{AccountId, NearToken};
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// Public DAO with alice as a keyed member and a 10% fee to `fee_recipient`
+fn dao_with_fee(fee_recipient: Option<AccountId>) -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().build();
+    dao.update_config(DAOConfig {
+        fee_recipient,
+        protocol_fee_basis_points: 1_000,
+        ..DAOConfig::default()
+    });
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+    dao
+}
+
+fn create_proposal(dao: &mut PrivateDAO, deposit: NearToken) {
+    caller("alice").deposit(deposit).set();
+    dao.create_proposal("Fees".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+}
+
+#[test]
+fn no_fee_by_default() {
+    caller("owner").set();
+    let mut dao = dao().build();
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+
+    create_proposal(&mut dao, NearToken::from_millinear(1));
+
+    assert!(transfers().is_empty());
+    assert_eq!(dao.get_protocol_fee_collected(), NearToken::from_yoctonear(0));
+}
+
+#[test]
+fn fee_sent_to_recipient() {
+    let mut dao = dao_with_fee(Some(account("treasury")));
+
+    create_proposal(&mut dao, NearToken::from_millinear(10));
+    assert_eq!(transfers(), vec![(account("treasury"), NearToken::from_millinear(1))]);
+
+    caller("alice").set();
+    dao.cast_vote(1, "ab".repeat(113));
+    assert_eq!(transfers(), vec![(account("treasury"), NearToken::from_millinear(2))]);
+
+    assert_eq!(dao.get_protocol_fee_collected(), NearToken::from_millinear(3));
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_yoctonear(0));
+}
+
+#[test]
+fn fee_to_own_account_funds_treasury() {
+    let mut dao = dao_with_fee(Some(account("dao")));
+
+    create_proposal(&mut dao, NearToken::from_millinear(10));
+
+    assert!(transfers().is_empty());
+    assert_eq!(dao.get_treasury_balance(), NearToken::from_millinear(1));
+    assert_eq!(dao.get_protocol_fee_collected(), NearToken::from_millinear(1));
+}
+
+#[test]
+fn join_forwards_deposit_net_of_fee() {
+    let mut dao = dao_with_fee(Some(account("treasury")));
+
+    caller("bob").set();
+    let _ = dao.join_dao();
+
+    let outlayer_deposit = get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| receipt.actions)
+        .find_map(|action| match action {
+            MockAction::FunctionCallWeight { method_name, attached_deposit, .. }
+                if method_name == b"request_execution" =>
+            {
+                Some(attached_deposit)
+            }
+            _ => None,
+        })
+        .unwrap();
+    // 20 - 2 (fee) - 2 (storage kept by the contract)
+    assert_eq!(outlayer_deposit, NearToken::from_millinear(16));
+    assert_eq!(dao.get_incomplete_joins(0)[0].deposit_paid, NearToken::from_millinear(2));
+}
+
+#[test]
+#[should_panic(expected = "Minimum deposit is")]
+fn deposit_must_cover_minimum_after_fee() {
+    let mut dao = dao_with_fee(Some(account("treasury")));
+    create_proposal(&mut dao, NearToken::from_millinear(1));
+
+    caller("alice").deposit(DAOConfig::default().min_vote_deposit).set();
+    dao.cast_vote(1, "ab".repeat(113));
+}
+
+#[test]
+fn min_costs_include_fee() {
+    let mut dao = dao_with_fee(Some(account("treasury")));
+    create_proposal(&mut dao, NearToken::from_millinear(2));
+
+    let net_vote_cost = DAOConfig::default().min_vote_deposit.as_yoctonear();
+    let vote_cost = dao.get_min_vote_cost();
+    assert_eq!(vote_cost.as_yoctonear(), (net_vote_cost * 10).div_ceil(9));
+
+    // The advertised minimum is accepted
+    caller("alice").deposit(vote_cost).set();
+    dao.cast_vote(1, "ab".repeat(113));
+
+    caller("bob").deposit(dao.get_min_join_cost()).set();
+    let _ = dao.join_dao();
+}
+
+#[test]
+fn min_costs_without_fee_recipient_are_net() {
+    let dao = dao_with_fee(None);
+    assert_eq!(dao.get_min_vote_cost(), DAOConfig::default().min_vote_deposit);
+}
+
+#[test]
+#[should_panic(expected = "protocol_fee_basis_points must be at most 1000")]
+fn fee_above_ten_percent_rejected() {
+    caller("owner").set();
+    let mut dao = dao().build();
+    dao.update_config(DAOConfig { protocol_fee_basis_points: 1_001, ..DAOConfig::default() });
+}
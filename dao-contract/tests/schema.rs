@@ -0,0 +1,36 @@
+//! JSON schemas must generate and carry field descriptions for frontends
+
+use private_dao_contract::types::*;
+use schemars::schema_for;
+use serde_json::Value;
+
+fn field(schema: &Value, name: &str) -> Value {
+    schema["properties"][name].clone()
+}
+
+#[test]
+fn proposal_schema_has_field_descriptions() {
+    let schema = serde_json::to_value(schema_for!(Proposal)).unwrap();
+
+    for name in [
+        "id", "title", "description", "creator", "created_at", "deadline", "quorum", "status",
+        "tally_result", "proposal_type", "tags",
+    ] {
+        let description = field(&schema, name)["description"].clone();
+        assert!(description.is_string(), "Proposal.{} has no description", name);
+    }
+    assert!(field(&schema, "deadline")["description"]
+        .as_str()
+        .unwrap()
+        .contains("nanoseconds"));
+}
+
+#[test]
+fn critical_fields_have_examples() {
+    let vote = serde_json::to_value(schema_for!(Vote)).unwrap();
+    assert!(field(&vote, "encrypted_vote")["examples"].is_array());
+
+    let tally = serde_json::to_value(schema_for!(TallyResult)).unwrap();
+    let examples = &field(&tally, "tee_attestation")["examples"];
+    assert!(examples[0].as_str().unwrap().starts_with("mvp-attestation:"));
+}
@@ -0,0 +1,74 @@
+//! `secrets_ref` picks the keymaster secret OutLayer injects, set at init and by the owner
+
+mod common;
+
+use common::{caller, dao, new_dao};
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::get_created_receipts;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// `secrets_ref` argument of the `request_execution` call made by `join_dao`
+fn join_secrets_ref(dao: &mut PrivateDAO, user: &str) -> serde_json::Value {
+    caller(user).set();
+    let _ = dao.join_dao();
+
+    get_created_receipts()
+        .into_iter()
+        .flat_map(|receipt| receipt.actions)
+        .find_map(|action| match action {
+            MockAction::FunctionCallWeight { method_name, args, .. } if method_name == b"request_execution" => {
+                let args: serde_json::Value = serde_json::from_slice(&args).unwrap();
+                Some(args["secrets_ref"].clone())
+            }
+            _ => None,
+        })
+        .expect("request_execution call")
+}
+
+#[test]
+fn defaults_to_shared_keymaster() {
+    let mut dao = new_dao();
+
+    let default = serde_json::json!({ "profile": "default", "account_id": "zavodil2.testnet" });
+    assert_eq!(dao.get_secrets_ref(), default);
+    assert_eq!(join_secrets_ref(&mut dao, "alice"), default);
+}
+
+#[test]
+fn init_and_owner_update_are_sent_to_outlayer() {
+    let own = serde_json::json!({ "profile": "production", "account_id": "keys.mydao.near" });
+    caller("owner").set();
+    let mut dao = dao().secrets_ref(own.clone()).build();
+    assert_eq!(join_secrets_ref(&mut dao, "alice"), own);
+
+    let rotated = serde_json::json!({ "profile": "rotated", "account_id": "keys.mydao.near" });
+    caller("owner").set();
+    dao.update_secrets_ref(rotated.clone());
+    assert_eq!(dao.get_secrets_ref(), rotated);
+    assert_eq!(join_secrets_ref(&mut dao, "bob"), rotated);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can call this method")]
+fn only_owner_can_update() {
+    let mut dao = new_dao();
+    caller("alice").set();
+    dao.update_secrets_ref(serde_json::json!({ "profile": "default", "account_id": "alice.testnet" }));
+}
+
+#[test]
+#[should_panic(expected = "Cannot change secrets_ref while proposals are active")]
+fn update_rejected_while_proposals_active() {
+    let mut dao = new_dao();
+    dao.create_proposal("Budget".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+
+    dao.update_secrets_ref(serde_json::json!({ "profile": "rotated", "account_id": "keys.mydao.near" }));
+}
+
+#[test]
+#[should_panic(expected = "secrets_ref must have non-empty \"profile\" and \"account_id\" strings")]
+fn malformed_ref_rejected() {
+    caller("owner").set();
+    dao().secrets_ref(serde_json::json!({ "profile": "default" })).build();
+}
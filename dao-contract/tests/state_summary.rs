@@ -0,0 +1,59 @@
+//! Diagnostic views: state counters and consistency checks
+
+mod common;
+
+use common::{account, caller, dao};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// Two proposals by alice, one ballot on the first
+fn dao_with_proposals() -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(2)).balance(NearToken::from_near(10)).set();
+    let mut dao = dao().private().build();
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+
+    caller("alice").deposit(NearToken::from_millinear(2)).balance(NearToken::from_near(10)).set();
+    for title in ["First", "Second"] {
+        dao.create_proposal(title.to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+    }
+    dao.cast_vote(1, "ab".repeat(113));
+
+    dao
+}
+
+#[test]
+fn summary_counts_state() {
+    let dao = dao_with_proposals();
+
+    assert_eq!(
+        dao.get_state_summary(),
+        StateSummary {
+            owner: account("owner"),
+            member_count: 2,
+            proposal_count: 2,
+            vote_vector_count: 2,
+            next_proposal_id: 3,
+            membership_mode: MembershipMode::Private,
+            contract_version: dao.get_version(),
+            is_paused: false,
+        }
+    );
+    assert!(dao.verify_state_consistency().is_empty());
+}
+
+#[test]
+fn inconsistencies_are_reported() {
+    let mut dao = dao_with_proposals();
+    dao.next_proposal_id = 1;
+    dao.active_proposal_counts.insert(&account("alice"), &5);
+    dao.treasury_balance = NearToken::from_near(11).as_yoctonear();
+
+    let issues = dao.verify_state_consistency();
+
+    assert_eq!(issues.len(), 5, "{:?}", issues);
+    assert!(issues[0].starts_with("next_proposal_id 1 is below the proposal count 2"));
+    assert!(issues.contains(&"Proposal 2 is not below next_proposal_id 1".to_string()));
+    assert!(issues.contains(&"active_proposal_counts for alice.testnet is 5 but 2 proposals are active".to_string()));
+    assert!(issues.last().unwrap().starts_with("treasury_balance"));
+}
@@ -0,0 +1,54 @@
+//! Storage deposit estimates for proposals and votes
+
+mod common;
+
+use common::new_dao;
+use near_sdk::{env, NearToken};
+
+/// Bytes paid for by `deposit`, without the 20% margin
+fn bytes(deposit: NearToken) -> u128 {
+    deposit.as_yoctonear() * 100 / 120 / env::storage_byte_cost().as_yoctonear()
+}
+
+#[test]
+fn proposal_estimate_grows_with_text() {
+    let dao = new_dao();
+
+    let short = dao.estimate_proposal_storage_deposit("Title".to_string(), String::new(), None);
+    let long = dao.estimate_proposal_storage_deposit("Title".to_string(), "x".repeat(1000), None);
+    let tagged = dao.estimate_proposal_storage_deposit(
+        "Title".to_string(),
+        String::new(),
+        Some(vec!["budget".to_string()]),
+    );
+
+    assert_eq!(bytes(long) - bytes(short), 1000);
+    assert_eq!(bytes(tagged) - bytes(short), 4 + 6);
+}
+
+#[test]
+fn vote_estimate_covers_ciphertext() {
+    let dao = new_dao();
+
+    // ECIES ciphertext of "yes": 113 bytes = 226 hex characters
+    let estimate = dao.estimate_vote_storage_deposit(226);
+
+    assert!(bytes(estimate) > 226 + 64);
+    assert!(estimate > dao.estimate_vote_storage_deposit(0));
+}
+
+#[test]
+fn vote_estimate_adds_ciphertext_bytes() {
+    let dao = new_dao();
+
+    let empty = dao.estimate_vote_storage_deposit(0);
+    let estimate = dao.estimate_vote_storage_deposit(1_000_000);
+
+    assert_eq!(bytes(estimate) - bytes(empty), 1_000_000);
+}
+
+#[test]
+#[should_panic(expected = "encrypted_vote_len is too large")]
+fn vote_estimate_rejects_oversized_ciphertext() {
+    new_dao().estimate_vote_storage_deposit(u64::MAX);
+}
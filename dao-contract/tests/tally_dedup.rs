@@ -0,0 +1,145 @@
+//! Only one OutLayer tally per proposal and one key derivation per account at a time
+
+mod common;
+
+use common::{account, caller, dao, yes_no_tally};
+use near_sdk::PromiseError;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// Shortest allowed voting period (1 day)
+const DEADLINE: u64 = 86_400_000_000_000;
+
+fn voted_dao() -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().build();
+    dao.migrate_add_member(account("alice"), Some("02abcd".to_string()));
+
+    caller("alice").set();
+    dao.create_proposal(
+        "Dedup".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 5 },
+        Some(DEADLINE),
+        None,
+        None,
+    );
+    dao.cast_vote(1, "ab".repeat(113));
+    dao
+}
+
+#[test]
+#[should_panic(expected = "Tally already in progress")]
+fn second_finalize_before_callback_is_rejected() {
+    let mut dao = voted_dao();
+    let _ = dao.finalize_proposal(1);
+
+    caller("bob").set();
+    let _ = dao.finalize_proposal(1);
+}
+
+#[test]
+#[should_panic(expected = "Tally already in progress")]
+fn chunk_rejected_while_tally_in_flight() {
+    let mut dao = voted_dao();
+    caller("alice").timestamp(DEADLINE).set();
+    let _ = dao.finalize_proposal(1);
+
+    let _ = dao.finalize_proposal_chunk(1, 0, 10);
+}
+
+#[test]
+fn finalize_again_after_callback() {
+    let mut dao = voted_dao();
+    let _ = dao.finalize_proposal(1);
+    assert!(dao.tallies_in_flight.contains_key(&1));
+
+    // A failed tally leaves the proposal active and finalizable
+    caller("dao").set();
+    dao.on_votes_tallied(1, None, Err(PromiseError::Failed));
+    assert!(!dao.tallies_in_flight.contains_key(&1));
+
+    caller("alice").set();
+    let _ = dao.finalize_proposal(1);
+    assert!(dao.tallies_in_flight.contains_key(&1));
+}
+
+#[test]
+fn rotate_key_again_after_callback() {
+    let mut dao = voted_dao();
+    let _ = dao.rotate_key();
+    assert!(dao.key_derivations_in_flight.contains_key(&account("alice")));
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _ = dao.rotate_key();
+    }));
+    let message = result.expect_err("second rotation must panic").downcast_ref::<String>().cloned().unwrap_or_default();
+    assert!(message.contains("Key derivation already in progress"), "unexpected message: {}", message);
+
+    caller("dao").set();
+    dao.on_key_derived(account("alice"), None, Ok(None));
+    assert!(!dao.key_derivations_in_flight.contains_key(&account("alice")));
+
+    caller("alice").set();
+    let _ = dao.rotate_key();
+}
+
+#[test]
+fn admin_refund_clears_key_derivation() {
+    let mut dao = voted_dao();
+    caller("bob").set();
+    let _ = dao.join_dao();
+
+    caller("owner").set();
+    dao.admin_refund_incomplete_join(account("bob"));
+    assert!(!dao.key_derivations_in_flight.contains_key(&account("bob")));
+}
+
+/// `IN_FLIGHT_TIMEOUT_NS`: after this long a lost callback no longer blocks retries
+const IN_FLIGHT_TIMEOUT: u64 = 3_600_000_000_000;
+
+#[test]
+fn finalize_retry_allowed_once_callback_is_overdue() {
+    let mut dao = voted_dao();
+    caller("alice").timestamp(1_000).set();
+    let _ = dao.finalize_proposal(1);
+
+    // The callback never ran
+    caller("bob").timestamp(1_000 + IN_FLIGHT_TIMEOUT).set();
+    let _ = dao.finalize_proposal(1);
+    assert_eq!(dao.tallies_in_flight.get(&1), Some(1_000 + IN_FLIGHT_TIMEOUT));
+}
+
+#[test]
+#[should_panic(expected = "Key derivation already in progress")]
+fn key_derivation_blocks_retry_until_timeout() {
+    let mut dao = voted_dao();
+    let _ = dao.rotate_key();
+
+    caller("alice").timestamp(IN_FLIGHT_TIMEOUT - 1).set();
+    let _ = dao.rotate_key();
+}
+
+#[test]
+fn key_derivation_retry_allowed_once_callback_is_overdue() {
+    let mut dao = voted_dao();
+    let _ = dao.rotate_key();
+
+    caller("alice").timestamp(IN_FLIGHT_TIMEOUT).set();
+    let _ = dao.rotate_key();
+    assert_eq!(dao.key_derivations_in_flight.get(&account("alice")), Some(IN_FLIGHT_TIMEOUT));
+}
+
+#[test]
+fn late_tally_does_not_replace_decision() {
+    let mut dao = voted_dao();
+    caller("dao").set();
+    dao.on_votes_tallied(1, None, yes_no_tally(1, 1, 0));
+    assert_eq!(dao.get_proposal_status(1), Some(ProposalStatus::Passed));
+
+    // Result of a retry sent once the first callback was overdue
+    dao.on_votes_tallied(1, None, yes_no_tally(1, 0, 1));
+    assert_eq!(dao.get_proposal_status(1), Some(ProposalStatus::Passed));
+    assert_eq!(dao.get_proposal(1).unwrap().tally_result.unwrap().yes_count, Some(1));
+    assert!(dao.tallies_in_flight.get(&1).is_none());
+}
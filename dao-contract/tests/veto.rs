@@ -0,0 +1,135 @@
+//! Admin veto of passed proposals
+
+mod common;
+
+use common::{account, caller, dao, proposal};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+const PROPOSAL_ID: u64 = 1;
+
+/// DAO with admins `alice` and `bob` and one passed proposal
+fn dao_with_passed_proposal(veto_threshold: u64) -> PrivateDAO {
+    caller("owner").set();
+    let mut dao = dao().private().build();
+
+    for admin in ["alice", "bob"] {
+        dao.migrate_add_member(account(admin), None);
+        dao.add_admin(account(admin));
+    }
+    dao.update_config(DAOConfig { veto_threshold, ..DAOConfig::default() });
+
+    dao.proposals.insert(
+        &PROPOSAL_ID,
+        &Proposal {
+            title: "Spend everything".to_string(),
+            status: ProposalStatus::Passed,
+            proposal_type: ProposalType::Funding,
+            ..proposal(PROPOSAL_ID)
+        },
+    );
+    dao.passed_proposal_ids.insert(&PROPOSAL_ID);
+
+    dao
+}
+
+fn status(dao: &PrivateDAO) -> ProposalStatus {
+    dao.get_proposal_status(PROPOSAL_ID).unwrap()
+}
+
+#[test]
+fn single_admin_veto() {
+    let mut dao = dao_with_passed_proposal(1);
+
+    caller("alice").set();
+    dao.veto_proposal(PROPOSAL_ID, "flawed".to_string());
+
+    assert_eq!(status(&dao), ProposalStatus::Vetoed { reason: "flawed".to_string() });
+    assert_eq!(dao.get_veto_approvals(PROPOSAL_ID), vec![account("alice")]);
+    assert!(!dao.is_proposal_passed(PROPOSAL_ID));
+}
+
+#[test]
+fn veto_waits_for_threshold() {
+    let mut dao = dao_with_passed_proposal(2);
+
+    caller("alice").set();
+    dao.veto_proposal(PROPOSAL_ID, "flawed".to_string());
+    assert_eq!(status(&dao), ProposalStatus::Passed);
+
+    // Same admin can't approve twice
+    let duplicate = catch_unwind(AssertUnwindSafe(|| {
+        dao.veto_proposal(PROPOSAL_ID, "flawed".to_string())
+    }));
+    assert!(duplicate.is_err());
+
+    caller("bob").set();
+    dao.veto_proposal(PROPOSAL_ID, "confirmed".to_string());
+    assert_eq!(status(&dao), ProposalStatus::Vetoed { reason: "confirmed".to_string() });
+
+    // Vetoed is final
+    caller("owner").set();
+    let again = catch_unwind(AssertUnwindSafe(|| {
+        dao.veto_proposal(PROPOSAL_ID, "again".to_string())
+    }));
+    assert!(again.is_err());
+}
+
+#[test]
+fn non_admin_cannot_veto() {
+    let mut dao = dao_with_passed_proposal(1);
+
+    caller("mallory").set();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        dao.veto_proposal(PROPOSAL_ID, "nope".to_string())
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(status(&dao), ProposalStatus::Passed);
+}
+
+#[test]
+fn removed_admin_approval_no_longer_counts() {
+    let mut dao = dao_with_passed_proposal(2);
+
+    caller("alice").set();
+    dao.veto_proposal(PROPOSAL_ID, "flawed".to_string());
+
+    caller("owner").set();
+    dao.remove_admin(account("alice"));
+    assert!(dao.get_veto_approvals(PROPOSAL_ID).is_empty());
+
+    // bob's approval alone doesn't reach the threshold
+    caller("bob").set();
+    dao.veto_proposal(PROPOSAL_ID, "confirmed".to_string());
+    assert_eq!(status(&dao), ProposalStatus::Passed);
+    assert_eq!(dao.get_veto_approvals(PROPOSAL_ID), vec![account("bob")]);
+}
+
+#[test]
+fn leaving_member_loses_admin_role_and_approvals() {
+    let mut dao = dao_with_passed_proposal(2);
+
+    caller("alice").set();
+    dao.veto_proposal(PROPOSAL_ID, "flawed".to_string());
+    dao.leave_dao();
+
+    assert!(dao.get_veto_approvals(PROPOSAL_ID).is_empty());
+    let veto = catch_unwind(AssertUnwindSafe(|| dao.veto_proposal(PROPOSAL_ID, "flawed".to_string())));
+    assert!(veto.is_err());
+}
+
+#[test]
+fn removed_member_loses_admin_role() {
+    let mut dao = dao_with_passed_proposal(1);
+
+    caller("owner").set();
+    dao.remove_member(account("bob"));
+
+    caller("bob").set();
+    let veto = catch_unwind(AssertUnwindSafe(|| dao.veto_proposal(PROPOSAL_ID, "flawed".to_string())));
+    assert!(veto.is_err());
+    assert_eq!(status(&dao), ProposalStatus::Passed);
+}
@@ -0,0 +1,142 @@
+//! `verify_vote_inclusion` recomputes the leaf from the stored vote before walking the proof
+
+mod common;
+
+use common::{caller, dao, proposal};
+use near_sdk::collections::Vector;
+use near_sdk::env;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+const PROPOSAL_ID: u64 = 1;
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(env::sha256(data))
+}
+
+fn leaf_hash(vote: &Vote) -> String {
+    sha256_hex(&[vote.user.as_bytes(), &vote.timestamp.to_le_bytes(), vote.encrypted_vote.as_bytes()].concat())
+}
+
+fn parent(left: &str, right: &str) -> String {
+    sha256_hex(&[left.as_bytes(), right.as_bytes()].concat())
+}
+
+/// Three stored votes tallied into the same tree shape the WASI worker builds
+/// (odd nodes are paired with themselves)
+fn tallied_dao() -> (PrivateDAO, Vec<MerkleProof>) {
+    caller("owner").set();
+    let mut dao = dao().build();
+
+    let votes: Vec<Vote> = ["alice", "bob", "carol"]
+        .iter()
+        .enumerate()
+        .map(|(index, name)| Vote {
+            user: format!("{}.testnet", name).parse().unwrap(),
+            encrypted_vote: format!("04{:02x}", index),
+            timestamp: (index as u64 + 1) * 1000,
+            block_height: index as u64,
+        })
+        .collect();
+    let mut stored = Vector::new(b"v1".to_vec());
+    for vote in &votes {
+        stored.push(vote);
+    }
+    dao.votes.insert(&PROPOSAL_ID, &stored);
+
+    let leaves: Vec<String> = votes.iter().map(leaf_hash).collect();
+    let left = parent(&leaves[0], &leaves[1]);
+    let right = parent(&leaves[2], &leaves[2]);
+    let root = parent(&left, &right);
+    let paths = [
+        vec![leaves[1].clone(), right.clone()],
+        vec![leaves[0].clone(), right.clone()],
+        vec![leaves[2].clone(), left.clone()],
+    ];
+    let proofs: Vec<MerkleProof> = votes
+        .iter()
+        .zip(paths)
+        .enumerate()
+        .map(|(index, (vote, proof_path))| MerkleProof {
+            voter: vote.user.to_string(),
+            vote_index: index as u64,
+            vote_hash: leaves[index].clone(),
+            proof_path,
+            timestamp: vote.timestamp,
+        })
+        .collect();
+
+    dao.proposals.insert(
+        &PROPOSAL_ID,
+        &Proposal {
+            quorum: QuorumType::Absolute { min_votes: 0 },
+            status: ProposalStatus::Passed,
+            tally_result: Some(TallyResult {
+                quorum_met: true,
+                yes_count: Some(3),
+                no_count: Some(0),
+                abstain_count: None,
+                vote_counts: Default::default(),
+                total_votes: 3,
+                tee_attestation: String::new(),
+                votes_merkle_root: root,
+                merkle_proofs: Vec::new(),
+                merkle_proofs_available: false,
+                counted_votes_root: String::new(),
+            }),
+            ..proposal(PROPOSAL_ID)
+        },
+    );
+
+    (dao, proofs)
+}
+
+#[test]
+fn stored_votes_verify() {
+    let (dao, proofs) = tallied_dao();
+
+    for proof in proofs {
+        let voter = proof.voter.clone();
+        assert!(dao.verify_vote_inclusion(PROPOSAL_ID, proof), "proof for {} rejected", voter);
+    }
+}
+
+#[test]
+fn malformed_proofs_rejected() {
+    let (dao, proofs) = tallied_dao();
+    let proof = proofs[0].clone();
+
+    // Claims another voter's leaf
+    let mut wrong_voter = proof.clone();
+    wrong_voter.voter = "bob.testnet".to_string();
+    assert!(!dao.verify_vote_inclusion(PROPOSAL_ID, wrong_voter));
+
+    // Leaf hash that doesn't match the stored ciphertext
+    let mut wrong_hash = proof.clone();
+    wrong_hash.vote_hash = proofs[1].vote_hash.clone();
+    assert!(!dao.verify_vote_inclusion(PROPOSAL_ID, wrong_hash));
+
+    // Truncated path
+    let mut truncated = proof.clone();
+    truncated.proof_path.pop();
+    assert!(!dao.verify_vote_inclusion(PROPOSAL_ID, truncated));
+
+    // Index past the last vote
+    let mut out_of_range = proof.clone();
+    out_of_range.vote_index = 7;
+    assert!(!dao.verify_vote_inclusion(PROPOSAL_ID, out_of_range));
+
+    // Unknown proposal
+    assert!(!dao.verify_vote_inclusion(99, proof));
+}
+
+#[test]
+fn untallied_proposal_rejected() {
+    let (mut dao, proofs) = tallied_dao();
+    let mut proposal = dao.proposals.get(&PROPOSAL_ID).unwrap();
+    proposal.tally_result = None;
+    proposal.status = ProposalStatus::Active;
+    dao.proposals.insert(&PROPOSAL_ID, &proposal);
+
+    assert!(!dao.verify_vote_inclusion(PROPOSAL_ID, proofs[0].clone()));
+}
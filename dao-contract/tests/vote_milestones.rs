@@ -0,0 +1,71 @@
+//! NEP-297 `vote_milestone_reached` events from `cast_vote`
+
+mod common;
+
+use common::{caller, dao_with_proposal};
+use near_sdk::test_utils::get_logs;
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// Cast one ballot and return the milestone events it emitted
+fn vote(dao: &mut PrivateDAO) -> Vec<serde_json::Value> {
+    caller("alice").deposit(NearToken::from_millinear(2)).timestamp(7_000).set();
+    dao.cast_vote(1, "ab".repeat(113));
+
+    get_logs()
+        .iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|json| serde_json::from_str::<serde_json::Value>(json).unwrap())
+        .filter(|event| event["event"] == "vote_milestone_reached")
+        .collect()
+}
+
+fn milestones(events: &[serde_json::Value]) -> Vec<u64> {
+    events
+        .iter()
+        .map(|event| event["data"]["milestone_percent"].as_u64().unwrap())
+        .collect()
+}
+
+#[test]
+fn each_milestone_emitted_once() {
+    let mut dao = dao_with_proposal(&["alice"], QuorumType::Absolute { min_votes: 4 });
+
+    let events = vote(&mut dao);
+    assert_eq!(
+        events,
+        vec![serde_json::json!({
+            "standard": "private_dao",
+            "version": "1.0.0",
+            "event": "vote_milestone_reached",
+            "data": {
+                "proposal_id": 1,
+                "milestone_percent": 25,
+                "current_votes": 1,
+                "quorum_min": 4,
+                "timestamp": 7_000,
+            },
+        })]
+    );
+
+    assert_eq!(milestones(&vote(&mut dao)), vec![50]);
+    assert_eq!(milestones(&vote(&mut dao)), vec![75]);
+    assert_eq!(milestones(&vote(&mut dao)), vec![100]);
+    assert!(vote(&mut dao).is_empty());
+}
+
+#[test]
+fn skipped_milestones_emitted_together() {
+    let mut dao = dao_with_proposal(&["alice"], QuorumType::Absolute { min_votes: 2 });
+
+    assert_eq!(milestones(&vote(&mut dao)), vec![25, 50]);
+    assert_eq!(milestones(&vote(&mut dao)), vec![75, 100]);
+}
+
+#[test]
+fn time_weighted_quorum_has_no_milestones() {
+    let mut dao = dao_with_proposal(&["alice"], QuorumType::TimeWeighted { base_votes: 1, decay_halflife_ns: 1_000 });
+
+    assert!(vote(&mut dao).is_empty());
+}
@@ -0,0 +1,60 @@
+//! Vote submission timeline views (timing metadata only, no ciphertexts)
+
+mod common;
+
+use common::{caller, dao};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// Proposal 1 with votes from alice (block 10), bob (block 20) and alice again (block 30)
+fn dao_with_timed_votes() -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(2)).block(1).set();
+    let mut dao = dao().private().members(&["alice", "bob"]).build();
+    dao.create_proposal(
+        "Timeline".to_string(),
+        String::new(),
+        QuorumType::Absolute { min_votes: 1 },
+        None,
+        None,
+        None,
+    );
+
+    for (voter, block) in [("alice", 10), ("bob", 20), ("alice", 30)] {
+        caller(voter).deposit(NearToken::from_millinear(2)).block(block).set();
+        dao.cast_vote(1, "ab".repeat(113));
+    }
+
+    dao
+}
+
+fn voters(entries: &[VoteTimelineEntry]) -> Vec<(u64, String)> {
+    entries.iter().map(|e| (e.vote_index, e.user.to_string())).collect()
+}
+
+#[test]
+fn timerange_is_inclusive() {
+    let dao = dao_with_timed_votes();
+
+    let entries = dao.get_vote_submissions_in_timerange(1, 10_000, 20_000);
+    assert_eq!(
+        voters(&entries),
+        vec![(0, "alice.testnet".to_string()), (1, "bob.testnet".to_string())]
+    );
+    assert_eq!(entries[1].block_height, 20);
+
+    assert!(dao.get_vote_submissions_in_timerange(1, 31_000, 40_000).is_empty());
+    assert!(dao.get_vote_submissions_in_timerange(2, 0, u64::MAX).is_empty());
+}
+
+#[test]
+fn block_height_range() {
+    let dao = dao_with_timed_votes();
+
+    let entries = dao.get_votes_by_block_height_range(1, 15, 30);
+    assert_eq!(
+        voters(&entries),
+        vec![(1, "bob.testnet".to_string()), (2, "alice.testnet".to_string())]
+    );
+    assert_eq!(dao.get_vote_at(1, 2).unwrap().block_height, 30);
+}
@@ -0,0 +1,49 @@
+//! `get_votes_paginated` / `get_vote_at` read votes without loading the whole vector
+
+mod common;
+
+use common::{caller, dao};
+use near_sdk::NearToken;
+use private_dao_contract::types::*;
+use private_dao_contract::PrivateDAO;
+
+/// `count` ballots by alice on proposal 1, numbered by their ciphertext
+fn dao_with_numbered_votes(count: u64) -> PrivateDAO {
+    caller("owner").deposit(NearToken::from_millinear(2)).set();
+    let config = DAOConfig { max_votes_per_proposal: 1_000, ..DAOConfig::default() };
+    let mut dao = dao().private().config(config).members(&["alice"]).build();
+    dao.create_proposal("Paged".to_string(), String::new(), QuorumType::Absolute { min_votes: 1 }, None, None, None);
+
+    for index in 0..count {
+        caller("alice").deposit(NearToken::from_millinear(2)).set();
+        dao.cast_vote(1, format!("{:04x}", index));
+    }
+    dao
+}
+
+fn ballots(votes: Vec<Vote>) -> Vec<String> {
+    votes.into_iter().map(|vote| vote.encrypted_vote).collect()
+}
+
+#[test]
+fn pages_follow_vote_order() {
+    let dao = dao_with_numbered_votes(5);
+
+    assert_eq!(ballots(dao.get_votes_paginated(1, 0, 2)), vec!["0000", "0001"]);
+    assert_eq!(ballots(dao.get_votes_paginated(1, 3, 10)), vec!["0003", "0004"]);
+    assert!(dao.get_votes_paginated(1, 5, 10).is_empty());
+    assert!(dao.get_votes_paginated(1, u64::MAX, u64::MAX).is_empty());
+    assert!(dao.get_votes_paginated(99, 0, 10).is_empty());
+
+    assert_eq!(dao.get_vote_at(1, 4).unwrap().encrypted_vote, "0004");
+    assert!(dao.get_vote_at(1, 5).is_none());
+    assert!(dao.get_vote_at(99, 0).is_none());
+}
+
+#[test]
+fn limit_capped_at_200() {
+    let dao = dao_with_numbered_votes(205);
+
+    assert_eq!(dao.get_votes_paginated(1, 0, 1_000).len(), 200);
+    assert_eq!(dao.get_votes_paginated(1, 200, 1_000).len(), 5);
+}
@@ -0,0 +1,21 @@
+#!/bin/bash
+set -e
+
+echo "Building Private DAO factory..."
+
+# Build NEAR smart contract (on-chain)
+# Use cargo near, same as dao-contract
+cargo near build non-reproducible-wasm
+
+# Create res directory if it doesn't exist
+mkdir -p res
+
+# Copy WASM binary
+cp target/wasm32-unknown-unknown/release/private_dao_factory.wasm res/
+
+# Get file size
+SIZE=$(ls -lh res/private_dao_factory.wasm | awk '{print $5}')
+
+echo "✅ Build complete!"
+echo "📦 WASM: res/private_dao_factory.wasm"
+echo "📏 Size: $SIZE"
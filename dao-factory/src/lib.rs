@@ -0,0 +1,268 @@
+/*!
+ * Private DAO Factory Contract
+ *
+ * Deploys new PrivateDAO instances as sub-accounts of the factory:
+ * - Stores the PrivateDAO WASM (uploaded by the factory owner)
+ * - `create_dao` creates `<subaccount>.<factory>`, deploys the code and calls `new`
+ * - Keeps a registry of every DAO it deployed
+ */
+
+pub mod types;
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap};
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8};
+use near_sdk::{
+    env, ext_contract, log, near_bindgen, AccountId, BorshStorageKey, Gas, NearToken,
+    PanicOnDefault, Promise, PromiseError,
+};
+
+type Balance = u128;
+
+use types::*;
+
+/// Gas for the DAO's `new` call
+const DAO_INIT_GAS: Gas = Gas::from_tgas(30);
+
+/// Gas for callback
+const CALLBACK_GAS: Gas = Gas::from_tgas(10);
+
+/// Extra storage (bytes) reserved for the DAO's own state on top of its code
+const DAO_STATE_STORAGE_BYTES: u128 = 10_000;
+
+/// Maximum page size for `get_daos` and `get_daos_by_owner`
+const MAX_DAOS_PAGE_SIZE: u64 = 100;
+
+/// External contract interface for self callbacks
+#[ext_contract(ext_self)]
+#[allow(dead_code)]
+trait ExtSelf {
+    fn on_dao_created(
+        &mut self,
+        record: DAORecord,
+        attached_deposit: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool;
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct DAOFactory {
+    /// Factory owner (can update the DAO code)
+    pub owner: AccountId,
+
+    /// PrivateDAO WASM deployed by `create_dao`
+    ///
+    /// Kept under its own key so calls that don't deploy don't load it with the root state.
+    pub code: LazyOption<Vec<u8>>,
+
+    /// Deployed DAOs (dao account → DAORecord)
+    pub deployed_daos: UnorderedMap<AccountId, DAORecord>,
+
+    /// Deployed DAOs per owner, in creation order (owner → dao accounts)
+    pub daos_by_owner: LookupMap<AccountId, Vec<AccountId>>,
+}
+
+#[derive(BorshSerialize, BorshStorageKey)]
+#[borsh(crate = "near_sdk::borsh")]
+enum StorageKey {
+    DeployedDaos,
+    DaosByOwner,
+    Code,
+}
+
+#[near_bindgen]
+impl DAOFactory {
+    /// Initialize the factory
+    ///
+    /// # Arguments
+    /// * `owner` - Factory owner/admin account
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        assert!(!env::state_exists(), "Already initialized");
+
+        Self {
+            owner,
+            code: LazyOption::new(StorageKey::Code, None),
+            deployed_daos: UnorderedMap::new(StorageKey::DeployedDaos),
+            daos_by_owner: LookupMap::new(StorageKey::DaosByOwner),
+        }
+    }
+
+    /// Upload the PrivateDAO WASM (owner-only)
+    ///
+    /// Only affects DAOs created afterwards.
+    pub fn set_code(&mut self, code: Base64VecU8) {
+        self.assert_owner();
+
+        let code: Vec<u8> = code.into();
+        assert!(!code.is_empty(), "Code cannot be empty");
+
+        log!("DAO code updated ({} bytes)", code.len());
+        self.code.set(&code);
+    }
+
+    /// Create a new DAO at `<subaccount>.<factory>`
+    ///
+    /// # Payment
+    /// The attached deposit becomes the DAO account's balance and must cover
+    /// storage for the contract code plus its initial state.
+    /// Refunded if deployment fails.
+    ///
+    /// # Arguments
+    /// * `name` - DAO name
+    /// * `membership_mode` - Public or Private membership
+    /// * `owner` - DAO owner/admin account
+    /// * `subaccount` - Sub-account prefix of the DAO (defaults to `name`)
+    /// * `options` - Config, OutLayer contract, member cap and secrets reference
+    ///   passed to `PrivateDAO::new` (omitted ones use the DAO's defaults)
+    #[payable]
+    pub fn create_dao(
+        &mut self,
+        name: String,
+        membership_mode: MembershipMode,
+        owner: AccountId,
+        subaccount: Option<String>,
+        options: Option<DAOInitOptions>,
+    ) -> Promise {
+        let code = self.code.get().unwrap_or_else(|| env::panic_str("DAO code not uploaded"));
+
+        let subaccount = subaccount.unwrap_or_else(|| name.clone());
+        assert!(!subaccount.contains('.'), "Sub-account prefix cannot contain '.'");
+        let dao_account: AccountId = format!("{}.{}", subaccount, env::current_account_id())
+            .parse()
+            .unwrap_or_else(|_| env::panic_str("Invalid DAO sub-account: must be a valid account ID prefix"));
+
+        if self.deployed_daos.get(&dao_account).is_some() {
+            env::panic_str("DAO already exists");
+        }
+
+        let attached = env::attached_deposit();
+        let required_deposit: Balance = env::storage_byte_cost()
+            .as_yoctonear()
+            .saturating_mul(code.len() as u128 + DAO_STATE_STORAGE_BYTES);
+        assert!(
+            attached.as_yoctonear() >= required_deposit,
+            "Minimum deposit is {} yoctoNEAR (contract code + state storage)",
+            required_deposit
+        );
+
+        let options = options.unwrap_or_default();
+        let init_args = serde_json::json!({
+            "name": name,
+            "membership_mode": membership_mode,
+            "owner": owner,
+            "config": options.config,
+            "outlayer_contract": options.outlayer_contract,
+            "max_members": options.max_members,
+            "secrets_ref": options.secrets_ref,
+        });
+
+        let record = DAORecord {
+            account_id: dao_account.clone(),
+            name,
+            owner,
+            membership_mode,
+            created_by: env::predecessor_account_id(),
+            created_at: env::block_timestamp(),
+        };
+
+        log!("Creating DAO {}", dao_account);
+
+        Promise::new(dao_account)
+            .create_account()
+            .transfer(attached)
+            .deploy_contract(code)
+            .function_call(
+                "new".to_string(),
+                serde_json::to_vec(&init_args).unwrap(),
+                NearToken::from_yoctonear(0),
+                DAO_INIT_GAS,
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(CALLBACK_GAS)
+                    .on_dao_created(record, attached),
+            )
+    }
+
+    /// Callback after DAO deployment
+    ///
+    /// Registers the DAO on success, refunds the creator on failure.
+    #[private]
+    pub fn on_dao_created(
+        &mut self,
+        record: DAORecord,
+        attached_deposit: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool {
+        if result.is_err() {
+            // Failed batch refunds the deposit to the factory; pass it back
+            log!("Failed to create DAO {}, refunding {}", record.account_id, record.created_by);
+            Promise::new(record.created_by).transfer(attached_deposit);
+            return false;
+        }
+
+        log!("DAO {} created for {}", record.account_id, record.owner);
+        self.deployed_daos.insert(&record.account_id, &record);
+        let mut owned = self.daos_by_owner.get(&record.owner).unwrap_or_default();
+        owned.push(record.account_id);
+        self.daos_by_owner.insert(&record.owner, &owned);
+
+        true
+    }
+
+    // ========== Internal methods ==========
+
+    /// Assert caller is factory owner
+    fn assert_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner,
+            "Only owner can call this method"
+        );
+    }
+
+    // ========== View methods ==========
+
+    /// List deployed DAOs in creation order, `limit` capped at 100
+    pub fn get_daos(&self, from_index: u64, limit: u64) -> Vec<DAORecord> {
+        self.deployed_daos
+            .values_as_vector()
+            .iter()
+            .skip(from_index as usize)
+            .take(limit.min(MAX_DAOS_PAGE_SIZE) as usize)
+            .collect()
+    }
+
+    /// Account IDs of DAOs owned by `owner` in creation order, `limit` capped at 100
+    pub fn get_daos_by_owner(&self, owner: AccountId, from_index: u64, limit: u64) -> Vec<AccountId> {
+        self.daos_by_owner
+            .get(&owner)
+            .unwrap_or_default()
+            .into_iter()
+            .skip(from_index as usize)
+            .take(limit.min(MAX_DAOS_PAGE_SIZE) as usize)
+            .collect()
+    }
+
+    /// Get a single DAO record
+    pub fn get_dao(&self, account_id: AccountId) -> Option<DAORecord> {
+        self.deployed_daos.get(&account_id)
+    }
+
+    /// Size of the stored DAO code (0 if not uploaded)
+    pub fn get_code_size(&self) -> u64 {
+        self.code.get().map_or(0, |code| code.len() as u64)
+    }
+
+    /// SHA256 of the stored DAO code, as reported for deployed contracts (None if not uploaded)
+    ///
+    /// Lets clients check which PrivateDAO build `create_dao` deploys.
+    pub fn get_code_hash(&self) -> Option<Base58CryptoHash> {
+        let code = self.code.get()?;
+        let hash: [u8; 32] = env::sha256_array(&code);
+        Some(hash.into())
+    }
+}
@@ -0,0 +1,50 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+use schemars::JsonSchema;
+
+/// Membership mode for the DAO
+///
+/// Mirrors `private_dao_contract::types::MembershipMode` (passed to `PrivateDAO::new` as JSON).
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum MembershipMode {
+    /// Anyone can join
+    Public,
+    /// Only invited members can join
+    Private,
+}
+
+/// A DAO deployed by this factory
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct DAORecord {
+    #[schemars(with = "String")]
+    pub account_id: AccountId,
+    pub name: String,
+    #[schemars(with = "String")]
+    pub owner: AccountId,
+    pub membership_mode: MembershipMode,
+    /// Account that paid for the deployment
+    #[schemars(with = "String")]
+    pub created_by: AccountId,
+    /// Block timestamp of the deployment (nanoseconds)
+    pub created_at: u64,
+}
+
+/// Optional `PrivateDAO::new` arguments forwarded by `create_dao` (None = the DAO's default)
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde", default)]
+pub struct DAOInitOptions {
+    /// `DAOConfig` as JSON (validated by the DAO)
+    pub config: Option<serde_json::Value>,
+    /// OutLayer contract, e.g. `outlayer.near` for mainnet DAOs
+    #[schemars(with = "Option<String>")]
+    pub outlayer_contract: Option<AccountId>,
+    /// Member cap
+    pub max_members: Option<u64>,
+    /// Keymaster secrets reference; the shared default is meant for testing only
+    pub secrets_ref: Option<serde_json::Value>,
+}
@@ -0,0 +1,182 @@
+//! `create_dao` targets the requested sub-account; the registry is paginated
+
+use near_sdk::mock::MockAction;
+use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+use near_sdk::{testing_env, AccountId, NearToken};
+use private_dao_factory::types::*;
+use private_dao_factory::DAOFactory;
+
+fn account(name: &str) -> AccountId {
+    format!("{}.testnet", name).parse().unwrap()
+}
+
+fn set_caller(predecessor: AccountId, deposit: NearToken) {
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(account("factory"))
+        .predecessor_account_id(predecessor)
+        .attached_deposit(deposit)
+        .build());
+}
+
+fn factory_with_code() -> DAOFactory {
+    set_caller(account("owner"), NearToken::from_yoctonear(0));
+    let mut factory = DAOFactory::new(account("owner"));
+    factory.set_code(vec![0u8; 100].into());
+    factory
+}
+
+/// Register `subaccount.factory.testnet` as if its deployment succeeded
+fn register(factory: &mut DAOFactory, subaccount: &str) {
+    register_owned_by(factory, subaccount, account("owner"));
+}
+
+fn register_owned_by(factory: &mut DAOFactory, subaccount: &str, owner: AccountId) {
+    set_caller(account("factory"), NearToken::from_yoctonear(0));
+    let record = DAORecord {
+        account_id: format!("{}.factory.testnet", subaccount).parse().unwrap(),
+        name: subaccount.to_string(),
+        owner,
+        membership_mode: MembershipMode::Public,
+        created_by: account("owner"),
+        created_at: 0,
+    };
+    assert!(factory.on_dao_created(record, NearToken::from_near(1), Ok(())));
+}
+
+#[test]
+fn dao_created_at_subaccount() {
+    let mut factory = factory_with_code();
+
+    set_caller(account("alice"), NearToken::from_near(5));
+    let _ = factory.create_dao(
+        "Alice's Private DAO".to_string(),
+        MembershipMode::Private,
+        account("alice"),
+        Some("alice-dao".to_string()),
+        None,
+    );
+
+    let receipt = get_created_receipts().into_iter().next().unwrap();
+    assert_eq!(receipt.receiver_id.as_str(), "alice-dao.factory.testnet");
+    assert!(matches!(receipt.actions[0], MockAction::CreateAccount { .. }));
+    let init_args = receipt
+        .actions
+        .iter()
+        .find_map(|action| match action {
+            MockAction::FunctionCallWeight { method_name, args, .. } if method_name == b"new" => {
+                Some(serde_json::from_slice::<serde_json::Value>(args).unwrap())
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(init_args["name"], "Alice's Private DAO");
+}
+
+#[test]
+fn dao_init_forwards_options() {
+    let mut factory = factory_with_code();
+    let config = private_dao_contract::types::DAOConfig { veto_threshold: 3, ..Default::default() };
+    let secrets_ref = serde_json::json!({ "profile": "dao", "account_id": "keymaster.testnet" });
+
+    set_caller(account("alice"), NearToken::from_near(5));
+    let _ = factory.create_dao(
+        "Alice's Private DAO".to_string(),
+        MembershipMode::Private,
+        account("alice"),
+        Some("alice-dao".to_string()),
+        Some(DAOInitOptions {
+            config: Some(serde_json::to_value(&config).unwrap()),
+            outlayer_contract: Some(account("outlayer")),
+            max_members: Some(10),
+            secrets_ref: Some(secrets_ref.clone()),
+        }),
+    );
+
+    let receipt = get_created_receipts().into_iter().next().unwrap();
+    let init_args = receipt
+        .actions
+        .iter()
+        .find_map(|action| match action {
+            MockAction::FunctionCallWeight { method_name, args, .. } if method_name == b"new" => {
+                Some(serde_json::from_slice::<serde_json::Value>(args).unwrap())
+            }
+            _ => None,
+        })
+        .unwrap();
+
+    // Initialise the DAO with exactly what the factory forwarded
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(receipt.receiver_id.clone())
+        .predecessor_account_id(receipt.receiver_id.clone())
+        .build());
+    let dao = private_dao_contract::PrivateDAO::new(
+        serde_json::from_value(init_args["name"].clone()).unwrap(),
+        serde_json::from_value(init_args["membership_mode"].clone()).unwrap(),
+        serde_json::from_value(init_args["owner"].clone()).unwrap(),
+        serde_json::from_value(init_args["config"].clone()).unwrap(),
+        serde_json::from_value(init_args["outlayer_contract"].clone()).unwrap(),
+        serde_json::from_value(init_args["max_members"].clone()).unwrap(),
+        serde_json::from_value(init_args["secrets_ref"].clone()).unwrap(),
+    );
+    assert_eq!(dao.get_config().veto_threshold, 3);
+    assert_eq!(dao.outlayer_contract, account("outlayer"));
+    assert_eq!(dao.max_members, Some(10));
+    assert_eq!(dao.get_secrets_ref(), secrets_ref);
+}
+
+#[test]
+#[should_panic(expected = "Sub-account prefix cannot contain '.'")]
+fn nested_subaccount_rejected() {
+    let mut factory = factory_with_code();
+
+    set_caller(account("alice"), NearToken::from_near(5));
+    let _ =
+        factory.create_dao("dao".to_string(), MembershipMode::Public, account("alice"), Some("a.b".to_string()), None);
+}
+
+#[test]
+fn daos_paginated_in_creation_order() {
+    let mut factory = factory_with_code();
+    for subaccount in ["one", "two", "three"] {
+        register(&mut factory, subaccount);
+    }
+
+    let names = |from_index, limit| -> Vec<String> {
+        factory.get_daos(from_index, limit).into_iter().map(|record| record.name).collect()
+    };
+    assert_eq!(names(0, 2), vec!["one", "two"]);
+    assert_eq!(names(2, 10), vec!["three"]);
+    assert!(names(3, 10).is_empty());
+}
+
+#[test]
+fn daos_by_owner_paginated() {
+    let mut factory = factory_with_code();
+    register_owned_by(&mut factory, "one", account("alice"));
+    register_owned_by(&mut factory, "two", account("bob"));
+    register_owned_by(&mut factory, "three", account("alice"));
+
+    let owned = |owner: &str, from_index, limit| -> Vec<String> {
+        factory
+            .get_daos_by_owner(account(owner), from_index, limit)
+            .into_iter()
+            .map(|account_id| account_id.to_string())
+            .collect()
+    };
+    assert_eq!(owned("alice", 0, 10), vec!["one.factory.testnet", "three.factory.testnet"]);
+    assert_eq!(owned("alice", 1, 10), vec!["three.factory.testnet"]);
+    assert_eq!(owned("alice", 0, 1), vec!["one.factory.testnet"]);
+    assert_eq!(owned("bob", 0, 10), vec!["two.factory.testnet"]);
+    assert!(owned("carol", 0, 10).is_empty());
+}
+
+#[test]
+fn code_hash_of_uploaded_code() {
+    set_caller(account("owner"), NearToken::from_yoctonear(0));
+    let mut factory = DAOFactory::new(account("owner"));
+    assert!(factory.get_code_hash().is_none());
+
+    factory.set_code(vec![0u8; 100].into());
+    let expected: [u8; 32] = near_sdk::env::sha256_array(&[0u8; 100]);
+    assert_eq!(factory.get_code_hash(), Some(expected.into()));
+}
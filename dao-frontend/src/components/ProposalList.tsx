@@ -57,8 +57,8 @@ export const ProposalList: React.FC<ProposalListProps> = ({
       const counts: Record<number, number> = {};
       for (const proposal of proposalsList as Proposal[]) {
         try {
-          const votes = await viewMethod('get_votes', { proposal_id: proposal.id });
-          counts[proposal.id] = votes ? votes.length : 0;
+          const count = await viewMethod('get_vote_count', { proposal_id: proposal.id });
+          counts[proposal.id] = count ?? 0;
         } catch (e) {
           counts[proposal.id] = 0;
         }
@@ -216,6 +216,9 @@ export const ProposalList: React.FC<ProposalListProps> = ({
                       <div className="vote-bar">
                         <span className="vote-label">YES: {proposal.tally_result.yes_count}</span>
                         <span className="vote-label">NO: {proposal.tally_result.no_count}</span>
+                        {proposal.tally_result.abstain_count != null && (
+                          <span className="vote-label">ABSTAIN: {proposal.tally_result.abstain_count}</span>
+                        )}
                       </div>
                       <div className="vote-total">
                         Total: {proposal.tally_result.total_votes} votes
@@ -13,8 +13,9 @@ export interface Proposal {
   created_at: number;
   deadline: number | null; // Optional deadline
   quorum: QuorumType;
-  status: 'Active' | 'Passed' | 'Rejected';
+  status: 'Active' | 'Passed' | 'Rejected' | 'Cancelled';
   tally_result: TallyResult | null;
+  vote_options: string[] | null; // null for yes/no proposals
 }
 
 export type QuorumType =
@@ -33,6 +34,8 @@ export interface TallyResult {
   quorum_met: boolean;
   yes_count: number | null; // Only present if quorum met
   no_count: number | null; // Only present if quorum met
+  abstain_count?: number | null; // Only present if quorum met (missing from older tallies)
+  vote_counts: Record<string, number>; // Empty unless quorum met
   total_votes: number;
   tee_attestation: string;
   votes_merkle_root: string;
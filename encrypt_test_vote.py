@@ -73,8 +73,8 @@ def main():
     vote = sys.argv[4]
 
     # Validate vote
-    if vote not in ["yes", "no"]:
-        print(f"Warning: vote '{vote}' is not 'yes' or 'no' - it will be treated as dummy/noise")
+    if vote not in ["yes", "no", "abstain"]:
+        print(f"Warning: vote '{vote}' is not 'yes', 'no' or 'abstain' - it will be treated as dummy/noise")
 
     # Convert master secret from hex
     master_secret = bytes.fromhex(master_secret_hex)
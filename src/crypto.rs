@@ -3,13 +3,19 @@
 // This module implements:
 // 1. Deterministic key derivation (HKDF-SHA256)
 // 2. ECIES encryption/decryption (secp256k1 + AES-256-GCM)
+// 3. P-256 (secp256r1) variant of both for hardware keys
+// 4. PBKDF2 alternative to HKDF for deployments that mandate it
 //
 // PRODUCTION READY: Full ECIES implementation with pure Rust secp256k1
 // Compatible with wasm32-wasip1 target (no C dependencies)
 
 use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 
+/// (32-byte private key, 33-byte compressed public key)
+pub type Keypair = ([u8; 32], Vec<u8>);
+
 /// Generate secp256k1 keypair from seed
 ///
 /// Uses deterministic key derivation from master secret.
@@ -27,14 +33,159 @@ pub fn derive_keypair(
     dao_account: &str,
     user_account: &str,
 ) -> Result<(Vec<u8>, Vec<u8>), String> {
-    // Derive deterministic seed using HKDF
-    let info = format!("ecies:{}:{}", dao_account, user_account);
     let hkdf = Hkdf::<Sha256>::new(None, master_secret);
+    let (seed, pubkey) = expand_keypair(&hkdf, dao_account, user_account)?;
+    Ok((seed.to_vec(), pubkey))
+}
+
+/// Generate secp256k1 keypair scoped to a single proposal
+///
+/// Same as `derive_keypair` but the HKDF info also includes the proposal ID,
+/// so a key for one proposal reveals nothing about keys for other proposals.
+///
+/// # Arguments
+/// * `master_secret` - Master secret from keymaster
+/// * `dao_account` - DAO account ID
+/// * `user_account` - User account ID
+/// * `proposal_id` - Proposal the key is valid for
+///
+/// # Returns
+/// * `(Vec<u8>, Vec<u8>)` - (32-byte private key, 33-byte compressed public key)
+pub fn derive_proposal_keypair(
+    master_secret: &[u8],
+    dao_account: &str,
+    user_account: &str,
+    proposal_id: u64,
+) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let hkdf = Hkdf::<Sha256>::new(None, master_secret);
+    let info = format!("ecies:{}:{}:{}", dao_account, user_account, proposal_id);
+    let (seed, pubkey) = expand_keypair_with_info(&hkdf, &info)?;
+    Ok((seed.to_vec(), pubkey))
+}
+
+/// PBKDF2 work factor used when `KeyDerivationAlgorithm::PBKDF2` omits `iterations`
+pub const DEFAULT_PBKDF2_ITERATIONS: u32 = 100_000;
+
+fn default_pbkdf2_iterations() -> u32 {
+    DEFAULT_PBKDF2_ITERATIONS
+}
+
+/// How a user's DAO-wide keypair is derived from the master secret
+///
+/// JSON: `"HKDF"` or `{ "PBKDF2": { "iterations": 100000 } }`
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyDerivationAlgorithm {
+    /// `derive_keypair` (default)
+    #[default]
+    HKDF,
+    /// `derive_keypair_pbkdf2`, for compliance frameworks that require PBKDF2
+    PBKDF2 {
+        #[serde(default = "default_pbkdf2_iterations")]
+        iterations: u32,
+    },
+}
+
+impl KeyDerivationAlgorithm {
+    /// Derive `user_account`'s keypair with this algorithm
+    pub fn derive_keypair(
+        self,
+        master_secret: &[u8],
+        dao_account: &str,
+        user_account: &str,
+    ) -> Result<(Vec<u8>, Vec<u8>), String> {
+        match self {
+            Self::HKDF => derive_keypair(master_secret, dao_account, user_account),
+            Self::PBKDF2 { iterations } => {
+                derive_keypair_pbkdf2(master_secret, dao_account, user_account, iterations)
+            }
+        }
+    }
+}
+
+/// Generate secp256k1 keypair with PBKDF2-HMAC-SHA256 instead of HKDF
+///
+/// Slower by design: `iterations` is the work factor (`DEFAULT_PBKDF2_ITERATIONS`
+/// unless a compliance framework says otherwise). Keys differ from `derive_keypair`.
+///
+/// # Arguments
+/// * `master_secret` - Master secret from keymaster (PBKDF2 password)
+/// * `dao_account` - DAO account ID
+/// * `user_account` - User account ID
+/// * `iterations` - PBKDF2 rounds (at least 1)
+///
+/// # Returns
+/// * `(Vec<u8>, Vec<u8>)` - (32-byte private key, 33-byte compressed public key)
+pub fn derive_keypair_pbkdf2(
+    master_secret: &[u8],
+    dao_account: &str,
+    user_account: &str,
+    iterations: u32,
+) -> Result<(Vec<u8>, Vec<u8>), String> {
+    use sha2::Digest;
+
+    if iterations == 0 {
+        return Err("PBKDF2 iterations must be at least 1".to_string());
+    }
+
+    // Salt = SHA256(dao_account || user_account)
+    let mut hasher = Sha256::new();
+    hasher.update(dao_account.as_bytes());
+    hasher.update(user_account.as_bytes());
+    let salt = hasher.finalize();
 
+    let mut seed = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(master_secret, &salt, iterations, &mut seed);
+
+    let (seed, pubkey) = keypair_from_seed(seed)?;
+    Ok((seed.to_vec(), pubkey))
+}
+
+/// Derive keypairs for many users with a single HKDF extract
+///
+/// Same keys as calling `derive_keypair` for each context, but the PRK is
+/// computed once from `master_secret` and reused for every expansion.
+///
+/// # Arguments
+/// * `master_secret` - Master secret from keymaster
+/// * `contexts` - `(dao_account, user_account)` pairs
+///
+/// # Returns
+/// * `Vec<Keypair>` - (private key, compressed public key) per context, in order
+pub fn hkdf_expand_many(
+    master_secret: &[u8],
+    contexts: &[(String, String)],
+) -> Result<Vec<Keypair>, String> {
+    let hkdf = Hkdf::<Sha256>::new(None, master_secret);
+
+    contexts
+        .iter()
+        .map(|(dao_account, user_account)| expand_keypair(&hkdf, dao_account, user_account))
+        .collect()
+}
+
+/// Expand one user's keypair from an already-extracted HKDF
+fn expand_keypair(
+    hkdf: &Hkdf<Sha256>,
+    dao_account: &str,
+    user_account: &str,
+) -> Result<Keypair, String> {
+    let info = format!("ecies:{}:{}", dao_account, user_account);
+    expand_keypair_with_info(hkdf, &info)
+}
+
+/// Expand a keypair for an arbitrary HKDF info string
+fn expand_keypair_with_info(hkdf: &Hkdf<Sha256>, info: &str) -> Result<Keypair, String> {
+    // Derive deterministic seed using HKDF
     let mut seed = [0u8; 32];
     hkdf.expand(info.as_bytes(), &mut seed)
         .map_err(|e| format!("HKDF failed: {}", e))?;
 
+    keypair_from_seed(seed)
+}
+
+/// Use a 32-byte seed as secp256k1 private key
+fn keypair_from_seed(seed: [u8; 32]) -> Result<Keypair, String> {
     // Create SecretKey from deterministic seed
     // The seed IS the private key (32 bytes)
     let secret_key = libsecp256k1::SecretKey::parse_slice(&seed)
@@ -45,7 +196,7 @@ pub fn derive_keypair(
 
     // Return serialized keys
     // NOTE: Using compressed public keys (33 bytes: 0x02/0x03 + X coordinate)
-    Ok((seed.to_vec(), public_key.serialize_compressed().to_vec()))
+    Ok((seed, public_key.serialize_compressed().to_vec()))
 }
 
 /// Derive user's public key (for client-side encryption)
@@ -60,6 +211,7 @@ pub fn derive_keypair(
 ///
 /// # Returns
 /// * Public key (33 bytes compressed)
+#[cfg_attr(not(test), allow(dead_code))]
 pub fn derive_user_pubkey(
     master_secret: &[u8],
     dao_account: &str,
@@ -69,6 +221,19 @@ pub fn derive_user_pubkey(
     Ok(pubkey)
 }
 
+/// Account string a user's DAO-wide key is derived for at `key_epoch`
+///
+/// Epoch 0 is the bare account, so keys derived before rotation existed stay
+/// valid. Each `rotate_key` bumps the epoch, which appends `#{epoch}` (`#` never
+/// appears in NEAR account IDs) and yields an unrelated keypair.
+pub fn epoch_user_account(user_account: &str, key_epoch: u32) -> String {
+    if key_epoch == 0 {
+        user_account.to_string()
+    } else {
+        format!("{}#{}", user_account, key_epoch)
+    }
+}
+
 /// Encrypt vote using ECIES
 ///
 /// This function is for testing/demonstration only.
@@ -100,6 +265,7 @@ pub fn encrypt_vote(pubkey: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String>
 ///
 /// # Returns
 /// * Decrypted plaintext ("yes", "no", or dummy message)
+#[cfg_attr(not(test), allow(dead_code))]
 pub fn decrypt_vote(
     master_secret: &[u8],
     dao_account: &str,
@@ -109,8 +275,19 @@ pub fn decrypt_vote(
     // Derive user's private key
     let (privkey, _pubkey) = derive_keypair(master_secret, dao_account, user_account)?;
 
+    decrypt_vote_with_key(&privkey, ciphertext)
+}
+
+/// Decrypt vote using an already-derived private key
+///
+/// Used by the tally worker together with `hkdf_expand_many`.
+///
+/// # Arguments
+/// * `privkey` - User's 32-byte private key
+/// * `ciphertext` - Encrypted vote from blockchain
+pub fn decrypt_vote_with_key(privkey: &[u8], ciphertext: &[u8]) -> Result<String, String> {
     // Decrypt using ECIES
-    let plaintext_bytes = ecies::decrypt(&privkey, ciphertext)
+    let plaintext_bytes = ecies::decrypt(privkey, ciphertext)
         .map_err(|e| format!("ECIES decryption failed: {}", e))?;
 
     // Convert to UTF-8 string
@@ -118,6 +295,539 @@ pub fn decrypt_vote(
         .map_err(|e| format!("Invalid UTF-8: {}", e))
 }
 
+/// Encrypt the same message to several recipients
+///
+/// One `encrypt_vote` per recipient: every ciphertext gets its own ephemeral
+/// key, so compromising one recipient's ciphertext reveals nothing about the
+/// others (no shared randomness to exploit).
+///
+/// # Arguments
+/// * `recipients` - Recipients' public keys (33 bytes compressed)
+/// * `plaintext` - Message to encrypt
+///
+/// # Returns
+/// * One ciphertext per recipient, in order
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn batch_encrypt_distinct(recipients: &[&[u8]], plaintext: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    recipients
+        .iter()
+        .enumerate()
+        .map(|(i, pubkey)| {
+            encrypt_vote(pubkey, plaintext).map_err(|e| format!("Recipient {}: {}", i, e))
+        })
+        .collect()
+}
+
+/// Decrypt several ciphertexts, each with its own private key
+///
+/// # Arguments
+/// * `private_keys` - 32-byte private keys, one per ciphertext
+/// * `ciphertexts` - Ciphertexts in the same order
+///
+/// # Returns
+/// * Plaintexts in order, or the first failure (with its index)
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn batch_decrypt(private_keys: &[&[u8]], ciphertexts: &[Vec<u8>]) -> Result<Vec<String>, String> {
+    if private_keys.len() != ciphertexts.len() {
+        return Err(format!(
+            "Got {} private keys for {} ciphertexts",
+            private_keys.len(),
+            ciphertexts.len()
+        ));
+    }
+
+    private_keys
+        .iter()
+        .zip(ciphertexts)
+        .enumerate()
+        .map(|(i, (privkey, ciphertext))| {
+            decrypt_vote_with_key(privkey, ciphertext).map_err(|e| format!("Ciphertext {}: {}", i, e))
+        })
+        .collect()
+}
+
+/// Encrypt a random non-vote plaintext to `pubkey` (test scenarios only)
+///
+/// Real dummy votes are generated client-side; this exists so tests can build
+/// reproducible noise. Everything random - the 3-letter plaintext, the ECIES
+/// ephemeral key and the AES nonce - comes from `rng`, so a seeded RNG yields
+/// identical ciphertexts. The output is a regular `encrypt_vote` ciphertext
+/// (65-byte ephemeral key || 16-byte nonce || 16-byte tag || ciphertext) of
+/// the same length as a "yes" ballot.
+///
+/// # Panics
+/// If `pubkey` is not a valid secp256k1 public key.
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn generate_dummy_ciphertext(rng: &mut dyn aes_gcm::aead::rand_core::RngCore, pubkey: &[u8]) -> Vec<u8> {
+    use aes_gcm::aead::consts::U16;
+    use aes_gcm::aead::{AeadInPlace, KeyInit};
+    use aes_gcm::aes::Aes256;
+    use aes_gcm::AesGcm;
+
+    let mut plaintext = *b"yes";
+    while &plaintext == b"yes" {
+        for byte in plaintext.iter_mut() {
+            *byte = b'a' + (rng.next_u32() % 26) as u8;
+        }
+    }
+
+    let receiver = ecies::PublicKey::parse_slice(pubkey, None).expect("Invalid recipient public key");
+    let ephemeral_sk = loop {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        if let Ok(sk) = ecies::SecretKey::parse_slice(&bytes) {
+            break sk;
+        }
+    };
+    let ephemeral_pk = ecies::PublicKey::from_secret_key(&ephemeral_sk);
+    let sym_key = ecies::utils::encapsulate(&ephemeral_sk, &receiver, ecies::config::is_hkdf_key_compressed())
+        .expect("Invalid recipient public key");
+
+    // Same AES-256-GCM layout as `ecies::encrypt` (16-byte nonce)
+    let mut nonce = [0u8; 16];
+    rng.fill_bytes(&mut nonce);
+    let mut ciphertext = plaintext.to_vec();
+    let tag = AesGcm::<Aes256, U16>::new(&sym_key.into())
+        .encrypt_in_place_detached(&nonce.into(), &[], &mut ciphertext)
+        .expect("AES-GCM encryption of 3 bytes cannot fail");
+
+    [ephemeral_pk.serialize().as_slice(), &nonce, tag.as_slice(), &ciphertext].concat()
+}
+
+/// Key type of a stored public key, identified by a 2-byte header
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    /// secp256k1 (default, 33-byte compressed keys)
+    Secp256k1,
+    /// P-256 / secp256r1 (hardware keys, 65-byte uncompressed keys)
+    P256,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl KeyType {
+    /// Header prepended to stored pubkeys: 0x8001 (secp256k1) or 0x8002 (P-256)
+    pub fn header(self) -> [u8; 2] {
+        match self {
+            KeyType::Secp256k1 => [0x80, 0x01],
+            KeyType::P256 => [0x80, 0x02],
+        }
+    }
+}
+
+/// Prepend the key type header to a public key (for storing as hex)
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn tag_pubkey(key_type: KeyType, pubkey: &[u8]) -> Vec<u8> {
+    let mut tagged = key_type.header().to_vec();
+    tagged.extend_from_slice(pubkey);
+    tagged
+}
+
+/// Split a stored public key into its key type and raw key bytes
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn parse_tagged_pubkey(tagged: &[u8]) -> Result<(KeyType, &[u8]), String> {
+    if tagged.len() < 2 {
+        return Err("Tagged public key too short".to_string());
+    }
+
+    let key_type = match [tagged[0], tagged[1]] {
+        [0x80, 0x01] => KeyType::Secp256k1,
+        [0x80, 0x02] => KeyType::P256,
+        header => return Err(format!("Unknown key type header: {}", hex::encode(header))),
+    };
+
+    Ok((key_type, &tagged[2..]))
+}
+
+/// Generate P-256 (secp256r1) keypair from seed
+///
+/// Same derivation as `derive_keypair` but with HKDF info `p256-ecies:{dao}:{user}`,
+/// so P-256 and secp256k1 keys for the same user are unrelated.
+///
+/// # Arguments
+/// * `master_secret` - Master secret from keymaster
+/// * `dao_account` - DAO account ID
+/// * `user_account` - User account ID
+///
+/// # Returns
+/// * `([u8; 32], [u8; 65])` - (private key, uncompressed public key 0x04 + X + Y)
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn derive_keypair_p256(
+    master_secret: &[u8],
+    dao_account: &str,
+    user_account: &str,
+) -> Result<([u8; 32], [u8; 65]), String> {
+    use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+    let hkdf = Hkdf::<Sha256>::new(None, master_secret);
+    let info = format!("p256-ecies:{}:{}", dao_account, user_account);
+    let mut seed = [0u8; 32];
+    hkdf.expand(info.as_bytes(), &mut seed)
+        .map_err(|e| format!("HKDF failed: {}", e))?;
+
+    let secret_key = p256::SecretKey::from_slice(&seed)
+        .map_err(|e| format!("Invalid P-256 secret key: {}", e))?;
+
+    let mut pubkey = [0u8; 65];
+    pubkey.copy_from_slice(secret_key.public_key().to_encoded_point(false).as_bytes());
+
+    Ok((seed, pubkey))
+}
+
+/// Encrypt vote to a P-256 public key (ECDH + HKDF-SHA256 + AES-256-GCM)
+///
+/// Like `encrypt_vote`, this is for testing; clients encrypt on their side.
+/// Ciphertext format: `ephemeral pubkey (65) || nonce (12) || AES-GCM ciphertext`
+///
+/// # Arguments
+/// * `pubkey` - Recipient's P-256 public key (SEC1, compressed or uncompressed)
+/// * `plaintext` - Vote data ("yes", "no", or dummy message)
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn encrypt_vote_p256(pubkey: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use aes_gcm::Aes256Gcm;
+    use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+    let recipient = p256::PublicKey::from_sec1_bytes(pubkey)
+        .map_err(|e| format!("Invalid P-256 public key: {}", e))?;
+
+    let ephemeral = p256::ecdh::EphemeralSecret::random(&mut OsRng);
+    let ephemeral_pubkey = ephemeral.public_key().to_encoded_point(false);
+    let shared = ephemeral.diffie_hellman(&recipient);
+
+    let key = p256_ecies_key(ephemeral_pubkey.as_bytes(), shared.raw_secret_bytes())?;
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| format!("AES key error: {}", e))?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ct = cipher.encrypt(&nonce, plaintext)
+        .map_err(|e| format!("P-256 ECIES encryption failed: {}", e))?;
+
+    let mut out = Vec::with_capacity(65 + nonce.len() + ct.len());
+    out.extend_from_slice(ephemeral_pubkey.as_bytes());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ct);
+    Ok(out)
+}
+
+/// Decrypt vote encrypted with `encrypt_vote_p256`
+///
+/// # Arguments
+/// * `privkey` - User's 32-byte P-256 private key
+/// * `ciphertext` - Encrypted vote from blockchain
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn decrypt_vote_p256(privkey: &[u8], ciphertext: &[u8]) -> Result<String, String> {
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    if ciphertext.len() < 65 + 12 {
+        return Err("P-256 ciphertext too short".to_string());
+    }
+    let (ephemeral_bytes, rest) = ciphertext.split_at(65);
+    let (nonce, ct) = rest.split_at(12);
+
+    let secret_key = p256::SecretKey::from_slice(privkey)
+        .map_err(|e| format!("Invalid P-256 secret key: {}", e))?;
+    let ephemeral = p256::PublicKey::from_sec1_bytes(ephemeral_bytes)
+        .map_err(|e| format!("Invalid ephemeral public key: {}", e))?;
+    let shared = p256::ecdh::diffie_hellman(secret_key.to_nonzero_scalar(), ephemeral.as_affine());
+
+    let key = p256_ecies_key(ephemeral_bytes, shared.raw_secret_bytes())?;
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| format!("AES key error: {}", e))?;
+    let plaintext_bytes = cipher.decrypt(Nonce::from_slice(nonce), ct)
+        .map_err(|e| format!("P-256 ECIES decryption failed: {}", e))?;
+
+    String::from_utf8(plaintext_bytes)
+        .map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+/// AES key for P-256 ECIES: HKDF-SHA256(ephemeral pubkey || shared X)
+#[cfg_attr(not(test), allow(dead_code))]
+fn p256_ecies_key(ephemeral_pubkey: &[u8], shared_x: &[u8]) -> Result<[u8; 32], String> {
+    let mut ikm = Vec::with_capacity(ephemeral_pubkey.len() + shared_x.len());
+    ikm.extend_from_slice(ephemeral_pubkey);
+    ikm.extend_from_slice(shared_x);
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, &ikm)
+        .expand(&[], &mut key)
+        .map_err(|e| format!("HKDF failed: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt state that only the TEE may read back (e.g. a running chunk tally)
+///
+/// AES-256-GCM with a key derived from the master secret for `context`, so a
+/// blob only unseals under the same context. The blob can be stored on-chain:
+/// it reveals nothing but its length and can't be forged outside the TEE.
+///
+/// # Blob format
+/// `nonce (12) || ct`
+pub fn seal_state(master_secret: &[u8], context: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use aes_gcm::Aes256Gcm;
+
+    let cipher = Aes256Gcm::new(&sealing_key(master_secret, context)?.into());
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ct = cipher.encrypt(&nonce, plaintext)
+        .map_err(|e| format!("Sealing failed: {}", e))?;
+
+    let mut out = Vec::with_capacity(nonce.len() + ct.len());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ct);
+    Ok(out)
+}
+
+/// Decrypt a blob produced by `seal_state` with the same `context`
+pub fn unseal_state(master_secret: &[u8], context: &str, sealed: &[u8]) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    if sealed.len() < 12 {
+        return Err("Sealed state too short".to_string());
+    }
+    let (nonce, ct) = sealed.split_at(12);
+
+    let cipher = Aes256Gcm::new(&sealing_key(master_secret, context)?.into());
+    cipher.decrypt(Nonce::from_slice(nonce), ct)
+        .map_err(|_| "Sealed state is corrupt or belongs to another context".to_string())
+}
+
+/// AES key for `seal_state`
+fn sealing_key(master_secret: &[u8], context: &str) -> Result<[u8; 32], String> {
+    derive_secret(master_secret, &format!("seal:{}", context))
+}
+
+/// 32-byte secret for `info`: HKDF-SHA256(master_secret, info)
+///
+/// For TEE-only values other than keypairs (sealing keys, blinding factors).
+pub fn derive_secret(master_secret: &[u8], info: &str) -> Result<[u8; 32], String> {
+    let mut secret = [0u8; 32];
+    Hkdf::<Sha256>::new(None, master_secret)
+        .expand(info.as_bytes(), &mut secret)
+        .map_err(|e| format!("HKDF failed: {}", e))?;
+    Ok(secret)
+}
+
+/// Format version of `encrypt_vote_threshold` blobs
+const THRESHOLD_BLOB_VERSION: u8 = 1;
+
+/// Encrypt vote for M-of-N trustees (threshold encryption)
+///
+/// The vote is encrypted with a random AES-256-GCM key. The key is split with
+/// Shamir's Secret Sharing over GF(256) into one share per recipient, so that
+/// any `threshold` shares reconstruct it. Each share is ECIES-encrypted to its
+/// recipient.
+///
+/// # Blob format
+/// `version (1) || threshold (1) || n (1) || nonce (12) || ct_len (u32 LE) || ct`
+/// followed by `n` × `share_len (u16 LE) || ecies(share)` in recipient order.
+///
+/// # Arguments
+/// * `pubkeys` - Recipients' public keys (33 bytes compressed), at most 255
+/// * `threshold` - Number of recipients needed to decrypt (1..=n)
+/// * `plaintext` - Vote data
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn encrypt_vote_threshold(
+    pubkeys: &[&[u8]],
+    threshold: usize,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::rand_core::RngCore;
+    use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use aes_gcm::Aes256Gcm;
+
+    let n = pubkeys.len();
+    if n == 0 || n > 255 {
+        return Err(format!("Recipient count must be 1..=255, got {}", n));
+    }
+    if threshold == 0 || threshold > n {
+        return Err(format!("Threshold must be 1..={}, got {}", n, threshold));
+    }
+
+    // Encrypt vote with a one-time symmetric key
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    let cipher = Aes256Gcm::new(&key.into());
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| format!("AES-GCM encryption failed: {}", e))?;
+
+    let mut blob = vec![THRESHOLD_BLOB_VERSION, threshold as u8, n as u8];
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&(ciphertext.len() as u32).to_le_bytes());
+    blob.extend_from_slice(&ciphertext);
+
+    // One ECIES-encrypted key share per recipient
+    for (pubkey, share) in pubkeys.iter().zip(shamir_split(&key, threshold, n)) {
+        let encrypted_share = encrypt_vote(pubkey, &share)?;
+        blob.extend_from_slice(&(encrypted_share.len() as u16).to_le_bytes());
+        blob.extend_from_slice(&encrypted_share);
+    }
+
+    Ok(blob)
+}
+
+/// Decrypt a threshold-encrypted vote (see `encrypt_vote_threshold`)
+///
+/// # Arguments
+/// * `blob` - Output of `encrypt_vote_threshold`
+/// * `privkeys` - `(recipient_index, private_key)` for at least `threshold` recipients
+///
+/// # Returns
+/// * Decrypted plaintext
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn decrypt_vote_threshold(blob: &[u8], privkeys: &[(usize, &[u8])]) -> Result<String, String> {
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    let (threshold, nonce, ciphertext, encrypted_shares) = parse_threshold_blob(blob)?;
+
+    if privkeys.len() < threshold {
+        return Err(format!(
+            "Need {} key shares, got {}",
+            threshold,
+            privkeys.len()
+        ));
+    }
+
+    // Recover `threshold` shares (x coordinate = recipient index + 1)
+    let mut shares: Vec<(u8, Vec<u8>)> = Vec::with_capacity(threshold);
+    for (index, privkey) in privkeys.iter().take(threshold) {
+        let encrypted_share = encrypted_shares
+            .get(*index)
+            .ok_or_else(|| format!("No share for recipient {}", index))?;
+        let share = ecies::decrypt(privkey, encrypted_share)
+            .map_err(|e| format!("Failed to decrypt share {}: {}", index, e))?;
+        shares.push((*index as u8 + 1, share));
+    }
+
+    let key = shamir_combine(&shares)?;
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| format!("Invalid reconstructed key: {}", e))?;
+    let plaintext_bytes = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "AES-GCM decryption failed (wrong shares?)".to_string())?;
+
+    String::from_utf8(plaintext_bytes)
+        .map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+/// Split a threshold blob into (threshold, nonce, ciphertext, encrypted shares)
+type ThresholdBlobParts<'a> = (usize, &'a [u8], &'a [u8], Vec<&'a [u8]>);
+
+fn parse_threshold_blob(blob: &[u8]) -> Result<ThresholdBlobParts<'_>, String> {
+    fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+        if data.len() < len {
+            return Err("Truncated threshold blob".to_string());
+        }
+        let (head, tail) = data.split_at(len);
+        *data = tail;
+        Ok(head)
+    }
+
+    let mut data = blob;
+    let header = take(&mut data, 3)?;
+    if header[0] != THRESHOLD_BLOB_VERSION {
+        return Err(format!("Unsupported threshold blob version {}", header[0]));
+    }
+    let (threshold, n) = (header[1] as usize, header[2] as usize);
+
+    let nonce = take(&mut data, 12)?;
+    let ct_len = u32::from_le_bytes(take(&mut data, 4)?.try_into().unwrap()) as usize;
+    let ciphertext = take(&mut data, ct_len)?;
+
+    let mut encrypted_shares = Vec::with_capacity(n);
+    for _ in 0..n {
+        let share_len = u16::from_le_bytes(take(&mut data, 2)?.try_into().unwrap()) as usize;
+        encrypted_shares.push(take(&mut data, share_len)?);
+    }
+
+    Ok((threshold, nonce, ciphertext, encrypted_shares))
+}
+
+/// Shamir split over GF(256): one random polynomial per secret byte
+/// Share `i` is the polynomials evaluated at x = i + 1.
+fn shamir_split(secret: &[u8], threshold: usize, n: usize) -> Vec<Vec<u8>> {
+    use aes_gcm::aead::rand_core::RngCore;
+    use aes_gcm::aead::OsRng;
+
+    let mut shares = vec![Vec::with_capacity(secret.len()); n];
+    let mut coefficients = vec![0u8; threshold];
+
+    for &byte in secret {
+        coefficients[0] = byte;
+        OsRng.fill_bytes(&mut coefficients[1..]);
+
+        for (i, share) in shares.iter_mut().enumerate() {
+            let x = i as u8 + 1;
+            // Horner's method
+            let y = coefficients
+                .iter()
+                .rev()
+                .fold(0u8, |acc, &c| gf256_mul(acc, x) ^ c);
+            share.push(y);
+        }
+    }
+
+    shares
+}
+
+/// Shamir combine over GF(256): Lagrange interpolation at x = 0
+fn shamir_combine(shares: &[(u8, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let len = shares.first().map(|(_, s)| s.len()).ok_or("No shares")?;
+    if shares.iter().any(|(_, s)| s.len() != len) {
+        return Err("Key shares have different lengths".to_string());
+    }
+
+    let mut secret = vec![0u8; len];
+    for (j, (xj, share)) in shares.iter().enumerate() {
+        // Lagrange basis l_j(0) = prod_{m != j} x_m / (x_m - x_j); subtraction is XOR in GF(256)
+        let mut basis = 1u8;
+        for (m, (xm, _)) in shares.iter().enumerate() {
+            if m != j {
+                if xm == xj {
+                    return Err("Duplicate key shares".to_string());
+                }
+                basis = gf256_mul(basis, gf256_mul(*xm, gf256_inv(xm ^ xj)));
+            }
+        }
+
+        for (out, &y) in secret.iter_mut().zip(share) {
+            *out ^= gf256_mul(y, basis);
+        }
+    }
+
+    Ok(secret)
+}
+
+/// Multiplication in GF(256) with the AES polynomial x^8 + x^4 + x^3 + x + 1
+fn gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplicative inverse in GF(256) (a^254); `a` must be non-zero
+fn gf256_inv(a: u8) -> u8 {
+    let mut result = 1u8;
+    for _ in 0..254 {
+        result = gf256_mul(result, a);
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,6 +852,43 @@ mod tests {
         assert_eq!(pub1.len(), 33);  // compressed public key (0x02/0x03 + X)
     }
 
+    #[test]
+    fn test_pbkdf2_keypair_derivation() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let dao = "dao.testnet";
+
+        let (privkey, pubkey) = derive_keypair_pbkdf2(master_secret, dao, "alice.testnet", 1_000).unwrap();
+        assert_eq!(privkey.len(), 32);
+        assert_eq!(pubkey.len(), 33);
+
+        // Deterministic, but distinct from HKDF keys and from other work factors
+        assert_eq!(derive_keypair_pbkdf2(master_secret, dao, "alice.testnet", 1_000).unwrap().1, pubkey);
+        assert_ne!(derive_keypair(master_secret, dao, "alice.testnet").unwrap().1, pubkey);
+        assert_ne!(derive_keypair_pbkdf2(master_secret, dao, "alice.testnet", 1_001).unwrap().1, pubkey);
+        assert_ne!(derive_keypair_pbkdf2(master_secret, dao, "bob.testnet", 1_000).unwrap().1, pubkey);
+
+        let ciphertext = encrypt_vote(&pubkey, b"yes").unwrap();
+        assert_eq!(decrypt_vote_with_key(&privkey, &ciphertext).unwrap(), "yes");
+
+        assert!(derive_keypair_pbkdf2(master_secret, dao, "alice.testnet", 0).is_err());
+    }
+
+    #[test]
+    fn test_key_derivation_algorithm_json() {
+        let parse = |json| serde_json::from_value::<KeyDerivationAlgorithm>(json).unwrap();
+
+        assert_eq!(parse(serde_json::json!("HKDF")), KeyDerivationAlgorithm::HKDF);
+        assert_eq!(
+            parse(serde_json::json!({ "PBKDF2": {} })),
+            KeyDerivationAlgorithm::PBKDF2 { iterations: DEFAULT_PBKDF2_ITERATIONS }
+        );
+        assert_eq!(
+            parse(serde_json::json!({ "PBKDF2": { "iterations": 10 } })),
+            KeyDerivationAlgorithm::PBKDF2 { iterations: 10 }
+        );
+        assert_eq!(KeyDerivationAlgorithm::default(), KeyDerivationAlgorithm::HKDF);
+    }
+
     #[test]
     fn test_deterministic_keys() {
         let master_secret = b"test_secret_32_bytes_long_xxxx!!";
@@ -157,6 +904,113 @@ mod tests {
         assert_eq!(pub1, pub2);
     }
 
+    #[test]
+    fn test_hkdf_expand_many_matches_individual() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let contexts = vec![
+            ("dao.testnet".to_string(), "alice.testnet".to_string()),
+            ("dao.testnet".to_string(), "bob.testnet".to_string()),
+            ("other-dao.testnet".to_string(), "alice.testnet".to_string()),
+        ];
+
+        let batch = hkdf_expand_many(master_secret, &contexts).unwrap();
+        assert_eq!(batch.len(), contexts.len());
+
+        for ((dao, user), (privkey, pubkey)) in contexts.iter().zip(&batch) {
+            let (expected_priv, expected_pub) = derive_keypair(master_secret, dao, user).unwrap();
+            assert_eq!(privkey.to_vec(), expected_priv);
+            assert_eq!(pubkey, &expected_pub);
+        }
+    }
+
+    #[test]
+    fn test_proposal_keys_are_scoped() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let dao = "dao.testnet";
+        let user = "alice.testnet";
+
+        let (priv1, pub1) = derive_proposal_keypair(master_secret, dao, user, 1).unwrap();
+        let (priv2, pub2) = derive_proposal_keypair(master_secret, dao, user, 2).unwrap();
+        let (user_priv, _) = derive_keypair(master_secret, dao, user).unwrap();
+
+        // Different proposals (and the DAO-wide key) are unrelated
+        assert_ne!(priv1, priv2);
+        assert_ne!(pub1, pub2);
+        assert_ne!(priv1, user_priv);
+
+        // Deterministic per proposal
+        assert_eq!(derive_proposal_keypair(master_secret, dao, user, 1).unwrap().0, priv1);
+    }
+
+    #[test]
+    fn test_threshold_any_two_of_three() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let dao = "dao.testnet";
+        let keys: Vec<_> = ["t1.testnet", "t2.testnet", "t3.testnet"]
+            .iter()
+            .map(|t| derive_keypair(master_secret, dao, t).unwrap())
+            .collect();
+        let pubkeys: Vec<&[u8]> = keys.iter().map(|(_, pk)| pk.as_slice()).collect();
+
+        let blob = encrypt_vote_threshold(&pubkeys, 2, b"yes").unwrap();
+
+        for (a, b) in [(0, 1), (0, 2), (2, 1)] {
+            let privkeys = [(a, keys[a].0.as_slice()), (b, keys[b].0.as_slice())];
+            assert_eq!(decrypt_vote_threshold(&blob, &privkeys).unwrap(), "yes");
+        }
+    }
+
+    #[test]
+    fn test_threshold_insufficient_or_wrong_shares_fail() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let dao = "dao.testnet";
+        let keys: Vec<_> = ["t1.testnet", "t2.testnet", "t3.testnet"]
+            .iter()
+            .map(|t| derive_keypair(master_secret, dao, t).unwrap())
+            .collect();
+        let pubkeys: Vec<&[u8]> = keys.iter().map(|(_, pk)| pk.as_slice()).collect();
+
+        let blob = encrypt_vote_threshold(&pubkeys, 2, b"no").unwrap();
+
+        // Only one share
+        assert!(decrypt_vote_threshold(&blob, &[(0, keys[0].0.as_slice())]).is_err());
+
+        // Key doesn't match recipient index
+        let mismatched = [(0, keys[1].0.as_slice()), (1, keys[0].0.as_slice())];
+        assert!(decrypt_vote_threshold(&blob, &mismatched).is_err());
+
+        // Invalid threshold
+        assert!(encrypt_vote_threshold(&pubkeys, 4, b"no").is_err());
+    }
+
+    #[test]
+    fn test_sealed_state_round_trip() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+
+        let sealed = seal_state(master_secret, "dao.testnet:1", b"{\"yes\":2}").unwrap();
+        assert_eq!(unseal_state(master_secret, "dao.testnet:1", &sealed).unwrap(), b"{\"yes\":2}");
+
+        // Other context, other secret or tampered blob
+        assert!(unseal_state(master_secret, "dao.testnet:2", &sealed).is_err());
+        assert!(unseal_state(b"other_secret_32_bytes_long_xxx!!", "dao.testnet:1", &sealed).is_err());
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(unseal_state(master_secret, "dao.testnet:1", &tampered).is_err());
+        assert!(unseal_state(master_secret, "dao.testnet:1", &sealed[..8]).is_err());
+    }
+
+    #[test]
+    fn test_shamir_round_trip() {
+        let secret = b"0123456789abcdef0123456789abcdef";
+        let shares = shamir_split(secret, 3, 5);
+
+        let subset: Vec<(u8, Vec<u8>)> = [4usize, 1, 3]
+            .iter()
+            .map(|&i| (i as u8 + 1, shares[i].clone()))
+            .collect();
+        assert_eq!(shamir_combine(&subset).unwrap(), secret.to_vec());
+    }
+
     #[test]
     fn test_encrypt_decrypt() {
         let master_secret = b"test_secret_32_bytes_long_xxxx!!";
@@ -191,6 +1045,33 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_cross_dao_isolation() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let user = "alice.testnet";
+
+        let (privkey_dao1, pubkey_dao1) = derive_keypair(master_secret, "dao1.testnet", user).unwrap();
+        let (privkey_dao2, pubkey_dao2) = derive_keypair(master_secret, "dao2.testnet", user).unwrap();
+
+        assert_ne!(privkey_dao1, privkey_dao2);
+        assert_ne!(pubkey_dao1, pubkey_dao2);
+    }
+
+    #[test]
+    fn test_message_encrypted_for_dao1_not_decryptable_by_dao2() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let user = "alice.testnet";
+
+        let (_privkey_dao1, pubkey_dao1) = derive_keypair(master_secret, "dao1.testnet", user).unwrap();
+        let (privkey_dao2, _pubkey_dao2) = derive_keypair(master_secret, "dao2.testnet", user).unwrap();
+
+        let ciphertext = encrypt_vote(&pubkey_dao1, b"yes").unwrap();
+
+        assert!(decrypt_vote_with_key(&privkey_dao2, &ciphertext).is_err());
+        assert!(decrypt_vote(master_secret, "dao2.testnet", user, &ciphertext).is_err());
+        assert_eq!(decrypt_vote(master_secret, "dao1.testnet", user, &ciphertext).unwrap(), "yes");
+    }
+
     #[test]
     fn test_pubkey_derivation() {
         let master_secret = b"test_secret_32_bytes_long_xxxx!!";
@@ -205,4 +1086,81 @@ mod tests {
         // First byte should be 0x02 or 0x03 (compressed format marker)
         assert!(pubkey[0] == 0x02 || pubkey[0] == 0x03);
     }
+
+    #[test]
+    fn test_p256_encrypt_decrypt() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let dao = "dao.testnet";
+
+        let (privkey, pubkey) = derive_keypair_p256(master_secret, dao, "alice.testnet").unwrap();
+        assert_eq!(pubkey[0], 0x04); // uncompressed SEC1 point
+
+        // Deterministic, and unrelated to the secp256k1 key
+        assert_eq!(derive_keypair_p256(master_secret, dao, "alice.testnet").unwrap(), (privkey, pubkey));
+        let (k1_priv, _) = derive_keypair(master_secret, dao, "alice.testnet").unwrap();
+        assert_ne!(privkey.to_vec(), k1_priv);
+
+        let ciphertext = encrypt_vote_p256(&pubkey, b"yes").unwrap();
+        assert_eq!(decrypt_vote_p256(&privkey, &ciphertext).unwrap(), "yes");
+
+        // Another user's key must not decrypt it
+        let (bob_priv, _) = derive_keypair_p256(master_secret, dao, "bob.testnet").unwrap();
+        assert!(decrypt_vote_p256(&bob_priv, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_key_epoch_changes_keypair() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let dao = "dao.testnet";
+
+        // Epoch 0 keeps the pre-rotation key
+        assert_eq!(epoch_user_account("alice.testnet", 0), "alice.testnet");
+
+        let original = derive_user_pubkey(master_secret, dao, &epoch_user_account("alice.testnet", 0)).unwrap();
+        let rotated = derive_user_pubkey(master_secret, dao, &epoch_user_account("alice.testnet", 1)).unwrap();
+        let rotated_again = derive_user_pubkey(master_secret, dao, &epoch_user_account("alice.testnet", 2)).unwrap();
+        assert_ne!(original, rotated);
+        assert_ne!(rotated, rotated_again);
+    }
+
+    #[test]
+    fn test_tagged_pubkey_roundtrip() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let (_, p256_pub) = derive_keypair_p256(master_secret, "dao.testnet", "alice.testnet").unwrap();
+        let k1_pub = derive_user_pubkey(master_secret, "dao.testnet", "alice.testnet").unwrap();
+
+        let tagged = tag_pubkey(KeyType::P256, &p256_pub);
+        assert_eq!(&tagged[..2], &[0x80, 0x02]);
+        assert_eq!(parse_tagged_pubkey(&tagged).unwrap(), (KeyType::P256, &p256_pub[..]));
+
+        let tagged = tag_pubkey(KeyType::Secp256k1, &k1_pub);
+        assert_eq!(hex::encode(&tagged[..2]), "8001");
+        assert_eq!(parse_tagged_pubkey(&tagged).unwrap(), (KeyType::Secp256k1, &k1_pub[..]));
+
+        assert!(parse_tagged_pubkey(&[0x80, 0x03, 0x04]).is_err());
+        assert!(parse_tagged_pubkey(&[0x80]).is_err());
+    }
+
+    #[test]
+    fn test_batch_encrypt_decrypt() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let keys: Vec<(Vec<u8>, Vec<u8>)> = ["alice.testnet", "bob.testnet", "carol.testnet"]
+            .iter()
+            .map(|user| derive_keypair(master_secret, "dao.testnet", user).unwrap())
+            .collect();
+        let pubkeys: Vec<&[u8]> = keys.iter().map(|(_, pubkey)| pubkey.as_slice()).collect();
+        let privkeys: Vec<&[u8]> = keys.iter().map(|(privkey, _)| privkey.as_slice()).collect();
+
+        let ciphertexts = batch_encrypt_distinct(&pubkeys, b"results are in").unwrap();
+        assert_eq!(ciphertexts.len(), 3);
+        // Fresh ephemeral key per recipient
+        assert_ne!(ciphertexts[0][..65], ciphertexts[1][..65]);
+
+        assert_eq!(batch_decrypt(&privkeys, &ciphertexts).unwrap(), vec!["results are in"; 3]);
+
+        // Wrong key order fails and names the ciphertext
+        let swapped = [privkeys[1], privkeys[0], privkeys[2]];
+        assert!(batch_decrypt(&swapped, &ciphertexts).unwrap_err().starts_with("Ciphertext 0:"));
+        assert!(batch_decrypt(&privkeys[..2], &ciphertexts).is_err());
+    }
 }
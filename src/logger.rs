@@ -0,0 +1,130 @@
+// Structured logging for the WASI worker
+//
+// OutLayer captures stderr separately from stdout (and the TEE may not expose
+// it at all), so log entries are collected in memory and returned as
+// `Output.logs` alongside the result. The contract can inspect them in its
+// callback when debugging.
+//
+// Every entry is also written to stderr as one JSON line
+// (`{"level":..,"msg":..,"ctx":..,"ts":..}`) for operators collecting it.
+//
+// The worker is single-threaded; a thread-local collector avoids threading a
+// logger through every function.
+
+use serde::Serialize;
+use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Single log line included in the output
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// "info", "warn" or "error"
+    pub level: &'static str,
+    pub message: String,
+    /// Structured details (see `log_event`)
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    pub ctx: serde_json::Value,
+}
+
+/// Line written to stderr for each entry
+#[derive(Serialize)]
+struct StderrLine<'a> {
+    level: &'a str,
+    msg: &'a str,
+    ctx: &'a serde_json::Value,
+    /// Worker clock, nanoseconds since the Unix epoch (0 if unavailable)
+    ts: u64,
+}
+
+/// Collects log entries for the current execution
+#[derive(Debug, Default)]
+pub struct Logger {
+    entries: Vec<LogEntry>,
+}
+
+impl Logger {
+    fn push(&mut self, entry: LogEntry) {
+        self.entries.push(entry);
+    }
+}
+
+thread_local! {
+    static LOGGER: RefCell<Logger> = RefCell::new(Logger::default());
+}
+
+/// Log `message` with structured details in `ctx` (e.g. `json!({ "user": .. })`)
+pub fn log_event(level: &'static str, message: impl Into<String>, ctx: serde_json::Value) {
+    let entry = LogEntry { level, message: message.into(), ctx };
+
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or(0);
+    let line = StderrLine { level, msg: &entry.message, ctx: &entry.ctx, ts };
+    eprintln!("{}", serde_json::to_string(&line).unwrap());
+
+    LOGGER.with(|logger| logger.borrow_mut().push(entry));
+}
+
+fn log(level: &'static str, message: impl Into<String>) {
+    log_event(level, message, serde_json::Value::Null);
+}
+
+/// Log progress information
+pub fn info(message: impl Into<String>) {
+    log("info", message);
+}
+
+/// Log a recoverable problem (e.g. a vote that couldn't be decrypted)
+pub fn warn(message: impl Into<String>) {
+    log("warn", message);
+}
+
+/// Log a failure
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn error(message: impl Into<String>) {
+    log("error", message);
+}
+
+/// Remove and return everything logged so far
+pub fn take() -> Vec<LogEntry> {
+    LOGGER.with(|logger| std::mem::take(&mut logger.borrow_mut().entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_collected_in_order_and_drained() {
+        take();
+
+        info("starting");
+        warn(format!("Failed to decrypt vote from {}: {}", "alice", "bad tag"));
+        error("giving up");
+
+        assert_eq!(
+            serde_json::to_value(take()).unwrap(),
+            serde_json::json!([
+                { "level": "info", "message": "starting" },
+                { "level": "warn", "message": "Failed to decrypt vote from alice: bad tag" },
+                { "level": "error", "message": "giving up" },
+            ])
+        );
+        assert!(take().is_empty());
+    }
+
+    #[test]
+    fn test_event_context_kept() {
+        take();
+
+        log_event("warn", "Failed to decrypt vote", serde_json::json!({ "user": "alice" }));
+
+        assert_eq!(
+            serde_json::to_value(take()).unwrap(),
+            serde_json::json!([
+                { "level": "warn", "message": "Failed to decrypt vote", "ctx": { "user": "alice" } },
+            ])
+        );
+    }
+}
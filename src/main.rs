@@ -14,16 +14,22 @@
 // and writes output to stdout as JSON, following OutLayer's execution model.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::{self, Read, Write};
 
 mod crypto;
+mod logger;
 mod tally;
 
+use crypto::KeyDerivationAlgorithm;
+use tally::{TallyOptions, VoteData};
+
 // Input structure for WASI execution
 // OutLayer passes this via stdin as JSON
 #[derive(Deserialize, Debug)]
 struct Input {
-    /// Action to perform: "derive_pubkey" or "tally_votes"
+    /// Action to perform: "derive_pubkey", "derive_proposal_pubkey", "tally_votes",
+    /// "tally_votes_chunk", "commit_chunked_tally", "replay_tally" or "compute_merkle_root_only"
     action: String,
 
     /// DAO account ID (e.g., "dao.near")
@@ -32,29 +38,117 @@ struct Input {
     /// User account ID (for derive_pubkey)
     user_account: Option<String>,
 
-    /// Proposal ID (for tally_votes)
+    /// Proposal ID (for tally_votes and derive_proposal_pubkey)
     proposal_id: Option<u64>,
 
-    /// Encrypted votes data (for tally_votes)
+    /// Encrypted votes data (for tally_votes, tally_votes_chunk and compute_merkle_root_only)
     votes: Option<Vec<VoteData>>,
 
     /// Quorum requirements (for tally_votes)
     quorum: Option<serde_json::Value>,
 
-}
+    /// How the user's key is derived (for derive_pubkey, default "HKDF")
+    #[serde(default)]
+    key_derivation_algorithm: KeyDerivationAlgorithm,
 
-// Single encrypted vote from contract storage
-#[derive(Deserialize, Debug)]
-struct VoteData {
-    /// Voter's NEAR account ID
-    user: String,
+    /// Voters whose keys were not derived with HKDF (for tally_votes and replay_tally)
+    /// user account → algorithm their pubkey was derived with
+    #[serde(default)]
+    key_derivation_algorithms: HashMap<String, KeyDerivationAlgorithm>,
+
+    /// Rotation count of the user's key (for derive_pubkey, default 0)
+    #[serde(default)]
+    key_epoch: u32,
+
+    /// Voters who rotated their key (for tally_votes and replay_tally)
+    /// user account → key epoch their pubkey was derived at
+    #[serde(default)]
+    key_epochs: HashMap<String, u32>,
+
+    /// Omit individual Merkle proofs from the tally output (for tally_votes)
+    /// Large proposals can exceed OutLayer output size limits otherwise.
+    /// Only the root and proof count are returned; proofs can be recomputed
+    /// off-chain since the WASI binary is reproducible.
+    #[serde(default)]
+    compress_merkle_proofs: bool,
+
+    /// Proposal creation time in nanoseconds (for tally_votes)
+    /// If present, vote timestamps are validated before counting
+    proposal_created_at: Option<u64>,
+
+    /// Current block timestamp in nanoseconds (for tally_votes)
+    current_timestamp: Option<u64>,
+
+    /// Include heap usage estimates in the tally output (for tally_votes)
+    /// Use to right-size OutLayer's `max_memory_mb`
+    #[serde(default)]
+    memory_profiling: bool,
+
+    /// Include a per-vote processing log in the tally output (for tally_votes)
+    /// WARNING: reveals each member's vote. Only for dispute resolution runs
+    /// whose output is not published on-chain.
+    #[serde(default)]
+    audit_mode: bool,
+
+    /// Votes were encrypted with proposal-scoped keys (for tally_votes)
+    #[serde(default)]
+    use_proposal_scoped_keys: bool,
+
+    /// Include a submission-time histogram in the tally output (for tally_votes)
+    /// Needs `proposal_created_at`
+    #[serde(default)]
+    include_histogram: bool,
+
+    /// Include submission statistics (`submission_gini`) in the tally output (for tally_votes)
+    #[serde(default)]
+    include_stats: bool,
 
-    /// ECIES encrypted vote (hex-encoded)
-    /// ECIES includes ephemeral key + nonce inside ciphertext
-    encrypted_vote: String,
+    /// Proposal deadline in nanoseconds (for tally_votes, sets the histogram span)
+    proposal_deadline: Option<u64>,
 
-    /// Block timestamp when vote was cast
-    timestamp: u64,
+    /// Members allowed to vote, snapshotted at proposal creation (for tally_votes)
+    /// Required for Unanimous and Percentage quorum
+    eligible_voters: Option<u64>,
+
+    /// Ballot choices of a multi-option proposal (for tally_votes, default "yes" / "no")
+    vote_options: Option<Vec<String>>,
+
+    /// Active vote delegations, delegator → delegate (for tally_votes and replay_tally)
+    #[serde(default)]
+    delegations: HashMap<String, String>,
+
+    /// Merkle root from an earlier tally attempt of the same votes (for tally_votes)
+    /// The tally fails if the votes no longer hash to it
+    previous_merkle_root: Option<String>,
+
+    /// Running tally returned by the previous chunk (for tally_votes_chunk and commit_chunked_tally)
+    /// Absent for the first chunk
+    sealed_tally: Option<String>,
+
+    /// Index of the chunk's first vote among the proposal's votes (for tally_votes_chunk)
+    chunk_offset: Option<u64>,
+
+    /// Index after the chunk's last vote (for tally_votes_chunk)
+    chunk_end: Option<u64>,
+
+    /// Number of votes on the proposal (for commit_chunked_tally)
+    vote_count: Option<u64>,
+
+    /// Only tally votes with `timestamp < include_votes_before_timestamp` (for replay_tally)
+    include_votes_before_timestamp: Option<u64>,
+
+    /// Only tally votes from these accounts (for replay_tally)
+    include_only_users: Option<Vec<String>>,
+
+    /// Report which secrets were injected (names and lengths, never values)
+    /// in the output's `metadata.env_audit` (any action)
+    #[serde(default)]
+    report_injected_env_vars: bool,
+
+    /// Hex seed for a reproducible RNG (see `worker_rng`)
+    /// Test builds only: release workers always use the OS RNG
+    #[cfg(test)]
+    seed_rng: Option<String>,
 }
 
 // Output structure returned via stdout
@@ -69,6 +163,128 @@ struct Output {
 
     /// Error message (if success=false)
     error: Option<String>,
+
+    /// Execution details for operators/auditors (only when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<OutputMetadata>,
+
+    /// Log entries collected during execution (replaces stderr output)
+    logs: Vec<logger::LogEntry>,
+}
+
+// Optional execution details attached to the output
+#[derive(Serialize, Debug, Default)]
+struct OutputMetadata {
+    /// Injected secrets report (if `report_injected_env_vars` was set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env_audit: Option<EnvAuditReport>,
+}
+
+// Environment variables the worker expects OutLayer to inject
+const AUDITED_ENV_VARS: &[&str] = &["PROTECTED_DAO_MASTER_SECRET"];
+
+// Which expected secrets were injected - names and byte lengths only, never values
+#[derive(Serialize, Debug, PartialEq)]
+struct EnvAuditReport {
+    env_vars_present: Vec<String>,
+    env_vars_absent: Vec<String>,
+    env_var_lengths: std::collections::BTreeMap<String, usize>,
+}
+
+// Build the report for `names`, reading values through `lookup` (std::env::var in main)
+fn env_audit_report(names: &[&str], lookup: impl Fn(&str) -> Option<String>) -> EnvAuditReport {
+    let mut report = EnvAuditReport {
+        env_vars_present: Vec::new(),
+        env_vars_absent: Vec::new(),
+        env_var_lengths: std::collections::BTreeMap::new(),
+    };
+
+    for name in names {
+        match lookup(name) {
+            Some(value) => {
+                report.env_vars_present.push(name.to_string());
+                report.env_var_lengths.insert(name.to_string(), value.len());
+            }
+            None => report.env_vars_absent.push(name.to_string()),
+        }
+    }
+
+    report
+}
+
+// Parse a `seed_rng` value: up to 16 hex digits, optional "0x" prefix
+#[cfg(test)]
+fn parse_hex_seed(seed: &str) -> Result<u64, String> {
+    let digits = seed.strip_prefix("0x").unwrap_or(seed);
+    if digits.is_empty() || digits.len() > 16 {
+        return Err(format!("seed_rng must be 1-16 hex digits, got {:?}", seed));
+    }
+    u64::from_str_radix(digits, 16).map_err(|e| format!("Invalid seed_rng {:?}: {}", seed, e))
+}
+
+// RNG for test scenarios: seeded `SmallRng` if `seed_rng` is set, OS RNG otherwise
+#[cfg(test)]
+fn worker_rng(seed_rng: Option<&str>) -> Result<Box<dyn rand::RngCore>, String> {
+    use rand::SeedableRng;
+
+    match seed_rng {
+        Some(seed) => Ok(Box::new(rand::rngs::SmallRng::seed_from_u64(parse_hex_seed(seed)?))),
+        None => Ok(Box::new(aes_gcm::aead::OsRng)),
+    }
+}
+
+// Required `Input` fields missing for the requested action
+// Returned as the `result` of a failed execution so callers can fix all fields at once
+#[derive(Serialize, Debug, PartialEq)]
+struct ValidationErrors {
+    action: String,
+    missing_fields: Vec<&'static str>,
+}
+
+// Check that every field the action needs is present
+// Unknown actions pass; dispatch reports them.
+fn validate_input(input: &Input) -> Result<(), ValidationErrors> {
+    let required: &[(&'static str, bool)] = match input.action.as_str() {
+        "derive_pubkey" => &[("user_account", input.user_account.is_some())],
+        "derive_proposal_pubkey" => &[
+            ("user_account", input.user_account.is_some()),
+            ("proposal_id", input.proposal_id.is_some()),
+        ],
+        "tally_votes" | "replay_tally" => &[
+            ("proposal_id", input.proposal_id.is_some()),
+            ("votes", input.votes.is_some()),
+            ("quorum", input.quorum.is_some()),
+        ],
+        "tally_votes_chunk" => &[
+            ("proposal_id", input.proposal_id.is_some()),
+            ("votes", input.votes.is_some()),
+            ("chunk_offset", input.chunk_offset.is_some()),
+            ("chunk_end", input.chunk_end.is_some()),
+        ],
+        "commit_chunked_tally" => &[
+            ("proposal_id", input.proposal_id.is_some()),
+            ("sealed_tally", input.sealed_tally.is_some()),
+            ("vote_count", input.vote_count.is_some()),
+            ("quorum", input.quorum.is_some()),
+        ],
+        "compute_merkle_root_only" => &[("votes", input.votes.is_some())],
+        _ => &[],
+    };
+
+    let missing_fields: Vec<&'static str> = required
+        .iter()
+        .filter(|(_, present)| !present)
+        .map(|(name, _)| *name)
+        .collect();
+
+    if missing_fields.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationErrors {
+            action: input.action.clone(),
+            missing_fields,
+        })
+    }
 }
 
 fn main() {
@@ -88,18 +304,37 @@ fn main() {
         }
     };
 
+    if let Err(errors) = validate_input(&input) {
+        output_validation_errors(&errors);
+        return;
+    }
+
+    // Reported even if the secret turns out to be missing - that's when it matters most
+    let metadata = input.report_injected_env_vars.then(|| OutputMetadata {
+        env_audit: Some(env_audit_report(AUDITED_ENV_VARS, |name| std::env::var(name).ok())),
+    });
+
+    // No decryption involved: runs without the master secret
+    if input.action == "compute_merkle_root_only" {
+        output_result(handle_compute_merkle_root_only(&input), metadata);
+        return;
+    }
+
     // Get master_secret from environment (injected by OutLayer from Keymaster)
     // This secret never leaves TEE and is used to derive all user keys
     let master_secret = match std::env::var("PROTECTED_DAO_MASTER_SECRET") {
         Ok(s) => match hex::decode(&s) {
             Ok(bytes) => bytes,
             Err(e) => {
-                output_error(&format!("Invalid PROTECTED_DAO_MASTER_SECRET hex: {}", e));
+                output_result(Err(format!("Invalid PROTECTED_DAO_MASTER_SECRET hex: {}", e)), metadata);
                 return;
             }
         },
         Err(_) => {
-            output_error("Missing PROTECTED_DAO_MASTER_SECRET environment variable");
+            output_result(
+                Err("Missing PROTECTED_DAO_MASTER_SECRET environment variable".to_string()),
+                metadata,
+            );
             return;
         }
     };
@@ -107,15 +342,16 @@ fn main() {
     // Dispatch based on action
     let result = match input.action.as_str() {
         "derive_pubkey" => handle_derive_pubkey(&master_secret, &input),
+        "derive_proposal_pubkey" => handle_derive_proposal_pubkey(&master_secret, &input),
         "tally_votes" => handle_tally_votes(&master_secret, &input),
+        "tally_votes_chunk" => handle_tally_votes_chunk(&master_secret, &input),
+        "commit_chunked_tally" => handle_commit_chunked_tally(&master_secret, &input),
+        "replay_tally" => handle_replay_tally(&master_secret, &input),
         _ => Err(format!("Unknown action: {}", input.action)),
     };
 
     // Output result
-    match result {
-        Ok(data) => output_success(data),
-        Err(e) => output_error(&e),
-    }
+    output_result(result, metadata);
 }
 
 // Action: Derive user's public encryption key
@@ -130,10 +366,41 @@ fn handle_derive_pubkey(
         .ok_or("Missing user_account")?;
 
     // Derive user's keypair from master secret
-    // This is deterministic: same inputs always produce same key
-    let pubkey = crypto::derive_user_pubkey(master_secret, &input.dao_account, user_account)?;
+    // This is deterministic: same inputs (including the key epoch) always produce same key
+    let (_privkey, pubkey) = input.key_derivation_algorithm.derive_keypair(
+        master_secret,
+        &input.dao_account,
+        &crypto::epoch_user_account(user_account, input.key_epoch),
+    )?;
+
+    // Return hex-encoded public key (33 bytes compressed) and how it was derived
+    // (the contract stores the algorithm and epoch so tallies can re-derive the private key)
+    Ok(serde_json::json!({
+        "pubkey": hex::encode(&pubkey),
+        "key_derivation_algorithm": input.key_derivation_algorithm,
+        "key_epoch": input.key_epoch
+    }))
+}
+
+// Action: Derive user's public encryption key for a single proposal
+// Called when the DAO uses proposal-scoped keys
+fn handle_derive_proposal_pubkey(
+    master_secret: &[u8],
+    input: &Input,
+) -> Result<serde_json::Value, String> {
+    let user_account = input
+        .user_account
+        .as_ref()
+        .ok_or("Missing user_account")?;
+    let proposal_id = input.proposal_id.ok_or("Missing proposal_id")?;
+
+    let (_privkey, pubkey) = crypto::derive_proposal_keypair(
+        master_secret,
+        &input.dao_account,
+        user_account,
+        proposal_id,
+    )?;
 
-    // Return hex-encoded public key (33 bytes compressed)
     Ok(serde_json::json!({
         "pubkey": hex::encode(&pubkey)
     }))
@@ -144,30 +411,176 @@ fn handle_derive_pubkey(
 fn handle_tally_votes(
     master_secret: &[u8],
     input: &Input,
+) -> Result<serde_json::Value, String> {
+    let votes_data = input.votes.as_ref().ok_or("Missing votes")?;
+
+    run_tally(master_secret, input, votes_data)
+}
+
+// Action: Tally one chunk of a proposal's votes (contract's `finalize_proposal_chunk`)
+// The contract sends every ballot of the voters assigned to the chunk together
+// with the running tally sealed by the previous chunk. Only the new sealed
+// tally is returned: chunk counts never leave the TEE.
+fn handle_tally_votes_chunk(
+    master_secret: &[u8],
+    input: &Input,
+) -> Result<serde_json::Value, String> {
+    let proposal_id = input.proposal_id.ok_or("Missing proposal_id")?;
+    let votes_data = input.votes.as_ref().ok_or("Missing votes")?;
+    let chunk_offset = input.chunk_offset.ok_or("Missing chunk_offset")?;
+    let chunk_end = input.chunk_end.ok_or("Missing chunk_end")?;
+
+    let result = tally::tally_votes_chunk(
+        master_secret,
+        &input.dao_account,
+        proposal_id,
+        votes_data,
+        input.sealed_tally.as_deref(),
+        chunk_offset..chunk_end,
+        &tally_options(input),
+    )?;
+
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
+// Action: Decide a chunked tally (contract's `commit_finalization`)
+// Same output as tally_votes, without Merkle root or proofs
+fn handle_commit_chunked_tally(
+    master_secret: &[u8],
+    input: &Input,
 ) -> Result<serde_json::Value, String> {
     let proposal_id = input.proposal_id.ok_or("Missing proposal_id")?;
+    let sealed_tally = input.sealed_tally.as_deref().ok_or("Missing sealed_tally")?;
+    let vote_count = input.vote_count.ok_or("Missing vote_count")?;
+    let quorum = input.quorum.as_ref().ok_or("Missing quorum")?;
+
+    let result = tally::commit_chunked_tally(
+        master_secret,
+        &input.dao_account,
+        proposal_id,
+        sealed_tally,
+        vote_count,
+        quorum,
+        &tally_options(input),
+    )?;
+
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
+// Action: Merkle root of the votes without tallying
+// Lets front-ends check the root stored on-chain without paying for decryption
+fn handle_compute_merkle_root_only(input: &Input) -> Result<serde_json::Value, String> {
+    let votes_data = input.votes.as_ref().ok_or("Missing votes")?;
+    let (votes_merkle_root, _proofs) = tally::build_merkle_tree_with_proofs(votes_data);
+
+    Ok(serde_json::json!({
+        "votes_merkle_root": votes_merkle_root,
+        "vote_count": votes_data.len(),
+    }))
+}
+
+// Action: Tally a subset of the votes (debugging, e.g. "result as of time T")
+// Same output as tally_votes; nothing is stored on-chain. The Merkle root
+// covers only the included votes, so it won't match the proposal's root, and
+// `counted_votes_root` commits to positions among the included votes.
+fn handle_replay_tally(
+    master_secret: &[u8],
+    input: &Input,
+) -> Result<serde_json::Value, String> {
     let votes_data = input.votes.as_ref().ok_or("Missing votes")?;
+
+    let included: Vec<VoteData> = votes_data
+        .iter()
+        .filter(|vote| {
+            input
+                .include_votes_before_timestamp
+                .is_none_or(|cutoff| vote.timestamp < cutoff)
+        })
+        .filter(|vote| {
+            input
+                .include_only_users
+                .as_ref()
+                .is_none_or(|users| users.contains(&vote.user))
+        })
+        .cloned()
+        .collect();
+
+    logger::info(format!(
+        "Replaying tally with {} of {} votes",
+        included.len(),
+        votes_data.len()
+    ));
+
+    run_tally(master_secret, input, &included)
+}
+
+// Shared by tally_votes and replay_tally
+fn run_tally(
+    master_secret: &[u8],
+    input: &Input,
+    votes_data: &[VoteData],
+) -> Result<serde_json::Value, String> {
+    let proposal_id = input.proposal_id.ok_or("Missing proposal_id")?;
     let quorum = input.quorum.as_ref().ok_or("Missing quorum")?;
 
     // Tally votes: decrypt all, filter real votes, count yes/no, check quorum
-    let result = tally::tally_votes(
+    let mut result = tally::tally_votes(
         master_secret,
         &input.dao_account,
         proposal_id,
         votes_data,
         quorum,
+        &tally_options(input),
     )?;
 
+    // Drop proofs from the output if requested (root and count are kept)
+    if input.compress_merkle_proofs {
+        result.merkle_proofs.clear();
+        result.merkle_proofs_available = false;
+    }
+
     // Return result as JSON
-    Ok(serde_json::to_value(result).map_err(|e| e.to_string())?)
+    serde_json::to_value(result).map_err(|e| e.to_string())
 }
 
-// Output success result to stdout
-fn output_success(result: serde_json::Value) {
-    let output = Output {
-        success: true,
-        result,
-        error: None,
+// Tally options taken from the input (shared by all tally actions)
+fn tally_options(input: &Input) -> TallyOptions {
+    TallyOptions {
+        proposal_created_at: input.proposal_created_at,
+        current_timestamp: input.current_timestamp,
+        memory_profiling: input.memory_profiling,
+        audit_mode: input.audit_mode,
+        use_proposal_scoped_keys: input.use_proposal_scoped_keys,
+        include_histogram: input.include_histogram,
+        proposal_deadline: input.proposal_deadline,
+        previous_merkle_root: input.previous_merkle_root.clone(),
+        include_stats: input.include_stats,
+        key_derivation_algorithms: input.key_derivation_algorithms.clone(),
+        key_epochs: input.key_epochs.clone(),
+        eligible_voters: input.eligible_voters,
+        vote_options: input.vote_options.clone(),
+        delegations: input.delegations.clone(),
+        tee_attestation_report: std::env::var("TEE_ATTESTATION_REPORT").ok(),
+    }
+}
+
+// Output action result (success or error) with optional metadata to stdout
+fn output_result(result: Result<serde_json::Value, String>, metadata: Option<OutputMetadata>) {
+    let output = match result {
+        Ok(result) => Output {
+            success: true,
+            result,
+            error: None,
+            metadata,
+            logs: logger::take(),
+        },
+        Err(e) => Output {
+            success: false,
+            result: serde_json::Value::Null,
+            error: Some(e),
+            metadata,
+            logs: logger::take(),
+        },
     };
 
     print!("{}", serde_json::to_string(&output).unwrap());
@@ -180,8 +593,278 @@ fn output_error(message: &str) {
         success: false,
         result: serde_json::Value::Null,
         error: Some(message.to_string()),
+        metadata: None,
+        logs: logger::take(),
+    };
+
+    print!("{}", serde_json::to_string(&output).unwrap());
+    io::stdout().flush().unwrap();
+}
+
+// Output input validation errors (details in `result`, summary in `error`)
+fn output_validation_errors(errors: &ValidationErrors) {
+    let output = Output {
+        success: false,
+        result: serde_json::to_value(errors).unwrap(),
+        error: Some(format!(
+            "Missing required fields for {}: {}",
+            errors.action,
+            errors.missing_fields.join(", ")
+        )),
+        metadata: None,
+        logs: logger::take(),
     };
 
     print!("{}", serde_json::to_string(&output).unwrap());
     io::stdout().flush().unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: serde_json::Value) -> Input {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_validate_reports_missing_fields_per_action() {
+        let cases = [
+            ("derive_pubkey", vec!["user_account"]),
+            ("derive_proposal_pubkey", vec!["user_account", "proposal_id"]),
+            ("tally_votes", vec!["proposal_id", "votes", "quorum"]),
+            ("tally_votes_chunk", vec!["proposal_id", "votes", "chunk_offset", "chunk_end"]),
+            ("commit_chunked_tally", vec!["proposal_id", "sealed_tally", "vote_count", "quorum"]),
+            ("compute_merkle_root_only", vec!["votes"]),
+        ];
+
+        for (action, missing) in cases {
+            let input = parse(serde_json::json!({ "action": action, "dao_account": "dao.testnet" }));
+            let errors = validate_input(&input).unwrap_err();
+
+            assert_eq!(
+                serde_json::to_value(&errors).unwrap(),
+                serde_json::json!({ "action": action, "missing_fields": missing })
+            );
+        }
+    }
+
+    #[test]
+    fn test_compute_merkle_root_only_matches_tally_root() {
+        let input = parse(serde_json::json!({
+            "action": "compute_merkle_root_only",
+            "dao_account": "dao.testnet",
+            "votes": [
+                { "user": "alice.testnet", "encrypted_vote": "abcd", "timestamp": 1 },
+                { "user": "bob.testnet", "encrypted_vote": "ef01", "timestamp": 2 },
+                { "user": "carol.testnet", "encrypted_vote": "not even hex", "timestamp": 3 }
+            ]
+        }));
+        assert!(validate_input(&input).is_ok());
+
+        let (expected_root, _) = tally::build_merkle_tree_with_proofs(input.votes.as_ref().unwrap());
+        assert_eq!(
+            handle_compute_merkle_root_only(&input).unwrap(),
+            serde_json::json!({ "votes_merkle_root": expected_root, "vote_count": 3 })
+        );
+    }
+
+    #[test]
+    fn test_validate_partial_tally_input() {
+        let input = parse(serde_json::json!({
+            "action": "tally_votes",
+            "dao_account": "dao.testnet",
+            "proposal_id": 1,
+            "votes": []
+        }));
+
+        assert_eq!(validate_input(&input).unwrap_err().missing_fields, vec!["quorum"]);
+    }
+
+    #[test]
+    fn test_replay_tally_requires_tally_fields() {
+        let input = parse(serde_json::json!({ "action": "replay_tally", "dao_account": "dao.testnet" }));
+
+        assert_eq!(
+            validate_input(&input).unwrap_err().missing_fields,
+            vec!["proposal_id", "votes", "quorum"]
+        );
+    }
+
+    #[test]
+    fn test_replay_tally_filters_votes() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let vote = |user: &str, choice: &str, timestamp: u64| {
+            let pubkey = crypto::derive_user_pubkey(master_secret, "dao.testnet", user).unwrap();
+            let ciphertext = crypto::encrypt_vote(&pubkey, choice.as_bytes()).unwrap();
+            serde_json::json!({ "user": user, "encrypted_vote": hex::encode(ciphertext), "timestamp": timestamp })
+        };
+        let replay = |extra: serde_json::Value| {
+            let mut json = serde_json::json!({
+                "action": "replay_tally",
+                "dao_account": "dao.testnet",
+                "proposal_id": 1,
+                "quorum": { "Absolute": { "min_votes": 1 } },
+                "votes": [
+                    vote("alice", "yes", 1000),
+                    vote("bob", "no", 2000),
+                    vote("alice", "no", 3000),
+                ]
+            });
+            json.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            handle_replay_tally(master_secret, &parse(json)).unwrap()
+        };
+
+        let before = replay(serde_json::json!({ "include_votes_before_timestamp": 2500 }));
+        assert_eq!((before["yes_count"].as_u64(), before["no_count"].as_u64()), (Some(1), Some(1)));
+
+        let all = replay(serde_json::json!({}));
+        assert_eq!((all["yes_count"].as_u64(), all["no_count"].as_u64()), (Some(0), Some(2)));
+
+        let bob_only = replay(serde_json::json!({ "include_only_users": ["bob"] }));
+        assert_eq!(bob_only["total_votes"], 1);
+        assert_eq!(bob_only["merkle_proofs"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_chunked_tally_keeps_chunk_counts_sealed() {
+        let master_secret = b"test_secret_32_bytes_long_xxxx!!";
+        let vote = |user: &str, choice: &str, timestamp: u64| {
+            let pubkey = crypto::derive_user_pubkey(master_secret, "dao.testnet", user).unwrap();
+            let ciphertext = crypto::encrypt_vote(&pubkey, choice.as_bytes()).unwrap();
+            serde_json::json!({ "user": user, "encrypted_vote": hex::encode(ciphertext), "timestamp": timestamp })
+        };
+        let chunk = |votes: serde_json::Value, offset: u64, end: u64, sealed: Option<&str>| {
+            let input = parse(serde_json::json!({
+                "action": "tally_votes_chunk",
+                "dao_account": "dao.testnet",
+                "proposal_id": 1,
+                "votes": votes,
+                "chunk_offset": offset,
+                "chunk_end": end,
+                "sealed_tally": sealed
+            }));
+            assert!(validate_input(&input).is_ok());
+            handle_tally_votes_chunk(master_secret, &input)
+        };
+        let commit = |sealed: &str, vote_count: u64, min_votes: u64| {
+            let input = parse(serde_json::json!({
+                "action": "commit_chunked_tally",
+                "dao_account": "dao.testnet",
+                "proposal_id": 1,
+                "sealed_tally": sealed,
+                "vote_count": vote_count,
+                "quorum": { "Absolute": { "min_votes": min_votes } }
+            }));
+            assert!(validate_input(&input).is_ok());
+            handle_commit_chunked_tally(master_secret, &input)
+        };
+
+        // A one-vote chunk reveals nothing but the sealed running tally
+        let first = chunk(serde_json::json!([vote("alice", "yes", 1000)]), 0, 1, None).unwrap();
+        assert_eq!(
+            first.as_object().unwrap().keys().collect::<Vec<_>>(),
+            vec!["next_offset", "proposal_id", "sealed_tally"]
+        );
+        let sealed = first["sealed_tally"].as_str().unwrap();
+
+        // Chunks must continue where the sealed tally ends
+        assert!(chunk(serde_json::json!([vote("bob", "no", 2000)]), 0, 1, Some(sealed)).is_err());
+        let second =
+            chunk(serde_json::json!([vote("bob", "no", 2000), vote("carol", "maybe", 2500)]), 1, 3, Some(sealed))
+                .unwrap();
+        let sealed = second["sealed_tally"].as_str().unwrap();
+
+        assert!(commit(sealed, 4, 1).unwrap_err().contains("covers 3 of 4 votes"));
+
+        let hidden = commit(sealed, 3, 3).unwrap();
+        assert!(hidden.get("yes_count").is_none());
+        assert_eq!(hidden["total_votes"], 0);
+
+        let decided = commit(sealed, 3, 2).unwrap();
+        assert_eq!((decided["yes_count"].as_u64(), decided["no_count"].as_u64()), (Some(1), Some(1)));
+        assert_eq!(decided["total_votes"], 2);
+        assert_eq!(decided["merkle_proofs_available"], false);
+    }
+
+    #[test]
+    fn test_env_audit_report_names_and_lengths_only() {
+        let secret = "ab".repeat(32);
+        let env = |name: &str| match name {
+            "PROTECTED_DAO_MASTER_SECRET" => Some(secret.clone()),
+            _ => None,
+        };
+
+        let report = env_audit_report(&["PROTECTED_DAO_MASTER_SECRET", "PROTECTED_BACKUP_SECRET"], env);
+
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "env_vars_present": ["PROTECTED_DAO_MASTER_SECRET"],
+                "env_vars_absent": ["PROTECTED_BACKUP_SECRET"],
+                "env_var_lengths": { "PROTECTED_DAO_MASTER_SECRET": 64 }
+            })
+        );
+        assert!(!serde_json::to_string(&report).unwrap().contains(&secret));
+    }
+
+    #[test]
+    fn test_env_audit_report_nothing_injected() {
+        let report = env_audit_report(AUDITED_ENV_VARS, |_| None);
+
+        assert!(report.env_vars_present.is_empty());
+        assert_eq!(report.env_vars_absent, vec!["PROTECTED_DAO_MASTER_SECRET"]);
+        assert!(report.env_var_lengths.is_empty());
+    }
+
+    #[test]
+    fn test_validate_accepts_complete_input() {
+        let input = parse(serde_json::json!({
+            "action": "derive_pubkey",
+            "dao_account": "dao.testnet",
+            "user_account": "alice.testnet"
+        }));
+
+        assert!(validate_input(&input).is_ok());
+    }
+
+    #[test]
+    fn test_parse_hex_seed() {
+        assert_eq!(parse_hex_seed("2a").unwrap(), 42);
+        assert_eq!(parse_hex_seed("0xFFFFFFFFFFFFFFFF").unwrap(), u64::MAX);
+        assert!(parse_hex_seed("").is_err());
+        assert!(parse_hex_seed("0x").is_err());
+        assert!(parse_hex_seed("xyz").is_err());
+        assert!(parse_hex_seed("10000000000000000").is_err());
+    }
+
+    #[test]
+    fn test_seeded_dummy_ciphertexts_are_reproducible() {
+        let (privkey, pubkey) = crypto::derive_keypair(b"test_secret", "dao.testnet", "alice.testnet").unwrap();
+        let input = parse(serde_json::json!({
+            "action": "tally_votes",
+            "dao_account": "dao.testnet",
+            "seed_rng": "0xdeadbeef"
+        }));
+
+        let dummies = |seed: Option<&str>| -> Vec<Vec<u8>> {
+            let mut rng = worker_rng(seed).unwrap();
+            (0..5).map(|_| crypto::generate_dummy_ciphertext(&mut *rng, &pubkey)).collect()
+        };
+
+        let first = dummies(input.seed_rng.as_deref());
+        assert_eq!(first, dummies(Some("deadbeef")));
+        assert_ne!(first, dummies(Some("deadbeee")));
+        assert_ne!(dummies(None), dummies(None));
+
+        let real_vote = crypto::encrypt_vote(&pubkey, b"yes").unwrap();
+        for ciphertext in &first {
+            // Indistinguishable by length, decrypts to something that isn't a vote
+            assert_eq!(ciphertext.len(), real_vote.len());
+            let plaintext = crypto::decrypt_vote_with_key(&privkey, ciphertext).unwrap();
+            assert!(plaintext != "yes" && plaintext != "no", "dummy decrypted to {}", plaintext);
+        }
+
+        assert!(worker_rng(Some("not hex")).is_err());
+    }
+}
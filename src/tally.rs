@@ -12,11 +12,35 @@
 // - Only aggregate result (yes/no counts) returned
 // - Optionally: only return passed/failed (hide exact counts)
 
-use crate::crypto;
-use crate::VoteData;
-use serde::Serialize;
+use crate::crypto::{self, KeyDerivationAlgorithm};
+use crate::logger;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Single encrypted vote from contract storage
+#[derive(Deserialize, Debug, Clone)]
+pub struct VoteData {
+    /// Voter's NEAR account ID
+    pub user: String,
+
+    /// ECIES encrypted vote (hex-encoded)
+    /// ECIES includes ephemeral key + nonce inside ciphertext
+    /// JSON `null` is read as an empty string (treated as a dummy)
+    #[serde(deserialize_with = "deserialize_null_as_empty")]
+    pub encrypted_vote: String,
+
+    /// Block timestamp when vote was cast
+    pub timestamp: u64,
+}
+
+/// Deserialize a string that may be JSON `null` (client bugs), mapping `null` to ""
+fn deserialize_null_as_empty<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 /// Merkle proof for a single vote
 #[derive(Serialize, Debug, Clone)]
 pub struct MerkleProof {
@@ -36,25 +60,163 @@ pub struct MerkleProof {
     pub timestamp: u64,
 }
 
+/// Optional tally parameters (all default to "not provided")
+#[derive(Debug, Default)]
+pub struct TallyOptions {
+    /// Proposal creation time (nanoseconds)
+    /// Enables timestamp validation when present
+    pub proposal_created_at: Option<u64>,
+
+    /// Current block time as seen by the contract (nanoseconds)
+    /// Falls back to the worker's clock if not provided
+    pub current_timestamp: Option<u64>,
+
+    /// Attach heap usage estimates to the result (`TallyResult::memory_profile`)
+    pub memory_profiling: bool,
+
+    /// Attach a per-vote processing log to the result (`TallyResult::audit_trail`)
+    /// WARNING: reveals how each member voted - for dispute resolution only
+    pub audit_mode: bool,
+
+    /// Votes are encrypted with proposal-scoped keys (`derive_proposal_keypair`)
+    pub use_proposal_scoped_keys: bool,
+
+    /// Attach a submission-time histogram to the result (`TallyResult::voting_histogram`)
+    /// Requires `proposal_created_at`
+    pub include_histogram: bool,
+
+    /// Proposal deadline in nanoseconds (sets the histogram's time span)
+    pub proposal_deadline: Option<u64>,
+
+    /// Merkle root of an earlier tally attempt (see `verify_votes_integrity`)
+    pub previous_merkle_root: Option<String>,
+
+    /// Attach submission statistics to the result (`TallyResult::submission_gini`)
+    pub include_stats: bool,
+
+    /// Voters whose DAO-wide keys weren't derived with HKDF (user → algorithm)
+    /// Ignored with `use_proposal_scoped_keys` (proposal keys are always HKDF)
+    pub key_derivation_algorithms: HashMap<String, KeyDerivationAlgorithm>,
+
+    /// Voters who rotated their DAO-wide key (user → key epoch, absent = 0)
+    /// Ignored with `use_proposal_scoped_keys`
+    pub key_epochs: HashMap<String, u32>,
+
+    /// Members allowed to vote, snapshotted at proposal creation
+    /// Required for Unanimous and Percentage quorum
+    pub eligible_voters: Option<u64>,
+
+    /// Ballot choices of a multi-option proposal (None = "yes" / "no" / "abstain")
+    pub vote_options: Option<Vec<String>>,
+
+    /// Active vote delegations (delegator → delegate)
+    /// Delegators are credited with their final delegate's ballot
+    pub delegations: HashMap<String, String>,
+
+    /// Base64 SGX quote injected by OutLayer (`TEE_ATTESTATION_REPORT`)
+    /// None outside a TEE: the result then carries the MVP placeholder attestation
+    pub tee_attestation_report: Option<String>,
+}
+
+/// Ballot choices of a proposal without `vote_options`
+/// "abstain" counts towards quorum but is neutral for the outcome
+const DEFAULT_VOTE_OPTIONS: [&str; 3] = ["yes", "no", "abstain"];
+
+/// Longest delegation chain followed by the tally (matches the contract's limit)
+const MAX_DELEGATION_DEPTH: usize = 10;
+
+/// Per-vote processing log (see `generate_audit_trail`)
+#[derive(Serialize, Debug, Default)]
+pub struct AuditTrail {
+    /// Votes that made it into the tally (one per user)
+    pub counted_votes: Vec<AuditEntry>,
+
+    /// Votes that were ignored, with the reason
+    pub skipped_votes: Vec<SkippedEntry>,
+
+    /// Real votes replaced by a later real vote from the same user
+    pub override_votes: Vec<OverriddenEntry>,
+}
+
+/// A counted vote
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub user: String,
+    pub timestamp: u64,
+    /// The option counted ("yes", "no" or "abstain" unless the proposal has `vote_options`)
+    pub counted_as: String,
+}
+
+/// An ignored vote
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SkippedEntry {
+    pub user: String,
+    pub timestamp: u64,
+    /// "decryption_failed", "dummy" or "superseded"
+    pub reason: String,
+}
+
+/// A real vote replaced by a newer one
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct OverriddenEntry {
+    pub user: String,
+    pub old_timestamp: u64,
+    pub new_timestamp: u64,
+}
+
+/// Estimated heap usage at key points of `tally_votes`
+///
+/// Estimates are computed from the size of live data structures (not a real
+/// allocator hook), so treat them as a lower bound when sizing `max_memory_mb`.
+#[derive(Serialize, Debug, Default)]
+pub struct MemoryProfile {
+    /// Input votes (as deserialized from stdin)
+    pub after_load_bytes: usize,
+
+    /// Input votes + derived keys + per-user vote map
+    pub after_decryption_bytes: usize,
+
+    /// Input votes + Merkle tree levels + proofs (decryption state dropped)
+    pub after_merkle_bytes: usize,
+
+    /// Largest of the checkpoints above
+    pub peak_estimated_bytes: usize,
+}
+
 /// Result of vote tallying
 #[derive(Serialize, Debug)]
 pub struct TallyResult {
     /// Proposal ID that was tallied
     pub proposal_id: u64,
 
-    /// Number of "yes" votes (only included if quorum met)
+    /// Number of "yes" votes (only included if quorum met and "yes" is an option)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub yes_count: Option<u32>,
 
-    /// Number of "no" votes (only included if quorum met)
+    /// Number of "no" votes (only included if quorum met and "no" is an option)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub no_count: Option<u32>,
 
-    /// Total valid votes (yes + no)
+    /// Number of "abstain" votes (only included if quorum met and "abstain" is an option)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abstain_count: Option<u32>,
+
+    /// Votes per option, including options nobody chose (empty if quorum not met)
+    pub vote_counts: HashMap<String, u32>,
+
+    /// Total valid votes (one per voter)
     pub total_votes: u32,
 
+    /// Ballots that failed to decode or decrypt (left out of the tally)
+    /// Reveals nothing about how anyone voted, so it's shown even without quorum
+    pub spoiled_votes: u32,
+
+    /// Sum of vote weights (equals `total_votes` unless quorum is TimeWeighted)
+    /// Hidden (0.0) if quorum not met
+    pub weighted_total: f32,
+
     /// TEE attestation (proof of execution in trusted environment)
-    /// In MVP: placeholder, Phase 2: real SGX/SEV attestation
+    /// `AttestationReport` JSON inside a TEE, "mvp-attestation:..." placeholder outside
     pub tee_attestation: String,
 
     /// Merkle root of all encrypted votes (for verification)
@@ -62,13 +224,46 @@ pub struct TallyResult {
 
     /// Merkle proofs for each vote (allows voters to verify inclusion)
     pub merkle_proofs: Vec<MerkleProof>,
+
+    /// Commitment to the counted ballots (see `counted_votes_root`)
+    /// Empty if quorum not met
+    pub counted_votes_root: String,
+
+    /// Number of Merkle proofs generated (one per vote)
+    /// Stays accurate even when proofs are omitted from the output
+    pub merkle_proof_count: usize,
+
+    /// False if proofs were omitted to keep the output small
+    /// (they can be recomputed off-chain from the same votes)
+    pub merkle_proofs_available: bool,
+
+    /// Heap usage estimates (only if `memory_profiling` was requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_profile: Option<MemoryProfile>,
+
+    /// Per-vote processing log (only if `audit_mode` was requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_trail: Option<AuditTrail>,
+
+    /// `(bin_start_ns, submission_count)` pairs (only if `include_histogram` was requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voting_histogram: Option<Vec<(u64, u64)>>,
+
+    /// Gini coefficient of submissions per user (only if `include_stats` was requested)
+    ///
+    /// 0.0 = every voter submitted equally often, towards 1.0 = a few accounts
+    /// made most submissions. Dummies and re-votes count, so above ~0.7 a few
+    /// users flooded the proposal - possibly noise generation or an attack on
+    /// the tally's cost. Submitters are public on-chain; this reveals no votes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission_gini: Option<f32>,
 }
 
 /// Tally all votes for a proposal
 ///
 /// This function is the core of the anonymous voting system. It:
 /// 1. Decrypts each vote using the voter's derived private key
-/// 2. Filters real votes ("yes"/"no") from dummy messages (noise)
+/// 2. Filters real votes (one of the proposal's options) from dummy messages (noise)
 /// 3. Handles multiple votes per user (last vote wins)
 /// 4. Computes final tally
 ///
@@ -77,6 +272,8 @@ pub struct TallyResult {
 /// * `dao_account` - DAO contract account ID
 /// * `proposal_id` - Proposal being tallied
 /// * `votes_data` - All encrypted votes from contract storage
+/// * `quorum` - Quorum requirements (JSON-encoded `QuorumType`)
+/// * `options` - Optional parameters (see `TallyOptions`)
 ///
 /// # Returns
 /// * `Ok(TallyResult)` - Tallying result with counts and attestation
@@ -90,14 +287,21 @@ pub struct TallyResult {
 /// # Vote Filtering Rules
 /// - "yes" → counted as yes vote
 /// - "no" → counted as no vote
+/// - "abstain" → counted as abstention (towards quorum, not for or against)
+/// - With `options.vote_options`, each listed option is counted instead of yes/no
 /// - Anything else → ignored as dummy/noise
 /// - Empty string → ignored
 /// - Random text → ignored
 ///
+/// # Delegation
+/// Members listed in `options.delegations` are counted with the ballot of the
+/// member at the end of their delegation chain (if that member voted). Their
+/// own ballots are ignored. Circular or over-long chains are ignored.
+///
 /// # Multiple Votes
 /// If a user submits multiple messages:
 /// - All messages are stored on-chain (with timestamps)
-/// - Only the LAST real vote ("yes" or "no") is counted
+/// - Only the LAST real vote ("yes", "no" or "abstain") is counted
 /// - Dummy messages don't affect the tally
 /// - This allows users to change their vote before deadline
 ///
@@ -109,7 +313,7 @@ pub struct TallyResult {
 ///     VoteData { user: "bob.near", encrypted_vote: "2345...", nonce: "", timestamp: 1500 },
 /// ];
 ///
-/// let result = tally_votes(&master_secret, "dao.near", 42, &votes)?;
+/// let result = tally_votes(&master_secret, "dao.near", 42, &votes, &quorum, &TallyOptions::default())?;
 /// assert_eq!(result.total_votes, 2); // Alice and Bob
 /// ```
 pub fn tally_votes(
@@ -118,46 +322,140 @@ pub fn tally_votes(
     proposal_id: u64,
     votes_data: &[VoteData],
     quorum: &serde_json::Value,
+    options: &TallyOptions,
 ) -> Result<TallyResult, String> {
+    // Reject votes with impossible timestamps before deduplication
+    // (a far-future timestamp would otherwise always win "last vote")
+    if let Some(proposal_created_at) = options.proposal_created_at {
+        let current_time_estimate = options.current_timestamp.unwrap_or_else(now_ns);
+        validate_vote_timestamps(votes_data, proposal_created_at, current_time_estimate)?;
+    }
+
+    // Votes must be unchanged since an earlier (e.g. failed) tally attempt
+    if let Some(previous_root) = &options.previous_merkle_root {
+        verify_votes_integrity(votes_data, Some(previous_root))?;
+    }
+
+    let mut memory_profile = MemoryProfile {
+        after_load_bytes: estimate_votes_bytes(votes_data),
+        ..Default::default()
+    };
+
+    let vote_options: Vec<&str> = match &options.vote_options {
+        Some(vote_options) => vote_options.iter().map(String::as_str).collect(),
+        None => DEFAULT_VOTE_OPTIONS.to_vec(),
+    };
+
     // Map to track last vote per user
     // Key: user account ID
     // Value: (decrypted_vote, timestamp)
     let mut user_votes: HashMap<String, (String, u64)> = HashMap::new();
 
+    // Votes ignored before deduplication (for the audit trail)
+    let mut skipped: Vec<SkippedEntry> = Vec::new();
+    let mut skip = |vote_data: &VoteData, reason: &str| {
+        skipped.push(SkippedEntry {
+            user: vote_data.user.clone(),
+            timestamp: vote_data.timestamp,
+            reason: reason.to_string(),
+        })
+    };
+
+    // Derive each voter's private key once
+    let mut voters: Vec<&str> = votes_data.iter().map(|v| v.user.as_str()).collect();
+    voters.sort_unstable();
+    voters.dedup();
+    let privkeys: HashMap<&str, Vec<u8>> = if options.use_proposal_scoped_keys {
+        voters
+            .into_iter()
+            .map(|user| {
+                crypto::derive_proposal_keypair(master_secret, dao_account, user, proposal_id)
+                    .map(|(privkey, _pubkey)| (user, privkey))
+            })
+            .collect::<Result<_, _>>()?
+    } else {
+        let (hkdf_voters, other_voters): (Vec<&str>, Vec<&str>) = voters.into_iter().partition(|user| {
+            options
+                .key_derivation_algorithms
+                .get(*user)
+                .is_none_or(|algorithm| *algorithm == KeyDerivationAlgorithm::HKDF)
+        });
+
+        // Account each voter's current key was derived for (see `crypto::epoch_user_account`)
+        let key_account = |user: &str| {
+            crypto::epoch_user_account(user, options.key_epochs.get(user).copied().unwrap_or(0))
+        };
+
+        // Single HKDF extract for all HKDF voters
+        let contexts: Vec<(String, String)> = hkdf_voters
+            .iter()
+            .map(|user| (dao_account.to_string(), key_account(user)))
+            .collect();
+        let mut privkeys: HashMap<&str, Vec<u8>> = hkdf_voters
+            .into_iter()
+            .zip(crypto::hkdf_expand_many(master_secret, &contexts)?)
+            .map(|(user, (privkey, _pubkey))| (user, privkey.to_vec()))
+            .collect();
+
+        for user in other_voters {
+            let (privkey, _pubkey) =
+                options.key_derivation_algorithms[user].derive_keypair(master_secret, dao_account, &key_account(user))?;
+            privkeys.insert(user, privkey);
+        }
+        privkeys
+    };
+
+    // Position of each user's current winning vote in `votes_data`
+    let mut counted_index: HashMap<&str, usize> = HashMap::new();
+
+    // Ciphertexts that failed to decode or decrypt
+    let mut spoiled_votes: u32 = 0;
+
     // Decrypt all votes
-    for vote_data in votes_data {
+    for (index, vote_data) in votes_data.iter().enumerate() {
+        // Empty or "null" ciphertexts come from client bugs: nothing to decrypt, count as dummy
+        if vote_data.encrypted_vote.is_empty() || vote_data.encrypted_vote == "null" {
+            logger::warn(format!("Empty encrypted_vote from {}", vote_data.user));
+            skip(vote_data, "dummy");
+            continue;
+        }
+
         // Decode hex-encoded ciphertext to bytes
         let ciphertext_bytes = match hex::decode(&vote_data.encrypted_vote) {
             Ok(bytes) => bytes,
             Err(e) => {
-                eprintln!(
-                    "Warning: Failed to decode hex for vote from {}: {}",
-                    vote_data.user, e
+                logger::log_event(
+                    "warn",
+                    "Failed to decode hex for vote",
+                    serde_json::json!({ "user": vote_data.user, "vote_index": index, "error": e.to_string() }),
                 );
+                spoiled_votes += 1;
+                skip(vote_data, "decryption_failed");
                 continue;
             }
         };
 
         // Decrypt using ECIES (no nonce needed - included in ciphertext)
-        let decrypted = match crypto::decrypt_vote(
-            master_secret,
-            dao_account,
-            &vote_data.user,
+        let decrypted = match crypto::decrypt_vote_with_key(
+            &privkeys[vote_data.user.as_str()],
             &ciphertext_bytes,
         ) {
             Ok(v) => v,
             Err(e) => {
                 // Log error but continue (don't fail entire tallying for one bad vote)
-                eprintln!(
-                    "Warning: Failed to decrypt vote from {}: {}",
-                    vote_data.user, e
+                logger::log_event(
+                    "warn",
+                    "Failed to decrypt vote",
+                    serde_json::json!({ "user": vote_data.user, "vote_index": index, "error": e.to_string() }),
                 );
+                spoiled_votes += 1;
+                skip(vote_data, "decryption_failed");
                 continue;
             }
         };
 
         // Check if this is a real vote (not dummy)
-        let is_real_vote = decrypted == "yes" || decrypted == "no";
+        let is_real_vote = vote_options.contains(&decrypted.as_str());
 
         if is_real_vote {
             // Update user's vote (last one wins)
@@ -166,68 +464,700 @@ pub fn tally_votes(
                 // Only update if this vote is newer
                 if vote_data.timestamp > *existing_timestamp {
                     user_votes.insert(vote_data.user.clone(), (decrypted, vote_data.timestamp));
+                    counted_index.insert(&vote_data.user, index);
                 }
             } else {
                 // First vote from this user
                 user_votes.insert(vote_data.user.clone(), (decrypted, vote_data.timestamp));
+                counted_index.insert(&vote_data.user, index);
             }
         } else {
             // This is a dummy message (noise)
             // Do not update user_votes - just skip
             // This allows users to send dummy messages without affecting their real vote
+            skip(vote_data, "dummy");
             continue;
         }
     }
 
-    // Count yes and no votes
-    let mut yes_count = 0u32;
-    let mut no_count = 0u32;
+    // Delegators vote through their delegate
+    let delegated_votes = resolve_delegated_votes(&mut user_votes, &options.delegations);
+    counted_index.retain(|user, _| user_votes.contains_key(*user));
 
-    for (vote, _timestamp) in user_votes.values() {
-        match vote.as_str() {
-            "yes" => yes_count += 1,
-            "no" => no_count += 1,
-            _ => {
-                // This shouldn't happen (filtered above), but be safe
-                eprintln!("Warning: Unexpected vote value: {}", vote);
-            }
-        }
+    // Count votes per option
+    let mut vote_counts: HashMap<String, u32> =
+        vote_options.iter().map(|option| (option.to_string(), 0)).collect();
+    for (vote, _timestamp) in user_votes.values().chain(delegated_votes.values()) {
+        // Only options pass the filter above
+        *vote_counts.get_mut(vote).unwrap() += 1;
     }
 
-    let total_votes = yes_count + no_count;
+    let yes_count = vote_counts.get("yes").copied();
+    let no_count = vote_counts.get("no").copied();
+    let abstain_count = vote_counts.get("abstain").copied();
+    let total_votes: u32 = vote_counts.values().sum();
+
+    // SuperMajority and Relative measure "yes", or the leading option of a multi-option proposal
+    let majority_votes = match options.vote_options {
+        Some(_) => vote_counts.values().copied().max().unwrap_or(0),
+        None => yes_count.unwrap_or(0),
+    };
+
+    memory_profile.after_decryption_bytes = memory_profile.after_load_bytes
+        + estimate_map_bytes(privkeys.len(), std::mem::size_of::<(&str, Vec<u8>)>(), 32 * privkeys.len())
+        + estimate_map_bytes(
+            user_votes.len(),
+            std::mem::size_of::<(String, (String, u64))>(),
+            user_votes.iter().map(|(user, (vote, _))| user.len() + vote.len()).sum(),
+        );
+
+    // Weighted vote total (only differs from the raw count for TimeWeighted quorum)
+    let weighted_total = match parse_quorum(quorum)? {
+        QuorumType::TimeWeighted { decay_halflife_ns, .. } => {
+            let proposal_created_at = options
+                .proposal_created_at
+                .ok_or("TimeWeighted quorum requires proposal_created_at")?;
+            user_votes
+                .values()
+                .chain(delegated_votes.values())
+                .map(|(_, timestamp)| time_weight(*timestamp, proposal_created_at, decay_halflife_ns))
+                .sum::<f64>() as f32
+        }
+        QuorumType::Absolute { .. }
+        | QuorumType::Unanimous
+        | QuorumType::Percentage { .. }
+        | QuorumType::SuperMajority { .. }
+        | QuorumType::Relative { .. } => total_votes as f32,
+    };
 
     // Check quorum
-    let quorum_met = check_quorum(quorum, total_votes)?;
+    let quorum_met = check_quorum(
+        quorum,
+        majority_votes,
+        total_votes,
+        abstain_count.unwrap_or(0),
+        weighted_total,
+        options.eligible_voters,
+    )?;
 
     // Build merkle tree and generate proofs for all votes
     let (votes_merkle_root, merkle_proofs) = build_merkle_tree_with_proofs(votes_data);
 
-    // Generate TEE attestation
-    // In MVP: placeholder
-    // In Phase 2: real SGX/SEV attestation proving:
-    //   - Code hash matches expected binary
+    memory_profile.after_merkle_bytes = memory_profile.after_load_bytes
+        + estimate_merkle_bytes(votes_data.len(), &merkle_proofs);
+    memory_profile.peak_estimated_bytes = memory_profile
+        .after_load_bytes
+        .max(memory_profile.after_decryption_bytes)
+        .max(memory_profile.after_merkle_bytes);
+
+    // Generate TEE attestation: the enclave's quote if OutLayer injected one, proving
+    //   - Code hash matches expected binary (MRENCLAVE)
     //   - Execution happened in TEE
-    //   - No tampering occurred
+    //   - The quote was produced for this tally (REPORTDATA)
+    // Outside a TEE: placeholder
     let tee_attestation = generate_tee_attestation(
         proposal_id,
         &votes_merkle_root,
-        yes_count,
-        no_count,
-    );
+        yes_count.unwrap_or(0),
+        no_count.unwrap_or(0),
+        options.tee_attestation_report.as_deref(),
+        options.current_timestamp.unwrap_or_else(now_ns),
+    )?;
+
+    let mut counted_vote_indices: Vec<u64> = counted_index.values().map(|&i| i as u64).collect();
+    counted_vote_indices.sort_unstable();
+    let counted_votes_root = if quorum_met {
+        counted_votes_root(master_secret, dao_account, proposal_id, votes_data, &counted_vote_indices)?
+    } else {
+        String::new()
+    };
 
     // Privacy protection: only include counts if quorum met
     // If quorum not met, hide all vote counts to protect voter privacy
     Ok(TallyResult {
         proposal_id,
-        yes_count: if quorum_met { Some(yes_count) } else { None },
-        no_count: if quorum_met { Some(no_count) } else { None },
+        yes_count: if quorum_met { yes_count } else { None },
+        no_count: if quorum_met { no_count } else { None },
+        abstain_count: if quorum_met { abstain_count } else { None },
+        vote_counts: if quorum_met { vote_counts } else { HashMap::new() },
         total_votes: if quorum_met { total_votes } else { 0 }, // Hide total if no quorum
+        spoiled_votes,
+        weighted_total: if quorum_met { weighted_total } else { 0.0 },
         tee_attestation,
         votes_merkle_root,
+        counted_votes_root,
+        merkle_proof_count: merkle_proofs.len(),
+        merkle_proofs_available: true,
         merkle_proofs,
+        memory_profile: if options.memory_profiling { Some(memory_profile) } else { None },
+        audit_trail: if options.audit_mode {
+            Some(generate_audit_trail(votes_data, &user_votes, &skipped))
+        } else {
+            None
+        },
+        voting_histogram: match (options.include_histogram, options.proposal_created_at) {
+            (true, Some(created_at)) => Some(compute_turnout_histogram(
+                votes_data,
+                created_at,
+                options.proposal_deadline,
+                HISTOGRAM_BINS,
+            )),
+            (true, None) => {
+                logger::warn("Histogram requested without proposal_created_at, skipping");
+                None
+            }
+            (false, _) => None,
+        },
+        submission_gini: if options.include_stats {
+            let mut user_vote_counts: HashMap<String, usize> = HashMap::new();
+            for vote in votes_data {
+                *user_vote_counts.entry(vote.user.clone()).or_default() += 1;
+            }
+            Some(compute_gini_coefficient(&user_vote_counts))
+        } else {
+            None
+        },
+    })
+}
+
+/// Commitment to which ballots a tally counted (`TallyResult::counted_votes_root`)
+///
+/// Merkle root, paired like `build_merkle_tree_with_proofs`, over
+/// `SHA256(vote_hash || blinding)` of each counted ballot in index order.
+/// `blinding` is derived from the master secret per ballot, so nobody outside
+/// the TEE can test candidate sets of counted ballots against the root:
+/// dummies stay indistinguishable from real votes.
+pub(crate) fn counted_votes_root(
+    master_secret: &[u8],
+    dao_account: &str,
+    proposal_id: u64,
+    votes_data: &[VoteData],
+    counted_vote_indices: &[u64],
+) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+
+    let mut level: Vec<String> = counted_vote_indices
+        .iter()
+        .map(|&index| {
+            let vote = &votes_data[index as usize];
+            let blinding =
+                crypto::derive_secret(master_secret, &format!("counted-vote:{}:{}:{}", dao_account, proposal_id, index))?;
+
+            let mut hasher = Sha256::new();
+            hasher.update(vote.user.as_bytes());
+            hasher.update(vote.timestamp.to_le_bytes());
+            hasher.update(vote.encrypted_vote.as_bytes());
+            let vote_hash = hex::encode(hasher.finalize());
+
+            let mut hasher = Sha256::new();
+            hasher.update(vote_hash.as_bytes());
+            hasher.update(blinding);
+            Ok(hex::encode(hasher.finalize()))
+        })
+        .collect::<Result<_, String>>()?;
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let mut hasher = Sha256::new();
+                hasher.update(pair[0].as_bytes());
+                hasher.update(pair.get(1).unwrap_or(&pair[0]).as_bytes());
+                hex::encode(hasher.finalize())
+            })
+            .collect();
+    }
+
+    Ok(level.pop().unwrap_or_default())
+}
+
+/// Running tally of a chunked finalization
+///
+/// Only ever leaves the TEE sealed (`crypto::seal_state`), so the contract
+/// stores it without learning any chunk's counts.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ChunkTallyState {
+    /// Votes (by index) covered so far; the next chunk starts here
+    pub next_offset: u64,
+
+    /// Votes per option summed over the chunks so far
+    pub vote_counts: HashMap<String, u32>,
+
+    /// Ballots that failed to decode or decrypt, summed over the chunks so far
+    pub spoiled_votes: u32,
+}
+
+/// Result of `tally_votes_chunk`: the new running tally, sealed
+#[derive(Serialize, Debug)]
+pub struct ChunkTallyResult {
+    /// Proposal ID that was tallied
+    pub proposal_id: u64,
+
+    /// `ChunkTallyState` sealed for this proposal (hex)
+    pub sealed_tally: String,
+
+    /// Votes covered by the sealed tally (end of this chunk)
+    pub next_offset: u64,
+}
+
+/// Sealing context of a proposal's running chunk tally
+fn chunk_tally_context(dao_account: &str, proposal_id: u64) -> String {
+    format!("chunk-tally:{}:{}", dao_account, proposal_id)
+}
+
+/// Unseal a proposal's running chunk tally (hex from `ChunkTallyResult::sealed_tally`)
+fn unseal_chunk_tally(
+    master_secret: &[u8],
+    dao_account: &str,
+    proposal_id: u64,
+    sealed: &str,
+) -> Result<ChunkTallyState, String> {
+    let sealed = hex::decode(sealed).map_err(|e| format!("Invalid sealed_tally hex: {}", e))?;
+    let state = crypto::unseal_state(master_secret, &chunk_tally_context(dao_account, proposal_id), &sealed)?;
+    serde_json::from_slice(&state).map_err(|e| format!("Invalid sealed tally: {}", e))
+}
+
+/// Tally one chunk of a proposal's votes into its sealed running tally
+///
+/// Used for proposals with too many votes for a single `tally_votes` run.
+/// `sealed` is the previous chunk's `sealed_tally` (None for the first chunk)
+/// and must cover exactly `chunk.start` votes; `votes_data` holds every ballot
+/// of the voters counted in this chunk.
+///
+/// # Privacy
+/// The chunk's counts never leave the TEE: the result only carries the new
+/// sealed tally, so even a one-vote chunk reveals nothing about the ballot.
+/// Quorum is applied once, by `commit_chunked_tally`.
+pub fn tally_votes_chunk(
+    master_secret: &[u8],
+    dao_account: &str,
+    proposal_id: u64,
+    votes_data: &[VoteData],
+    sealed: Option<&str>,
+    chunk: std::ops::Range<u64>,
+    options: &TallyOptions,
+) -> Result<ChunkTallyResult, String> {
+    let mut state = match sealed {
+        Some(sealed) => unseal_chunk_tally(master_secret, dao_account, proposal_id, sealed)?,
+        None => ChunkTallyState::default(),
+    };
+    if state.next_offset != chunk.start {
+        return Err(format!(
+            "Sealed tally covers {} votes, but the chunk starts at {}",
+            state.next_offset, chunk.start
+        ));
+    }
+
+    let result = tally_votes(
+        master_secret,
+        dao_account,
+        proposal_id,
+        votes_data,
+        &serde_json::json!({ "Absolute": { "min_votes": 0 } }),
+        options,
+    )?;
+
+    for (option, count) in result.vote_counts {
+        *state.vote_counts.entry(option).or_default() += count;
+    }
+    state.spoiled_votes += result.spoiled_votes;
+    state.next_offset = chunk.end;
+
+    let plaintext = serde_json::to_vec(&state).map_err(|e| e.to_string())?;
+    let sealed = crypto::seal_state(master_secret, &chunk_tally_context(dao_account, proposal_id), &plaintext)?;
+
+    Ok(ChunkTallyResult {
+        proposal_id,
+        sealed_tally: hex::encode(sealed),
+        next_offset: state.next_offset,
+    })
+}
+
+/// Decide a chunked tally: unseal the running tally and check quorum
+///
+/// `vote_count` is the proposal's number of votes, which the sealed tally
+/// must cover. Returns a `TallyResult` like `tally_votes` (counts hidden if
+/// quorum isn't met) without Merkle root or proofs: each chunk only hashed
+/// its own ballots. TimeWeighted quorum needs per-vote weights and isn't supported.
+pub fn commit_chunked_tally(
+    master_secret: &[u8],
+    dao_account: &str,
+    proposal_id: u64,
+    sealed: &str,
+    vote_count: u64,
+    quorum: &serde_json::Value,
+    options: &TallyOptions,
+) -> Result<TallyResult, String> {
+    let state = unseal_chunk_tally(master_secret, dao_account, proposal_id, sealed)?;
+    if state.next_offset != vote_count {
+        return Err(format!("Sealed tally covers {} of {} votes", state.next_offset, vote_count));
+    }
+    if let QuorumType::TimeWeighted { .. } = parse_quorum(quorum)? {
+        return Err("Chunked tallies don't support TimeWeighted quorum".to_string());
+    }
+
+    let vote_counts = state.vote_counts;
+    let yes_count = vote_counts.get("yes").copied();
+    let no_count = vote_counts.get("no").copied();
+    let abstain_count = vote_counts.get("abstain").copied();
+    let total_votes: u32 = vote_counts.values().sum();
+    let majority_votes = match options.vote_options {
+        Some(_) => vote_counts.values().copied().max().unwrap_or(0),
+        None => yes_count.unwrap_or(0),
+    };
+
+    let quorum_met = check_quorum(
+        quorum,
+        majority_votes,
+        total_votes,
+        abstain_count.unwrap_or(0),
+        total_votes as f32,
+        options.eligible_voters,
+    )?;
+
+    let tee_attestation = generate_tee_attestation(
+        proposal_id,
+        "",
+        yes_count.unwrap_or(0),
+        no_count.unwrap_or(0),
+        options.tee_attestation_report.as_deref(),
+        options.current_timestamp.unwrap_or_else(now_ns),
+    )?;
+
+    Ok(TallyResult {
+        proposal_id,
+        yes_count: if quorum_met { yes_count } else { None },
+        no_count: if quorum_met { no_count } else { None },
+        abstain_count: if quorum_met { abstain_count } else { None },
+        vote_counts: if quorum_met { vote_counts } else { HashMap::new() },
+        total_votes: if quorum_met { total_votes } else { 0 },
+        spoiled_votes: state.spoiled_votes,
+        weighted_total: if quorum_met { total_votes as f32 } else { 0.0 },
+        tee_attestation,
+        votes_merkle_root: String::new(),
+        merkle_proofs: Vec::new(),
+        counted_votes_root: String::new(),
+        merkle_proof_count: 0,
+        merkle_proofs_available: false,
+        memory_profile: None,
+        audit_trail: None,
+        voting_histogram: None,
+        submission_gini: None,
     })
 }
 
+/// Ballots credited to delegators by their final delegate
+///
+/// Follows each chain in `delegations` (delegator → delegate) up to
+/// `MAX_DELEGATION_DEPTH` hops. Delegators with a valid chain lose their own
+/// ballot in `user_votes`; those whose final delegate voted get a copy of it
+/// in the returned map. Circular or over-long chains are logged and ignored.
+pub fn resolve_delegated_votes(
+    user_votes: &mut HashMap<String, (String, u64)>,
+    delegations: &HashMap<String, String>,
+) -> HashMap<String, (String, u64)> {
+    let mut final_delegates: Vec<(&str, &str)> = Vec::new();
+    for delegator in delegations.keys() {
+        match final_delegate(delegator, delegations) {
+            Some(delegate) => final_delegates.push((delegator, delegate)),
+            None => logger::warn(format!(
+                "Delegation chain from {} is circular or too long, ignored",
+                delegator
+            )),
+        }
+    }
+
+    // Final delegates aren't delegators, so their ballots survive the removal
+    let delegated_votes = final_delegates
+        .iter()
+        .filter_map(|(delegator, delegate)| {
+            user_votes.get(*delegate).map(|vote| (delegator.to_string(), vote.clone()))
+        })
+        .collect();
+    for (delegator, _) in &final_delegates {
+        user_votes.remove(*delegator);
+    }
+    delegated_votes
+}
+
+/// Member at the end of `delegator`'s chain (None if circular or too long)
+fn final_delegate<'a>(delegator: &'a str, delegations: &'a HashMap<String, String>) -> Option<&'a str> {
+    let mut current = delegator;
+    for _ in 0..=MAX_DELEGATION_DEPTH {
+        match delegations.get(current) {
+            Some(next) => current = next,
+            None => return Some(current),
+        }
+    }
+    None
+}
+
+/// Gini coefficient of per-user submission counts
+///
+/// `G = 2 * sum(i * x_i) / (n * sum(x_i)) - (n + 1) / n` with the counts `x_i`
+/// sorted ascending and `i` starting at 1. 0.0 for no users or no submissions.
+pub fn compute_gini_coefficient(user_vote_counts: &HashMap<String, usize>) -> f32 {
+    let mut counts: Vec<usize> = user_vote_counts.values().copied().collect();
+    counts.sort_unstable();
+
+    let n = counts.len() as f64;
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let weighted_sum: f64 = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| (i + 1) as f64 * count as f64)
+        .sum();
+
+    (2.0 * weighted_sum / (n * total as f64) - (n + 1.0) / n) as f32
+}
+
+/// Number of bins in `TallyResult::voting_histogram`
+const HISTOGRAM_BINS: u32 = 10;
+
+/// Bin vote submissions by time since proposal creation
+///
+/// The span `[proposal_created_at, end]` is split into `bins` equal bins, where
+/// `end` is the deadline, or the last submission if there is no deadline. Every
+/// submission is counted, dummies and re-votes included: this is timing metadata
+/// that is already public on-chain, not turnout of real votes. Submissions
+/// outside the span are clamped into the first/last bin.
+///
+/// # Returns
+/// `(bin_start_ns, vote_count)` pairs in time order (empty if `bins` is 0)
+pub fn compute_turnout_histogram(
+    votes_data: &[VoteData],
+    proposal_created_at: u64,
+    proposal_deadline: Option<u64>,
+    bins: u32,
+) -> Vec<(u64, u64)> {
+    if bins == 0 {
+        return Vec::new();
+    }
+
+    let end = proposal_deadline.unwrap_or_else(|| {
+        votes_data
+            .iter()
+            .map(|v| v.timestamp)
+            .max()
+            .unwrap_or(proposal_created_at)
+    });
+    let bin_width = (end.saturating_sub(proposal_created_at) / bins as u64).max(1);
+
+    let mut counts = vec![0u64; bins as usize];
+    for vote in votes_data {
+        let elapsed = vote.timestamp.saturating_sub(proposal_created_at);
+        let bin = ((elapsed / bin_width) as usize).min(counts.len() - 1);
+        counts[bin] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (proposal_created_at + i as u64 * bin_width, count))
+        .collect()
+}
+
+/// Build a per-vote processing log for dispute resolution
+///
+/// Every input vote ends up in exactly one place:
+/// - `counted_votes` - the user's winning vote from `user_votes`
+/// - `skipped_votes` - `skipped` (decryption failures, dummies) plus real votes
+///   that lost to a newer one (`superseded`)
+/// - each superseded vote also gets an `override_votes` entry linking it to the winner
+///
+/// # Privacy
+/// `counted_as` reveals each member's choice. Only request this when the
+/// output stays private (e.g. a dispute run outside the public contract flow).
+pub fn generate_audit_trail(
+    votes_data: &[VoteData],
+    user_votes: &HashMap<String, (String, u64)>,
+    skipped: &[SkippedEntry],
+) -> AuditTrail {
+    let mut trail = AuditTrail {
+        skipped_votes: skipped.to_vec(),
+        ..Default::default()
+    };
+
+    // Multiset of already-skipped votes (same user can send several ballots per block)
+    let mut skipped_remaining: HashMap<(&str, u64), usize> = HashMap::new();
+    for entry in skipped {
+        *skipped_remaining.entry((entry.user.as_str(), entry.timestamp)).or_default() += 1;
+    }
+
+    let mut counted_users: std::collections::HashSet<&str> = std::collections::HashSet::new();
+
+    for vote_data in votes_data {
+        let key = (vote_data.user.as_str(), vote_data.timestamp);
+        if let Some(remaining) = skipped_remaining.get_mut(&key).filter(|r| **r > 0) {
+            *remaining -= 1;
+            continue;
+        }
+
+        // Real vote: either the winner or superseded by it
+        let Some((vote, winning_timestamp)) = user_votes.get(&vote_data.user) else {
+            continue;
+        };
+
+        if vote_data.timestamp == *winning_timestamp && counted_users.insert(&vote_data.user) {
+            trail.counted_votes.push(AuditEntry {
+                user: vote_data.user.clone(),
+                timestamp: vote_data.timestamp,
+                counted_as: vote.clone(),
+            });
+        } else {
+            trail.skipped_votes.push(SkippedEntry {
+                user: vote_data.user.clone(),
+                timestamp: vote_data.timestamp,
+                reason: "superseded".to_string(),
+            });
+            trail.override_votes.push(OverriddenEntry {
+                user: vote_data.user.clone(),
+                old_timestamp: vote_data.timestamp,
+                new_timestamp: *winning_timestamp,
+            });
+        }
+    }
+
+    trail
+}
+
+/// Heap bytes held by the input votes
+fn estimate_votes_bytes(votes_data: &[VoteData]) -> usize {
+    std::mem::size_of_val(votes_data)
+        + votes_data
+            .iter()
+            .map(|v| v.user.capacity() + v.encrypted_vote.capacity())
+            .sum::<usize>()
+}
+
+/// Heap bytes held by a HashMap with `len` entries of `entry_size` bytes
+/// plus `heap_bytes` owned by keys/values
+///
+/// Accounts for hashbrown's 7/8 load factor and one control byte per bucket.
+fn estimate_map_bytes(len: usize, entry_size: usize, heap_bytes: usize) -> usize {
+    if len == 0 {
+        return heap_bytes;
+    }
+    let buckets = (len * 8 / 7).next_power_of_two();
+    buckets * (entry_size + 1) + heap_bytes
+}
+
+/// Heap bytes held by the Merkle tree levels and the generated proofs
+fn estimate_merkle_bytes(leaf_count: usize, proofs: &[MerkleProof]) -> usize {
+    // Every tree node is a 64-char hex String; a binary tree has < 2n nodes
+    const HASH_STRING_BYTES: usize = std::mem::size_of::<String>() + 64;
+    let tree_bytes = 2 * leaf_count * HASH_STRING_BYTES;
+
+    let proof_bytes: usize = proofs
+        .iter()
+        .map(|p| {
+            std::mem::size_of::<MerkleProof>()
+                + p.voter.len()
+                + p.vote_hash.len()
+                + p.proof_path.len() * HASH_STRING_BYTES
+        })
+        .sum();
+
+    tree_bytes + proof_bytes
+}
+
+/// Tolerance for vote timestamps ahead of the current time (1 minute in nanoseconds)
+const TIMESTAMP_TOLERANCE_NS: u64 = 60_000_000_000;
+
+/// Validate vote timestamps against the proposal's lifetime
+///
+/// # Checks
+/// 1. Every timestamp is >= `proposal_created_at` (error)
+/// 2. Every timestamp is <= `current_time_estimate` + 1 minute (error)
+/// 3. More than 50% of votes from different users share one timestamp (warning only,
+///    since votes included in the same block legitimately share a timestamp)
+///
+/// # Returns
+/// * `Ok(())` - All timestamps plausible
+/// * `Err(String)` - First offending vote
+pub fn validate_vote_timestamps(
+    votes_data: &[VoteData],
+    proposal_created_at: u64,
+    current_time_estimate: u64,
+) -> Result<(), String> {
+    let max_allowed = current_time_estimate.saturating_add(TIMESTAMP_TOLERANCE_NS);
+
+    // Key: timestamp, Value: distinct users who voted at that timestamp
+    let mut users_by_timestamp: HashMap<u64, std::collections::HashSet<&str>> = HashMap::new();
+
+    for vote_data in votes_data {
+        if vote_data.timestamp < proposal_created_at {
+            return Err(format!(
+                "Vote from {} has timestamp {} before proposal creation ({})",
+                vote_data.user, vote_data.timestamp, proposal_created_at
+            ));
+        }
+        if vote_data.timestamp > max_allowed {
+            return Err(format!(
+                "Vote from {} has timestamp {} in the future (current time {})",
+                vote_data.user, vote_data.timestamp, current_time_estimate
+            ));
+        }
+
+        users_by_timestamp
+            .entry(vote_data.timestamp)
+            .or_default()
+            .insert(&vote_data.user);
+    }
+
+    if let Some((timestamp, users)) = users_by_timestamp.iter().max_by_key(|(_, users)| users.len()) {
+        if users.len() > 1 && users.len() * 2 > votes_data.len() {
+            logger::warn(format!(
+                "{} of {} votes from different users share timestamp {}",
+                users.len(),
+                votes_data.len(),
+                timestamp
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Worker clock in nanoseconds (fallback when the contract doesn't pass block time)
+fn now_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(u64::MAX)
+}
+
+/// Recompute the votes' Merkle root and compare it to a previously stored one
+///
+/// Detects votes being modified between two OutLayer calls, e.g. after a
+/// failed tally attempt.
+///
+/// # Arguments
+/// * `votes_data` - Votes exactly as received from the contract
+/// * `expected_root` - Root from the earlier attempt (None = just compute)
+///
+/// # Returns
+/// * `Ok(root)` - Computed root (hex)
+/// * `Err(String)` - Root doesn't match `expected_root`
+pub(crate) fn verify_votes_integrity(
+    votes_data: &[VoteData],
+    expected_root: Option<&str>,
+) -> Result<String, String> {
+    let (computed_root, _proofs) = build_merkle_tree_with_proofs(votes_data);
+
+    match expected_root {
+        Some(expected) if expected != computed_root => Err(format!(
+            "Merkle root mismatch: expected {}, computed {}",
+            expected, computed_root
+        )),
+        _ => Ok(computed_root),
+    }
+}
+
 /// Build Merkle tree and generate proofs for all votes
 ///
 /// Constructs a binary Merkle tree from vote hashes and generates
@@ -252,7 +1182,7 @@ pub fn tally_votes(
 /// ```
 /// Proof for h0: [h1, h23]
 /// Proof for h2: [h3, h01]
-fn build_merkle_tree_with_proofs(votes_data: &[VoteData]) -> (String, Vec<MerkleProof>) {
+pub(crate) fn build_merkle_tree_with_proofs(votes_data: &[VoteData]) -> (String, Vec<MerkleProof>) {
     use sha2::{Digest, Sha256};
 
     if votes_data.is_empty() {
@@ -266,7 +1196,7 @@ fn build_merkle_tree_with_proofs(votes_data: &[VoteData]) -> (String, Vec<Merkle
     for (index, vote) in votes_data.iter().enumerate() {
         let mut hasher = Sha256::new();
         hasher.update(vote.user.as_bytes());
-        hasher.update(&vote.timestamp.to_le_bytes());
+        hasher.update(vote.timestamp.to_le_bytes());
         hasher.update(vote.encrypted_vote.as_bytes());
         let hash = hex::encode(hasher.finalize());
         leaf_hashes.push(hash.clone());
@@ -315,8 +1245,7 @@ fn build_merkle_tree_with_proofs(votes_data: &[VoteData]) -> (String, Vec<Merkle
         let mut path: Vec<String> = Vec::new();
         let mut current_index = leaf_index;
 
-        for level in 0..(all_levels.len() - 1) {
-            let level_data = &all_levels[level];
+        for level_data in &all_levels[..all_levels.len() - 1] {
             let sibling_index = if current_index % 2 == 0 {
                 current_index + 1
             } else {
@@ -346,6 +1275,15 @@ fn build_merkle_tree_with_proofs(votes_data: &[VoteData]) -> (String, Vec<Merkle
 ///
 /// # Quorum Types
 /// - Absolute { min_votes }: Requires at least N votes total
+/// - TimeWeighted { base_votes, .. }: Requires the weighted vote sum to reach N
+/// - Unanimous: Requires a real vote from every eligible voter
+/// - Percentage { min_percent_of_members }: Requires votes from at least N% of eligible voters
+/// - SuperMajority { min_yes_percent }: Requires yes / (yes + no) >= N%
+///   (abstentions are left out; leading option / all votes for multi-option proposals)
+/// - Relative { min_yes_percent }: Requires yes * 100 / total_votes >= N, rounded down,
+///   and at least one vote (unlike SuperMajority, abstentions count against the share)
+///
+/// Abstentions count towards `total_votes` for every threshold except SuperMajority.
 ///
 /// # Privacy Rationale
 /// Checking quorum in TEE ensures that vote counts are only revealed if threshold met.
@@ -353,34 +1291,155 @@ fn build_merkle_tree_with_proofs(votes_data: &[VoteData]) -> (String, Vec<Merkle
 ///
 /// # Arguments
 /// * `quorum` - JSON value with quorum config (from contract)
-/// * `total_votes` - Number of votes tallied (yes + no)
+/// * `yes_votes` - Number of yes votes tallied (leading option's votes for multi-option proposals)
+/// * `total_votes` - Number of votes tallied (yes + no + abstain)
+/// * `abstain_votes` - Abstentions among `total_votes` (left out of the SuperMajority share)
+/// * `weighted_total` - Sum of vote weights (see `time_weight`)
+/// * `eligible_voters` - Member count snapshot (required for Unanimous and Percentage)
 ///
 /// # Returns
 /// * `Ok(true)` - Quorum met
 /// * `Ok(false)` - Quorum not met
 /// * `Err(String)` - Invalid quorum config
-fn check_quorum(
+pub(crate) fn check_quorum(
     quorum: &serde_json::Value,
+    yes_votes: u32,
     total_votes: u32,
+    abstain_votes: u32,
+    weighted_total: f32,
+    eligible_voters: Option<u64>,
 ) -> Result<bool, String> {
-    use serde::Deserialize;
-
-    #[derive(Deserialize)]
-    #[serde(rename_all = "PascalCase")]
-    enum QuorumType {
-        Absolute { min_votes: u64 },
+    let met = match parse_quorum(quorum)? {
+        QuorumType::Absolute { min_votes } => {
+            total_votes as u64 >= min_votes
+        }
+        QuorumType::TimeWeighted { base_votes, .. } => {
+            weighted_total as f64 >= base_votes as f64
+        }
+        QuorumType::Unanimous => {
+            let eligible_voters = eligible_voters.ok_or("Unanimous quorum requires eligible_voters")?;
+            total_votes as u64 == eligible_voters
+        }
+        QuorumType::Percentage { min_percent_of_members } => {
+            let eligible_voters = eligible_voters.ok_or("Percentage quorum requires eligible_voters")?;
+            total_votes as u128 * 100 >= min_percent_of_members as u128 * eligible_voters as u128
+        }
+        QuorumType::SuperMajority { min_yes_percent } => {
+            let decisive_votes = total_votes.saturating_sub(abstain_votes);
+            decisive_votes > 0 && yes_votes as u64 * 100 >= min_yes_percent as u64 * decisive_votes as u64
+        }
+        QuorumType::Relative { min_yes_percent } => {
+            // Every vote cast, abstentions included; checked before dividing
+            total_votes > 0 && yes_votes as u64 * 100 / total_votes as u64 >= min_yes_percent as u64
+        }
+    };
+
+    Ok(met)
+}
+
+/// Quorum outlook from submission counts alone (no decryption)
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct EarlyQuorumStatus {
+    /// Quorum is met if every counted submission is a real vote
+    pub definitely_met: bool,
+    /// Quorum could be met (false means finalizing now is certain to fail quorum)
+    pub possibly_met: bool,
+}
+
+/// Estimate quorum from the number of submissions, before the TEE tally
+///
+/// Used to decide whether auto-finalizing is worth an OutLayer call.
+/// Submissions can be dummies, so "definitely" holds only under the
+/// assumption that none are; the real answer comes from `check_quorum`.
+///
+/// # Quorum Types
+/// - Absolute { min_votes }: both flags are `current_submission_count >= min_votes`
+/// - TimeWeighted { base_votes, .. }: weights are at most 1.0, so only
+///   `possibly_met` can be decided (`current_submission_count >= base_votes`);
+///   `definitely_met` is always false
+/// - Unanimous: both flags are `current_submission_count >= eligible_voters`
+/// - Percentage { min_percent_of_members }: both flags are
+///   `current_submission_count` reaching that share of `eligible_voters`
+/// - SuperMajority { .. } / Relative { .. }: depends on how members voted, so
+///   `definitely_met` is always false and `possibly_met` needs at least one submission
+///
+/// # Arguments
+/// * `quorum` - Parsed quorum config
+/// * `current_submission_count` - Distinct voters so far (re-votes counted once)
+/// * `eligible_voters` - Members who may vote
+#[cfg_attr(not(test), allow(dead_code))]
+pub(crate) fn check_early_quorum(
+    quorum: &QuorumType,
+    current_submission_count: u64,
+    eligible_voters: u64,
+) -> EarlyQuorumStatus {
+    match quorum {
+        QuorumType::Absolute { min_votes } => {
+            let met = current_submission_count >= *min_votes;
+            EarlyQuorumStatus { definitely_met: met, possibly_met: met }
+        }
+        QuorumType::TimeWeighted { base_votes, .. } => EarlyQuorumStatus {
+            definitely_met: false,
+            possibly_met: current_submission_count >= *base_votes,
+        },
+        QuorumType::Unanimous => {
+            let met = current_submission_count >= eligible_voters;
+            EarlyQuorumStatus { definitely_met: met, possibly_met: met }
+        }
+        QuorumType::Percentage { min_percent_of_members } => {
+            let met = current_submission_count as u128 * 100
+                >= *min_percent_of_members as u128 * eligible_voters as u128;
+            EarlyQuorumStatus { definitely_met: met, possibly_met: met }
+        }
+        QuorumType::SuperMajority { .. } | QuorumType::Relative { .. } => EarlyQuorumStatus {
+            definitely_met: false,
+            possibly_met: current_submission_count > 0,
+        },
     }
+}
 
+/// Quorum requirements (mirrors the contract's `QuorumType`)
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) enum QuorumType {
+    Absolute { min_votes: u64 },
+    TimeWeighted { base_votes: u64, decay_halflife_ns: u64 },
+    Unanimous,
+    Percentage { min_percent_of_members: u8 },
+    SuperMajority { min_yes_percent: u8 },
+    Relative { min_yes_percent: u8 },
+}
+
+/// Parse and validate quorum JSON from the contract
+fn parse_quorum(quorum: &serde_json::Value) -> Result<QuorumType, String> {
     let quorum_type: QuorumType = serde_json::from_value(quorum.clone())
         .map_err(|e| format!("Invalid quorum format: {}", e))?;
 
-    let met = match quorum_type {
-        QuorumType::Absolute { min_votes } => {
-            total_votes as u64 >= min_votes
+    match quorum_type {
+        QuorumType::TimeWeighted { decay_halflife_ns: 0, .. } => {
+            return Err("Invalid quorum format: decay_halflife_ns must be > 0".to_string());
         }
-    };
+        QuorumType::Percentage { min_percent_of_members: percent }
+        | QuorumType::SuperMajority { min_yes_percent: percent }
+        | QuorumType::Relative { min_yes_percent: percent }
+            if !(1..=100).contains(&percent) =>
+        {
+            return Err("Invalid quorum format: percentage must be between 1 and 100".to_string());
+        }
+        _ => {}
+    }
 
-    Ok(met)
+    Ok(quorum_type)
+}
+
+/// Weight of a vote under TimeWeighted quorum
+///
+/// Exponential decay: `2^(-(vote_timestamp - proposal_created_at) / decay_halflife_ns)`.
+/// A vote cast at creation weighs 1.0, one half-life later 0.5, and so on.
+fn time_weight(vote_timestamp: u64, proposal_created_at: u64, decay_halflife_ns: u64) -> f64 {
+    let elapsed = vote_timestamp.saturating_sub(proposal_created_at);
+    (-(elapsed as f64) / decay_halflife_ns as f64).exp2()
 }
 
 /// Compute hash of all votes (for verification)
@@ -396,7 +1455,7 @@ fn check_quorum(
 /// # Returns
 /// - Hex-encoded SHA256 hash (64 characters)
 #[allow(dead_code)]
-fn compute_votes_hash(votes_data: &[VoteData]) -> String {
+pub(crate) fn compute_votes_hash(votes_data: &[VoteData]) -> String {
     use sha2::{Digest, Sha256};
 
     // Create deterministic ordering
@@ -408,7 +1467,7 @@ fn compute_votes_hash(votes_data: &[VoteData]) -> String {
     for vote in sorted_votes {
         // Hash each vote component
         hasher.update(vote.user.as_bytes());
-        hasher.update(&vote.timestamp.to_le_bytes());
+        hasher.update(vote.timestamp.to_le_bytes());
         hasher.update(vote.encrypted_vote.as_bytes());
     }
 
@@ -416,57 +1475,130 @@ fn compute_votes_hash(votes_data: &[VoteData]) -> String {
     hex::encode(result)
 }
 
+/// Prefix of the placeholder attestation produced outside a TEE
+const MVP_ATTESTATION_PREFIX: &str = "mvp-attestation:";
+
+/// Byte ranges of an SGX quote (48-byte header, then the 384-byte report body)
+const SGX_QUOTE_MRENCLAVE: std::ops::Range<usize> = 112..144;
+const SGX_QUOTE_REPORT_DATA: std::ops::Range<usize> = 368..432;
+
+/// TEE attestation serialized into `TallyResult::tee_attestation`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AttestationReport {
+    /// SGX quote as injected by OutLayer (base64)
+    pub quote: String,
+    /// Tally commitment the quote was produced for: first 32 bytes of its REPORTDATA (hex)
+    pub report_data: String,
+    /// When the tally ran (nanoseconds)
+    pub timestamp: u64,
+    /// Measurement of the enclave that ran the tally (hex)
+    pub mrenclave: String,
+    /// Whether `report_data` is this tally's commitment
+    /// False when OutLayer's quote was produced for other data: it still proves
+    /// which enclave ran, but not that this result came from it.
+    pub report_data_matches: bool,
+}
+
 /// Generate TEE attestation (proof of trusted execution)
 ///
-/// In MVP: Returns placeholder attestation
-/// In Phase 2: Generates real SGX/SEV attestation
+/// With a quote from OutLayer (`report`), returns an `AttestationReport` as
+/// JSON; `report_data_matches` tells whether the quote's REPORTDATA starts with
+/// the tally commitment `SHA256(proposal_id || merkle_root || yes_count || no_count)`,
+/// i.e. whether the quote is bound to this tally. Without one (running outside a TEE),
+/// returns the placeholder `"mvp-attestation:" || hex(commitment)`.
 ///
 /// # Attestation Contents
 /// - Proposal ID (which vote was tallied)
 /// - Votes merkle root (what data was used)
 /// - Result commitment: hash(yes_count, no_count)
-/// - Code hash (which binary executed)
+/// - Code hash (MRENCLAVE - which binary executed)
 /// - Timestamp (when execution occurred)
-/// - TEE hardware signature (proves execution in enclave)
+/// - TEE hardware signature (inside the quote, proves execution in enclave)
 ///
 /// # Security
-/// - Attestation is cryptographically signed by TEE hardware
-/// - Cannot be forged without compromising TEE
-/// - Contract can verify signature on-chain
+/// - The quote is signed by TEE hardware and can't be forged without compromising it
+/// - The signature is not checked here; verifiers check it against Intel's collateral
+///
+/// # Returns
+/// * `Err(String)` - Quote isn't valid base64 or is too short
 fn generate_tee_attestation(
     proposal_id: u64,
     votes_merkle_root: &str,
     yes_count: u32,
     no_count: u32,
-) -> String {
+    report: Option<&str>,
+    timestamp: u64,
+) -> Result<String, String> {
+    use base64::Engine;
     use sha2::{Digest, Sha256};
 
-    // In MVP: Create a simple hash as placeholder
-    // Format: "mvp-attestation:" || hash(proposal_id || votes_root || counts)
     let mut hasher = Sha256::new();
-    hasher.update(&proposal_id.to_le_bytes());
+    hasher.update(proposal_id.to_le_bytes());
     hasher.update(votes_merkle_root.as_bytes());
-    hasher.update(&yes_count.to_le_bytes());
-    hasher.update(&no_count.to_le_bytes());
+    hasher.update(yes_count.to_le_bytes());
+    hasher.update(no_count.to_le_bytes());
+    let commitment = hasher.finalize();
+
+    let Some(report) = report else {
+        return Ok(format!("{}{}", MVP_ATTESTATION_PREFIX, hex::encode(commitment)));
+    };
+
+    let quote = base64::engine::general_purpose::STANDARD
+        .decode(report.trim())
+        .map_err(|e| format!("Invalid TEE_ATTESTATION_REPORT base64: {}", e))?;
+    if quote.len() < SGX_QUOTE_REPORT_DATA.end {
+        return Err(format!("TEE_ATTESTATION_REPORT is too short for an SGX quote ({} bytes)", quote.len()));
+    }
+
+    let report_data = &quote[SGX_QUOTE_REPORT_DATA][..32];
+    let report_data_matches = report_data == commitment.as_slice();
+    if !report_data_matches {
+        logger::warn(format!(
+            "TEE attestation report_data {} doesn't commit to this tally ({})",
+            hex::encode(report_data),
+            hex::encode(commitment)
+        ));
+    }
+
+    let attestation = AttestationReport {
+        quote: base64::engine::general_purpose::STANDARD.encode(&quote),
+        report_data: hex::encode(report_data),
+        timestamp,
+        mrenclave: hex::encode(&quote[SGX_QUOTE_MRENCLAVE]),
+        report_data_matches,
+    };
+    serde_json::to_string(&attestation).map_err(|e| e.to_string())
+}
 
-    let hash = hasher.finalize();
+/// Whether `attestation` is shaped like a `generate_tee_attestation` result
+///
+/// Checks encodings and lengths only, not the quote's signature.
+#[cfg_attr(not(test), allow(dead_code))]
+pub(crate) fn verify_tee_attestation_format(attestation: &str) -> bool {
+    use base64::Engine;
+
+    let is_hash = |value: &str| value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit());
 
-    // In Phase 2: Replace with real TEE attestation
-    // Example SGX format:
-    // {
-    //   "quote": "base64_encoded_sgx_quote",
-    //   "report_data": "sha256(proposal_id || merkle_root || result)",
-    //   "timestamp": unix_timestamp,
-    //   "measurement": "mrenclave_hash"
-    // }
+    if let Some(hash) = attestation.strip_prefix(MVP_ATTESTATION_PREFIX) {
+        return is_hash(hash);
+    }
 
-    format!("mvp-attestation:{}", hex::encode(hash))
+    let Ok(report) = serde_json::from_str::<AttestationReport>(attestation) else {
+        return false;
+    };
+    let quote_ok = base64::engine::general_purpose::STANDARD
+        .decode(&report.quote)
+        .is_ok_and(|quote| quote.len() >= SGX_QUOTE_REPORT_DATA.end);
+    quote_ok && is_hash(&report.report_data) && is_hash(&report.mrenclave)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const MASTER_SECRET: &[u8] = b"test_secret_32_bytes_long_xxxx!!";
+    const DAO: &str = "dao.testnet";
+
     fn create_test_vote(user: &str, encrypted: &str, ts: u64) -> VoteData {
         VoteData {
             user: user.to_string(),
@@ -475,11 +1607,378 @@ mod tests {
         }
     }
 
+    /// A vote encrypted to the user's real derived key (decryptable by `tally_votes`)
+    fn create_encrypted_test_vote(
+        master_secret: &[u8],
+        dao: &str,
+        user: &str,
+        vote: &str,
+        timestamp: u64,
+    ) -> VoteData {
+        let (_privkey, pubkey) = crypto::derive_keypair(master_secret, dao, user).unwrap();
+        let ciphertext = crypto::encrypt_vote(&pubkey, vote.as_bytes()).unwrap();
+        create_test_vote(user, &hex::encode(ciphertext), timestamp)
+    }
+
+    fn encrypted_vote(user: &str, vote: &str, timestamp: u64) -> VoteData {
+        create_encrypted_test_vote(MASTER_SECRET, DAO, user, vote, timestamp)
+    }
+
+    #[test]
+    fn test_tally_uses_each_voters_key_derivation_algorithm() {
+        let pbkdf2 = KeyDerivationAlgorithm::PBKDF2 { iterations: 1_000 };
+        let (_, bob_pubkey) = pbkdf2.derive_keypair(MASTER_SECRET, DAO, "bob").unwrap();
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),
+            VoteData {
+                user: "bob".to_string(),
+                encrypted_vote: hex::encode(crypto::encrypt_vote(&bob_pubkey, b"no").unwrap()),
+                timestamp: 1100,
+            },
+        ];
+        let quorum = serde_json::json!({ "Absolute": { "min_votes": 1 } });
+
+        // Without the algorithm, bob's ballot can't be decrypted
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &TallyOptions::default()).unwrap();
+        assert_eq!((result.yes_count, result.no_count), (Some(1), Some(0)));
+
+        let options = TallyOptions {
+            key_derivation_algorithms: HashMap::from([("bob".to_string(), pbkdf2)]),
+            ..Default::default()
+        };
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &options).unwrap();
+        assert_eq!((result.yes_count, result.no_count), (Some(1), Some(1)));
+    }
+
+    #[test]
+    fn test_tally_uses_each_voters_key_epoch() {
+        let (_, bob_pubkey) = crypto::derive_keypair(MASTER_SECRET, DAO, &crypto::epoch_user_account("bob", 2)).unwrap();
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),
+            VoteData {
+                user: "bob".to_string(),
+                encrypted_vote: hex::encode(crypto::encrypt_vote(&bob_pubkey, b"no").unwrap()),
+                timestamp: 1100,
+            },
+        ];
+        let quorum = serde_json::json!({ "Absolute": { "min_votes": 1 } });
+
+        // Without the epoch, bob's ballot (encrypted to his rotated key) can't be decrypted
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &TallyOptions::default()).unwrap();
+        assert_eq!((result.yes_count, result.no_count), (Some(1), Some(0)));
+
+        let options = TallyOptions { key_epochs: HashMap::from([("bob".to_string(), 2)]), ..Default::default() };
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &options).unwrap();
+        assert_eq!((result.yes_count, result.no_count), (Some(1), Some(1)));
+    }
+
+    #[test]
+    fn test_tally_counts_latest_vote_per_user() {
+        // 3 users x 2 votes: only each user's latest vote counts
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("bob", "no", 1100),
+            encrypted_vote("carol", "yes", 1200),
+            encrypted_vote("alice", "no", 2000),
+            encrypted_vote("bob", "yes", 2100),
+            encrypted_vote("carol", "yes", 2200),
+        ];
+
+        let result = tally_votes(
+            MASTER_SECRET,
+            DAO,
+            1,
+            &votes,
+            &serde_json::json!({ "Absolute": { "min_votes": 1 } }),
+            &TallyOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.total_votes, 3);
+        assert_eq!(result.yes_count, Some(2));
+        assert_eq!(result.no_count, Some(1));
+        assert_eq!(result.merkle_proofs.len(), 6);
+        assert_eq!(result.counted_votes_root, counted_votes_root(MASTER_SECRET, DAO, 1, &votes, &[3, 4, 5]).unwrap());
+    }
+
+    #[test]
+    fn test_tally_counts_abstain_towards_quorum() {
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("bob", "abstain", 1100),
+            encrypted_vote("carol", "maybe", 1200),
+        ];
+
+        let result = tally_votes(
+            MASTER_SECRET,
+            DAO,
+            1,
+            &votes,
+            &serde_json::json!({ "Absolute": { "min_votes": 2 } }),
+            &TallyOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.total_votes, 2);
+        assert_eq!((result.yes_count, result.no_count, result.abstain_count), (Some(1), Some(0), Some(1)));
+        assert_eq!(result.vote_counts["abstain"], 1);
+    }
+
+    #[test]
+    fn test_counted_votes_root_skips_dummies_and_is_blinded() {
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("alice", "dummy_noise", 2000), // dummy after the real vote
+            encrypted_vote("bob", "no", 1500),
+        ];
+        let tally = |min_votes: u64| {
+            tally_votes(
+                MASTER_SECRET,
+                DAO,
+                1,
+                &votes,
+                &serde_json::json!({ "Absolute": { "min_votes": min_votes } }),
+                &TallyOptions::default(),
+            )
+            .unwrap()
+        };
+
+        let result = tally(2);
+        assert_eq!(
+            result.counted_votes_root,
+            counted_votes_root(MASTER_SECRET, DAO, 1, &votes, &[0, 2]).unwrap()
+        );
+        assert_ne!(
+            result.counted_votes_root,
+            counted_votes_root(MASTER_SECRET, DAO, 1, &votes, &[1, 2]).unwrap()
+        );
+
+        // Public vote hashes alone don't reproduce the root
+        let (unblinded_root, _) = build_merkle_tree_with_proofs(&[votes[0].clone(), votes[2].clone()]);
+        assert_ne!(result.counted_votes_root, unblinded_root);
+
+        assert!(tally(3).counted_votes_root.is_empty());
+    }
+
+    #[test]
+    fn test_undecryptable_vote_logged_as_warning() {
+        logger::take();
+        let votes = vec![encrypted_vote("alice", "yes", 1000), create_test_vote("bob", "abcd", 1100)];
+
+        let result = tally_votes(
+            MASTER_SECRET,
+            DAO,
+            1,
+            &votes,
+            &serde_json::json!({ "Absolute": { "min_votes": 1 } }),
+            &TallyOptions::default(),
+        )
+        .unwrap();
+
+        let logs = logger::take();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].level, "warn");
+        assert_eq!(logs[0].message, "Failed to decrypt vote");
+        assert_eq!(logs[0].ctx["user"], "bob");
+        assert_eq!(logs[0].ctx["vote_index"], 1);
+        assert_eq!(result.spoiled_votes, 1);
+    }
+
+    /// Base64 SGX-shaped quote with `report_data` (first 32 bytes of REPORTDATA) and an MRENCLAVE of 0xab
+    fn sgx_quote(report_data: &[u8]) -> String {
+        use base64::Engine;
+
+        let mut quote = vec![0u8; 432];
+        quote[SGX_QUOTE_MRENCLAVE].fill(0xab);
+        quote[368..368 + report_data.len()].copy_from_slice(report_data);
+        base64::engine::general_purpose::STANDARD.encode(quote)
+    }
+
+    #[test]
+    fn test_tee_attestation_from_quote() {
+        let placeholder = generate_tee_attestation(1, "root", 2, 1, None, 5).unwrap();
+        assert!(verify_tee_attestation_format(&placeholder));
+        let commitment = hex::decode(placeholder.strip_prefix(MVP_ATTESTATION_PREFIX).unwrap()).unwrap();
+
+        let attestation = generate_tee_attestation(1, "root", 2, 1, Some(&sgx_quote(&commitment)), 5).unwrap();
+        assert!(verify_tee_attestation_format(&attestation));
+        let report: AttestationReport = serde_json::from_str(&attestation).unwrap();
+        assert_eq!(report.report_data, hex::encode(&commitment));
+        assert_eq!(report.mrenclave, "ab".repeat(32));
+        assert_eq!(report.timestamp, 5);
+        assert!(report.report_data_matches);
+
+        // Quote produced for another result: attached, but flagged
+        let attestation = generate_tee_attestation(1, "root", 1, 2, Some(&sgx_quote(&commitment)), 5).unwrap();
+        assert!(verify_tee_attestation_format(&attestation));
+        let report: AttestationReport = serde_json::from_str(&attestation).unwrap();
+        assert_eq!(report.report_data, hex::encode(&commitment));
+        assert!(!report.report_data_matches);
+        assert!(generate_tee_attestation(1, "root", 2, 1, Some("not base64!"), 5).is_err());
+        assert!(generate_tee_attestation(1, "root", 2, 1, Some("AAAA"), 5).is_err());
+    }
+
+    #[test]
+    fn test_verify_tee_attestation_format_rejects_malformed() {
+        assert!(!verify_tee_attestation_format("mvp-attestation:abcd"));
+        assert!(!verify_tee_attestation_format("attestation"));
+        // Quote too short to hold REPORTDATA
+        let short_quote = AttestationReport {
+            quote: "AAAA".to_string(),
+            report_data: "00".repeat(32),
+            timestamp: 0,
+            mrenclave: "00".repeat(32),
+            report_data_matches: true,
+        };
+        assert!(!verify_tee_attestation_format(&serde_json::to_string(&short_quote).unwrap()));
+    }
+
+    #[test]
+    fn test_verify_votes_integrity() {
+        let votes = vec![encrypted_vote("alice", "yes", 1000), encrypted_vote("bob", "no", 1100)];
+        let (root, _) = build_merkle_tree_with_proofs(&votes);
+
+        assert_eq!(verify_votes_integrity(&votes, None).unwrap(), root);
+        assert_eq!(verify_votes_integrity(&votes, Some(&root)).unwrap(), root);
+
+        let err = verify_votes_integrity(&votes, Some("00")).unwrap_err();
+        assert_eq!(err, format!("Merkle root mismatch: expected 00, computed {}", root));
+    }
+
+    #[test]
+    fn test_tally_rejects_votes_changed_since_previous_attempt() {
+        let mut votes = vec![encrypted_vote("alice", "yes", 1000), encrypted_vote("bob", "no", 1100)];
+        let (previous_root, _) = build_merkle_tree_with_proofs(&votes);
+        let quorum = serde_json::json!({ "Absolute": { "min_votes": 1 } });
+        let options = TallyOptions {
+            previous_merkle_root: Some(previous_root),
+            ..Default::default()
+        };
+
+        assert!(tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &options).is_ok());
+
+        // Bob's ballot swapped between the two OutLayer calls
+        votes[1] = encrypted_vote("bob", "yes", 1100);
+        let err = tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &options).unwrap_err();
+        assert!(err.starts_with("Merkle root mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn test_null_encrypted_vote_deserializes_as_empty() {
+        let vote: VoteData = serde_json::from_value(serde_json::json!({
+            "user": "alice",
+            "encrypted_vote": null,
+            "timestamp": 1000
+        }))
+        .unwrap();
+
+        assert_eq!(vote.encrypted_vote, "");
+    }
+
+    #[test]
+    fn test_empty_and_null_string_votes_are_dummies() {
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),
+            create_test_vote("alice", "", 2000),
+            create_test_vote("bob", "null", 1500),
+        ];
+
+        let result = tally_votes(
+            MASTER_SECRET,
+            DAO,
+            1,
+            &votes,
+            &serde_json::json!({ "Absolute": { "min_votes": 1 } }),
+            &TallyOptions { audit_mode: true, ..Default::default() },
+        )
+        .unwrap();
+
+        // Alice's real vote still counts; nothing fails to decrypt
+        assert_eq!(result.yes_count, Some(1));
+        let skipped = &result.audit_trail.unwrap().skipped_votes;
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped.iter().all(|entry| entry.reason == "dummy"));
+    }
+
+    #[test]
+    fn test_histogram_with_deadline() {
+        let votes = vec![
+            create_test_vote("alice", "ab", 1_000),
+            create_test_vote("bob", "ab", 1_249),
+            create_test_vote("carol", "ab", 1_250),
+            create_test_vote("alice", "ab", 1_999),
+        ];
+
+        let histogram = compute_turnout_histogram(&votes, 1_000, Some(2_000), 4);
+
+        assert_eq!(histogram, vec![(1_000, 2), (1_250, 1), (1_500, 0), (1_750, 1)]);
+    }
+
+    #[test]
+    fn test_histogram_without_deadline_spans_to_last_vote() {
+        let votes = vec![
+            create_test_vote("alice", "ab", 100),
+            create_test_vote("bob", "ab", 300),
+        ];
+
+        // Last vote lands exactly on the end of the span: counted in the last bin
+        assert_eq!(
+            compute_turnout_histogram(&votes, 100, None, 2),
+            vec![(100, 1), (200, 1)]
+        );
+        assert!(compute_turnout_histogram(&votes, 100, None, 0).is_empty());
+    }
+
+    #[test]
+    fn test_histogram_degenerate_span() {
+        // All votes at creation time, no deadline: span is 0, bins are 1ns wide
+        let votes = vec![create_test_vote("alice", "ab", 500), create_test_vote("bob", "ab", 500)];
+
+        assert_eq!(compute_turnout_histogram(&votes, 500, None, 3), vec![(500, 2), (501, 0), (502, 0)]);
+    }
+
+    #[test]
+    fn test_gini_coefficient() {
+        let counts = |pairs: &[(&str, usize)]| -> HashMap<String, usize> {
+            pairs.iter().map(|(user, count)| (user.to_string(), *count)).collect()
+        };
+
+        assert_eq!(compute_gini_coefficient(&HashMap::new()), 0.0);
+        assert_eq!(compute_gini_coefficient(&counts(&[("alice", 5)])), 0.0);
+        assert_eq!(compute_gini_coefficient(&counts(&[("alice", 2), ("bob", 2), ("carol", 2)])), 0.0);
+
+        // Sorted [1, 3]: 2 * (1 + 6) / (2 * 4) - 3 / 2 = 0.25
+        assert!((compute_gini_coefficient(&counts(&[("alice", 3), ("bob", 1)])) - 0.25).abs() < 1e-6);
+
+        // One account floods the proposal
+        let flooded = counts(&[("alice", 1), ("bob", 1), ("carol", 1), ("mallory", 97)]);
+        assert!(compute_gini_coefficient(&flooded) > 0.7);
+    }
+
+    #[test]
+    fn test_submission_gini_only_when_requested() {
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1_000),
+            encrypted_vote("alice", "dummy", 1_001),
+            encrypted_vote("alice", "dummy", 1_002),
+            encrypted_vote("bob", "no", 1_003),
+        ];
+        let quorum = serde_json::json!({ "Absolute": { "min_votes": 1 } });
+
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &TallyOptions::default()).unwrap();
+        assert!(result.submission_gini.is_none());
+
+        let options = TallyOptions { include_stats: true, ..Default::default() };
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &options).unwrap();
+        // Sorted [1, 3]
+        assert!((result.submission_gini.unwrap() - 0.25).abs() < 1e-6);
+    }
+
     #[test]
     fn test_votes_hash_deterministic() {
         let votes = vec![
-            create_test_vote("alice", "abc", 1000),
-            create_test_vote("bob", "def", 2000),
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("bob", "no", 2000),
         ];
 
         let hash1 = compute_votes_hash(&votes);
@@ -491,14 +1990,12 @@ mod tests {
     #[test]
     fn test_votes_hash_order_independent() {
         let votes1 = vec![
-            create_test_vote("alice", "abc", 1000),
-            create_test_vote("bob", "def", 2000),
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("bob", "no", 2000),
         ];
 
-        let votes2 = vec![
-            create_test_vote("bob", "def", 2000),
-            create_test_vote("alice", "abc", 1000),
-        ];
+        // Same votes, reversed (ECIES ciphertexts are randomized, so reuse them)
+        let votes2: Vec<_> = votes1.iter().rev().cloned().collect();
 
         let hash1 = compute_votes_hash(&votes1);
         let hash2 = compute_votes_hash(&votes2);
@@ -506,4 +2003,445 @@ mod tests {
         // Should be equal because sorted internally
         assert_eq!(hash1, hash2);
     }
+
+    #[test]
+    fn test_audit_trail_classifies_every_vote() {
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),   // superseded
+            encrypted_vote("alice", "no", 2000),    // counted
+            encrypted_vote("alice", "dummy", 3000), // dummy
+            encrypted_vote("bob", "yes", 1500),     // counted
+            create_test_vote("carol", "zz", 1700),  // decryption failed (not hex)
+        ];
+
+        let mut user_votes = HashMap::new();
+        user_votes.insert("alice".to_string(), ("no".to_string(), 2000));
+        user_votes.insert("bob".to_string(), ("yes".to_string(), 1500));
+
+        let skipped = vec![
+            SkippedEntry { user: "alice".to_string(), timestamp: 3000, reason: "dummy".to_string() },
+            SkippedEntry { user: "carol".to_string(), timestamp: 1700, reason: "decryption_failed".to_string() },
+        ];
+
+        let trail = generate_audit_trail(&votes, &user_votes, &skipped);
+
+        assert_eq!(
+            trail.counted_votes,
+            vec![
+                AuditEntry { user: "alice".to_string(), timestamp: 2000, counted_as: "no".to_string() },
+                AuditEntry { user: "bob".to_string(), timestamp: 1500, counted_as: "yes".to_string() },
+            ]
+        );
+        assert_eq!(trail.skipped_votes.len(), 3);
+        assert_eq!(trail.skipped_votes[2].reason, "superseded");
+        assert_eq!(
+            trail.override_votes,
+            vec![OverriddenEntry { user: "alice".to_string(), old_timestamp: 1000, new_timestamp: 2000 }]
+        );
+    }
+
+    #[test]
+    fn test_single_vote_merkle_tree() {
+        let (merkle_root, proofs) = build_merkle_tree_with_proofs(&[create_test_vote("alice", "abc", 1000)]);
+
+        // The single leaf is the root
+        assert_eq!(merkle_root.len(), 64); // SHA256 hex
+        assert_eq!(proofs.len(), 1);
+        assert_eq!(proofs[0].vote_index, 0);
+        assert!(proofs[0].proof_path.is_empty());
+        assert_eq!(proofs[0].vote_hash, merkle_root);
+    }
+
+    #[test]
+    fn test_empty_votes_merkle_tree() {
+        let (merkle_root, proofs) = build_merkle_tree_with_proofs(&[]);
+
+        assert_eq!(merkle_root, String::new());
+        assert!(proofs.is_empty());
+    }
+
+    #[test]
+    fn test_memory_estimates_grow_with_input() {
+        let small: Vec<_> = (0..10).map(|i| encrypted_vote(&format!("u{}", i), "yes", i)).collect();
+        let large: Vec<_> = (0..100).map(|i| encrypted_vote(&format!("u{}", i), "yes", i)).collect();
+
+        assert!(estimate_votes_bytes(&large) > estimate_votes_bytes(&small));
+        assert!(estimate_map_bytes(100, 48, 0) > estimate_map_bytes(10, 48, 0));
+
+        let (_, small_proofs) = build_merkle_tree_with_proofs(&small);
+        let (_, large_proofs) = build_merkle_tree_with_proofs(&large);
+        assert!(estimate_merkle_bytes(100, &large_proofs) > estimate_merkle_bytes(10, &small_proofs));
+    }
+
+    #[test]
+    fn test_time_weight_decay() {
+        let created = 1_000;
+        let halflife = 100;
+
+        assert_eq!(time_weight(created, created, halflife), 1.0);
+        assert_eq!(time_weight(created + halflife, created, halflife), 0.5);
+        assert_eq!(time_weight(created + 2 * halflife, created, halflife), 0.25);
+        assert_eq!(time_weight(created + 10 * halflife, created, halflife), 1.0 / 1024.0);
+
+        // Halfway through a half-life: 2^-0.5
+        let w = time_weight(created + halflife / 2, created, halflife);
+        assert!((w - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_time_weight_before_creation_is_full() {
+        assert_eq!(time_weight(500, 1_000, 100), 1.0);
+    }
+
+    fn absolute_quorum(min_votes: u64) -> serde_json::Value {
+        serde_json::json!({ "Absolute": { "min_votes": min_votes } })
+    }
+
+    #[test]
+    fn test_quorum_zero_votes() {
+        assert!(!check_quorum(&absolute_quorum(1), 0, 0, 0, 0.0, None).unwrap());
+    }
+
+    #[test]
+    fn test_quorum_exactly_met() {
+        assert!(check_quorum(&absolute_quorum(5), 0, 5, 0, 5.0, None).unwrap());
+    }
+
+    #[test]
+    fn test_quorum_one_below() {
+        assert!(!check_quorum(&absolute_quorum(5), 0, 4, 0, 4.0, None).unwrap());
+    }
+
+    #[test]
+    fn test_quorum_zero_min_votes_always_met() {
+        assert!(check_quorum(&absolute_quorum(0), 0, 0, 0, 0.0, None).unwrap());
+    }
+
+    #[test]
+    fn test_quorum_unknown_variant_rejected() {
+        let quorum = serde_json::json!({ "Quadratic": { "min_credits": 50 } });
+        assert!(check_quorum(&quorum, 0, 10, 0, 10.0, None).is_err());
+    }
+
+    #[test]
+    fn test_early_quorum_absolute() {
+        let quorum = QuorumType::Absolute { min_votes: 3 };
+
+        let below = check_early_quorum(&quorum, 2, 10);
+        assert!(!below.definitely_met && !below.possibly_met);
+
+        let met = check_early_quorum(&quorum, 3, 10);
+        assert!(met.definitely_met && met.possibly_met);
+    }
+
+    #[test]
+    fn test_unanimous_quorum() {
+        let quorum = serde_json::json!("Unanimous");
+
+        assert!(check_quorum(&quorum, 0, 3, 0, 3.0, Some(3)).unwrap());
+        assert!(!check_quorum(&quorum, 0, 2, 0, 2.0, Some(3)).unwrap());
+        assert!(!check_quorum(&quorum, 0, 0, 0, 0.0, Some(3)).unwrap());
+    }
+
+    #[test]
+    fn test_unanimous_quorum_requires_eligible_voters() {
+        let quorum = serde_json::json!("Unanimous");
+        assert!(check_quorum(&quorum, 0, 3, 0, 3.0, None).is_err());
+    }
+
+    #[test]
+    fn test_tally_counts_each_vote_option() {
+        let options = TallyOptions {
+            vote_options: Some(vec!["red".to_string(), "green".to_string(), "blue".to_string()]),
+            ..Default::default()
+        };
+        let votes = vec![
+            encrypted_vote("alice", "green", 1000),
+            encrypted_vote("bob", "blue", 1100),
+            encrypted_vote("carol", "green", 1200),
+            // Not an option of this proposal: treated as a dummy
+            encrypted_vote("dave", "yes", 1300),
+        ];
+
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &absolute_quorum(1), &options).unwrap();
+
+        assert_eq!(
+            result.vote_counts,
+            HashMap::from([("red".to_string(), 0), ("green".to_string(), 2), ("blue".to_string(), 1)])
+        );
+        assert_eq!(result.total_votes, 3);
+        assert_eq!(result.yes_count, None);
+        assert_eq!(result.no_count, None);
+    }
+
+    #[test]
+    fn test_tally_binary_vote_counts_alias_yes_no() {
+        let votes = vec![encrypted_vote("alice", "yes", 1000), encrypted_vote("bob", "no", 1100)];
+
+        let result =
+            tally_votes(MASTER_SECRET, DAO, 1, &votes, &absolute_quorum(1), &TallyOptions::default()).unwrap();
+        assert_eq!(
+            result.vote_counts,
+            HashMap::from([("yes".to_string(), 1), ("no".to_string(), 1), ("abstain".to_string(), 0)])
+        );
+        assert_eq!((result.yes_count, result.no_count), (Some(1), Some(1)));
+
+        // Counts are hidden without quorum
+        let result =
+            tally_votes(MASTER_SECRET, DAO, 1, &votes, &absolute_quorum(3), &TallyOptions::default()).unwrap();
+        assert!(result.vote_counts.is_empty());
+    }
+
+    #[test]
+    fn test_tally_super_majority_uses_leading_option() {
+        let options = TallyOptions {
+            vote_options: Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            ..Default::default()
+        };
+        let quorum = serde_json::json!({ "SuperMajority": { "min_yes_percent": 60 } });
+        let votes = vec![
+            encrypted_vote("alice", "a", 1000),
+            encrypted_vote("bob", "a", 1100),
+            encrypted_vote("carol", "b", 1200),
+        ];
+
+        // "a" leads with 2 of 3 votes (67%)
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &quorum, &options).unwrap();
+        assert_eq!(result.vote_counts["a"], 2);
+    }
+
+    fn delegations(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(from, to)| (from.to_string(), to.to_string())).collect()
+    }
+
+    #[test]
+    fn test_tally_credits_delegators_through_chain() {
+        // dave → carol → bob; alice voted herself before delegating to bob
+        let options = TallyOptions {
+            delegations: delegations(&[("alice", "bob"), ("carol", "bob"), ("dave", "carol")]),
+            ..Default::default()
+        };
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("bob", "no", 1100),
+            encrypted_vote("erin", "yes", 1200),
+        ];
+
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &absolute_quorum(1), &options).unwrap();
+        assert_eq!(result.yes_count, Some(1));
+        assert_eq!(result.no_count, Some(4));
+        // alice's own ballot is no longer counted
+        assert_eq!(result.counted_votes_root, counted_votes_root(MASTER_SECRET, DAO, 1, &votes, &[1, 2]).unwrap());
+    }
+
+    #[test]
+    fn test_tally_delegate_without_ballot_counts_nobody() {
+        let options = TallyOptions { delegations: delegations(&[("alice", "bob")]), ..Default::default() };
+        let votes = vec![encrypted_vote("alice", "yes", 1000), encrypted_vote("carol", "yes", 1100)];
+
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &absolute_quorum(1), &options).unwrap();
+        assert_eq!(result.total_votes, 1);
+    }
+
+    #[test]
+    fn test_tally_ignores_circular_delegation() {
+        let options = TallyOptions {
+            delegations: delegations(&[("alice", "bob"), ("bob", "alice")]),
+            ..Default::default()
+        };
+        let votes = vec![encrypted_vote("alice", "yes", 1000), encrypted_vote("bob", "no", 1100)];
+
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &votes, &absolute_quorum(1), &options).unwrap();
+        assert_eq!((result.yes_count, result.no_count), (Some(1), Some(1)));
+    }
+
+    #[test]
+    fn test_tally_unanimous_ignores_dummies() {
+        let quorum = serde_json::json!("Unanimous");
+        let options = TallyOptions { eligible_voters: Some(3), ..Default::default() };
+
+        let all = vec![
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("bob", "yes", 1100),
+            encrypted_vote("carol", "no", 1200),
+        ];
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &all, &quorum, &options).unwrap();
+        assert_eq!(result.yes_count, Some(2));
+
+        // carol only sent a dummy
+        let with_dummy = vec![
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("bob", "yes", 1100),
+            encrypted_vote("carol", "dummy", 1200),
+        ];
+        let result = tally_votes(MASTER_SECRET, DAO, 1, &with_dummy, &quorum, &options).unwrap();
+        assert_eq!(result.yes_count, None);
+    }
+
+    #[test]
+    fn test_early_quorum_unanimous() {
+        let status = check_early_quorum(&QuorumType::Unanimous, 2, 3);
+        assert!(!status.definitely_met && !status.possibly_met);
+
+        let status = check_early_quorum(&QuorumType::Unanimous, 3, 3);
+        assert!(status.definitely_met && status.possibly_met);
+    }
+
+    #[test]
+    fn test_percentage_quorum() {
+        let quorum = serde_json::json!({ "Percentage": { "min_percent_of_members": 50 } });
+
+        assert!(check_quorum(&quorum, 0, 5, 0, 5.0, Some(10)).unwrap());
+        assert!(!check_quorum(&quorum, 0, 4, 0, 4.0, Some(10)).unwrap());
+        // 50% of 7 members is 3.5, so 4 votes are needed
+        assert!(!check_quorum(&quorum, 0, 3, 0, 3.0, Some(7)).unwrap());
+        assert!(check_quorum(&quorum, 0, 4, 0, 4.0, Some(7)).unwrap());
+        assert!(check_quorum(&quorum, 0, 5, 0, 5.0, None).is_err());
+    }
+
+    #[test]
+    fn test_super_majority_quorum() {
+        let quorum = serde_json::json!({ "SuperMajority": { "min_yes_percent": 67 } });
+
+        assert!(check_quorum(&quorum, 67, 100, 0, 100.0, None).unwrap());
+        assert!(!check_quorum(&quorum, 66, 100, 0, 100.0, None).unwrap());
+        assert!(!check_quorum(&quorum, 0, 0, 0, 0.0, None).unwrap());
+
+        // Abstentions are neutral: 67 yes of 100 decisive votes
+        assert!(check_quorum(&quorum, 67, 150, 50, 150.0, None).unwrap());
+        assert!(!check_quorum(&quorum, 0, 3, 3, 3.0, None).unwrap());
+    }
+
+    #[test]
+    fn test_relative_quorum_at_half() {
+        let quorum = serde_json::json!({ "Relative": { "min_yes_percent": 50 } });
+
+        // Exactly 50%
+        assert!(check_quorum(&quorum, 1, 2, 0, 2.0, None).unwrap());
+        assert!(check_quorum(&quorum, 50, 100, 0, 100.0, None).unwrap());
+        // 49.9% rounds down to 49
+        assert!(!check_quorum(&quorum, 499, 1000, 0, 1000.0, None).unwrap());
+
+        // No turnout requirement, but at least one vote
+        assert!(check_quorum(&quorum, 1, 1, 0, 1.0, None).unwrap());
+        assert!(!check_quorum(&quorum, 0, 0, 0, 0.0, None).unwrap());
+        assert!(!check_quorum(&quorum, 0, 2, 2, 2.0, None).unwrap());
+
+        // Abstentions count against the share: 2 yes of 4 votes passes, 2 yes of 5 doesn't
+        assert!(check_quorum(&quorum, 2, 4, 1, 4.0, None).unwrap());
+        assert!(!check_quorum(&quorum, 2, 5, 2, 5.0, None).unwrap());
+
+        // Where SuperMajority at the same percentage passes (2 yes of 3 decisive votes)
+        let super_majority = serde_json::json!({ "SuperMajority": { "min_yes_percent": 50 } });
+        assert!(check_quorum(&super_majority, 2, 5, 2, 5.0, None).unwrap());
+    }
+
+    #[test]
+    fn test_percentage_out_of_range_rejected() {
+        for quorum in [
+            serde_json::json!({ "Percentage": { "min_percent_of_members": 0 } }),
+            serde_json::json!({ "Percentage": { "min_percent_of_members": 101 } }),
+            serde_json::json!({ "SuperMajority": { "min_yes_percent": 0 } }),
+            serde_json::json!({ "SuperMajority": { "min_yes_percent": 101 } }),
+            serde_json::json!({ "Relative": { "min_yes_percent": 0 } }),
+            serde_json::json!({ "Relative": { "min_yes_percent": 101 } }),
+        ] {
+            assert!(check_quorum(&quorum, 1, 1, 0, 1.0, Some(1)).is_err(), "{} accepted", quorum);
+        }
+    }
+
+    #[test]
+    fn test_early_quorum_percentage_and_super_majority() {
+        let percentage = QuorumType::Percentage { min_percent_of_members: 50 };
+        assert!(!check_early_quorum(&percentage, 4, 10).possibly_met);
+        assert!(check_early_quorum(&percentage, 5, 10).definitely_met);
+
+        let super_majority = QuorumType::SuperMajority { min_yes_percent: 67 };
+        assert!(!check_early_quorum(&super_majority, 0, 10).possibly_met);
+        let status = check_early_quorum(&super_majority, 10, 10);
+        assert!(status.possibly_met && !status.definitely_met);
+    }
+
+    #[test]
+    fn test_early_quorum_time_weighted_never_definite() {
+        let quorum = QuorumType::TimeWeighted { base_votes: 3, decay_halflife_ns: 100 };
+
+        assert!(!check_early_quorum(&quorum, 2, 10).possibly_met);
+
+        let status = check_early_quorum(&quorum, 5, 10);
+        assert!(status.possibly_met);
+        assert!(!status.definitely_met);
+    }
+
+    #[test]
+    fn test_time_weighted_quorum_exactly_met() {
+        let quorum = serde_json::json!({
+            "TimeWeighted": { "base_votes": 5, "decay_halflife_ns": 100 }
+        });
+
+        assert!(check_quorum(&quorum, 0, 5, 0, 5.0, None).unwrap());
+        assert!(!check_quorum(&quorum, 0, 5, 0, 4.99, None).unwrap());
+    }
+
+    #[test]
+    fn test_time_weighted_quorum() {
+        let quorum = serde_json::json!({
+            "TimeWeighted": { "base_votes": 2, "decay_halflife_ns": 100 }
+        });
+
+        // 3 raw votes, but weighted sum 1.75 misses a base of 2
+        assert!(!check_quorum(&quorum, 0, 3, 0, 1.75, None).unwrap());
+        assert!(check_quorum(&quorum, 0, 2, 0, 2.0, None).unwrap());
+    }
+
+    #[test]
+    fn test_time_weighted_quorum_zero_halflife_rejected() {
+        let quorum = serde_json::json!({
+            "TimeWeighted": { "base_votes": 2, "decay_halflife_ns": 0 }
+        });
+
+        assert!(check_quorum(&quorum, 0, 3, 0, 3.0, None).is_err());
+    }
+
+    #[test]
+    fn test_validate_timestamps_within_window() {
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1000),
+            encrypted_vote("bob", "no", 2000),
+        ];
+
+        assert!(validate_vote_timestamps(&votes, 1000, 2000).is_ok());
+    }
+
+    #[test]
+    fn test_validate_timestamps_before_creation() {
+        let votes = vec![encrypted_vote("alice", "yes", 999)];
+
+        let err = validate_vote_timestamps(&votes, 1000, 2000).unwrap_err();
+        assert!(err.contains("before proposal creation"));
+    }
+
+    #[test]
+    fn test_validate_timestamps_future() {
+        let now = 1_000_000;
+        let votes = vec![
+            encrypted_vote("alice", "yes", now + TIMESTAMP_TOLERANCE_NS),
+            encrypted_vote("bob", "no", now + TIMESTAMP_TOLERANCE_NS + 1),
+        ];
+
+        let err = validate_vote_timestamps(&votes, 0, now).unwrap_err();
+        assert!(err.contains("bob"));
+    }
+
+    #[test]
+    fn test_validate_timestamps_identical_is_not_error() {
+        // Same-block votes share a timestamp: warn, don't fail
+        let votes = vec![
+            encrypted_vote("alice", "yes", 1500),
+            encrypted_vote("bob", "no", 1500),
+            encrypted_vote("carol", "yes", 1500),
+        ];
+
+        assert!(validate_vote_timestamps(&votes, 1000, 2000).is_ok());
+    }
 }